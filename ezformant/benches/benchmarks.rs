@@ -2,6 +2,7 @@ use serde::Deserialize;
 use std::fs;
 use std::path::PathBuf;
 use criterion::{criterion_group, criterion_main, Criterion};
+use ezformant::fixed::FormantAnalyzerFixed;
 use std::collections::HashMap;
 
 const CARGO_MANIFEST_DIR: &str = env!("CARGO_MANIFEST_DIR");
@@ -43,17 +44,12 @@ fn criterion_bench(c: &mut Criterion) {
     let r = autocorrelate(&downsampled, FIXED_LPC_ORDER);
 
     
-    // Solver bench
-    /*
-    c.bench_function("bench test", |b| b.iter(|| {
-        // Test 1
-        //formant_detection(data.clone(), FIXED_LPC_ORDER, 441000f64);
-
-        
-        // Tets2: no change
-        formant_detection_fixed(data.clone(), 441000.0f64);
+    // Allocation-free pipeline at a compile-time order
+    let analyzer = FormantAnalyzerFixed::<FIXED_LPC_ORDER>::new(44100.0 / 4.0);
+    c.bench_function("fixed-order formants", |b| b.iter(|| {
+        let mut frame = downsampled;
+        analyzer.analyze(&mut frame[..FRAME_LENGTH / 4])
     }));
-    */
 
     c.bench_function("levinson, test", |b| b.iter(|| {
 
@@ -63,6 +59,7 @@ fn criterion_bench(c: &mut Criterion) {
     }));
 }
 
+#[allow(dead_code)]
 fn pre_emphasis(signal: &mut [f64], alpha: f64) {
     for i in (1..signal.len()).rev() {
         signal[i] -= alpha * signal[i - 1];
@@ -112,6 +109,7 @@ fn autocorrelate(signal: &[f64], maxlag: usize) -> Vec<f64> {
 /// A tuple containing:
 /// - A vector of filter coefficients (`a`).
 /// - The final prediction error (`E`).
+#[allow(dead_code)]
 fn levinson(order: usize, r: &[f64]) -> (Vec<f64>, f64){
     let p = order;
 
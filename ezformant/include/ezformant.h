@@ -0,0 +1,134 @@
+#ifndef EZFORMANT_H
+#define EZFORMANT_H
+
+/* Generated by cbindgen from src/ffi.rs; do not edit. */
+
+#include <stdarg.h>
+#include <stdbool.h>
+#include <stdint.h>
+#include <stdlib.h>
+
+// A required pointer argument was null.
+#define EZ_ERROR_NULL_POINTER -1
+
+// The frame was empty.
+#define EZ_ERROR_EMPTY_INPUT -2
+
+// The frame was shorter than the analysis needs.
+#define EZ_ERROR_INPUT_TOO_SHORT -3
+
+// The frame contained NaN or an infinity.
+#define EZ_ERROR_NON_FINITE_INPUT -4
+
+// A parameter was outside its valid range.
+#define EZ_ERROR_INVALID_PARAMETER -5
+
+// Accuracy/speed tier, see [`Quality`].
+typedef enum EzQuality {
+  EZ_QUALITY_FAST = 0,
+  EZ_QUALITY_BALANCED = 1,
+  EZ_QUALITY_ACCURATE = 2,
+} EzQuality;
+
+// Opaque analyzer handle created by [`ez_analyzer_new`].
+typedef struct EzAnalyzer EzAnalyzer;
+
+#ifdef __cplusplus
+extern "C" {
+#endif // __cplusplus
+
+// Creates an analyzer; free it with [`ez_analyzer_free`].
+//
+// # Arguments
+//
+// * `lpc_order`   - The LPC order.
+// * `sample_rate` - The sampling rate of incoming frames, in Hz.
+// * `max_formant` - Formant ceiling in Hz, or `0` to analyze at `sample_rate`.
+// * `quality`     - The algorithm tier.
+//
+// # Returns
+//
+// The analyzer, or null if `sample_rate` or `max_formant` is invalid.
+struct EzAnalyzer *ez_analyzer_new(uintptr_t lpc_order,
+                                   double sample_rate,
+                                   double max_formant,
+                                   enum EzQuality quality);
+
+// Frees an analyzer. Null is ignored.
+//
+// # Safety
+//
+// `analyzer` must be null or come from [`ez_analyzer_new`], and must not be used
+// afterwards.
+void ez_analyzer_free(struct EzAnalyzer *analyzer);
+
+// Estimates the formants of one frame.
+//
+// # Arguments
+//
+// * `analyzer` - The analyzer.
+// * `frame`    - `len` samples.
+// * `out`      - Receives up to `capacity` formant frequencies in Hz, ascending.
+//
+// # Returns
+//
+// The number of formants found (which may exceed `capacity`), or an error code.
+//
+// # Safety
+//
+// `analyzer` must be a live handle, `frame` must point to `len` readable values and
+// `out` to `capacity` writable ones (`out` may be null if `capacity` is 0).
+intptr_t ez_formants(const struct EzAnalyzer *analyzer,
+                     const double *frame,
+                     uintptr_t len,
+                     double *out,
+                     uintptr_t capacity);
+
+// Estimates the fundamental frequency of one frame.
+//
+// # Arguments
+//
+// * `analyzer` - The analyzer.
+// * `frame`    - `len` samples.
+// * `f0`       - Receives f0 in Hz, or 0 when the frame is unvoiced.
+//
+// # Returns
+//
+// `1` if the frame is voiced, `0` if not, or an error code.
+//
+// # Safety
+//
+// `analyzer` must be a live handle, `frame` must point to `len` readable values and
+// `f0` must be writable.
+intptr_t ez_pitch(const struct EzAnalyzer *analyzer,
+                  const double *frame,
+                  uintptr_t len,
+                  double *f0);
+
+// Computes autocorrelation-method LPC coefficients `[1, a1, ..., ap]` of a frame as
+// is (no window or pre-emphasis).
+//
+// # Arguments
+//
+// * `frame` - `len` samples; more than `order` are needed.
+// * `order` - The LPC order `p`.
+// * `out`   - Receives up to `capacity` coefficients; `order + 1` are needed.
+//
+// # Returns
+//
+// `order + 1`, or an error code (`EZ_ERROR_INPUT_TOO_SHORT` if `len <= order`).
+//
+// # Safety
+//
+// `frame` must point to `len` readable values and `out` to `capacity` writable ones.
+intptr_t ez_lpc_coefficients(const double *frame,
+                             uintptr_t len,
+                             uintptr_t order,
+                             double *out,
+                             uintptr_t capacity);
+
+#ifdef __cplusplus
+}  // extern "C"
+#endif  // __cplusplus
+
+#endif  /* EZFORMANT_H */
@@ -0,0 +1,141 @@
+//! Lag estimation and alignment of two channels of the same event (e.g. audio and EGG,
+//! or two microphones at different distances), so per-cycle measures taken on one
+//! channel line up with the other.
+
+use rustfft::{num_complex::Complex, FftPlanner};
+
+/// Result of [`estimate_lag`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Alignment {
+    /// Integer lag in samples: `other[n + lag]` lines up with `reference[n]`, so a
+    /// positive lag means `other` is late.
+    pub lag: isize,
+    /// `lag` refined by parabolic interpolation of the correlation peak.
+    pub fractional_lag: f64,
+    /// Normalized cross-correlation at `lag`, in `[-1, 1]`.
+    pub correlation: f64,
+}
+
+/// Estimates the lag between two channels from the peak of their cross-correlation.
+///
+/// # Arguments
+///
+/// * `reference` - The reference channel.
+/// * `other`     - The channel to align to `reference`.
+/// * `max_lag`   - The largest lag searched, in samples, in either direction.
+///
+/// # Returns
+///
+/// The best alignment, or `None` if either channel is empty or silent.
+#[tracing::instrument(level = "debug", skip_all, fields(len = reference.len(), max_lag))]
+pub fn estimate_lag(reference: &[f64], other: &[f64], max_lag: usize) -> Option<Alignment> {
+    let energy = |x: &[f64]| x.iter().map(|v| v * v).sum::<f64>();
+    let norm = (energy(reference) * energy(other)).sqrt();
+    if norm == 0.0 {
+        return None;
+    }
+
+    let fft_len = (reference.len() + other.len()).next_power_of_two();
+    let mut planner = FftPlanner::<f64>::new();
+    let forward = planner.plan_fft_forward(fft_len);
+    let spectrum = |x: &[f64]| {
+        let mut buffer: Vec<Complex<f64>> = x
+            .iter()
+            .map(|&v| Complex::new(v, 0.0))
+            .chain(std::iter::repeat(Complex::new(0.0, 0.0)))
+            .take(fft_len)
+            .collect();
+        forward.process(&mut buffer);
+        buffer
+    };
+    let mut cross: Vec<Complex<f64>> = spectrum(reference)
+        .iter()
+        .zip(spectrum(other))
+        .map(|(a, b)| a.conj() * b)
+        .collect();
+    planner.plan_fft_inverse(fft_len).process(&mut cross);
+
+    // c[k] = sum_n reference[n] * other[n + k]; negative lags wrap to the end.
+    let scale = 1.0 / (fft_len as f64 * norm);
+    let at = |k: isize| cross[k.rem_euclid(fft_len as isize) as usize].re * scale;
+    let max_lag = max_lag.min(fft_len / 2 - 1) as isize;
+    let lag = (-max_lag..=max_lag).max_by(|&a, &b| at(a).total_cmp(&at(b)))?;
+
+    let (left, centre, right) = (at(lag - 1), at(lag), at(lag + 1));
+    let denominator = left - 2.0 * centre + right;
+    let offset = if lag.abs() < max_lag && denominator < 0.0 {
+        0.5 * (left - right) / denominator
+    } else {
+        0.0
+    };
+    Some(Alignment {
+        lag,
+        fractional_lag: lag as f64 + offset,
+        correlation: centre,
+    })
+}
+
+/// Trims two channels so that they start at the same instant and have equal length.
+///
+/// # Arguments
+///
+/// * `reference` - The reference channel.
+/// * `other`     - The other channel.
+/// * `lag`       - The lag of `other` relative to `reference`, e.g. from [`estimate_lag`].
+///
+/// # Returns
+///
+/// The overlapping parts of `reference` and `other`.
+pub fn align<'a>(reference: &'a [f64], other: &'a [f64], lag: isize) -> (&'a [f64], &'a [f64]) {
+    let shift = lag.unsigned_abs();
+    let (reference, other) = if lag >= 0 {
+        (reference, other.get(shift..).unwrap_or(&[]))
+    } else {
+        (reference.get(shift..).unwrap_or(&[]), other)
+    };
+    let len = reference.len().min(other.len());
+    (&reference[..len], &other[..len])
+}
+
+// ------------------
+// Tests
+// ------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Deterministic broadband test signal.
+    fn noise(len: usize) -> Vec<f64> {
+        let mut state = 0x2545_f491_u64;
+        (0..len)
+            .map(|_| {
+                state ^= state << 13;
+                state ^= state >> 7;
+                state ^= state << 17;
+                (state % 2001) as f64 / 1000.0 - 1.0
+            })
+            .collect()
+    }
+
+    #[test]
+    fn recovers_delays_in_both_directions() {
+        let source = noise(4096);
+        let reference = &source[100..3100];
+
+        let late = &source[63..3063]; // `other` lags by 37 samples
+        let alignment = estimate_lag(reference, late, 200).unwrap();
+        assert_eq!(alignment.lag, 37);
+        assert!(alignment.correlation > 0.95);
+        let (a, b) = align(reference, late, alignment.lag);
+        assert_eq!(a, b);
+
+        let early = &source[112..3112];
+        assert_eq!(estimate_lag(reference, early, 200).unwrap().lag, -12);
+        let (a, b) = align(reference, early, -12);
+        assert_eq!(a, b);
+
+        assert_eq!(estimate_lag(reference, &[0.0; 16], 8), None);
+    }
+}
@@ -0,0 +1,598 @@
+use crate::counters::{DropReason, FrameCounters};
+use crate::error::{check_finite, check_positive, EzFormantError, Result};
+use crate::lpc::{LagWindow, Peak};
+use crate::pitch::PitchResult;
+use crate::quality::{Quality, QualitySettings};
+use crate::resample::{PolyphaseResampler, Resampler};
+use crate::units::FrequencyUnit;
+use crate::{decimate, WindowFunction};
+
+/// A reusable, immutable analysis configuration.
+///
+/// `Analyzer` holds no global or shared mutable state, so it is `Send + Sync`:
+/// one instance can be shared (e.g. behind an `Arc`) by threads analyzing
+/// independent streams concurrently.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Analyzer {
+    lpc_order: usize,
+    sample_rate: f64,
+    downsample_factor: usize,
+    max_formant: Option<f64>,
+    quality: QualitySettings,
+    units: FrequencyUnit,
+    num_formants: Option<usize>,
+}
+
+impl Analyzer {
+    /// Creates an analyzer for frames sampled at `sample_rate`.
+    ///
+    /// # Arguments
+    ///
+    /// * `lpc_order`   - The LPC order.
+    /// * `sample_rate` - The sampling rate of incoming frames.
+    pub fn new(lpc_order: usize, sample_rate: f64) -> Self {
+        Self {
+            lpc_order,
+            sample_rate,
+            downsample_factor: 1,
+            max_formant: None,
+            quality: QualitySettings::default(),
+            units: FrequencyUnit::Hertz,
+            num_formants: None,
+        }
+    }
+
+    /// Downsamples frames by `factor` before formant analysis. Replaces any
+    /// [`Analyzer::with_max_formant`] ceiling.
+    pub fn with_downsample_factor(mut self, factor: usize) -> Self {
+        self.downsample_factor = factor.max(1);
+        self.max_formant = None;
+        self
+    }
+
+    /// Resamples frames so that the Nyquist frequency equals `max_formant_hz` before
+    /// formant analysis, like Praat's "Maximum formant" (5000 Hz for adult males,
+    /// 5500 Hz for adult females). Replaces any downsample factor; a ceiling at or
+    /// above the input's Nyquist frequency leaves frames untouched.
+    pub fn with_max_formant(mut self, max_formant_hz: f64) -> Self {
+        self.max_formant = Some(max_formant_hz);
+        self.downsample_factor = 1;
+        self
+    }
+
+    /// Selects the algorithms used by this analyzer. Also resets the window,
+    /// pre-emphasis, lag window, warping and pitch range, so call it before
+    /// [`Analyzer::with_window`], [`Analyzer::with_pre_emphasis`],
+    /// [`Analyzer::with_lag_window`], [`Analyzer::with_warping`] and
+    /// [`Analyzer::with_pitch_range`].
+    pub fn with_quality(mut self, quality: Quality) -> Self {
+        self.quality = quality.settings();
+        self
+    }
+
+    /// Selects the window applied to frames before LPC analysis, keeping the other
+    /// algorithm choices.
+    pub fn with_window(mut self, window: WindowFunction) -> Self {
+        self.quality.window = window;
+        self
+    }
+
+    /// Sets the pre-emphasis coefficient applied to frames before LPC analysis
+    /// (see [`crate::pre_emphasis_from_frequency`] to derive it from a frequency).
+    pub fn with_pre_emphasis(mut self, alpha: f64) -> Self {
+        self.quality.pre_emphasis = alpha;
+        self
+    }
+
+    /// Regularizes the LPC autocorrelation of each frame, which keeps near-singular
+    /// frames (pure sustained vowels) from producing spurious formants.
+    pub fn with_lag_window(mut self, lag_window: LagWindow) -> Self {
+        self.quality.lag_window = lag_window;
+        self
+    }
+
+    /// Analyzes frames with warped LPC using the allpass coefficient `warping` (see
+    /// [`crate::warped::bark_warping_coefficient`]). Combine with a downsample factor
+    /// of 1 to analyze full-rate audio with a low order.
+    pub fn with_warping(mut self, warping: f64) -> Self {
+        self.quality.warping = warping;
+        self
+    }
+
+    /// Only considers f0 between `floor` and `ceiling` Hz, e.g. 75-300 Hz for adult
+    /// male speech or 100-600 Hz for female and child speech. Frames should hold two
+    /// periods of `floor`.
+    pub fn with_pitch_range(mut self, floor: f64, ceiling: f64) -> Self {
+        self.quality.pitch_range = Some((floor, ceiling));
+        self
+    }
+
+    /// Keeps at most the `count` lowest formants of each frame.
+    pub fn with_num_formants(mut self, count: usize) -> Self {
+        self.num_formants = Some(count);
+        self
+    }
+
+    /// Reports formants and f0 in `units` instead of Hz.
+    pub fn with_units(mut self, units: FrequencyUnit) -> Self {
+        self.units = units;
+        self
+    }
+
+    /// The LPC order.
+    pub fn lpc_order(&self) -> usize {
+        self.lpc_order
+    }
+
+    /// The sampling rate of incoming frames.
+    pub fn sample_rate(&self) -> f64 {
+        self.sample_rate
+    }
+
+    /// The downsampling factor applied before formant analysis.
+    pub fn downsample_factor(&self) -> usize {
+        self.downsample_factor
+    }
+
+    /// The formant ceiling set by [`Analyzer::with_max_formant`], in Hz.
+    pub fn max_formant(&self) -> Option<f64> {
+        self.max_formant
+    }
+
+    /// The sampling rate formants are analyzed at, after downsampling or resampling
+    /// to the formant ceiling.
+    pub fn analysis_rate(&self) -> f64 {
+        match self.max_formant {
+            Some(max_formant) if 2.0 * max_formant < self.sample_rate => 2.0 * max_formant,
+            _ => self.sample_rate / self.downsample_factor as f64,
+        }
+    }
+
+    /// The number of formants kept per frame, if limited by
+    /// [`Analyzer::with_num_formants`].
+    pub fn num_formants(&self) -> Option<usize> {
+        self.num_formants
+    }
+
+    /// The unit of formant and f0 outputs.
+    pub fn units(&self) -> FrequencyUnit {
+        self.units
+    }
+
+    /// The algorithm choices used by this analyzer.
+    pub fn quality(&self) -> &QualitySettings {
+        &self.quality
+    }
+
+    /// Whether `frame` can be analyzed: non-empty, finite, and long enough for the LPC
+    /// order after downsampling.
+    pub fn accepts(&self, frame: &[f64]) -> bool {
+        self.validate(frame).is_ok()
+    }
+
+    /// Checks the sampling rate and `frame`, reporting why it cannot be analyzed.
+    pub fn validate(&self, frame: &[f64]) -> Result<()> {
+        check_positive("sample_rate", self.sample_rate)?;
+        if let Some(max_formant) = self.max_formant {
+            check_positive("max_formant_hz", max_formant)?;
+            // The resampler works with rates rounded to whole Hz.
+            let rate = self.analysis_rate();
+            if rate < self.sample_rate && rate.round() < 1.0 {
+                return Err(EzFormantError::InvalidParameter {
+                    name: "max_formant_hz",
+                    value: max_formant,
+                });
+            }
+        }
+        check_finite(frame)?;
+        // At least `lpc_order + 1` samples must remain after downsampling.
+        let ratio = self.sample_rate / self.analysis_rate();
+        let required = (self.lpc_order as f64 * ratio).floor() as usize + 1;
+        if frame.len() < required {
+            return Err(EzFormantError::InputTooShort {
+                len: frame.len(),
+                required,
+            });
+        }
+        Ok(())
+    }
+
+    /// Detects the formants of a frame, recording the outcome in `counters`.
+    ///
+    /// # Returns
+    ///
+    /// The formant frequencies in Hz, or `None` if the frame was rejected by
+    /// [`Analyzer::accepts`].
+    pub fn formants_counted(&self, frame: &[f64], counters: &FrameCounters) -> Option<Vec<f64>> {
+        if !self.accepts(frame) {
+            counters.record_dropped(DropReason::InvalidInput);
+            return None;
+        }
+        counters.record_analyzed();
+        Some(self.formants(frame))
+    }
+
+    /// Detects the formants of a frame.
+    ///
+    /// # Returns
+    ///
+    /// A vector of formant frequencies in [`Analyzer::units`] (Hz by default).
+    #[tracing::instrument(level = "debug", skip_all, fields(len = frame.len()))]
+    pub fn formants(&self, frame: &[f64]) -> Vec<f64> {
+        let data = self.to_analysis_rate(frame);
+        let mut formants =
+            self.quality
+                .detect_formants(&data, self.lpc_order, self.analysis_rate());
+        self.limit(&mut formants);
+        self.units.convert_in_place(&mut formants);
+        formants
+    }
+
+    /// Like [`Analyzer::formants`], for `f32` samples such as Web Audio buffers.
+    ///
+    /// Preprocessing and the autocorrelation, the passes over the whole frame, run in
+    /// single precision; the Levinson recursion and root solving stay in `f64`. Frames
+    /// that must be resampled to [`Analyzer::analysis_rate`] are widened first.
+    pub fn formants_f32(&self, frame: &[f32]) -> Vec<f64> {
+        if self.analysis_rate() < self.sample_rate {
+            let frame: Vec<f64> = frame.iter().map(|&x| x as f64).collect();
+            return self.formants(&frame);
+        }
+        let mut formants = self
+            .quality
+            .detect_formants(frame, self.lpc_order, self.sample_rate);
+        self.limit(&mut formants);
+        self.units.convert_in_place(&mut formants);
+        formants
+    }
+
+    /// Detects the formants of a frame along with their bandwidths.
+    ///
+    /// # Returns
+    ///
+    /// One [`Peak`] per formant, sorted by frequency. Frequencies are in
+    /// [`Analyzer::units`]; bandwidths are always in Hz.
+    pub fn formant_peaks(&self, frame: &[f64]) -> Vec<Peak> {
+        let data = self.to_analysis_rate(frame);
+        let mut peaks =
+            self.quality
+                .detect_formant_peaks(&data, self.lpc_order, self.analysis_rate());
+        self.limit(&mut peaks);
+        for peak in peaks.iter_mut() {
+            peak.frequency = self.units.from_hertz(peak.frequency);
+        }
+        peaks
+    }
+
+    /// Detects formants from the power spectrum of a frame sampled at
+    /// [`Analyzer::sample_rate`], e.g. one taken from a Web Audio `AnalyserNode`.
+    ///
+    /// Instead of resampling, only the bins below half the analysis rate are
+    /// modelled (selective linear prediction), which has the same effect as
+    /// downsampling the frame or setting a formant ceiling.
+    ///
+    /// # Arguments
+    ///
+    /// * `power` - The power spectrum from DC up to (not including) Nyquist; see
+    ///   [`crate::spectrum::SpectrumScale`] to convert other scales.
+    pub fn formants_from_spectrum(&self, power: &[f64]) -> Vec<f64> {
+        let rate = self.analysis_rate();
+        let bins = ((power.len() as f64 * rate / self.sample_rate).round() as usize)
+            .clamp(1, power.len().max(1));
+        let mut formants = self.quality.detect_formants_from_spectrum(
+            &power[..bins.min(power.len())],
+            self.lpc_order,
+            rate,
+        );
+        self.limit(&mut formants);
+        self.units.convert_in_place(&mut formants);
+        formants
+    }
+
+    /// Downsamples, or resamples to the formant ceiling, a signal at
+    /// [`Analyzer::sample_rate`] to [`Analyzer::analysis_rate`].
+    pub fn to_analysis_rate(&self, signal: &[f64]) -> Vec<f64> {
+        let sample_rate = self.analysis_rate();
+        if self.max_formant.is_some() && sample_rate < self.sample_rate {
+            PolyphaseResampler::default().resample(signal, self.sample_rate, sample_rate)
+        } else {
+            decimate(signal, self.downsample_factor)
+        }
+    }
+
+    /// Like [`Analyzer::formants`], but returns an error for frames rejected by
+    /// [`Analyzer::validate`].
+    pub fn try_formants(&self, frame: &[f64]) -> Result<Vec<f64>> {
+        self.validate(frame)?;
+        Ok(self.formants(frame))
+    }
+
+    /// Detects the fundamental frequency of a frame.
+    ///
+    /// # Returns
+    ///
+    /// The fundamental frequency in [`Analyzer::units`] (Hz by default), or `-1.0` if
+    /// the frame is not periodic.
+    pub fn pitch(&self, frame: &[f64]) -> f64 {
+        let mut f0 = [self.quality.detect_pitch(frame, self.sample_rate)];
+        self.units.convert_in_place(&mut f0);
+        f0[0]
+    }
+
+    /// Estimates the fundamental frequency of a frame along with its voicing. `f0` is
+    /// in [`Analyzer::units`].
+    #[tracing::instrument(level = "debug", skip_all, fields(len = frame.len()))]
+    pub fn pitch_result(&self, frame: &[f64]) -> PitchResult {
+        let mut result = self.quality.estimate_pitch(frame, self.sample_rate);
+        let mut f0 = [result.f0];
+        self.units.convert_in_place(&mut f0);
+        result.f0 = f0[0];
+        result
+    }
+
+    /// Like [`Analyzer::pitch`], but returns an error for an empty or non-finite frame
+    /// or a non-positive sampling rate.
+    pub fn try_pitch(&self, frame: &[f64]) -> Result<f64> {
+        check_positive("sample_rate", self.sample_rate)?;
+        check_finite(frame)?;
+        Ok(self.pitch(frame))
+    }
+
+    /// Like [`Analyzer::pitch_result`], but returns an error for an empty or
+    /// non-finite frame or a non-positive sampling rate.
+    pub fn try_pitch_result(&self, frame: &[f64]) -> Result<PitchResult> {
+        check_positive("sample_rate", self.sample_rate)?;
+        check_finite(frame)?;
+        Ok(self.pitch_result(frame))
+    }
+
+    // Drops formants beyond `num_formants`.
+    fn limit<T>(&self, formants: &mut Vec<T>) {
+        if let Some(count) = self.num_formants {
+            formants.truncate(count);
+        }
+    }
+}
+
+// ------------------
+// Tests
+// ------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_signals::vowel;
+    use std::f64::consts::PI;
+    use std::sync::Arc;
+    use std::thread;
+
+    fn assert_send_sync<T: Send + Sync>() {}
+
+    #[test]
+    fn analyzer_is_send_sync() {
+        assert_send_sync::<Analyzer>();
+    }
+
+    #[test]
+    fn counts_invalid_frames() {
+        let analyzer = Analyzer::new(12, 16000.0).with_downsample_factor(4);
+        let counters = FrameCounters::new();
+
+        let mut frame: Vec<f64> = (0..1024).map(|n| (n as f64 * 0.1).sin()).collect();
+        assert!(analyzer.formants_counted(&frame, &counters).is_some());
+        assert!(analyzer.formants_counted(&frame[..40], &counters).is_none());
+        frame[10] = f64::NAN;
+        assert!(analyzer.formants_counted(&frame, &counters).is_none());
+        counters.record_dropped(DropReason::Overload);
+
+        let snapshot = counters.snapshot();
+        assert_eq!(snapshot.analyzed, 1);
+        assert_eq!(snapshot.dropped_invalid, 2);
+        assert_eq!(snapshot.dropped_overload, 1);
+        assert_eq!(snapshot.drop_ratio(), 0.75);
+    }
+
+    #[test]
+    fn try_variants_report_invalid_input() {
+        let analyzer = Analyzer::new(12, 16000.0).with_downsample_factor(4);
+        let mut frame: Vec<f64> = (0..1024).map(|n| (n as f64 * 0.1).sin()).collect();
+
+        assert_eq!(analyzer.try_formants(&frame), Ok(analyzer.formants(&frame)));
+        assert_eq!(
+            analyzer.try_formants(&frame[..48]),
+            Err(EzFormantError::InputTooShort {
+                len: 48,
+                required: 49
+            })
+        );
+        assert!(analyzer.try_formants(&frame[..49]).is_ok());
+        // Too short for the model: no formants rather than evenly spaced artefacts.
+        assert!(analyzer.formants(&frame[..48]).is_empty());
+        assert!(analyzer.formant_peaks(&frame[..20]).is_empty());
+        assert!(analyzer.formants_from_spectrum(&frame[..20]).is_empty());
+        assert_eq!(
+            analyzer.try_pitch_result(&[]),
+            Err(EzFormantError::EmptyInput)
+        );
+        assert_eq!(analyzer.try_pitch(&frame), Ok(analyzer.pitch(&frame)));
+        let envelope = analyzer.quality().try_gain_envelope_db(
+            &frame[..48],
+            12,
+            16000.0,
+            4,
+            64,
+            Default::default(),
+        );
+        assert_eq!(
+            envelope,
+            Err(EzFormantError::InputTooShort {
+                len: 48,
+                required: 49
+            })
+        );
+
+        frame[10] = f64::INFINITY;
+        assert_eq!(
+            analyzer.try_formants(&frame),
+            Err(EzFormantError::NonFiniteInput { index: 10 })
+        );
+        assert!(matches!(
+            Analyzer::new(12, 0.0).try_formants(&frame[..20]),
+            Err(EzFormantError::InvalidParameter {
+                name: "sample_rate",
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn max_formant_resamples_to_the_ceiling() {
+        let sample_rate = 44100.0;
+        let frame = vowel(44100.0, 120.0, 2048);
+
+        let analyzer = Analyzer::new(10, sample_rate).with_max_formant(5500.0);
+        assert_eq!(analyzer.analysis_rate(), 11000.0);
+        let formants = analyzer.formants(&frame);
+        for expected in [700.0, 1200.0, 2600.0] {
+            assert!(
+                formants.iter().any(|f| (f - expected).abs() < 60.0),
+                "{:?}",
+                formants
+            );
+        }
+
+        // Ten coefficients need 10 * 44100 / 11000 + 1 input samples.
+        assert!(analyzer.try_formants(&frame[..41]).is_ok());
+        assert!(analyzer.try_formants(&frame[..40]).is_err());
+        // Non-positive ceilings, and ceilings that round to a 0 Hz analysis rate.
+        for max_formant in [0.0, -5.0, 0.2] {
+            assert!(matches!(
+                Analyzer::new(10, sample_rate)
+                    .with_max_formant(max_formant)
+                    .try_formants(&frame),
+                Err(EzFormantError::InvalidParameter {
+                    name: "max_formant_hz",
+                    ..
+                })
+            ));
+        }
+        // A ceiling above Nyquist analyzes at the input rate.
+        assert_eq!(
+            Analyzer::new(10, 8000.0)
+                .with_max_formant(5500.0)
+                .analysis_rate(),
+            8000.0
+        );
+    }
+
+    #[test]
+    fn single_precision_formants_match_double() {
+        let sample_rate = 11025.0;
+        let frame = vowel(sample_rate, 120.0, 2048);
+        let narrow: Vec<f32> = frame.iter().map(|&x| x as f32).collect();
+
+        let analyzer = Analyzer::new(10, sample_rate);
+        let (single, double) = (analyzer.formants_f32(&narrow), analyzer.formants(&frame));
+        assert_eq!(single.len(), double.len());
+        // Well within display resolution.
+        for (s, d) in single.iter().zip(&double) {
+            assert!((s - d).abs() < 0.01 * d, "{:?} vs {:?}", single, double);
+        }
+
+        // With resampling to a 5000 Hz ceiling, the vowel's formants still come out.
+        let resampled = analyzer.with_max_formant(5000.0);
+        let formants = resampled.formants_f32(&narrow);
+        for expected in [700.0, 1200.0, 2600.0] {
+            assert!(
+                formants.iter().any(|f| (f - expected).abs() < 60.0),
+                "{:?}",
+                formants
+            );
+        }
+    }
+
+    #[test]
+    fn formants_from_an_analyser_node_spectrum() {
+        use crate::spectrum::SpectrumScale;
+        use rustfft::{num_complex::Complex, FftPlanner};
+
+        let mut frame = vowel(44100.0, 120.0, 2048);
+        crate::apply_hamming_window_in_place(&mut frame);
+        let mut buffer: Vec<Complex<f64>> = frame.iter().map(|&x| Complex::new(x, 0.0)).collect();
+        FftPlanner::new()
+            .plan_fft_forward(buffer.len())
+            .process(&mut buffer);
+        // What AnalyserNode.getFloatFrequencyData returns for fftSize = 2048.
+        let decibels: Vec<f64> = buffer[..1024]
+            .iter()
+            .map(|x| 20.0 * x.norm().log10())
+            .collect();
+
+        let analyzer = Analyzer::new(10, 44100.0).with_max_formant(5500.0);
+        let formants =
+            analyzer.formants_from_spectrum(&SpectrumScale::Decibels.to_power(&decibels));
+        for expected in [700.0, 1200.0, 2600.0] {
+            assert!(
+                formants.iter().any(|f| (f - expected).abs() < 60.0),
+                "{:?}",
+                formants
+            );
+        }
+        assert_eq!("dB".parse(), Ok(SpectrumScale::Decibels));
+    }
+
+    #[test]
+    fn units_apply_to_formants_and_pitch() {
+        let sample_rate = 16000.0;
+        let frame: Vec<f64> = (0..2048)
+            .map(|n| (2.0 * PI * 200.0 * n as f64 / sample_rate).sin())
+            .collect();
+        let hertz = Analyzer::new(12, sample_rate);
+        let semitones = hertz.clone().with_units(FrequencyUnit::Semitones);
+
+        let f0 = semitones.pitch(&frame);
+        assert!((f0 - FrequencyUnit::Semitones.from_hertz(hertz.pitch(&frame))).abs() < 1e-9);
+        assert!((f0 - 12.0).abs() < 0.05, "f0 = {} st", f0);
+        assert_eq!(semitones.pitch_result(&frame).f0, f0);
+
+        let mut expected = hertz.formants(&frame);
+        FrequencyUnit::Semitones.convert_in_place(&mut expected);
+        assert_eq!(semitones.formants(&frame), expected);
+        assert_eq!(semitones.pitch(&vec![0.0; 2048]), -1.0);
+    }
+
+    #[test]
+    fn concurrent_analysis_matches_sequential() {
+        let sample_rate = 16000.0;
+        let analyzer = Arc::new(Analyzer::new(12, sample_rate).with_downsample_factor(2));
+
+        let frames: Vec<Vec<f64>> = [110.0, 165.0, 220.0, 330.0]
+            .iter()
+            .map(|&f0| {
+                (0..2048)
+                    .map(|n| {
+                        let t = n as f64 / sample_rate;
+                        (2.0 * PI * f0 * t).sin() + 0.5 * (2.0 * PI * 3.0 * f0 * t).sin()
+                    })
+                    .collect()
+            })
+            .collect();
+
+        let sequential: Vec<(Vec<f64>, f64)> = frames
+            .iter()
+            .map(|frame| (analyzer.formants(frame), analyzer.pitch(frame)))
+            .collect();
+
+        let handles: Vec<_> = frames
+            .into_iter()
+            .map(|frame| {
+                let analyzer = Arc::clone(&analyzer);
+                thread::spawn(move || (analyzer.formants(&frame), analyzer.pitch(&frame)))
+            })
+            .collect();
+
+        for (handle, expected) in handles.into_iter().zip(sequential) {
+            assert_eq!(handle.join().unwrap(), expected);
+        }
+    }
+}
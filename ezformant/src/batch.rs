@@ -0,0 +1,323 @@
+//! Frame-based analysis of whole recordings.
+//!
+//! [`analyze_signal`] cuts a recording into overlapping frames and measures formants,
+//! f0, energy, zero-crossing rate and voicing in each, giving time-stamped tracks for offline analysis of
+//! files. The same
+//! per-frame measurement backs [`crate::streaming::StreamingAnalyzer`], so a recording
+//! analyzed in one call and streamed in chunks gives the same frames.
+//!
+//! With the `rayon` feature the frames are analyzed in parallel on all cores. Frames
+//! are independent, so the tracks are the same as with one thread.
+
+use crate::analyzer::Analyzer;
+use crate::config::AnalysisConfig;
+use crate::error::Result;
+use crate::quality::Quality;
+use crate::vad::{Vad, VadConfig};
+
+/// Analysis parameters for [`analyze_signal`]. [`AnalysisConfig`] exposes more of
+/// them and converts from this one.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct BatchConfig {
+    /// The LPC order.
+    pub lpc_order: usize,
+    /// Formant ceiling in Hz (see [`Analyzer::with_max_formant`]).
+    pub max_formant: f64,
+    /// Algorithm choices.
+    pub quality: Quality,
+    /// Analysis frame length, in seconds.
+    pub frame_duration: f64,
+    /// Hop between frames, in seconds.
+    pub time_step: f64,
+    /// Voice activity detection; frames it rejects are marked silent and not analyzed.
+    /// `None` analyzes every frame.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub vad: Option<VadConfig>,
+}
+
+impl Default for BatchConfig {
+    fn default() -> Self {
+        Self {
+            lpc_order: 10,
+            max_formant: 5500.0,
+            quality: Quality::Accurate,
+            frame_duration: 0.025,
+            time_step: 0.01,
+            vad: None,
+        }
+    }
+}
+
+impl BatchConfig {
+    /// The per-frame analyzer for a signal sampled at `sample_rate`.
+    pub fn analyzer(&self, sample_rate: f64) -> Analyzer {
+        AnalysisConfig::from(*self).analyzer(sample_rate)
+    }
+}
+
+/// Measurements for one analysis frame.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct TrackFrame {
+    /// Centre of the frame, in seconds from the start of the signal.
+    pub time: f64,
+    /// Formant frequencies in the analyzer's units.
+    pub formants: Vec<f64>,
+    /// Bandwidth of each formant, in Hz.
+    pub bandwidths: Vec<f64>,
+    /// Fundamental frequency in the analyzer's units, or `-1.0` if unvoiced.
+    pub pitch: f64,
+    /// Mean-square amplitude of the frame (before windowing), see [`crate::frame_energy`].
+    pub energy: f64,
+    /// Zero-crossing rate of the frame, see [`crate::zcr`].
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub zcr: f64,
+    /// Whether the pitch detector found the frame periodic.
+    pub voiced: bool,
+    /// Whether voice activity detection rejected the frame, which then has no formants
+    /// or f0.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub silent: bool,
+}
+
+impl TrackFrame {
+    /// Analyzes one frame.
+    ///
+    /// # Arguments
+    ///
+    /// * `analyzer` - The per-frame analysis.
+    /// * `frame`    - The samples, at `analyzer.sample_rate()`.
+    /// * `start`    - Index of the frame's first sample in the whole signal.
+    pub fn analyze(analyzer: &Analyzer, frame: &[f64], start: u64) -> Self {
+        let centre = start as f64 + frame.len() as f64 / 2.0;
+        let pitch = analyzer.pitch_result(frame);
+        let (formants, bandwidths) = analyzer
+            .formant_peaks(frame)
+            .iter()
+            .map(|peak| (peak.frequency, peak.bandwidth))
+            .unzip();
+        TrackFrame {
+            time: centre / analyzer.sample_rate(),
+            formants,
+            bandwidths,
+            pitch: if pitch.voiced { pitch.f0 } else { -1.0 },
+            energy: crate::frame_energy(frame),
+            zcr: crate::zcr(frame),
+            voiced: pitch.voiced,
+            silent: false,
+        }
+    }
+
+    /// A frame rejected by voice activity detection: energy and zero-crossing rate
+    /// only, without running the pitch or formant analysis.
+    ///
+    /// # Arguments
+    ///
+    /// * `frame`       - The samples.
+    /// * `start`       - Index of the frame's first sample in the whole signal.
+    /// * `sample_rate` - The sampling rate of the samples.
+    pub fn silent(frame: &[f64], start: u64, sample_rate: f64) -> Self {
+        let centre = start as f64 + frame.len() as f64 / 2.0;
+        TrackFrame {
+            time: centre / sample_rate,
+            formants: Vec::new(),
+            bandwidths: Vec::new(),
+            pitch: -1.0,
+            energy: crate::frame_energy(frame),
+            zcr: crate::zcr(frame),
+            voiced: false,
+            silent: true,
+        }
+    }
+
+    /// Analyzes one frame if `vad` finds it active, and returns a
+    /// [`TrackFrame::silent`] frame otherwise.
+    pub fn analyze_gated(analyzer: &Analyzer, frame: &[f64], start: u64, vad: &mut Vad) -> Self {
+        if vad.is_active(frame) {
+            Self::analyze(analyzer, frame, start)
+        } else {
+            Self::silent(frame, start, analyzer.sample_rate())
+        }
+    }
+}
+
+/// Analyzes a recording frame by frame.
+///
+/// # Arguments
+///
+/// * `signal`      - The recording.
+/// * `sample_rate` - The sampling rate of the recording.
+/// * `config`      - Analysis and framing parameters.
+///
+/// # Returns
+///
+/// One [`TrackFrame`] per full frame, in time order; empty if the recording is shorter
+/// than one frame.
+pub fn analyze_signal(signal: &[f64], sample_rate: f64, config: &BatchConfig) -> Vec<TrackFrame> {
+    AnalysisConfig::from(*config).analyze(signal, sample_rate)
+}
+
+/// Like [`analyze_signal`], with the checks of [`AnalysisConfig::try_analyze`].
+pub fn try_analyze_signal(
+    signal: &[f64],
+    sample_rate: f64,
+    config: &BatchConfig,
+) -> Result<Vec<TrackFrame>> {
+    AnalysisConfig::from(*config).try_analyze(signal, sample_rate)
+}
+
+/// Analyzes a recording frame by frame with an existing analyzer.
+///
+/// # Arguments
+///
+/// * `signal`    - The recording, at `analyzer.sample_rate()`.
+/// * `analyzer`  - The per-frame analysis.
+/// * `frame_len` - Samples per frame.
+/// * `hop`       - Samples between the starts of consecutive frames.
+///
+/// # Returns
+///
+/// One [`TrackFrame`] per full frame, in time order.
+#[tracing::instrument(level = "debug", skip_all, fields(len = signal.len(), frame_len, hop))]
+pub fn analyze_frames(
+    signal: &[f64],
+    analyzer: &Analyzer,
+    frame_len: usize,
+    hop: usize,
+) -> Vec<TrackFrame> {
+    map_frames(signal, frame_len, hop, |frame, start, _| {
+        TrackFrame::analyze(analyzer, frame, start)
+    })
+}
+
+/// Like [`analyze_frames`], but only analyzes the frames `vad` finds active; the others
+/// are marked [`TrackFrame::silent`].
+///
+/// `vad` adapts to the frames it sees, so it classifies every frame in time order
+/// first; only the analysis of the active frames runs in parallel.
+pub fn analyze_frames_gated(
+    signal: &[f64],
+    analyzer: &Analyzer,
+    frame_len: usize,
+    hop: usize,
+    vad: &mut Vad,
+) -> Vec<TrackFrame> {
+    let active: Vec<bool> = frame_starts(signal.len(), frame_len, hop)
+        .map(|start| vad.is_active(&signal[start..start + frame_len.max(1)]))
+        .collect();
+    map_frames(signal, frame_len, hop, |frame, start, index| {
+        if active[index] {
+            TrackFrame::analyze(analyzer, frame, start)
+        } else {
+            TrackFrame::silent(frame, start, analyzer.sample_rate())
+        }
+    })
+}
+
+// ------------------
+// Helpers
+// ------------------
+
+/// Start indices of the full frames of a signal of `len` samples, in time order.
+fn frame_starts(len: usize, frame_len: usize, hop: usize) -> impl Iterator<Item = usize> {
+    let frame_len = frame_len.max(1);
+    (0..(len + 1).saturating_sub(frame_len)).step_by(hop.max(1))
+}
+
+/// Calls `f` with every full frame, its start index and its position, and collects the
+/// results in time order; in parallel with the `rayon` feature.
+fn map_frames(
+    signal: &[f64],
+    frame_len: usize,
+    hop: usize,
+    f: impl Fn(&[f64], u64, usize) -> TrackFrame + Sync + Send,
+) -> Vec<TrackFrame> {
+    let frame_len = frame_len.max(1);
+    let starts: Vec<usize> = frame_starts(signal.len(), frame_len, hop).collect();
+    let frame = |(index, &start): (usize, &usize)| {
+        f(&signal[start..start + frame_len], start as u64, index)
+    };
+    #[cfg(feature = "rayon")]
+    let frames = {
+        use rayon::prelude::*;
+        starts.par_iter().enumerate().map(frame).collect()
+    };
+    #[cfg(not(feature = "rayon"))]
+    let frames = starts.iter().enumerate().map(frame).collect();
+    frames
+}
+
+// ------------------
+// Tests
+// ------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::smoothing::{smooth_frames, FormantSmoothing};
+    use crate::test_signals::vowel;
+
+    #[test]
+    fn tracks_a_vowel_followed_by_silence() {
+        let fs = 16000.0;
+        // 0.3 s of a vowel at 150 Hz, then 0.2 s silence.
+        let mut signal = vowel(fs, 150.0, 8000);
+        signal[4800..].fill(0.0);
+
+        let frames = analyze_signal(&signal, fs, &BatchConfig::default());
+        // (8000 - 400) / 160 + 1 frames, centred 12.5 ms after each hop.
+        assert_eq!(frames.len(), 48);
+        assert!((frames[0].time - 0.0125).abs() < 1e-12);
+        assert!((frames[10].time - 0.1125).abs() < 1e-12);
+
+        let voiced = &frames[5];
+        assert!((voiced.pitch - 150.0).abs() < 5.0, "{}", voiced.pitch);
+        let f1 = voiced
+            .formants
+            .iter()
+            .position(|f| (f - 700.0).abs() < 50.0);
+        assert!(voiced.bandwidths[f1.unwrap()] < 200.0);
+        assert!(voiced.voiced && voiced.energy > 0.0);
+        assert_eq!(frames[47].pitch, -1.0);
+        assert!(!frames[47].voiced && frames[47].energy == 0.0);
+
+        assert!(analyze_signal(&signal[..100], fs, &BatchConfig::default()).is_empty());
+        assert_eq!(
+            try_analyze_signal(&signal, fs, &BatchConfig::default()),
+            Ok(frames.clone())
+        );
+        assert_eq!(
+            try_analyze_signal(&signal[..100], fs, &BatchConfig::default()),
+            Err(crate::error::EzFormantError::InputTooShort {
+                len: 100,
+                required: 400
+            })
+        );
+
+        // With a VAD, the trailing silence is marked instead of analyzed.
+        let config = BatchConfig {
+            vad: Some(VadConfig::default()),
+            ..BatchConfig::default()
+        };
+        let gated = analyze_signal(&signal, fs, &config);
+        assert_eq!(gated.len(), 48);
+        assert!(!gated[5].silent && gated[5].formants == voiced.formants);
+        assert!(gated[47].silent && gated[47].formants.is_empty());
+
+        // Frame by frame in order, as the parallel version must reproduce, then smoothed
+        // as the accurate tier asks.
+        let analyzer = BatchConfig::default().analyzer(fs);
+        let mut vad = Vad::for_recording(VadConfig::default(), &signal, 400, 160);
+        let mut sequential: Vec<TrackFrame> = (0..48)
+            .map(|i| {
+                let start = 160 * i;
+                let frame = &signal[start..start + 400];
+                TrackFrame::analyze_gated(&analyzer, frame, start as u64, &mut vad)
+            })
+            .collect();
+        assert!(analyzer.quality().smoothing);
+        smooth_frames(&mut sequential, &FormantSmoothing::default());
+        assert_eq!(gated, sequential);
+    }
+}
@@ -0,0 +1,257 @@
+//! Command-line formant tracker.
+//!
+//! Analyzes WAV files frame by frame and writes time, f0, F1-F4 and B1-B4 as CSV or
+//! JSON. Build with `cargo build --release --features cli`.
+
+use ezformant::batch::{BatchConfig, TrackFrame};
+use ezformant::io::analyze_wav;
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::process::ExitCode;
+
+/// Formants and bandwidths written per frame.
+const NUM_FORMANTS: usize = 4;
+
+const USAGE: &str = "\
+Usage: ezformant-cli [OPTIONS] <FILE.wav>...
+
+Options:
+  -f, --format <csv|json>   Output format [default: csv]
+  -o, --output <PATH>       Write to PATH instead of stdout
+      --order <N>           LPC order [default: 10]
+      --max-formant <HZ>    Formant ceiling [default: 5500]
+      --window <SECONDS>    Frame length [default: 0.025]
+      --step <SECONDS>      Time step [default: 0.01]
+      --quality <TIER>      fast, balanced or accurate [default: accurate]
+  -h, --help                Print this help";
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Format {
+    Csv,
+    Json,
+}
+
+#[derive(Debug, PartialEq)]
+struct Options {
+    format: Format,
+    output: Option<String>,
+    config: BatchConfig,
+    files: Vec<String>,
+}
+
+fn main() -> ExitCode {
+    let options = match parse_args(std::env::args().skip(1)) {
+        Ok(Some(options)) => options,
+        Ok(None) => {
+            println!("{}", USAGE);
+            return ExitCode::SUCCESS;
+        }
+        Err(message) => {
+            eprintln!("error: {}\n\n{}", message, USAGE);
+            return ExitCode::from(2);
+        }
+    };
+
+    let mut tracks = Vec::with_capacity(options.files.len());
+    for path in &options.files {
+        match analyze_wav(path, &options.config) {
+            Ok(frames) => tracks.push((path.as_str(), frames)),
+            Err(err) => {
+                eprintln!("error: {}: {}", path, err);
+                return ExitCode::FAILURE;
+            }
+        }
+    }
+
+    let result = match &options.output {
+        Some(path) => File::create(path)
+            .and_then(|file| write_tracks(BufWriter::new(file), options.format, &tracks)),
+        None => write_tracks(io::stdout().lock(), options.format, &tracks),
+    };
+    match result {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(err) => {
+            eprintln!("error: {}", err);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+/// Parses the command line; `Ok(None)` means help was requested.
+fn parse_args(mut args: impl Iterator<Item = String>) -> Result<Option<Options>, String> {
+    let mut options = Options {
+        format: Format::Csv,
+        output: None,
+        config: BatchConfig::default(),
+        files: Vec::new(),
+    };
+    while let Some(arg) = args.next() {
+        let mut value = |name: &str| args.next().ok_or_else(|| format!("{} needs a value", name));
+        match arg.as_str() {
+            "-h" | "--help" => return Ok(None),
+            "-f" | "--format" => {
+                options.format = match value(&arg)?.as_str() {
+                    "csv" => Format::Csv,
+                    "json" => Format::Json,
+                    other => return Err(format!("unknown format: {}", other)),
+                }
+            }
+            "-o" | "--output" => options.output = Some(value(&arg)?),
+            "--order" => options.config.lpc_order = parse_number(&arg, &value(&arg)?)?,
+            "--max-formant" => options.config.max_formant = parse_number(&arg, &value(&arg)?)?,
+            "--window" => options.config.frame_duration = parse_number(&arg, &value(&arg)?)?,
+            "--step" => options.config.time_step = parse_number(&arg, &value(&arg)?)?,
+            "--quality" => options.config.quality = value(&arg)?.parse()?,
+            flag if flag.starts_with('-') && flag.len() > 1 => {
+                return Err(format!("unknown option: {}", flag))
+            }
+            _ => options.files.push(arg),
+        }
+    }
+    if options.files.is_empty() {
+        return Err("no input files".to_string());
+    }
+    Ok(Some(options))
+}
+
+fn parse_number<T: std::str::FromStr>(name: &str, value: &str) -> Result<T, String> {
+    value
+        .parse()
+        .map_err(|_| format!("invalid value for {}: {}", name, value))
+}
+
+fn write_tracks<W: Write>(
+    mut out: W,
+    format: Format,
+    tracks: &[(&str, Vec<TrackFrame>)],
+) -> io::Result<()> {
+    match format {
+        Format::Csv => write_csv(&mut out, tracks)?,
+        Format::Json => write_json(&mut out, tracks)?,
+    }
+    out.flush()
+}
+
+/// One row per frame; unvoiced f0 and missing formants are left empty.
+fn write_csv<W: Write>(out: &mut W, tracks: &[(&str, Vec<TrackFrame>)]) -> io::Result<()> {
+    write!(out, "file,time,f0")?;
+    for i in 1..=NUM_FORMANTS {
+        write!(out, ",F{}", i)?;
+    }
+    for i in 1..=NUM_FORMANTS {
+        write!(out, ",B{}", i)?;
+    }
+    writeln!(out)?;
+
+    for (file, frames) in tracks {
+        for frame in frames {
+            write!(
+                out,
+                "{},{:.4},{}",
+                csv_field(file),
+                frame.time,
+                csv_number(pitch(frame))
+            )?;
+            for values in [&frame.formants, &frame.bandwidths] {
+                for i in 0..NUM_FORMANTS {
+                    write!(out, ",{}", csv_number(values.get(i).copied()))?;
+                }
+            }
+            writeln!(out)?;
+        }
+    }
+    Ok(())
+}
+
+/// `[{"file": ..., "frames": [{"time", "f0", "formants", "bandwidths"}, ...]}, ...]`,
+/// with `null` for unvoiced f0.
+fn write_json<W: Write>(out: &mut W, tracks: &[(&str, Vec<TrackFrame>)]) -> io::Result<()> {
+    writeln!(out, "[")?;
+    for (t, (file, frames)) in tracks.iter().enumerate() {
+        writeln!(out, "  {{\"file\": {:?}, \"frames\": [", file)?;
+        for (i, frame) in frames.iter().enumerate() {
+            let f0 = pitch(frame).map_or("null".to_string(), |f| format!("{:.2}", f));
+            let list = |values: &[f64]| {
+                let values: Vec<String> = values
+                    .iter()
+                    .take(NUM_FORMANTS)
+                    .map(|v| format!("{:.2}", v))
+                    .collect();
+                values.join(", ")
+            };
+            write!(
+                out,
+                "    {{\"time\": {:.4}, \"f0\": {}, \"formants\": [{}], \"bandwidths\": [{}]}}",
+                frame.time,
+                f0,
+                list(&frame.formants),
+                list(&frame.bandwidths)
+            )?;
+            writeln!(out, "{}", if i + 1 < frames.len() { "," } else { "" })?;
+        }
+        let comma = if t + 1 < tracks.len() { "," } else { "" };
+        writeln!(out, "  ]}}{}", comma)?;
+    }
+    writeln!(out, "]")
+}
+
+fn pitch(frame: &TrackFrame) -> Option<f64> {
+    frame.voiced.then_some(frame.pitch)
+}
+
+fn csv_number(value: Option<f64>) -> String {
+    value.map_or(String::new(), |v| format!("{:.2}", v))
+}
+
+fn csv_field(field: &str) -> String {
+    if field.contains([',', '"', '\n']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+// ------------------
+// Tests
+// ------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(line: &str) -> impl Iterator<Item = String> + '_ {
+        line.split_whitespace().map(String::from)
+    }
+
+    #[test]
+    fn parses_options_and_writes_csv_rows() {
+        let options = parse_args(args("-f json --max-formant 5000 --step 0.005 a.wav b.wav"))
+            .unwrap()
+            .unwrap();
+        assert_eq!(options.format, Format::Json);
+        assert_eq!(options.config.max_formant, 5000.0);
+        assert_eq!(options.config.time_step, 0.005);
+        assert_eq!(options.files, ["a.wav", "b.wav"]);
+        assert_eq!(parse_args(args("--help")), Ok(None));
+        assert!(parse_args(args("--order ten a.wav")).is_err());
+        assert!(parse_args(args("--format csv")).is_err());
+
+        let frame = TrackFrame {
+            time: 0.0125,
+            formants: vec![700.0, 1200.0, 2600.0],
+            bandwidths: vec![80.0, 90.0, 120.0],
+            pitch: -1.0,
+            energy: 0.1,
+            zcr: 0.2,
+            voiced: false,
+            silent: false,
+        };
+        let mut csv = Vec::new();
+        write_csv(&mut csv, &[("a,b.wav", vec![frame])]).unwrap();
+        assert_eq!(
+            String::from_utf8(csv).unwrap(),
+            "file,time,f0,F1,F2,F3,F4,B1,B2,B3,B4\n\
+             \"a,b.wav\",0.0125,,700.00,1200.00,2600.00,,80.00,90.00,120.00,\n"
+        );
+    }
+}
@@ -0,0 +1,218 @@
+//! Live microphone analysis (native builds with the `capture` feature).
+//!
+//! [`Capture`] opens an input device through cpal, mixes it down to mono and feeds it
+//! to a [`StreamingAnalyzer`], the same path the web demo's AudioWorklet takes.
+//!
+//! ```no_run
+//! use ezformant::batch::BatchConfig;
+//! use ezformant::capture::Capture;
+//!
+//! let (tx, rx) = std::sync::mpsc::channel();
+//! let capture = Capture::start(&BatchConfig::default(), move |frame| {
+//!     let _ = tx.send(frame);
+//! })?;
+//! for frame in rx.iter().take(500) {
+//!     println!("{:.2} s: {:?}", frame.time, frame.formants);
+//! }
+//! drop(capture);
+//! # Ok::<(), ezformant::capture::CaptureError>(())
+//! ```
+
+use crate::batch::{BatchConfig, TrackFrame};
+use crate::streaming::StreamingAnalyzer;
+use cpal::traits::{DeviceTrait, HostTrait, StreamTrait};
+use cpal::{FromSample, SizedSample};
+use std::fmt;
+
+/// Errors from opening or starting an input stream.
+#[derive(Debug)]
+pub enum CaptureError {
+    /// The host has no default input device.
+    NoInputDevice,
+    /// The device's input configuration could not be queried.
+    Config(cpal::DefaultStreamConfigError),
+    /// The stream could not be built, e.g. for an unsupported sample format.
+    Build(cpal::BuildStreamError),
+    /// The stream could not be started.
+    Play(cpal::PlayStreamError),
+}
+
+impl fmt::Display for CaptureError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CaptureError::NoInputDevice => write!(f, "no input device available"),
+            CaptureError::Config(err) => write!(f, "cannot query the input config: {}", err),
+            CaptureError::Build(err) => write!(f, "cannot open the input stream: {}", err),
+            CaptureError::Play(err) => write!(f, "cannot start the input stream: {}", err),
+        }
+    }
+}
+
+impl std::error::Error for CaptureError {}
+
+/// A running input stream. Capture stops when this is dropped.
+pub struct Capture {
+    // Kept alive for its side effect; cpal streams stop on drop.
+    _stream: cpal::Stream,
+    sample_rate: f64,
+    channels: usize,
+}
+
+impl Capture {
+    /// Starts analyzing the host's default input device.
+    ///
+    /// # Arguments
+    ///
+    /// * `config`   - Analysis and framing parameters.
+    /// * `callback` - Called with each analyzed frame. It runs on the audio thread, so
+    ///   it should return quickly (e.g. by sending the frame over a channel).
+    pub fn start<F>(config: &BatchConfig, callback: F) -> Result<Self, CaptureError>
+    where
+        F: FnMut(TrackFrame) + Send + 'static,
+    {
+        let device = cpal::default_host()
+            .default_input_device()
+            .ok_or(CaptureError::NoInputDevice)?;
+        Self::start_with_device(&device, config, callback)
+    }
+
+    /// Starts analyzing a specific input device in its default configuration.
+    ///
+    /// # Arguments
+    ///
+    /// * `device`   - The input device.
+    /// * `config`   - Analysis and framing parameters.
+    /// * `callback` - Called on the audio thread with each analyzed frame.
+    pub fn start_with_device<F>(
+        device: &cpal::Device,
+        config: &BatchConfig,
+        callback: F,
+    ) -> Result<Self, CaptureError>
+    where
+        F: FnMut(TrackFrame) + Send + 'static,
+    {
+        let supported = device
+            .default_input_config()
+            .map_err(CaptureError::Config)?;
+        let stream_config = supported.config();
+        let sample_rate = stream_config.sample_rate.0 as f64;
+        let channels = stream_config.channels.max(1) as usize;
+        let mut streaming = StreamingAnalyzer::new(
+            config.analyzer(sample_rate),
+            (config.frame_duration * sample_rate).round() as usize,
+            (config.time_step * sample_rate).round() as usize,
+        );
+        streaming.set_vad(config.vad);
+        let sink = MonoSink {
+            streaming,
+            channels,
+            mono: Vec::new(),
+            callback,
+        };
+
+        let stream = match supported.sample_format() {
+            cpal::SampleFormat::F32 => build::<f32, F>(device, &stream_config, sink),
+            cpal::SampleFormat::I16 => build::<i16, F>(device, &stream_config, sink),
+            cpal::SampleFormat::U16 => build::<u16, F>(device, &stream_config, sink),
+            cpal::SampleFormat::I32 => build::<i32, F>(device, &stream_config, sink),
+            _ => Err(cpal::BuildStreamError::StreamConfigNotSupported),
+        }
+        .map_err(CaptureError::Build)?;
+        stream.play().map_err(CaptureError::Play)?;
+
+        Ok(Self {
+            _stream: stream,
+            sample_rate,
+            channels,
+        })
+    }
+
+    /// The device's sampling rate in Hz (frame times are relative to stream start).
+    pub fn sample_rate(&self) -> f64 {
+        self.sample_rate
+    }
+
+    /// The number of channels mixed down to mono.
+    pub fn channels(&self) -> usize {
+        self.channels
+    }
+}
+
+// ------------------
+// Helpers
+// ------------------
+
+/// State moved into the audio callback.
+struct MonoSink<F> {
+    streaming: StreamingAnalyzer,
+    channels: usize,
+    mono: Vec<f32>,
+    callback: F,
+}
+
+impl<F: FnMut(TrackFrame)> MonoSink<F> {
+    /// Mixes interleaved samples to mono and forwards every completed frame.
+    fn push<T: SizedSample>(&mut self, data: &[T])
+    where
+        f32: FromSample<T>,
+    {
+        self.mono.clear();
+        self.mono
+            .extend(data.chunks_exact(self.channels).map(|frame| {
+                frame.iter().map(|&s| s.to_sample::<f32>()).sum::<f32>() / self.channels as f32
+            }));
+        for frame in self.streaming.push(&self.mono) {
+            (self.callback)(frame);
+        }
+    }
+}
+
+fn build<T, F>(
+    device: &cpal::Device,
+    config: &cpal::StreamConfig,
+    mut sink: MonoSink<F>,
+) -> Result<cpal::Stream, cpal::BuildStreamError>
+where
+    T: SizedSample,
+    f32: FromSample<T>,
+    F: FnMut(TrackFrame) + Send + 'static,
+{
+    device.build_input_stream(
+        config,
+        move |data: &[T], _: &cpal::InputCallbackInfo| sink.push(data),
+        |error| tracing::warn!(%error, "input stream error"),
+        None,
+    )
+}
+
+// ------------------
+// Tests
+// ------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analyzer::Analyzer;
+
+    #[test]
+    fn sink_mixes_interleaved_input_down_to_mono() {
+        let mut frames = Vec::new();
+        let mut sink = MonoSink {
+            streaming: StreamingAnalyzer::new(Analyzer::new(8, 8000.0), 200, 80),
+            channels: 2,
+            mono: Vec::new(),
+            callback: |frame: TrackFrame| frames.push(frame),
+        };
+        let stereo: Vec<i16> = (0..1000)
+            .flat_map(|n| {
+                let x = ((n as f64 * 0.2).sin() * 8000.0) as i16;
+                [x, -x]
+            })
+            .collect();
+        sink.push(&stereo);
+        assert!(sink.mono.iter().all(|&x| x.abs() < 1e-3));
+        drop(sink);
+        assert_eq!(frames.len(), 11);
+        assert_eq!(frames[1].time, (80.0 + 100.0) / 8000.0);
+    }
+}
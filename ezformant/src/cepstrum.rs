@@ -0,0 +1,109 @@
+//! Real cepstrum and cepstral pitch detection.
+//!
+//! The real cepstrum is the inverse FFT of the log magnitude spectrum. A periodic
+//! source shows up as a peak at the quefrency of its period, well separated from the
+//! slowly varying vocal tract envelope at low quefrencies.
+
+use crate::pitch::parabolic_interpolation;
+use num_complex::Complex;
+
+/// Floor applied to magnitudes before taking the log, so silent bins stay finite.
+const MAGNITUDE_FLOOR: f64 = 1e-12;
+
+/// Computes the real cepstrum of a frame.
+///
+/// # Arguments
+///
+/// * `frame` - The input frame (window it first to limit leakage).
+///
+/// # Returns
+///
+/// The cepstrum `c[q] = IFFT(ln |FFT(frame)|)[q]`, with one value per quefrency
+/// (in samples) `0..frame.len()`.
+pub fn real_cepstrum(frame: &[f64]) -> Vec<f64> {
+    let n = frame.len();
+    if n == 0 {
+        return Vec::new();
+    }
+
+    let mut buffer: Vec<Complex<f64>> = frame.iter().map(|&x| Complex::new(x, 0.0)).collect();
+    crate::fft::forward::<f64>(n).process(&mut buffer);
+    for x in buffer.iter_mut() {
+        *x = Complex::new(x.norm().max(MAGNITUDE_FLOOR).ln(), 0.0);
+    }
+    crate::fft::inverse::<f64>(n).process(&mut buffer);
+
+    let scale = 1.0 / n as f64;
+    buffer.iter().map(|x| x.re * scale).collect()
+}
+
+/// Estimates the fundamental frequency of a frame from the highest cepstral peak.
+///
+/// The frame is Hamming-windowed before the cepstrum is taken.
+///
+/// # Arguments
+///
+/// * `frame`       - The input frame. Should cover at least two periods of `fmin`.
+/// * `sample_rate` - The sampling rate of the frame.
+/// * `fmin`        - The lowest f0 to consider, in Hz.
+/// * `fmax`        - The highest f0 to consider, in Hz.
+///
+/// # Returns
+///
+/// The fundamental frequency in Hz, or `-1.0` if the quefrency range is empty or the
+/// cepstrum has no positive peak in it.
+pub fn pitch_detection_cepstrum(frame: &[f64], sample_rate: f64, fmin: f64, fmax: f64) -> f64 {
+    let mut windowed = frame.to_vec();
+    crate::apply_hamming_window_in_place(&mut windowed);
+    let cepstrum = real_cepstrum(&windowed);
+
+    let q_min = ((sample_rate / fmax).floor() as usize).max(1);
+    let q_max = ((sample_rate / fmin).ceil() as usize).min(cepstrum.len() / 2);
+    if q_min >= q_max {
+        return -1.0;
+    }
+
+    let peak = (q_min..=q_max)
+        .max_by(|&a, &b| cepstrum[a].total_cmp(&cepstrum[b]))
+        .unwrap();
+    if cepstrum[peak] <= 0.0 {
+        return -1.0;
+    }
+    sample_rate / parabolic_interpolation(&cepstrum, peak)
+}
+
+// ------------------
+// Tests
+// ------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cepstrum_of_impulse_is_zero() {
+        let mut impulse = vec![0.0; 64];
+        impulse[0] = 1.0;
+        assert!(real_cepstrum(&impulse).iter().all(|c| c.abs() < 1e-12));
+    }
+
+    #[test]
+    fn cepstral_pitch_of_pulse_train() {
+        // 160 Hz pulse train through a single resonance at 600 Hz.
+        let fs = 16000.0;
+        let period = fs / 160.0;
+        let (r, theta) = (0.97f64, 2.0 * std::f64::consts::PI * 600.0 / fs);
+        let (mut y1, mut y2) = (0.0, 0.0);
+        let frame: Vec<f64> = (0..2048)
+            .map(|n| {
+                let x = if (n as f64 % period) < 1.0 { 1.0 } else { 0.0 };
+                let y = x + 2.0 * r * theta.cos() * y1 - r * r * y2;
+                (y2, y1) = (y1, y);
+                y
+            })
+            .collect();
+
+        let f0 = pitch_detection_cepstrum(&frame, fs, 60.0, 400.0);
+        assert!((f0 - 160.0).abs() < 2.0, "f0 = {}", f0);
+    }
+}
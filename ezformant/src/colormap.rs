@@ -0,0 +1,175 @@
+//! Colormaps for spectrogram rendering.
+//!
+//! Maps levels in dB to opaque RGBA pixels through a clamped dB range, so displays can
+//! write spectra straight into `ImageData`-style byte rows. The perceptually uniform maps
+//! are matplotlib's, linearly interpolated between ten stops.
+
+use std::str::FromStr;
+
+/// A level-to-color mapping, from quiet (`0`) to loud (`1`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Colormap {
+    /// Black to white.
+    #[default]
+    Grayscale,
+    /// Dark purple through green to yellow.
+    Viridis,
+    /// Black through purple and orange to pale yellow.
+    Magma,
+    /// Black through purple and red to bright yellow.
+    Inferno,
+}
+
+impl FromStr for Colormap {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "grayscale" | "greyscale" | "gray" | "grey" => Ok(Colormap::Grayscale),
+            "viridis" => Ok(Colormap::Viridis),
+            "magma" => Ok(Colormap::Magma),
+            "inferno" => Ok(Colormap::Inferno),
+            other => Err(format!("unknown colormap: {}", other)),
+        }
+    }
+}
+
+impl Colormap {
+    /// The color of `level`, clamped to `[0, 1]` (NaN maps to 0).
+    pub fn rgba(self, level: f32) -> [u8; 4] {
+        let level = if level.is_nan() {
+            0.0
+        } else {
+            level.clamp(0.0, 1.0)
+        };
+        let stops = match self {
+            Colormap::Grayscale => {
+                let value = (level * 255.0).round() as u8;
+                return [value, value, value, 255];
+            }
+            Colormap::Viridis => &VIRIDIS,
+            Colormap::Magma => &MAGMA,
+            Colormap::Inferno => &INFERNO,
+        };
+        let position = level * (stops.len() - 1) as f32;
+        let i = (position as usize).min(stops.len() - 2);
+        let frac = position - i as f32;
+        let mix = |c: usize| {
+            let (a, b) = (stops[i][c] as f32, stops[i + 1][c] as f32);
+            (a + (b - a) * frac).round() as u8
+        };
+        [mix(0), mix(1), mix(2), 255]
+    }
+
+    /// The color of a value in dB shown over `min_db..=max_db`.
+    pub fn rgba_db(self, db: f32, min_db: f32, max_db: f32) -> [u8; 4] {
+        self.rgba(db_level(db, min_db, max_db))
+    }
+}
+
+/// Colors a row of dB values.
+///
+/// # Arguments
+///
+/// * `db`       - The values, in dB.
+/// * `colormap` - The colormap.
+/// * `min_db`   - Level drawn with the lowest color; quieter values are clamped.
+/// * `max_db`   - Level drawn with the highest color; louder values are clamped.
+/// * `out`      - RGBA bytes, 4 per value; extra values or bytes are left alone.
+pub fn db_row_to_rgba(db: &[f32], colormap: Colormap, min_db: f32, max_db: f32, out: &mut [u8]) {
+    for (&value, pixel) in db.iter().zip(out.chunks_exact_mut(4)) {
+        pixel.copy_from_slice(&colormap.rgba_db(value, min_db, max_db));
+    }
+}
+
+/// Colors a row of linear magnitudes (e.g. from [`crate::fourier_trans`]), converted to
+/// dB with `20 log10`. Arguments are as in [`db_row_to_rgba`].
+pub fn magnitude_row_to_rgba(
+    magnitudes: &[f32],
+    colormap: Colormap,
+    min_db: f32,
+    max_db: f32,
+    out: &mut [u8],
+) {
+    for (&magnitude, pixel) in magnitudes.iter().zip(out.chunks_exact_mut(4)) {
+        pixel.copy_from_slice(&colormap.rgba_db(20.0 * magnitude.log10(), min_db, max_db));
+    }
+}
+
+// ------------------
+// Helpers
+// ------------------
+
+/// Position of `db` in `min_db..=max_db`; a zero-width range is a hard threshold.
+fn db_level(db: f32, min_db: f32, max_db: f32) -> f32 {
+    let span = max_db - min_db;
+    if span > 0.0 {
+        (db - min_db) / span
+    } else {
+        f32::from(db >= max_db)
+    }
+}
+
+const VIRIDIS: [[u8; 3]; 10] = [
+    [0x44, 0x01, 0x54],
+    [0x48, 0x28, 0x78],
+    [0x3e, 0x4a, 0x89],
+    [0x31, 0x68, 0x8e],
+    [0x26, 0x82, 0x8e],
+    [0x1f, 0x9e, 0x89],
+    [0x35, 0xb7, 0x79],
+    [0x6d, 0xcd, 0x59],
+    [0xb4, 0xde, 0x2c],
+    [0xfd, 0xe7, 0x25],
+];
+
+const MAGMA: [[u8; 3]; 10] = [
+    [0x00, 0x00, 0x04],
+    [0x18, 0x0f, 0x3e],
+    [0x45, 0x10, 0x77],
+    [0x72, 0x1f, 0x81],
+    [0x9f, 0x2f, 0x7f],
+    [0xcd, 0x40, 0x71],
+    [0xf1, 0x60, 0x5d],
+    [0xfd, 0x95, 0x67],
+    [0xfe, 0xc9, 0x8d],
+    [0xfc, 0xfd, 0xbf],
+];
+
+const INFERNO: [[u8; 3]; 10] = [
+    [0x00, 0x00, 0x04],
+    [0x1b, 0x0c, 0x42],
+    [0x4b, 0x0c, 0x6b],
+    [0x78, 0x1c, 0x6d],
+    [0xa5, 0x2c, 0x60],
+    [0xcf, 0x44, 0x46],
+    [0xed, 0x69, 0x25],
+    [0xfb, 0x9a, 0x06],
+    [0xf7, 0xd0, 0x3c],
+    [0xfc, 0xff, 0xa4],
+];
+
+// ------------------
+// Tests
+// ------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn maps_clamped_db_range_onto_colormap_stops() {
+        let viridis: Colormap = "Viridis".parse().unwrap();
+        assert_eq!(viridis.rgba(0.0), [0x44, 0x01, 0x54, 255]);
+        assert_eq!(viridis.rgba(1.0), [0xfd, 0xe7, 0x25, 255]);
+        assert_eq!(viridis.rgba_db(-200.0, -100.0, 0.0), viridis.rgba(0.0));
+        assert_eq!(viridis.rgba_db(20.0, -100.0, 0.0), viridis.rgba(1.0));
+        // Halfway between the 5th and 6th stops.
+        assert_eq!(Colormap::Magma.rgba(0.5), [0xb6, 0x38, 0x78, 255]);
+        assert!("jet".parse::<Colormap>().is_err());
+
+        let mut row = [0u8; 12];
+        magnitude_row_to_rgba(&[1.0, 0.1, 0.0], Colormap::Grayscale, -40.0, 0.0, &mut row);
+        assert_eq!(row, [255, 255, 255, 255, 128, 128, 128, 255, 0, 0, 0, 255]);
+    }
+}
@@ -0,0 +1,355 @@
+//! One configuration for every high-level entry point.
+//!
+//! [`AnalysisConfig`] gathers the parameters users tune most (window, pre-emphasis,
+//! LPC order, formant ceiling, number of formants, pitch range, framing and input
+//! repair) in one value that builds an [`Analyzer`], a [`StreamingAnalyzer`] or
+//! analyzes a whole recording. With the `serde` feature it round-trips through JSON
+//! ([`crate::json::ToJson::to_json`], [`crate::json::from_json`]); fields missing from
+//! the JSON keep their defaults, so a settings file only needs the values it changes.
+
+use crate::analyzer::Analyzer;
+use crate::batch::{analyze_frames, analyze_frames_gated, BatchConfig, TrackFrame};
+use crate::error::{check_finite, check_positive, EzFormantError, Result};
+use crate::quality::Quality;
+use crate::sanitize::sanitize_in_place;
+use crate::smoothing::{smooth_frames, FormantSmoothing};
+use crate::streaming::StreamingAnalyzer;
+use crate::vad::{Vad, VadConfig};
+use crate::WindowFunction;
+
+/// Analysis and framing parameters.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
+pub struct AnalysisConfig {
+    /// Algorithm choices.
+    pub quality: Quality,
+    /// Window applied before LPC analysis; `None` keeps the one of `quality`.
+    pub window: Option<WindowFunction>,
+    /// Pre-emphasis coefficient; `None` keeps the one of `quality`.
+    pub pre_emphasis: Option<f64>,
+    /// The LPC order; `None` picks two poles per formant (see
+    /// [`AnalysisConfig::lpc_order`]).
+    pub lpc_order: Option<usize>,
+    /// Formant ceiling in Hz (see [`Analyzer::with_max_formant`]).
+    pub max_formant: f64,
+    /// Formants reported per frame.
+    pub num_formants: usize,
+    /// Lowest and highest f0 in Hz (see [`Analyzer::with_pitch_range`]); `None` keeps
+    /// the trackers' defaults.
+    pub pitch_range: Option<(f64, f64)>,
+    /// Analysis frame length, in seconds.
+    pub frame_duration: f64,
+    /// Hop between frames, in seconds.
+    pub time_step: f64,
+    /// Voice activity detection; frames it rejects are marked silent and not analyzed.
+    /// `None` analyzes every frame.
+    pub vad: Option<VadConfig>,
+    /// Magnitude limit of the input repair pass (see [`crate::sanitize`]); `None`
+    /// analyzes samples as given.
+    pub sanitize: Option<f64>,
+}
+
+impl Default for AnalysisConfig {
+    fn default() -> Self {
+        Self {
+            quality: Quality::default(),
+            window: None,
+            pre_emphasis: None,
+            lpc_order: None,
+            max_formant: 5500.0,
+            num_formants: 5,
+            pitch_range: None,
+            frame_duration: 0.025,
+            time_step: 0.01,
+            vad: None,
+            sanitize: None,
+        }
+    }
+}
+
+impl From<BatchConfig> for AnalysisConfig {
+    fn from(config: BatchConfig) -> Self {
+        Self {
+            quality: config.quality,
+            lpc_order: Some(config.lpc_order),
+            max_formant: config.max_formant,
+            // All the formants the order can resolve.
+            num_formants: config.lpc_order.div_ceil(2),
+            frame_duration: config.frame_duration,
+            time_step: config.time_step,
+            vad: config.vad,
+            ..Self::default()
+        }
+    }
+}
+
+impl AnalysisConfig {
+    /// The default configuration: balanced quality, five formants below 5500 Hz in
+    /// 25 ms frames every 10 ms.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Selects the algorithm tier.
+    pub fn with_quality(mut self, quality: Quality) -> Self {
+        self.quality = quality;
+        self
+    }
+
+    /// Overrides the window of the quality tier.
+    pub fn with_window(mut self, window: WindowFunction) -> Self {
+        self.window = Some(window);
+        self
+    }
+
+    /// Overrides the pre-emphasis coefficient of the quality tier.
+    pub fn with_pre_emphasis(mut self, alpha: f64) -> Self {
+        self.pre_emphasis = Some(alpha);
+        self
+    }
+
+    /// Fixes the LPC order instead of deriving it from the number of formants.
+    pub fn with_lpc_order(mut self, order: usize) -> Self {
+        self.lpc_order = Some(order);
+        self
+    }
+
+    /// Sets the formant ceiling in Hz.
+    pub fn with_max_formant(mut self, max_formant_hz: f64) -> Self {
+        self.max_formant = max_formant_hz;
+        self
+    }
+
+    /// Sets the number of formants reported per frame.
+    pub fn with_num_formants(mut self, count: usize) -> Self {
+        self.num_formants = count;
+        self
+    }
+
+    /// Limits f0 to `floor..=ceiling` Hz.
+    pub fn with_pitch_range(mut self, floor: f64, ceiling: f64) -> Self {
+        self.pitch_range = Some((floor, ceiling));
+        self
+    }
+
+    /// Sets the frame length in seconds.
+    pub fn with_frame_duration(mut self, seconds: f64) -> Self {
+        self.frame_duration = seconds;
+        self
+    }
+
+    /// Sets the hop between frames in seconds.
+    pub fn with_time_step(mut self, seconds: f64) -> Self {
+        self.time_step = seconds;
+        self
+    }
+
+    /// Gates analysis with voice activity detection.
+    pub fn with_vad(mut self, config: VadConfig) -> Self {
+        self.vad = Some(config);
+        self
+    }
+
+    /// Replaces NaN samples with silence and clamps the others to `[-limit, limit]`
+    /// before analysis.
+    pub fn with_sanitize(mut self, limit: f64) -> Self {
+        self.sanitize = Some(limit);
+        self
+    }
+
+    /// The LPC order: the fixed one, or `2 * num_formants` (Praat's rule).
+    pub fn lpc_order(&self) -> usize {
+        self.lpc_order.unwrap_or(2 * self.num_formants)
+    }
+
+    /// Samples per frame at `sample_rate`.
+    pub fn frame_len(&self, sample_rate: f64) -> usize {
+        (self.frame_duration * sample_rate).round() as usize
+    }
+
+    /// Samples between frames at `sample_rate`.
+    pub fn hop(&self, sample_rate: f64) -> usize {
+        (self.time_step * sample_rate).round() as usize
+    }
+
+    /// Checks that every parameter is usable.
+    ///
+    /// # Returns
+    ///
+    /// [`EzFormantError::InvalidParameter`] naming the first field out of range.
+    pub fn validate(&self) -> Result<()> {
+        let invalid = |name, value| Err(EzFormantError::InvalidParameter { name, value });
+        check_positive("max_formant", self.max_formant)?;
+        check_positive("frame_duration", self.frame_duration)?;
+        check_positive("time_step", self.time_step)?;
+        if self.num_formants == 0 {
+            return invalid("num_formants", 0.0);
+        }
+        if self.lpc_order() < 2 {
+            return invalid("lpc_order", self.lpc_order() as f64);
+        }
+        if let Some(alpha) = self.pre_emphasis {
+            if !(0.0..1.0).contains(&alpha) {
+                return invalid("pre_emphasis", alpha);
+            }
+        }
+        if let Some(limit) = self.sanitize {
+            check_positive("sanitize", limit)?;
+        }
+        if let Some((floor, ceiling)) = self.pitch_range {
+            check_positive("pitch_floor", floor)?;
+            if !(ceiling > floor && ceiling.is_finite()) {
+                return invalid("pitch_ceiling", ceiling);
+            }
+        }
+        Ok(())
+    }
+
+    /// The per-frame analyzer for frames sampled at `sample_rate`.
+    pub fn analyzer(&self, sample_rate: f64) -> Analyzer {
+        let mut analyzer = Analyzer::new(self.lpc_order(), sample_rate)
+            .with_quality(self.quality)
+            .with_max_formant(self.max_formant)
+            .with_num_formants(self.num_formants);
+        if let Some(window) = self.window {
+            analyzer = analyzer.with_window(window);
+        }
+        if let Some(alpha) = self.pre_emphasis {
+            analyzer = analyzer.with_pre_emphasis(alpha);
+        }
+        if let Some((floor, ceiling)) = self.pitch_range {
+            analyzer = analyzer.with_pitch_range(floor, ceiling);
+        }
+        analyzer
+    }
+
+    /// A streaming analyzer for a stream sampled at `sample_rate`.
+    pub fn streaming(&self, sample_rate: f64) -> StreamingAnalyzer {
+        let mut streaming = StreamingAnalyzer::new(
+            self.analyzer(sample_rate),
+            self.frame_len(sample_rate),
+            self.hop(sample_rate),
+        );
+        streaming.set_sanitize(self.sanitize);
+        match self.vad {
+            Some(vad) => streaming.with_vad(vad),
+            None => streaming,
+        }
+    }
+
+    /// Like [`AnalysisConfig::analyze`], but returns an error for parameters rejected by
+    /// [`AnalysisConfig::validate`], a non-positive sampling rate, or a recording that
+    /// is empty, shorter than one frame, or (without [`AnalysisConfig::sanitize`])
+    /// non-finite.
+    pub fn try_analyze(&self, signal: &[f64], sample_rate: f64) -> Result<Vec<TrackFrame>> {
+        self.validate()?;
+        check_positive("sample_rate", sample_rate)?;
+        if self.sanitize.is_none() {
+            check_finite(signal)?;
+        } else if signal.is_empty() {
+            return Err(EzFormantError::EmptyInput);
+        }
+        let required = self.frame_len(sample_rate);
+        if signal.len() < required {
+            return Err(EzFormantError::InputTooShort {
+                len: signal.len(),
+                required,
+            });
+        }
+        Ok(self.analyze(signal, sample_rate))
+    }
+
+    /// Analyzes a recording frame by frame, like [`crate::batch::analyze_signal`].
+    /// Tiers with [`QualitySettings::smoothing`](crate::quality::QualitySettings::smoothing)
+    /// smooth the formant tracks afterwards.
+    ///
+    /// # Returns
+    ///
+    /// One [`TrackFrame`] per full frame, in time order.
+    pub fn analyze(&self, signal: &[f64], sample_rate: f64) -> Vec<TrackFrame> {
+        let repaired;
+        let signal = match self.sanitize {
+            Some(limit) => {
+                let mut samples = signal.to_vec();
+                sanitize_in_place(&mut samples, limit);
+                repaired = samples;
+                &repaired
+            }
+            None => signal,
+        };
+        let (frame_len, hop) = (self.frame_len(sample_rate), self.hop(sample_rate));
+        let analyzer = self.analyzer(sample_rate);
+        let mut frames = match self.vad {
+            Some(vad) => {
+                let mut vad = Vad::for_recording(vad, signal, frame_len, hop);
+                analyze_frames_gated(signal, &analyzer, frame_len, hop, &mut vad)
+            }
+            None => analyze_frames(signal, &analyzer, frame_len, hop),
+        };
+        if analyzer.quality().smoothing {
+            smooth_frames(&mut frames, &FormantSmoothing::default());
+        }
+        frames
+    }
+}
+
+// ------------------
+// Tests
+// ------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::batch::analyze_signal;
+    use std::f64::consts::PI;
+
+    #[test]
+    fn builder_drives_every_entry_point() {
+        let fs = 16000.0;
+        let signal: Vec<f64> = (0..4000)
+            .map(|i| (2.0 * PI * 150.0 * i as f64 / fs).sin() + 0.01 * (i as f64).sin())
+            .collect();
+        let config = AnalysisConfig::new()
+            .with_quality(Quality::Fast)
+            .with_num_formants(3)
+            .with_max_formant(5000.0)
+            .with_pitch_range(100.0, 300.0);
+        assert_eq!(config.validate(), Ok(()));
+        assert_eq!(config.lpc_order(), 6);
+
+        let frames = config.analyze(&signal, fs);
+        assert_eq!(frames.len(), 1 + (4000 - 400) / 160);
+        assert!(frames.iter().all(|f| f.formants.len() <= 3));
+        let mut streaming = config.streaming(fs);
+        assert_eq!(streaming.push(&signal), frames);
+
+        // A batch configuration converts without changing the results.
+        let batch = BatchConfig::default();
+        assert_eq!(
+            AnalysisConfig::from(batch).analyze(&signal, fs),
+            analyze_signal(&signal, fs, &batch)
+        );
+
+        assert_eq!(
+            config.with_pitch_range(300.0, 100.0).validate(),
+            Err(EzFormantError::InvalidParameter {
+                name: "pitch_ceiling",
+                value: 100.0
+            })
+        );
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn round_trips_through_json() {
+        use crate::json::{from_json, ToJson};
+        let config = AnalysisConfig::new()
+            .with_window(WindowFunction::Gaussian)
+            .with_lpc_order(12);
+        assert!(config.to_json().contains(r#""window":"gaussian""#));
+        assert_eq!(from_json(&config.to_json()), Ok(config));
+        let partial: AnalysisConfig = from_json(r#"{"num_formants":4}"#).unwrap();
+        assert_eq!(partial, AnalysisConfig::new().with_num_formants(4));
+    }
+}
@@ -0,0 +1,96 @@
+use std::sync::atomic::{AtomicU64, Ordering};
+
+/// Why a frame was not analyzed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DropReason {
+    /// The caller could not keep up and skipped the frame.
+    Overload,
+    /// The frame was empty, too short, or contained NaN/Inf samples.
+    InvalidInput,
+}
+
+/// Per-instance counters of analyzed and dropped frames.
+///
+/// Uses atomics so it can be shared between threads alongside an
+/// [`Analyzer`](crate::analyzer::Analyzer). Counts are independent of any timing
+/// measurements: they say *how many* frames were lost, not how late results were.
+/// Cloning copies the current counts.
+#[derive(Debug, Default)]
+pub struct FrameCounters {
+    analyzed: AtomicU64,
+    dropped_overload: AtomicU64,
+    dropped_invalid: AtomicU64,
+}
+
+impl Clone for FrameCounters {
+    fn clone(&self) -> Self {
+        let counts = self.snapshot();
+        Self {
+            analyzed: AtomicU64::new(counts.analyzed),
+            dropped_overload: AtomicU64::new(counts.dropped_overload),
+            dropped_invalid: AtomicU64::new(counts.dropped_invalid),
+        }
+    }
+}
+
+/// A point-in-time copy of [`FrameCounters`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct FrameCountersSnapshot {
+    pub analyzed: u64,
+    pub dropped_overload: u64,
+    pub dropped_invalid: u64,
+}
+
+impl FrameCountersSnapshot {
+    /// Total number of frames dropped for any reason.
+    pub fn dropped(&self) -> u64 {
+        self.dropped_overload + self.dropped_invalid
+    }
+
+    /// Fraction of frames dropped, in `[0.0, 1.0]` (`0.0` if nothing was seen yet).
+    pub fn drop_ratio(&self) -> f64 {
+        let total = self.analyzed + self.dropped();
+        if total == 0 {
+            0.0
+        } else {
+            self.dropped() as f64 / total as f64
+        }
+    }
+}
+
+impl FrameCounters {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Records a successfully analyzed frame.
+    pub fn record_analyzed(&self) {
+        self.analyzed.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Records a frame that was skipped.
+    pub fn record_dropped(&self, reason: DropReason) {
+        let counter = match reason {
+            DropReason::Overload => &self.dropped_overload,
+            DropReason::InvalidInput => &self.dropped_invalid,
+        };
+        counter.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Returns the current counts.
+    pub fn snapshot(&self) -> FrameCountersSnapshot {
+        FrameCountersSnapshot {
+            analyzed: self.analyzed.load(Ordering::Relaxed),
+            dropped_overload: self.dropped_overload.load(Ordering::Relaxed),
+            dropped_invalid: self.dropped_invalid.load(Ordering::Relaxed),
+        }
+    }
+
+    /// Resets all counts to zero.
+    pub fn reset(&self) {
+        self.analyzed.store(0, Ordering::Relaxed);
+        self.dropped_overload.store(0, Ordering::Relaxed);
+        self.dropped_invalid.store(0, Ordering::Relaxed);
+    }
+}
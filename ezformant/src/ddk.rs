@@ -0,0 +1,206 @@
+//! Diadochokinetic (DDK) rate for syllable repetition tasks such as /pa-ta-ka/.
+//!
+//! Each syllable shows up as a peak of the intensity envelope, separated from its
+//! neighbours by the closure of the next plosive. Peaks are kept when they are loud
+//! enough and separated from the previous peak by a sufficiently deep dip.
+
+/// Envelope and peak-picking parameters for [`ddk`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DdkConfig {
+    /// Hop of the intensity envelope, in seconds.
+    pub time_step: f64,
+    /// Window of the intensity envelope, in seconds.
+    pub window_duration: f64,
+    /// Peaks quieter than this, in dB relative to the loudest frame, are ignored.
+    pub silence_threshold_db: f64,
+    /// Minimum depth of the dip between two syllables, in dB below the lower peak.
+    pub min_dip_db: f64,
+    /// Minimum time between two syllables, in seconds.
+    pub min_interval: f64,
+}
+
+impl Default for DdkConfig {
+    fn default() -> Self {
+        Self {
+            time_step: 0.005,
+            window_duration: 0.02,
+            silence_threshold_db: -25.0,
+            min_dip_db: 3.0,
+            min_interval: 0.06,
+        }
+    }
+}
+
+/// Syllable rate and regularity of a DDK task.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Ddk {
+    /// Times of the syllable nuclei (envelope peaks), in seconds.
+    pub syllables: Vec<f64>,
+    /// Syllables per second between the first and last nucleus.
+    pub rate: f64,
+    /// Mean interval between consecutive syllables, in seconds.
+    pub mean_interval: f64,
+    /// Coefficient of variation of the intervals; lower is more regular.
+    pub interval_cv: f64,
+}
+
+/// Computes the intensity envelope of a signal.
+///
+/// # Arguments
+///
+/// * `signal`      - The input signal.
+/// * `sample_rate` - The sampling rate of the signal.
+/// * `config`      - The envelope hop and window.
+///
+/// # Returns
+///
+/// The mean power of each frame in dB (`-200` for digital silence); with the hop and
+/// window rounded to whole samples, frame `i` is centred at `(i * hop + window / 2) /
+/// sample_rate` seconds.
+pub fn intensity_envelope(signal: &[f64], sample_rate: f64, config: &DdkConfig) -> Vec<f64> {
+    let (window, hop) = envelope_frames(sample_rate, config);
+    if signal.len() < window {
+        return Vec::new();
+    }
+    (0..=signal.len() - window)
+        .step_by(hop)
+        .map(|start| {
+            let power = signal[start..start + window]
+                .iter()
+                .map(|x| x * x)
+                .sum::<f64>()
+                / window as f64;
+            if power > 0.0 {
+                10.0 * power.log10()
+            } else {
+                -200.0
+            }
+        })
+        .collect()
+}
+
+/// Finds the syllables of a DDK recording and measures their rate and regularity.
+///
+/// # Arguments
+///
+/// * `signal`      - The recording.
+/// * `sample_rate` - The sampling rate of the recording.
+/// * `config`      - Envelope and peak-picking parameters.
+///
+/// # Returns
+///
+/// The measurements, or `None` if fewer than two syllables were found.
+#[tracing::instrument(level = "debug", skip_all, fields(len = signal.len(), sample_rate))]
+pub fn ddk(signal: &[f64], sample_rate: f64, config: &DdkConfig) -> Option<Ddk> {
+    let envelope = intensity_envelope(signal, sample_rate, config);
+    let loudest = envelope.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+    let floor = loudest + config.silence_threshold_db;
+    // Times follow the hop actually used, not the requested `time_step`.
+    let (window, hop) = envelope_frames(sample_rate, config);
+    let frame_step = hop as f64 / sample_rate;
+    let min_gap = (config.min_interval / frame_step).round() as usize;
+
+    // Indices of accepted peaks.
+    let mut peaks: Vec<usize> = Vec::new();
+    for i in 1..envelope.len().saturating_sub(1) {
+        let value = envelope[i];
+        if value < floor || value < envelope[i - 1] || value <= envelope[i + 1] {
+            continue;
+        }
+        let Some(&last) = peaks.last() else {
+            peaks.push(i);
+            continue;
+        };
+        let dip = envelope[last..=i]
+            .iter()
+            .copied()
+            .fold(f64::INFINITY, f64::min);
+        let separated = value.min(envelope[last]) - dip >= config.min_dip_db;
+        if separated && i - last >= min_gap {
+            peaks.push(i);
+        } else if value > envelope[last] {
+            // Same syllable, louder maximum.
+            *peaks.last_mut().unwrap() = i;
+        }
+    }
+    if peaks.len() < 2 {
+        return None;
+    }
+
+    let centre = 0.5 * window as f64 / sample_rate;
+    let syllables: Vec<f64> = peaks
+        .iter()
+        .map(|&i| i as f64 * frame_step + centre)
+        .collect();
+    let intervals: Vec<f64> = syllables.windows(2).map(|w| w[1] - w[0]).collect();
+    let n = intervals.len() as f64;
+    let mean_interval = intervals.iter().sum::<f64>() / n;
+    let variance = intervals
+        .iter()
+        .map(|d| (d - mean_interval).powi(2))
+        .sum::<f64>()
+        / n;
+
+    Some(Ddk {
+        rate: 1.0 / mean_interval,
+        mean_interval,
+        interval_cv: variance.sqrt() / mean_interval,
+        syllables,
+    })
+}
+
+// ------------------
+// Helpers
+// ------------------
+
+/// Window and hop of the intensity envelope, in samples.
+fn envelope_frames(sample_rate: f64, config: &DdkConfig) -> (usize, usize) {
+    let window = ((config.window_duration * sample_rate).round() as usize).max(1);
+    let hop = ((config.time_step * sample_rate).round() as usize).max(1);
+    (window, hop)
+}
+
+// ------------------
+// Tests
+// ------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::f64::consts::PI;
+
+    #[test]
+    fn counts_syllables_of_a_regular_train() {
+        // 80 ms tone bursts at 5 per second, starting at 0.1 s.
+        let fs = 8000.0;
+        let signal: Vec<f64> = (0..(2.0 * fs) as usize)
+            .map(|n| {
+                let t = n as f64 / fs;
+                let phase = (t - 0.1).rem_euclid(0.2);
+                if t >= 0.1 && phase < 0.08 {
+                    (PI * phase / 0.08).sin().powi(2) * (2.0 * PI * 220.0 * t).sin()
+                } else {
+                    0.0
+                }
+            })
+            .collect();
+
+        let result = ddk(&signal, fs, &DdkConfig::default()).unwrap();
+        assert_eq!(result.syllables.len(), 10, "{:?}", result.syllables);
+        assert!((result.syllables[0] - 0.14).abs() < 0.01);
+        assert!((result.rate - 5.0).abs() < 0.05, "rate = {}", result.rate);
+        assert!(result.interval_cv < 0.02);
+
+        // 40.49 samples round to a 40-sample hop; times must follow the rounded hop.
+        let config = DdkConfig {
+            time_step: 40.49 / fs,
+            ..DdkConfig::default()
+        };
+        let result = ddk(&signal, fs, &config).unwrap();
+        assert!((result.syllables[0] - 0.14).abs() < 0.01);
+        assert!((result.rate - 5.0).abs() < 0.02, "rate = {}", result.rate);
+
+        assert_eq!(ddk(&vec![0.0; 8000], fs, &DdkConfig::default()), None);
+    }
+}
@@ -0,0 +1,106 @@
+//! Errors of the fallible entry points.
+//!
+//! The plain functions never fail: they return an empty result, `-1.0` or a
+//! meaningless value for input they cannot analyze. Their `try_*` counterparts check
+//! the input first and say what is wrong with it:
+//!
+//! - LPC: [`crate::lpc::try_levinson`], [`crate::lpc::try_levinson_full`] and
+//!   [`crate::lpc::try_formant_detection`].
+//! - Frames: [`crate::analyzer::Analyzer::try_formants`],
+//!   [`crate::analyzer::Analyzer::try_pitch`] and
+//!   [`crate::analyzer::Analyzer::try_pitch_result`].
+//! - Pitch: [`crate::pitch::try_pitch_detection_yin`].
+//! - Spectrum and envelope: [`crate::try_fourier_trans_with`] and
+//!   [`crate::quality::QualitySettings::try_gain_envelope_db`].
+//! - Recordings: [`crate::batch::try_analyze_signal`] and
+//!   [`crate::config::AnalysisConfig::try_analyze`].
+//! - Downsampling: [`crate::try_downsample`] and [`crate::try_decimate`].
+//!
+//! Without the `std` feature only the LPC, pitch and downsampling checks exist.
+
+use core::fmt;
+
+/// Errors reported by the `try_*` variants of the analysis functions.
+#[derive(Debug, Clone, PartialEq)]
+pub enum EzFormantError {
+    /// The input signal or coefficient slice was empty.
+    EmptyInput,
+    /// The input was shorter than the analysis needs.
+    InputTooShort { len: usize, required: usize },
+    /// The input contained NaN or an infinity at `index`.
+    NonFiniteInput { index: usize },
+    /// A parameter was outside its valid range.
+    InvalidParameter { name: &'static str, value: f64 },
+}
+
+/// Shorthand for results of the `try_*` functions.
+pub type Result<T> = core::result::Result<T, EzFormantError>;
+
+impl fmt::Display for EzFormantError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            EzFormantError::EmptyInput => write!(f, "input is empty"),
+            EzFormantError::InputTooShort { len, required } => write!(
+                f,
+                "input has {} samples but at least {} are required",
+                len, required
+            ),
+            EzFormantError::NonFiniteInput { index } => {
+                write!(f, "input contains NaN or infinity at index {}", index)
+            }
+            EzFormantError::InvalidParameter { name, value } => {
+                write!(f, "invalid value for `{}`: {}", name, value)
+            }
+        }
+    }
+}
+
+impl core::error::Error for EzFormantError {}
+
+/// Checks that `input` is non-empty and finite.
+pub(crate) fn check_finite(input: &[f64]) -> Result<()> {
+    if input.is_empty() {
+        return Err(EzFormantError::EmptyInput);
+    }
+    match input.iter().position(|x| !x.is_finite()) {
+        Some(index) => Err(EzFormantError::NonFiniteInput { index }),
+        None => Ok(()),
+    }
+}
+
+/// Checks that a parameter is finite and strictly positive.
+pub(crate) fn check_positive(name: &'static str, value: f64) -> Result<()> {
+    if value.is_finite() && value > 0.0 {
+        Ok(())
+    } else {
+        Err(EzFormantError::InvalidParameter { name, value })
+    }
+}
+
+// ------------------
+// Tests
+// ------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn input_checks_locate_the_problem() {
+        assert_eq!(check_finite(&[]), Err(EzFormantError::EmptyInput));
+        assert_eq!(
+            check_finite(&[0.0, 1.0, f64::NAN]),
+            Err(EzFormantError::NonFiniteInput { index: 2 })
+        );
+        assert_eq!(check_finite(&[0.0, 1.0]), Ok(()));
+        assert!(check_positive("sample_rate", 0.0).is_err());
+        assert_eq!(
+            EzFormantError::InputTooShort {
+                len: 4,
+                required: 13
+            }
+            .to_string(),
+            "input has 4 samples but at least 13 are required"
+        );
+    }
+}
@@ -0,0 +1,164 @@
+//! Writers for Praat's short text formats (`export` feature).
+//!
+//! Tracks from [`crate::batch::analyze_signal`] can be saved as `.Formant` and `.Pitch`
+//! files and opened in Praat with "Read from file...", e.g. to compare against
+//! Praat's own tracker or to correct them by hand. Frequencies must be in Hz.
+//!
+//! Frames are assumed to be evenly spaced, as the batch and streaming analyzers
+//! produce them.
+
+use crate::batch::TrackFrame;
+use std::io::{self, Write};
+
+/// Writes frames as a Praat `Formant` object in short text format.
+///
+/// # Arguments
+///
+/// * `out`      - Where to write the file.
+/// * `frames`   - The frames, evenly spaced, with formants and bandwidths in Hz.
+/// * `duration` - Length of the analyzed signal, in seconds (the object's time domain).
+pub fn write_praat_formant<W: Write>(
+    out: &mut W,
+    frames: &[TrackFrame],
+    duration: f64,
+) -> io::Result<()> {
+    let max_formants = frames.iter().map(|f| f.formants.len()).max().unwrap_or(0);
+    write_header(out, "Formant 2", frames, duration)?;
+    writeln!(out, "{}", max_formants)?;
+    for frame in frames {
+        writeln!(out, "{}", frame.energy)?;
+        writeln!(out, "{}", frame.formants.len())?;
+        for (i, &frequency) in frame.formants.iter().enumerate() {
+            let bandwidth = frame.bandwidths.get(i).copied().unwrap_or(f64::NAN);
+            writeln!(out, "{}", frequency)?;
+            writeln!(out, "{}", PraatNumber(bandwidth))?;
+        }
+    }
+    Ok(())
+}
+
+/// Writes frames as a Praat `Pitch` object in short text format.
+///
+/// Each frame gets a single candidate: its f0 with strength 1 if voiced, or frequency
+/// 0 (Praat's unvoiced candidate) otherwise.
+///
+/// # Arguments
+///
+/// * `out`      - Where to write the file.
+/// * `frames`   - The frames, evenly spaced, with f0 in Hz.
+/// * `duration` - Length of the analyzed signal, in seconds.
+/// * `ceiling`  - The pitch ceiling of the analysis, in Hz (600 Hz is Praat's usual).
+pub fn write_praat_pitch<W: Write>(
+    out: &mut W,
+    frames: &[TrackFrame],
+    duration: f64,
+    ceiling: f64,
+) -> io::Result<()> {
+    write_header(out, "Pitch 1", frames, duration)?;
+    writeln!(out, "{}", ceiling)?;
+    writeln!(out, "1")?;
+    for frame in frames {
+        let (frequency, strength) = if frame.voiced {
+            (frame.pitch, 1.0)
+        } else {
+            (0.0, 0.0)
+        };
+        writeln!(out, "{}", frame.energy)?;
+        writeln!(out, "1")?;
+        writeln!(out, "{}", frequency)?;
+        writeln!(out, "{}", strength)?;
+    }
+    Ok(())
+}
+
+// ------------------
+// Helpers
+// ------------------
+
+/// Formats non-finite values as Praat's `--undefined--`.
+struct PraatNumber(f64);
+
+impl std::fmt::Display for PraatNumber {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.0.is_finite() {
+            write!(f, "{}", self.0)
+        } else {
+            write!(f, "--undefined--")
+        }
+    }
+}
+
+/// Writes the file header and the time sampling (`xmin`, `xmax`, `nx`, `dx`, `x1`).
+fn write_header<W: Write>(
+    out: &mut W,
+    class: &str,
+    frames: &[TrackFrame],
+    duration: f64,
+) -> io::Result<()> {
+    let x1 = frames.first().map_or(duration / 2.0, |f| f.time);
+    let dx = match frames {
+        [first, .., last] => (last.time - first.time) / (frames.len() - 1) as f64,
+        _ => duration,
+    };
+    writeln!(out, "File type = \"ooTextFile\"")?;
+    writeln!(out, "Object class = \"{}\"", class)?;
+    writeln!(out)?;
+    writeln!(out, "0")?;
+    writeln!(out, "{}", duration)?;
+    writeln!(out, "{}", frames.len())?;
+    writeln!(out, "{}", dx)?;
+    writeln!(out, "{}", x1)
+}
+
+// ------------------
+// Tests
+// ------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn frames() -> Vec<TrackFrame> {
+        vec![
+            TrackFrame {
+                time: 0.25,
+                formants: vec![700.0, 1200.0],
+                bandwidths: vec![80.0, 90.0],
+                pitch: 150.0,
+                energy: 0.25,
+                zcr: 0.05,
+                voiced: true,
+                silent: false,
+            },
+            TrackFrame {
+                time: 0.75,
+                formants: vec![650.0],
+                bandwidths: vec![],
+                pitch: -1.0,
+                energy: 0.0,
+                zcr: 0.0,
+                voiced: false,
+                silent: false,
+            },
+        ]
+    }
+
+    #[test]
+    fn writes_short_text_formant_and_pitch_files() {
+        let mut formant = Vec::new();
+        write_praat_formant(&mut formant, &frames(), 1.0).unwrap();
+        assert_eq!(
+            String::from_utf8(formant).unwrap(),
+            "File type = \"ooTextFile\"\nObject class = \"Formant 2\"\n\n\
+             0\n1\n2\n0.5\n0.25\n2\n\
+             0.25\n2\n700\n80\n1200\n90\n\
+             0\n1\n650\n--undefined--\n"
+        );
+
+        let mut pitch = Vec::new();
+        write_praat_pitch(&mut pitch, &frames(), 1.0, 600.0).unwrap();
+        let pitch = String::from_utf8(pitch).unwrap();
+        assert!(pitch.starts_with("File type = \"ooTextFile\"\nObject class = \"Pitch 1\"\n"));
+        assert!(pitch.ends_with("600\n1\n0.25\n1\n150\n1\n0\n1\n0\n0\n"));
+    }
+}
@@ -0,0 +1,309 @@
+//! C interface (`ffi` feature).
+//!
+//! `extern "C"` wrappers around [`Analyzer`] and the LPC routines for embedding in
+//! C/C++ hosts (audio plugins, iOS and Android apps). The matching header is
+//! `include/ezformant.h`, generated from this module with
+//! `cbindgen --config cbindgen.toml --output include/ezformant.h src/ffi.rs`.
+//!
+//! Functions that can fail return a negative `EZ_ERROR_*` code. Frames are arrays of
+//! `double` owned by the caller; nothing returned by this module has to be freed
+//! except the analyzer itself.
+
+use crate::analyzer::Analyzer;
+use crate::error::{check_positive, EzFormantError};
+use crate::lpc;
+use crate::quality::Quality;
+
+/// A required pointer argument was null.
+pub const EZ_ERROR_NULL_POINTER: isize = -1;
+/// The frame was empty.
+pub const EZ_ERROR_EMPTY_INPUT: isize = -2;
+/// The frame was shorter than the analysis needs.
+pub const EZ_ERROR_INPUT_TOO_SHORT: isize = -3;
+/// The frame contained NaN or an infinity.
+pub const EZ_ERROR_NON_FINITE_INPUT: isize = -4;
+/// A parameter was outside its valid range.
+pub const EZ_ERROR_INVALID_PARAMETER: isize = -5;
+
+/// Accuracy/speed tier, see [`Quality`].
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EzQuality {
+    Fast = 0,
+    Balanced = 1,
+    Accurate = 2,
+}
+
+impl From<EzQuality> for Quality {
+    fn from(quality: EzQuality) -> Self {
+        match quality {
+            EzQuality::Fast => Quality::Fast,
+            EzQuality::Balanced => Quality::Balanced,
+            EzQuality::Accurate => Quality::Accurate,
+        }
+    }
+}
+
+/// Opaque analyzer handle created by [`ez_analyzer_new`].
+pub struct EzAnalyzer(Analyzer);
+
+/// Creates an analyzer; free it with [`ez_analyzer_free`].
+///
+/// # Arguments
+///
+/// * `lpc_order`   - The LPC order.
+/// * `sample_rate` - The sampling rate of incoming frames, in Hz.
+/// * `max_formant` - Formant ceiling in Hz, or `0` to analyze at `sample_rate`.
+/// * `quality`     - The algorithm tier.
+///
+/// # Returns
+///
+/// The analyzer, or null if `sample_rate` or `max_formant` is invalid.
+#[no_mangle]
+pub extern "C" fn ez_analyzer_new(
+    lpc_order: usize,
+    sample_rate: f64,
+    max_formant: f64,
+    quality: EzQuality,
+) -> *mut EzAnalyzer {
+    let valid = check_positive("sample_rate", sample_rate).is_ok()
+        && (max_formant == 0.0 || check_positive("max_formant", max_formant).is_ok());
+    if !valid {
+        return std::ptr::null_mut();
+    }
+    let mut analyzer = Analyzer::new(lpc_order, sample_rate).with_quality(quality.into());
+    if max_formant > 0.0 {
+        analyzer = analyzer.with_max_formant(max_formant);
+    }
+    Box::into_raw(Box::new(EzAnalyzer(analyzer)))
+}
+
+/// Frees an analyzer. Null is ignored.
+///
+/// # Safety
+///
+/// `analyzer` must be null or come from [`ez_analyzer_new`], and must not be used
+/// afterwards.
+#[no_mangle]
+pub unsafe extern "C" fn ez_analyzer_free(analyzer: *mut EzAnalyzer) {
+    if !analyzer.is_null() {
+        drop(Box::from_raw(analyzer));
+    }
+}
+
+/// Estimates the formants of one frame.
+///
+/// # Arguments
+///
+/// * `analyzer` - The analyzer.
+/// * `frame`    - `len` samples.
+/// * `out`      - Receives up to `capacity` formant frequencies in Hz, ascending.
+///
+/// # Returns
+///
+/// The number of formants found (which may exceed `capacity`), or an error code.
+///
+/// # Safety
+///
+/// `analyzer` must be a live handle, `frame` must point to `len` readable values and
+/// `out` to `capacity` writable ones (`out` may be null if `capacity` is 0).
+#[no_mangle]
+pub unsafe extern "C" fn ez_formants(
+    analyzer: *const EzAnalyzer,
+    frame: *const f64,
+    len: usize,
+    out: *mut f64,
+    capacity: usize,
+) -> isize {
+    let (Some(analyzer), Some(frame)) = (analyzer.as_ref(), input(frame, len)) else {
+        return EZ_ERROR_NULL_POINTER;
+    };
+    match analyzer.0.try_formants(frame) {
+        Ok(formants) => write_output(&formants, out, capacity),
+        Err(err) => error_code(&err),
+    }
+}
+
+/// Estimates the fundamental frequency of one frame.
+///
+/// # Arguments
+///
+/// * `analyzer` - The analyzer.
+/// * `frame`    - `len` samples.
+/// * `f0`       - Receives f0 in Hz, or 0 when the frame is unvoiced.
+///
+/// # Returns
+///
+/// `1` if the frame is voiced, `0` if not, or an error code.
+///
+/// # Safety
+///
+/// `analyzer` must be a live handle, `frame` must point to `len` readable values and
+/// `f0` must be writable.
+#[no_mangle]
+pub unsafe extern "C" fn ez_pitch(
+    analyzer: *const EzAnalyzer,
+    frame: *const f64,
+    len: usize,
+    f0: *mut f64,
+) -> isize {
+    let (Some(analyzer), Some(frame), Some(f0)) =
+        (analyzer.as_ref(), input(frame, len), f0.as_mut())
+    else {
+        return EZ_ERROR_NULL_POINTER;
+    };
+    match analyzer.0.try_pitch_result(frame) {
+        Ok(result) => {
+            *f0 = if result.voiced { result.f0 } else { 0.0 };
+            isize::from(result.voiced)
+        }
+        Err(err) => error_code(&err),
+    }
+}
+
+/// Computes autocorrelation-method LPC coefficients `[1, a1, ..., ap]` of a frame as
+/// is (no window or pre-emphasis).
+///
+/// # Arguments
+///
+/// * `frame` - `len` samples; more than `order` are needed.
+/// * `order` - The LPC order `p`.
+/// * `out`   - Receives up to `capacity` coefficients; `order + 1` are needed.
+///
+/// # Returns
+///
+/// `order + 1`, or an error code (`EZ_ERROR_INPUT_TOO_SHORT` if `len <= order`).
+///
+/// # Safety
+///
+/// `frame` must point to `len` readable values and `out` to `capacity` writable ones.
+#[no_mangle]
+pub unsafe extern "C" fn ez_lpc_coefficients(
+    frame: *const f64,
+    len: usize,
+    order: usize,
+    out: *mut f64,
+    capacity: usize,
+) -> isize {
+    let Some(frame) = input(frame, len) else {
+        return EZ_ERROR_NULL_POINTER;
+    };
+    if let Err(err) = lpc::check_frame(frame, order) {
+        return error_code(&err);
+    }
+    let r = lpc::autocorrelate(frame, order);
+    let (coefficients, _) = lpc::levinson(order, &r);
+    write_output(&coefficients, out, capacity)
+}
+
+// ------------------
+// Helpers
+// ------------------
+
+/// Borrows a caller-owned array; null is only accepted for an empty one.
+unsafe fn input<'a>(ptr: *const f64, len: usize) -> Option<&'a [f64]> {
+    match (ptr.is_null(), len) {
+        (true, 0) => Some(&[]),
+        (true, _) => None,
+        (false, _) => Some(std::slice::from_raw_parts(ptr, len)),
+    }
+}
+
+/// Copies as many values as fit and returns the full count.
+unsafe fn write_output(values: &[f64], out: *mut f64, capacity: usize) -> isize {
+    let n = values.len().min(capacity);
+    if n > 0 {
+        if out.is_null() {
+            return EZ_ERROR_NULL_POINTER;
+        }
+        std::ptr::copy_nonoverlapping(values.as_ptr(), out, n);
+    }
+    values.len() as isize
+}
+
+fn error_code(err: &EzFormantError) -> isize {
+    match err {
+        EzFormantError::EmptyInput => EZ_ERROR_EMPTY_INPUT,
+        EzFormantError::InputTooShort { .. } => EZ_ERROR_INPUT_TOO_SHORT,
+        EzFormantError::NonFiniteInput { .. } => EZ_ERROR_NON_FINITE_INPUT,
+        EzFormantError::InvalidParameter { .. } => EZ_ERROR_INVALID_PARAMETER,
+    }
+}
+
+// ------------------
+// Tests
+// ------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn analyzes_through_the_c_interface() {
+        let fs = 10000.0;
+        let frame: Vec<f64> = (0..400)
+            .map(|n| {
+                let t = n as f64 / fs;
+                (2.0 * std::f64::consts::PI * 120.0 * t).sin()
+                    + 0.5 * (2.0 * std::f64::consts::PI * 240.0 * t).sin()
+            })
+            .collect();
+        assert!(ez_analyzer_new(10, -1.0, 0.0, EzQuality::Fast).is_null());
+
+        let analyzer = ez_analyzer_new(10, fs, 0.0, EzQuality::Balanced);
+        unsafe {
+            let mut f0 = -1.0;
+            assert_eq!(ez_pitch(analyzer, frame.as_ptr(), frame.len(), &mut f0), 1);
+            assert!((f0 - 120.0).abs() < 5.0, "{}", f0);
+
+            let mut formants = [0.0; 8];
+            let n = ez_formants(
+                analyzer,
+                frame.as_ptr(),
+                frame.len(),
+                formants.as_mut_ptr(),
+                8,
+            );
+            assert!(n >= 0, "{}", n);
+            assert_eq!(
+                ez_formants(analyzer, std::ptr::null(), 0, std::ptr::null_mut(), 0),
+                EZ_ERROR_EMPTY_INPUT
+            );
+            assert_eq!(
+                ez_formants(
+                    std::ptr::null(),
+                    frame.as_ptr(),
+                    frame.len(),
+                    std::ptr::null_mut(),
+                    0
+                ),
+                EZ_ERROR_NULL_POINTER
+            );
+
+            let mut a = [0.0; 4];
+            let n = ez_lpc_coefficients(frame.as_ptr(), frame.len(), 3, a.as_mut_ptr(), 4);
+            assert_eq!(n, 4);
+            assert_eq!(a[0], 1.0);
+            assert_eq!(
+                ez_lpc_coefficients(frame.as_ptr(), 3, 3, a.as_mut_ptr(), 4),
+                EZ_ERROR_INPUT_TOO_SHORT
+            );
+            ez_analyzer_free(analyzer);
+        }
+    }
+
+    #[test]
+    fn header_declares_every_export() {
+        let header = include_str!("../include/ezformant.h");
+        for name in [
+            "ez_analyzer_new",
+            "ez_analyzer_free",
+            "ez_formants",
+            "ez_pitch",
+            "ez_lpc_coefficients",
+            "EZ_ERROR_INVALID_PARAMETER",
+        ] {
+            assert!(header.contains(name), "{} missing from ezformant.h", name);
+        }
+    }
+}
@@ -0,0 +1,86 @@
+//! Per-thread FFT plan cache.
+//!
+//! Planning an FFT costs far more than running a short one, and live displays ask for
+//! the same lengths every frame. Each thread keeps one complex and one real-input
+//! planner per precision, which hand back the plan they already built for a length
+//! instead of planning again.
+
+use realfft::{RealFftPlanner, RealToComplex};
+use rustfft::{Fft, FftNum, FftPlanner};
+use std::cell::RefCell;
+use std::sync::Arc;
+
+/// The complex and real-input planners of one thread and precision.
+pub struct Planners<T: FftNum> {
+    complex: FftPlanner<T>,
+    real: RealFftPlanner<T>,
+}
+
+impl<T: FftNum> Planners<T> {
+    fn new() -> Self {
+        Self {
+            complex: FftPlanner::new(),
+            real: RealFftPlanner::new(),
+        }
+    }
+}
+
+thread_local! {
+    static PLANNERS_F32: RefCell<Planners<f32>> = RefCell::new(Planners::new());
+    static PLANNERS_F64: RefCell<Planners<f64>> = RefCell::new(Planners::new());
+}
+
+/// Sample types with cached planners.
+pub trait CachedFft: FftNum {
+    /// Runs `f` with this thread's planners for `Self`.
+    fn with_planners<R>(f: impl FnOnce(&mut Planners<Self>) -> R) -> R;
+}
+
+impl CachedFft for f32 {
+    fn with_planners<R>(f: impl FnOnce(&mut Planners<f32>) -> R) -> R {
+        PLANNERS_F32.with(|planners| f(&mut planners.borrow_mut()))
+    }
+}
+
+impl CachedFft for f64 {
+    fn with_planners<R>(f: impl FnOnce(&mut Planners<f64>) -> R) -> R {
+        PLANNERS_F64.with(|planners| f(&mut planners.borrow_mut()))
+    }
+}
+
+/// The forward FFT of length `len`, planned once per thread.
+pub fn forward<T: CachedFft>(len: usize) -> Arc<dyn Fft<T>> {
+    T::with_planners(|planners| planners.complex.plan_fft_forward(len))
+}
+
+/// The inverse (unnormalized) FFT of length `len`, planned once per thread.
+pub fn inverse<T: CachedFft>(len: usize) -> Arc<dyn Fft<T>> {
+    T::with_planners(|planners| planners.complex.plan_fft_inverse(len))
+}
+
+/// The forward FFT of `len` real samples, giving bins `0..=len / 2`, planned once per
+/// thread. About twice as fast as [`forward`] on the same input.
+pub fn real_forward<T: CachedFft>(len: usize) -> Arc<dyn RealToComplex<T>> {
+    T::with_planners(|planners| planners.real.plan_fft_forward(len))
+}
+
+// ------------------
+// Tests
+// ------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn repeated_lengths_reuse_the_plan() {
+        let first = forward::<f32>(480);
+        assert!(Arc::ptr_eq(&first, &forward::<f32>(480)));
+        assert!(!Arc::ptr_eq(&first, &inverse::<f32>(480)));
+        assert_eq!(forward::<f64>(96).len(), 96);
+        assert!(Arc::ptr_eq(
+            &real_forward::<f64>(96),
+            &real_forward::<f64>(96)
+        ));
+    }
+}
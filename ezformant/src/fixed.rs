@@ -0,0 +1,274 @@
+//! Allocation-free formant analysis at a compile-time LPC order.
+//!
+//! [`FormantAnalyzerFixed`] runs the LPC pipeline of the default quality settings
+//! (mean removal, Hamming window, pre-emphasis, autocorrelation, Levinson recursion,
+//! pole extraction) on stack arrays sized by the order, so analyzing a frame never
+//! touches the heap. That keeps the cost of each frame predictable in audio callbacks
+//! and works without `std`. The poles come from an Aberth iteration run to full
+//! precision, so the formants match those of [`crate::roots::RootSolver::Eigen`].
+
+use crate::lpc::{self, Peak};
+use crate::simd::SimdFloat;
+use crate::DEFAULT_PRE_EMPHASIS;
+use num_complex::Complex;
+
+/// Iteration limit of the root finder; LPC polynomials converge in far fewer.
+const MAX_ITERATIONS: u32 = 100;
+
+/// The root finder stops once no root moves by more than this.
+const EPSILON: f64 = 1e-12;
+
+/// Formant analysis at LPC order `ORDER` without heap allocations.
+///
+/// # Example
+///
+/// ```
+/// use ezformant::fixed::FormantAnalyzerFixed;
+///
+/// let analyzer = FormantAnalyzerFixed::<10>::new(11025.0);
+/// let mut frame = [0.0; 512];
+/// for (i, x) in frame.iter_mut().enumerate() {
+///     *x = (i as f64 * 0.4).sin() * (i as f64 * 0.03).cos();
+/// }
+/// for peak in analyzer.analyze(&mut frame).peaks() {
+///     println!("{:.0} Hz, bandwidth {:.0} Hz", peak.frequency, peak.bandwidth);
+/// }
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FormantAnalyzerFixed<const ORDER: usize> {
+    sample_rate: f64,
+    pre_emphasis: f64,
+}
+
+impl<const ORDER: usize> FormantAnalyzerFixed<ORDER> {
+    /// Creates an analyzer for frames sampled at `sample_rate`, with
+    /// [`DEFAULT_PRE_EMPHASIS`].
+    pub fn new(sample_rate: f64) -> Self {
+        Self {
+            sample_rate,
+            pre_emphasis: DEFAULT_PRE_EMPHASIS,
+        }
+    }
+
+    /// Sets the pre-emphasis coefficient.
+    pub fn with_pre_emphasis(mut self, alpha: f64) -> Self {
+        self.pre_emphasis = alpha;
+        self
+    }
+
+    /// The sampling rate of incoming frames.
+    pub fn sample_rate(&self) -> f64 {
+        self.sample_rate
+    }
+
+    /// Detects the formants of a frame.
+    ///
+    /// # Arguments
+    ///
+    /// * `frame` - The samples, at [`FormantAnalyzerFixed::sample_rate`]. Preprocessing
+    ///   runs in place, so it holds the windowed, pre-emphasized frame afterwards.
+    ///
+    /// # Returns
+    ///
+    /// The formants with their bandwidths, sorted by frequency; none if the frame has
+    /// `ORDER` samples or fewer.
+    pub fn analyze(&self, frame: &mut [f64]) -> FixedFormants<ORDER> {
+        let mut formants = FixedFormants::new();
+        if ORDER == 0 || frame.len() <= ORDER {
+            return formants;
+        }
+        crate::subtract_mean_in_place(frame);
+        let n = frame.len() as f64;
+        for (i, sample) in frame.iter_mut().enumerate() {
+            let ratio = i as f64 / (n - 1.0);
+            *sample *= 0.54 - 0.46 * (2.0 * core::f64::consts::PI * ratio).cos();
+        }
+        crate::pre_emphasize_in_place(frame, self.pre_emphasis);
+
+        let (r0, r) = autocorrelate::<ORDER>(frame);
+        let a = levinson::<ORDER>(r0, &r);
+        for root in roots(&a) {
+            if root.norm() <= 1.0 + 1e-9 && root.im >= 0.0 {
+                let peak = lpc::peak_from_root(root, self.sample_rate);
+                if peak.is_formant {
+                    formants.push(peak);
+                }
+            }
+        }
+        formants.peaks[..formants.len].sort_by(|a, b| a.frequency.total_cmp(&b.frequency));
+        formants
+    }
+}
+
+/// The formants of one frame, stored inline; at most `ORDER / 2` of the slots are
+/// used.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FixedFormants<const ORDER: usize> {
+    peaks: [Peak; ORDER],
+    len: usize,
+}
+
+impl<const ORDER: usize> FixedFormants<ORDER> {
+    fn new() -> Self {
+        let empty = Peak {
+            frequency: 0.0,
+            bandwidth: 0.0,
+            radius: 0.0,
+            is_formant: false,
+        };
+        Self {
+            peaks: [empty; ORDER],
+            len: 0,
+        }
+    }
+
+    fn push(&mut self, peak: Peak) {
+        self.peaks[self.len] = peak;
+        self.len += 1;
+    }
+
+    /// The formants, sorted by frequency.
+    pub fn peaks(&self) -> &[Peak] {
+        &self.peaks[..self.len]
+    }
+
+    /// The formant frequencies in Hz, in ascending order.
+    pub fn frequencies(&self) -> impl Iterator<Item = f64> + '_ {
+        self.peaks().iter().map(|peak| peak.frequency)
+    }
+
+    /// Number of formants found.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Whether no formant was found.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+}
+
+// ------------------
+// Helpers
+// ------------------
+
+/// Autocorrelation at lag 0 and at lags `1..=ORDER`, as in [`lpc::autocorrelate`].
+fn autocorrelate<const ORDER: usize>(signal: &[f64]) -> (f64, [f64; ORDER]) {
+    let n = signal.len();
+    let mut r = [0.0; ORDER];
+    for (lag, value) in (1..).zip(r.iter_mut()) {
+        if lag < n {
+            *value = f64::dot(&signal[..n - lag], &signal[lag..]);
+        }
+    }
+    (f64::dot(signal, signal), r)
+}
+
+/// The Levinson recursion of [`lpc::levinson_full`], with the same floor on the
+/// prediction error.
+///
+/// # Returns
+///
+/// The coefficients `[a1, ..., a_ORDER]`; `a0 = 1.0` is implied.
+fn levinson<const ORDER: usize>(r0: f64, r: &[f64; ORDER]) -> [f64; ORDER] {
+    let mut a = [0.0; ORDER];
+    let mut e = if r0.abs() < 1e-12 { 1e-12 } else { r0 };
+    for i in 1..=ORDER {
+        let mut acc = r[i - 1];
+        for j in 1..i {
+            acc += a[j - 1] * r[i - j - 1];
+        }
+        let k = -acc / e;
+
+        let previous = a;
+        for j in 1..i {
+            a[j - 1] = previous[j - 1] + k * previous[i - j - 1];
+        }
+        a[i - 1] = k;
+
+        e = (e * (1.0 - k * k)).max(1e-12);
+    }
+    a
+}
+
+/// Roots of `z^p + a1 z^(p-1) + ... + ap` by Aberth–Ehrlich iteration, updating each
+/// root as soon as its correction is known.
+fn roots<const ORDER: usize>(a: &[f64; ORDER]) -> [Complex<f64>; ORDER] {
+    // Start on a circle inside the unit circle, where stable LPC poles lie, rotated so
+    // that no starting point is real.
+    let mut z = [Complex::new(0.0, 0.0); ORDER];
+    for (k, root) in z.iter_mut().enumerate() {
+        let angle = 2.0 * core::f64::consts::PI * k as f64 / ORDER as f64 + 0.4;
+        *root = Complex::from_polar(0.9, angle);
+    }
+
+    for _ in 0..MAX_ITERATIONS {
+        let mut converged = true;
+        for k in 0..ORDER {
+            // The polynomial and its derivative by Horner's rule.
+            let (mut p, mut dp) = (Complex::new(1.0, 0.0), Complex::new(0.0, 0.0));
+            for &a_i in a {
+                dp = dp * z[k] + p;
+                p = p * z[k] + a_i;
+            }
+            let newton = p / dp;
+            let repulsion: Complex<f64> = (0..ORDER)
+                .filter(|&j| j != k)
+                .map(|j| (z[k] - z[j]).inv())
+                .sum();
+            let step = newton / (Complex::new(1.0, 0.0) - newton * repulsion);
+            if step.is_finite() {
+                z[k] -= step;
+                converged &= step.norm() <= EPSILON;
+            }
+        }
+        if converged {
+            break;
+        }
+    }
+    z
+}
+
+// ------------------
+// Tests
+// ------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::quality::Quality;
+    use crate::roots::RootSolver;
+    use crate::test_signals::{pulse_train, resonate};
+
+    #[test]
+    fn matches_the_allocating_pipeline() {
+        // A 120 Hz pulse train through resonances at 600, 1400 and 2500 Hz.
+        let fs = 11025.0;
+        let mut signal = pulse_train(fs, 512, |_| 120.0);
+        resonate(
+            &mut signal,
+            fs,
+            &[(600.0, 60.0), (1400.0, 90.0), (2500.0, 120.0)],
+        );
+
+        let settings = crate::quality::QualitySettings {
+            root_solver: RootSolver::Eigen,
+            ..Quality::default().settings()
+        };
+        let expected = settings.detect_formant_peaks(&signal, 10, fs);
+        let mut frame = signal.clone();
+        let formants = FormantAnalyzerFixed::<10>::new(fs).analyze(&mut frame);
+        assert_eq!(formants.len(), expected.len());
+        for (peak, expected) in formants.peaks().iter().zip(&expected) {
+            assert!((peak.frequency - expected.frequency).abs() < 1e-6);
+            assert!((peak.bandwidth - expected.bandwidth).abs() < 1e-6);
+        }
+        assert!(formants
+            .frequencies()
+            .any(|frequency| (frequency - 600.0).abs() < 30.0));
+
+        assert!(FormantAnalyzerFixed::<10>::new(fs)
+            .analyze(&mut signal[..10])
+            .is_empty());
+    }
+}
@@ -0,0 +1,273 @@
+//! Voice source estimation: glottal flow by iterative adaptive inverse filtering
+//! (IAIF, Alku 1992) and glottal closure instants by zero-frequency filtering (ZFF,
+//! Murty & Yegnanarayana 2008).
+//!
+//! IAIF alternates between estimating the glottal contribution to the spectral tilt
+//! with a low-order LPC model and the vocal tract with a high-order one, each time
+//! cancelling the other from the speech before re-estimating. The final vocal tract
+//! model is inverse filtered out of the speech and the lip radiation (a
+//! differentiator) is undone with a leaky integrator.
+
+use crate::apply_hamming_window_in_place;
+use crate::lpc;
+
+/// Parameters for [`iaif`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct IaifConfig {
+    /// Vocal tract LPC order, or `None` for `2 * round(fs / 2000) + 4`.
+    pub vocal_tract_order: Option<usize>,
+    /// Glottal source LPC order, or `None` for `2 * round(fs / 4000)`.
+    pub glottis_order: Option<usize>,
+    /// Pole of the leaky integrator that undoes lip radiation, just below 1.
+    pub leak: f64,
+}
+
+impl Default for IaifConfig {
+    fn default() -> Self {
+        Self {
+            vocal_tract_order: None,
+            glottis_order: None,
+            leak: 0.99,
+        }
+    }
+}
+
+/// Output of [`iaif`].
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Iaif {
+    /// Estimated glottal flow, the same length as the frame.
+    pub flow: Vec<f64>,
+    /// Estimated glottal flow derivative (the flow before integration).
+    pub derivative: Vec<f64>,
+    /// Final vocal tract LPC coefficients `[1.0, a1, ..., ap]`.
+    pub vocal_tract: Vec<f64>,
+    /// Final glottal contribution LPC coefficients.
+    pub glottis: Vec<f64>,
+}
+
+/// Estimates the glottal flow of a voiced frame with IAIF.
+///
+/// # Arguments
+///
+/// * `frame`       - A voiced frame, a few pitch periods long (e.g. 30-50 ms).
+/// * `sample_rate` - The sampling rate of the frame.
+/// * `config`      - Model orders and integrator leak.
+///
+/// # Returns
+///
+/// The flow, its derivative and the final models. The flow is only defined up to a
+/// constant and a scale.
+#[tracing::instrument(level = "debug", skip_all, fields(len = frame.len(), sample_rate))]
+pub fn iaif(frame: &[f64], sample_rate: f64, config: &IaifConfig) -> Iaif {
+    let vt_order = config
+        .vocal_tract_order
+        .unwrap_or(2 * (sample_rate / 2000.0).round() as usize + 4);
+    let gl_order = config
+        .glottis_order
+        .unwrap_or(2 * (sample_rate / 4000.0).round() as usize);
+    let mean = frame.iter().sum::<f64>() / frame.len().max(1) as f64;
+    let x: Vec<f64> = frame.iter().map(|v| v - mean).collect();
+
+    // 1. Cancel a first-order estimate of the glottal tilt, then model the tract.
+    let tilt = windowed_lpc(&x, 1);
+    let vocal_tract = windowed_lpc(&lpc::inverse_filter(&x, &tilt), vt_order);
+
+    // 2. Model the source from the first flow estimate, cancel it, and re-model the
+    // tract.
+    let first_flow = integrate(&lpc::inverse_filter(&x, &vocal_tract), config.leak);
+    let glottis = windowed_lpc(&first_flow, gl_order);
+    let without_source = integrate(&lpc::inverse_filter(&x, &glottis), config.leak);
+    let vocal_tract = windowed_lpc(&without_source, vt_order);
+
+    // 3. Remove the final tract model and undo lip radiation.
+    let derivative = lpc::inverse_filter(&x, &vocal_tract);
+    Iaif {
+        flow: integrate(&derivative, config.leak),
+        derivative,
+        vocal_tract,
+        glottis,
+    }
+}
+
+/// Parameters for [`glottal_closure_instants`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct GciConfig {
+    /// Length of the trend-removal window, in seconds. Between one and two average
+    /// pitch periods works best; 10 ms suits most adult voices.
+    pub window_duration: f64,
+}
+
+impl Default for GciConfig {
+    fn default() -> Self {
+        Self {
+            window_duration: 0.01,
+        }
+    }
+}
+
+/// Detects glottal closure instants (epochs) with zero-frequency filtering.
+///
+/// The differenced signal is passed through two zero-frequency resonators (each a
+/// double integration), removing the growing trend after each one with a moving
+/// average. What remains oscillates at the pitch, and each closure shows up as a
+/// negative-to-positive zero crossing.
+///
+/// # Arguments
+///
+/// * `signal`      - The speech signal.
+/// * `sample_rate` - The sampling rate of the signal.
+/// * `config`      - The trend-removal window.
+///
+/// # Returns
+///
+/// Sample indices of the epochs, in ascending order. Unvoiced regions may produce
+/// spurious epochs; gate them with a voicing decision.
+#[tracing::instrument(level = "debug", skip_all, fields(len = signal.len(), sample_rate))]
+pub fn glottal_closure_instants(
+    signal: &[f64],
+    sample_rate: f64,
+    config: &GciConfig,
+) -> Vec<usize> {
+    let half = ((config.window_duration * sample_rate / 2.0).round() as usize).max(1);
+    let mut y: Vec<f64> = (0..signal.len())
+        .map(|n| signal[n] - if n > 0 { signal[n - 1] } else { 0.0 })
+        .collect();
+
+    for pass in 0..2 {
+        for _ in 0..2 {
+            y = integrate(&y, 1.0);
+        }
+        // Once after the first resonator, three times after the second.
+        for _ in 0..if pass == 0 { 1 } else { 3 } {
+            y = remove_trend(&y, half);
+        }
+    }
+
+    // The trend estimate is unreliable within one window of either end.
+    let end = y.len().saturating_sub(half);
+    (half.max(1)..end)
+        .filter(|&n| y[n - 1] < 0.0 && y[n] >= 0.0)
+        .collect()
+}
+
+// ------------------
+// Helpers
+// ------------------
+
+// LPC coefficients of a Hamming-windowed copy of `x`.
+fn windowed_lpc(x: &[f64], order: usize) -> Vec<f64> {
+    let mut frame = x.to_vec();
+    apply_hamming_window_in_place(&mut frame);
+    lpc::levinson(order, &lpc::autocorrelate(&frame, order)).0
+}
+
+// Leaky integrator `y[n] = x[n] + leak * y[n-1]`.
+fn integrate(x: &[f64], leak: f64) -> Vec<f64> {
+    let mut previous = 0.0;
+    x.iter()
+        .map(|v| {
+            previous = v + leak * previous;
+            previous
+        })
+        .collect()
+}
+
+// Subtracts the centred moving average over `2 * half + 1` samples (shorter at the
+// edges).
+fn remove_trend(x: &[f64], half: usize) -> Vec<f64> {
+    let mut prefix = Vec::with_capacity(x.len() + 1);
+    prefix.push(0.0);
+    for v in x {
+        prefix.push(prefix.last().unwrap() + v);
+    }
+    (0..x.len())
+        .map(|n| {
+            let (from, to) = (n.saturating_sub(half), (n + half + 1).min(x.len()));
+            x[n] - (prefix[to] - prefix[from]) / (to - from) as f64
+        })
+        .collect()
+}
+
+// ------------------
+// Tests
+// ------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_signals::{resonate, VOWEL_FORMANTS};
+    use std::f64::consts::PI;
+
+    // Rosenberg glottal pulses at `f0` Hz: opening over 40 % of the period, closing
+    // over 16 %, closed for the rest.
+    fn rosenberg(fs: f64, f0: f64, len: usize) -> Vec<f64> {
+        let period = fs / f0;
+        (0..len)
+            .map(|n| {
+                let t = (n as f64 % period) / period;
+                if t < 0.4 {
+                    0.5 * (1.0 - (PI * t / 0.4).cos())
+                } else if t < 0.56 {
+                    (PI * (t - 0.4) / 0.32).cos()
+                } else {
+                    0.0
+                }
+            })
+            .collect()
+    }
+
+    fn correlation(a: &[f64], b: &[f64]) -> f64 {
+        let centred = |x: &[f64]| {
+            let mean = x.iter().sum::<f64>() / x.len() as f64;
+            x.iter().map(|v| v - mean).collect::<Vec<_>>()
+        };
+        let (a, b) = (centred(a), centred(b));
+        let dot = |x: &[f64], y: &[f64]| x.iter().zip(y).map(|(p, q)| p * q).sum::<f64>();
+        dot(&a, &b) / (dot(&a, &a) * dot(&b, &b)).sqrt()
+    }
+
+    // Rosenberg flow at `f0` Hz through lip radiation and the resonances of the shared
+    // test vowel.
+    fn vowel(fs: f64, f0: f64, len: usize) -> (Vec<f64>, Vec<f64>) {
+        let flow = rosenberg(fs, f0, len);
+        let mut speech: Vec<f64> = (0..len)
+            .map(|n| flow[n] - if n > 0 { flow[n - 1] } else { 0.0 })
+            .collect();
+        resonate(&mut speech, fs, &VOWEL_FORMANTS);
+        (flow, speech)
+    }
+
+    #[test]
+    fn recovers_the_flow_derivative_of_a_synthetic_vowel() {
+        let fs = 16000.0;
+        let len = 1024;
+        let (flow, speech) = vowel(fs, 125.0, len);
+
+        let result = iaif(&speech, fs, &IaifConfig::default());
+        assert_eq!(result.flow.len(), len);
+        assert_eq!(result.vocal_tract.len(), 2 * 8 + 4 + 1);
+        assert_eq!(result.glottis.len(), 2 * 4 + 1);
+
+        let true_derivative: Vec<f64> = (1..len).map(|n| flow[n] - flow[n - 1]).collect();
+        // Skip the filter start-up.
+        let r = correlation(&result.derivative[200..], &true_derivative[199..]);
+        assert!(r > 0.8, "correlation = {}", r);
+    }
+
+    #[test]
+    fn finds_one_epoch_per_period() {
+        let fs = 16000.0;
+        let (_, speech) = vowel(fs, 125.0, 8000);
+        let epochs = glottal_closure_instants(&speech, fs, &GciConfig::default());
+
+        // 0.5 s at 125 Hz, minus the window trimmed at either end.
+        assert!((60..=63).contains(&epochs.len()), "{} epochs", epochs.len());
+        for pair in epochs.windows(2) {
+            assert!((pair[1] - pair[0]).abs_diff(128) <= 2, "{:?}", pair);
+        }
+        // Closure ends 56 % into each 128-sample period; allow for filter delays.
+        let phase = epochs[10] % 128;
+        assert!(phase.abs_diff(72) <= 12, "phase = {}", phase);
+    }
+}
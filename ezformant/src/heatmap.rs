@@ -0,0 +1,251 @@
+//! F1/F2 density heatmap ("vowel cloud").
+//!
+//! [`FormantHeatmap`] counts (F1, F2) points in a 2-D histogram laid out like a vowel
+//! chart: F1 grows downwards from the top row and F2 grows leftwards from the right
+//! column, so front vowels sit on the left and open vowels at the bottom. The counts
+//! export as a blurred intensity grid normalized to `[0, 1]`, or straight to RGBA
+//! pixels through a [`Colormap`].
+
+use crate::batch::TrackFrame;
+use crate::colormap::Colormap;
+
+/// Axis ranges and resolution of a [`FormantHeatmap`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct HeatmapConfig {
+    /// F1 at the top and bottom edges, in Hz.
+    pub f1_range: (f64, f64),
+    /// F2 at the right and left edges, in Hz.
+    pub f2_range: (f64, f64),
+    /// Number of rows (F1 bins).
+    pub f1_bins: usize,
+    /// Number of columns (F2 bins).
+    pub f2_bins: usize,
+}
+
+impl Default for HeatmapConfig {
+    fn default() -> Self {
+        Self {
+            f1_range: (150.0, 1200.0),
+            f2_range: (500.0, 3000.0),
+            f1_bins: 64,
+            f2_bins: 64,
+        }
+    }
+}
+
+/// A 2-D histogram of (F1, F2) points.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FormantHeatmap {
+    config: HeatmapConfig,
+    counts: Vec<u32>,
+    total: u64,
+}
+
+impl FormantHeatmap {
+    /// Creates an empty heatmap. Zero bin counts are raised to one.
+    pub fn new(config: HeatmapConfig) -> Self {
+        let config = HeatmapConfig {
+            f1_bins: config.f1_bins.max(1),
+            f2_bins: config.f2_bins.max(1),
+            ..config
+        };
+        Self {
+            counts: vec![0; config.f1_bins * config.f2_bins],
+            config,
+            total: 0,
+        }
+    }
+
+    /// The axis ranges and resolution.
+    pub fn config(&self) -> &HeatmapConfig {
+        &self.config
+    }
+
+    /// Counts one point.
+    ///
+    /// # Returns
+    ///
+    /// Whether the point fell inside the ranges; points outside them, or with `NaN`, are
+    /// dropped.
+    pub fn push(&mut self, f1: f64, f2: f64) -> bool {
+        let (Some(row), Some(column)) = (
+            bin(f1, self.config.f1_range, self.config.f1_bins),
+            bin(f2, self.config.f2_range, self.config.f2_bins),
+        ) else {
+            return false;
+        };
+        // Highest F2 in column 0.
+        let column = self.config.f2_bins - 1 - column;
+        self.counts[row * self.config.f2_bins + column] += 1;
+        self.total += 1;
+        true
+    }
+
+    /// Counts the F1/F2 point of an analyzed frame; silent and unvoiced frames are
+    /// skipped. Feed frames from smoothed tracks
+    /// ([`crate::smoothing::smooth_formant_tracks`]) for a tighter cloud.
+    pub fn push_frame(&mut self, frame: &TrackFrame) -> bool {
+        match frame.formants[..] {
+            [f1, f2, ..] if frame.voiced && !frame.silent => self.push(f1, f2),
+            _ => false,
+        }
+    }
+
+    /// Raw counts, row-major (`f1_bins` rows of `f2_bins` columns).
+    pub fn counts(&self) -> &[u32] {
+        &self.counts
+    }
+
+    /// Number of points counted.
+    pub fn total(&self) -> u64 {
+        self.total
+    }
+
+    /// The counts blurred with a Gaussian of `blur` bins standard deviation (`0` for
+    /// none) and divided by the largest value.
+    ///
+    /// # Returns
+    ///
+    /// Intensities in `[0, 1]` in the layout of [`FormantHeatmap::counts`]; all zero
+    /// while the heatmap is empty.
+    pub fn intensity(&self, blur: f64) -> Vec<f32> {
+        let (rows, columns) = (self.config.f1_bins, self.config.f2_bins);
+        let mut grid: Vec<f64> = self.counts.iter().map(|&c| c as f64).collect();
+        if blur > 0.0 {
+            let kernel = gaussian_kernel(blur);
+            grid = convolve(&grid, rows, columns, &kernel, 1, columns);
+            grid = convolve(&grid, rows, columns, &kernel, columns, 1);
+        }
+        let max = grid.iter().fold(0.0, |a: f64, &b| a.max(b));
+        grid.iter()
+            .map(|&v| if max > 0.0 { (v / max) as f32 } else { 0.0 })
+            .collect()
+    }
+
+    /// Renders [`FormantHeatmap::intensity`] as RGBA pixels, one per bin.
+    ///
+    /// # Arguments
+    ///
+    /// * `out`      - `f1_bins * f2_bins * 4` bytes; extra bytes are left alone.
+    /// * `colormap` - The colormap.
+    /// * `blur`     - As in [`FormantHeatmap::intensity`].
+    pub fn render_into(&self, out: &mut [u8], colormap: Colormap, blur: f64) {
+        for (level, pixel) in self
+            .intensity(blur)
+            .into_iter()
+            .zip(out.chunks_exact_mut(4))
+        {
+            pixel.copy_from_slice(&colormap.rgba(level));
+        }
+    }
+
+    /// Forgets all points.
+    pub fn clear(&mut self) {
+        self.counts.fill(0);
+        self.total = 0;
+    }
+}
+
+// ------------------
+// Helpers
+// ------------------
+
+/// The bin of `value` among `bins` equal bins over `low..high`.
+fn bin(value: f64, (low, high): (f64, f64), bins: usize) -> Option<usize> {
+    let position = (value - low) / (high - low);
+    (0.0..1.0)
+        .contains(&position)
+        .then(|| ((position * bins as f64) as usize).min(bins - 1))
+}
+
+/// Normalized Gaussian taps out to three standard deviations.
+fn gaussian_kernel(sigma: f64) -> Vec<f64> {
+    let radius = (3.0 * sigma).ceil() as isize;
+    let taps: Vec<f64> = (-radius..=radius)
+        .map(|i| (-0.5 * (i as f64 / sigma).powi(2)).exp())
+        .collect();
+    let sum: f64 = taps.iter().sum();
+    taps.iter().map(|t| t / sum).collect()
+}
+
+/// Convolves every line of a `rows` x `columns` grid with `kernel`, treating values past
+/// the edges as zero. Lines run along `step` and start `line_step` apart.
+fn convolve(
+    grid: &[f64],
+    rows: usize,
+    columns: usize,
+    kernel: &[f64],
+    step: usize,
+    line_step: usize,
+) -> Vec<f64> {
+    let (lines, len) = if step == 1 {
+        (rows, columns)
+    } else {
+        (columns, rows)
+    };
+    let radius = (kernel.len() / 2) as isize;
+    let mut out = vec![0.0; grid.len()];
+    for line in 0..lines {
+        let start = line * line_step;
+        for i in 0..len as isize {
+            out[start + i as usize * step] = kernel
+                .iter()
+                .enumerate()
+                .filter_map(|(k, &w)| {
+                    let j = i + k as isize - radius;
+                    (0..len as isize)
+                        .contains(&j)
+                        .then(|| w * grid[start + j as usize * step])
+                })
+                .sum();
+        }
+    }
+    out
+}
+
+// ------------------
+// Tests
+// ------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn points_land_in_vowel_chart_layout_and_blur_spreads_them() {
+        let mut heatmap = FormantHeatmap::new(HeatmapConfig {
+            f1_range: (200.0, 1000.0),
+            f2_range: (500.0, 2500.0),
+            f1_bins: 8,
+            f2_bins: 10,
+        });
+        // /i/ (low F1, high F2) twice, /a/ once, one point off the chart.
+        assert!(heatmap.push(250.0, 2300.0));
+        assert!(heatmap.push(250.0, 2300.0));
+        assert!(heatmap.push(750.0, 1300.0));
+        assert!(!heatmap.push(1500.0, 1000.0));
+        assert_eq!(heatmap.total(), 3);
+
+        // Top-left corner for /i/; /a/ in row 5, column 10 - 1 - 4.
+        assert_eq!(heatmap.counts()[0], 2);
+        assert_eq!(heatmap.counts()[5 * 10 + 5], 1);
+
+        let sharp = heatmap.intensity(0.0);
+        assert_eq!((sharp[0], sharp[55]), (1.0, 0.5));
+        assert_eq!(sharp.iter().filter(|&&v| v > 0.0).count(), 2);
+
+        let blurred = heatmap.intensity(1.0);
+        assert_eq!(blurred[0], 1.0);
+        assert!(blurred[1] > 0.0 && blurred[1] < 1.0 && blurred[10] == blurred[1]);
+        assert!(blurred.iter().all(|v| (0.0..=1.0).contains(v)));
+
+        let mut pixels = vec![0u8; 8 * 10 * 4];
+        heatmap.render_into(&mut pixels, Colormap::Grayscale, 0.0);
+        assert_eq!(pixels[..4], [255, 255, 255, 255]);
+        assert_eq!(pixels[4..8], [0, 0, 0, 255]);
+
+        heatmap.clear();
+        assert!(heatmap.intensity(1.0).iter().all(|&v| v == 0.0));
+    }
+}
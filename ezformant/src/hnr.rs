@@ -0,0 +1,156 @@
+//! Harmonics-to-noise ratio, as in Praat's "To Harmonicity (ac)".
+//!
+//! A frame whose normalized autocorrelation peaks at `r` is modelled as a periodic
+//! part carrying the fraction `r` of the energy plus noise carrying `1 - r`, so the
+//! HNR is `10 * log10(r / (1 - r))` dB.
+
+use crate::pitch::ac::{pitch_ac, AcPitchConfig};
+
+/// HNR reported for silent or unvoiced frames (Praat's convention).
+pub const UNVOICED_HNR: f64 = -200.0;
+
+/// Parameters of the HNR measurement. Defaults match Praat.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct HnrConfig {
+    /// Time between frames in seconds.
+    pub time_step: f64,
+    /// Lowest f0 in Hz; sets the window length together with `periods_per_window`.
+    pub pitch_floor: f64,
+    /// Frames whose peak is below this fraction of the global peak count as silent.
+    pub silence_threshold: f64,
+    /// Window length in periods of the pitch floor.
+    pub periods_per_window: f64,
+}
+
+impl Default for HnrConfig {
+    fn default() -> Self {
+        Self {
+            time_step: 0.01,
+            pitch_floor: 75.0,
+            silence_threshold: 0.1,
+            periods_per_window: 4.5,
+        }
+    }
+}
+
+impl HnrConfig {
+    /// The pitch detector settings Praat uses for harmonicity: no voicing threshold
+    /// and no path costs, so each frame keeps its strongest autocorrelation peak.
+    fn pitch_config(&self, sample_rate: f64) -> AcPitchConfig {
+        AcPitchConfig {
+            time_step: self.time_step,
+            pitch_floor: self.pitch_floor,
+            pitch_ceiling: 0.5 * sample_rate,
+            periods_per_window: self.periods_per_window,
+            max_candidates: 15,
+            silence_threshold: self.silence_threshold,
+            voicing_threshold: 0.0,
+            octave_cost: 0.0,
+            octave_jump_cost: 0.0,
+            voiced_unvoiced_cost: 0.0,
+        }
+    }
+}
+
+/// Measures the harmonics-to-noise ratio of a signal frame by frame.
+///
+/// Frames start at sample 0 and advance by the time step; a trailing partial frame
+/// is dropped.
+///
+/// # Arguments
+///
+/// * `signal`      - The input signal.
+/// * `sample_rate` - The sampling rate of the signal.
+/// * `config`      - The measurement parameters.
+///
+/// # Returns
+///
+/// The HNR in dB of each frame, or [`UNVOICED_HNR`] for silent/unvoiced frames.
+/// Values are limited to ±150 dB.
+#[tracing::instrument(level = "debug", skip_all, fields(len = signal.len(), sample_rate))]
+pub fn hnr(signal: &[f64], sample_rate: f64, config: &HnrConfig) -> Vec<f64> {
+    pitch_ac(signal, sample_rate, &config.pitch_config(sample_rate))
+        .into_iter()
+        .map(|frame| {
+            if !frame.voiced {
+                return UNVOICED_HNR;
+            }
+            let r = frame.confidence;
+            if r <= 1e-15 {
+                -150.0
+            } else if r > 1.0 - 1e-15 {
+                150.0
+            } else {
+                10.0 * (r / (1.0 - r)).log10()
+            }
+        })
+        .collect()
+}
+
+/// Averages the HNR of the voiced frames, like Praat's "Get mean" on a Harmonicity.
+///
+/// # Arguments
+///
+/// * `frames` - Per-frame HNR values, as returned by [`hnr`].
+///
+/// # Returns
+///
+/// The mean HNR in dB, or `None` if no frame is voiced.
+pub fn mean_hnr(frames: &[f64]) -> Option<f64> {
+    let voiced: Vec<f64> = frames
+        .iter()
+        .copied()
+        .filter(|&h| h != UNVOICED_HNR)
+        .collect();
+    if voiced.is_empty() {
+        None
+    } else {
+        Some(voiced.iter().sum::<f64>() / voiced.len() as f64)
+    }
+}
+
+// ------------------
+// Tests
+// ------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::f64::consts::PI;
+
+    fn noisy_tone(noise_amplitude: f64) -> Vec<f64> {
+        let fs = 16000.0;
+        let mut state: u64 = 12345;
+        (0..16000)
+            .map(|n| {
+                state = state
+                    .wrapping_mul(6364136223846793005)
+                    .wrapping_add(1442695040888963407);
+                let noise = (state >> 11) as f64 / (1u64 << 53) as f64 * 2.0 - 1.0;
+                (2.0 * PI * 150.0 * n as f64 / fs).sin() + noise_amplitude * noise
+            })
+            .collect()
+    }
+
+    #[test]
+    fn hnr_tracks_signal_to_noise_ratio() {
+        let fs = 16000.0;
+        let config = HnrConfig::default();
+
+        let clean = mean_hnr(&hnr(&noisy_tone(0.0), fs, &config)).unwrap();
+        assert!(clean > 40.0, "clean HNR = {clean}");
+
+        // Uniform noise in [-a, a] has power a^2 / 3; a sine has power 1/2.
+        let amplitude = (1.5f64 / 10.0).sqrt();
+        let noisy = mean_hnr(&hnr(&noisy_tone(amplitude), fs, &config)).unwrap();
+        assert!((noisy - 10.0).abs() < 2.0, "noisy HNR = {noisy}");
+    }
+
+    #[test]
+    fn hnr_of_silence_is_unvoiced() {
+        let frames = hnr(&[0.0; 8000], 16000.0, &HnrConfig::default());
+        assert!(!frames.is_empty());
+        assert!(frames.iter().all(|&h| h == UNVOICED_HNR));
+        assert_eq!(mean_hnr(&frames), None);
+    }
+}
@@ -0,0 +1,199 @@
+//! Harmonic-percussive source separation by median filtering (Fitzgerald, 2010).
+//!
+//! In a magnitude spectrogram, sustained harmonics are smooth along time and
+//! transients (plosives, clicks) are smooth along frequency. A median across frames
+//! keeps the former, a median across bins keeps the latter, and a soft mask built from
+//! the two splits every bin between them.
+
+use std::collections::VecDeque;
+
+/// Median filter lengths and mask exponent.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct HpssConfig {
+    /// Length of the median along time (in frames) for the harmonic estimate.
+    pub harmonic_frames: usize,
+    /// Length of the median along frequency (in bins) for the percussive estimate.
+    pub percussive_bins: usize,
+    /// Exponent of the soft (Wiener) mask; larger values give a harder split.
+    pub power: f64,
+}
+
+impl Default for HpssConfig {
+    fn default() -> Self {
+        Self {
+            harmonic_frames: 17,
+            percussive_bins: 17,
+            power: 2.0,
+        }
+    }
+}
+
+/// Splits a magnitude spectrogram into harmonic and percussive parts.
+///
+/// # Arguments
+///
+/// * `spectrogram` - Magnitude frames in time order, all of the same length.
+/// * `config`      - The filter lengths.
+///
+/// # Returns
+///
+/// `(harmonic, percussive)` spectrograms of the same shape, which sum to the input.
+pub fn hpss(spectrogram: &[Vec<f64>], config: &HpssConfig) -> (Vec<Vec<f64>>, Vec<Vec<f64>>) {
+    let half = config.harmonic_frames / 2;
+    let mut column = Vec::with_capacity(config.harmonic_frames);
+
+    spectrogram
+        .iter()
+        .enumerate()
+        .map(|(t, frame)| {
+            let neighbours =
+                &spectrogram[t.saturating_sub(half)..(t + half + 1).min(spectrogram.len())];
+            let harmonic: Vec<f64> = (0..frame.len())
+                .map(|k| {
+                    column.clear();
+                    column.extend(neighbours.iter().map(|f| f[k]));
+                    median(&mut column)
+                })
+                .collect();
+            split(frame, &harmonic, config)
+        })
+        .unzip()
+}
+
+/// Streaming variant of [`hpss`] that only looks at past frames, for display paths.
+///
+/// The harmonic estimate is the median over the last `harmonic_frames` frames, so a
+/// transient has to persist for about half that many frames before it passes.
+#[derive(Debug, Clone)]
+pub struct HpssFilter {
+    config: HpssConfig,
+    history: VecDeque<Vec<f64>>,
+}
+
+impl HpssFilter {
+    /// Creates a filter with an empty history.
+    pub fn new(config: HpssConfig) -> Self {
+        Self {
+            history: VecDeque::with_capacity(config.harmonic_frames),
+            config,
+        }
+    }
+
+    /// Adds a magnitude frame and returns its harmonic part.
+    ///
+    /// A frame whose length differs from the previous one clears the history.
+    pub fn push(&mut self, frame: &[f64]) -> Vec<f64> {
+        if self.history.front().is_some_and(|f| f.len() != frame.len()) {
+            self.history.clear();
+        }
+        if self.history.len() == self.config.harmonic_frames.max(1) {
+            self.history.pop_front();
+        }
+        self.history.push_back(frame.to_vec());
+
+        let mut column = Vec::with_capacity(self.history.len());
+        let harmonic: Vec<f64> = (0..frame.len())
+            .map(|k| {
+                column.clear();
+                column.extend(self.history.iter().map(|f| f[k]));
+                median(&mut column)
+            })
+            .collect();
+        split(frame, &harmonic, &self.config).0
+    }
+
+    /// Forgets all past frames.
+    pub fn reset(&mut self) {
+        self.history.clear();
+    }
+}
+
+/// Applies the soft masks built from the time-median `harmonic` and a frequency median
+/// of `frame` to `frame`.
+fn split(frame: &[f64], harmonic: &[f64], config: &HpssConfig) -> (Vec<f64>, Vec<f64>) {
+    let half = config.percussive_bins / 2;
+    let mut row = Vec::with_capacity(config.percussive_bins);
+
+    (0..frame.len())
+        .map(|k| {
+            row.clear();
+            row.extend_from_slice(&frame[k.saturating_sub(half)..(k + half + 1).min(frame.len())]);
+            let h = harmonic[k].powf(config.power);
+            let p = median(&mut row).powf(config.power);
+            let mask = if h + p > 0.0 { h / (h + p) } else { 0.5 };
+            (frame[k] * mask, frame[k] * (1.0 - mask))
+        })
+        .unzip()
+}
+
+/// Median of `values` (mean of the middle two for even lengths); reorders `values`.
+fn median(values: &mut [f64]) -> f64 {
+    let mid = values.len() / 2;
+    let upper = *values.select_nth_unstable_by(mid, f64::total_cmp).1;
+    if values.len() % 2 == 1 {
+        upper
+    } else {
+        let lower = values[..mid]
+            .iter()
+            .copied()
+            .fold(f64::NEG_INFINITY, f64::max);
+        0.5 * (lower + upper)
+    }
+}
+
+// ------------------
+// Tests
+// ------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // A steady tone in bin 8 with a broadband click in frame 10.
+    fn tone_with_click() -> Vec<Vec<f64>> {
+        (0..20)
+            .map(|t| {
+                (0..32)
+                    .map(|k| {
+                        let tone = if k == 8 { 10.0 } else { 0.1 };
+                        let click = if t == 10 { 5.0 } else { 0.0 };
+                        tone + click
+                    })
+                    .collect()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn tone_is_harmonic_and_click_is_percussive() {
+        let spectrogram = tone_with_click();
+        let (harmonic, percussive) = hpss(&spectrogram, &HpssConfig::default());
+
+        for t in 0..spectrogram.len() {
+            for k in 0..32 {
+                let sum = harmonic[t][k] + percussive[t][k];
+                assert!((sum - spectrogram[t][k]).abs() < 1e-9);
+            }
+            // The tone keeps most of its energy, even under the click.
+            assert!(harmonic[t][8] > 0.75 * spectrogram[t][8]);
+        }
+        // Off the tone, the click goes almost entirely to the percussive part.
+        assert!(harmonic[10][20] < 0.01 * spectrogram[10][20]);
+        assert!(percussive[10][20] > 0.99 * spectrogram[10][20]);
+    }
+
+    #[test]
+    fn streaming_filter_suppresses_click() {
+        let mut filter = HpssFilter::new(HpssConfig::default());
+        let outputs: Vec<Vec<f64>> = tone_with_click()
+            .iter()
+            .map(|frame| filter.push(frame))
+            .collect();
+
+        assert!(outputs[10][20] < 0.01 * 5.1);
+        assert!(outputs[10][8] > 10.0 * 0.75);
+
+        filter.reset();
+        assert_eq!(filter.push(&[1.0, 2.0]).len(), 2);
+    }
+}
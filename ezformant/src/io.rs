@@ -0,0 +1,100 @@
+//! Reading recordings from WAV files (native builds with the `io` feature).
+//!
+//! Multichannel files are mixed down to mono and integer samples are scaled to
+//! `[-1, 1]`, so the result can go straight into [`crate::batch::analyze_signal`].
+
+use crate::batch::{analyze_signal, BatchConfig, TrackFrame};
+use std::path::Path;
+
+pub use hound::Error as WavError;
+
+/// Reads a WAV file as mono samples.
+///
+/// # Arguments
+///
+/// * `path` - The file to read. 8 to 32-bit integer and 32-bit float PCM are supported.
+///
+/// # Returns
+///
+/// The samples, averaged over channels and scaled to `[-1, 1]`, and the sampling rate
+/// in Hz.
+pub fn read_wav<P: AsRef<Path>>(path: P) -> Result<(Vec<f64>, f64), WavError> {
+    let mut reader = hound::WavReader::open(path)?;
+    let spec = reader.spec();
+    let interleaved: Vec<f64> = match spec.sample_format {
+        hound::SampleFormat::Float => reader
+            .samples::<f32>()
+            .map(|s| s.map(f64::from))
+            .collect::<Result<_, _>>()?,
+        hound::SampleFormat::Int => {
+            let scale = (1u64 << (spec.bits_per_sample - 1)) as f64;
+            reader
+                .samples::<i32>()
+                .map(|s| s.map(|x| x as f64 / scale))
+                .collect::<Result<_, _>>()?
+        }
+    };
+
+    let channels = spec.channels.max(1) as usize;
+    let samples = interleaved
+        .chunks_exact(channels)
+        .map(|frame| frame.iter().sum::<f64>() / channels as f64)
+        .collect();
+    Ok((samples, spec.sample_rate as f64))
+}
+
+/// Reads a WAV file and analyzes it frame by frame.
+///
+/// # Arguments
+///
+/// * `path`   - The file to analyze.
+/// * `config` - Analysis and framing parameters.
+///
+/// # Returns
+///
+/// The tracks from [`analyze_signal`].
+pub fn analyze_wav<P: AsRef<Path>>(
+    path: P,
+    config: &BatchConfig,
+) -> Result<Vec<TrackFrame>, WavError> {
+    let (samples, sample_rate) = read_wav(path)?;
+    Ok(analyze_signal(&samples, sample_rate, config))
+}
+
+// ------------------
+// Tests
+// ------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reads_a_stereo_16_bit_file_as_mono() {
+        let path = std::env::temp_dir().join(format!("ezformant-io-{}.wav", std::process::id()));
+        let spec = hound::WavSpec {
+            channels: 2,
+            sample_rate: 16000,
+            bits_per_sample: 16,
+            sample_format: hound::SampleFormat::Int,
+        };
+        let mut writer = hound::WavWriter::create(&path, spec).unwrap();
+        for n in 0..8000 {
+            let x = ((n as f64 * 0.05).sin() * 16384.0) as i16;
+            writer.write_sample(x).unwrap();
+            writer.write_sample(0i16).unwrap();
+        }
+        writer.finalize().unwrap();
+
+        let (samples, sample_rate) = read_wav(&path).unwrap();
+        assert_eq!(sample_rate, 16000.0);
+        assert_eq!(samples.len(), 8000);
+        let peak = samples.iter().fold(0.0f64, |m, x| m.max(x.abs()));
+        assert!((peak - 0.25).abs() < 1e-3, "{}", peak);
+
+        let frames = analyze_wav(&path, &BatchConfig::default()).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(frames.len(), 48);
+        assert!(read_wav(&path).is_err());
+    }
+}
@@ -0,0 +1,77 @@
+//! JSON persistence of analysis results (`serde` feature).
+//!
+//! Result types such as [`crate::batch::TrackFrame`], [`crate::pitch::PitchResult`]
+//! and [`crate::textgrid::TextGrid`] derive `Serialize` and `Deserialize` with this
+//! feature, so a session can be saved with [`ToJson::to_json`] and restored with
+//! [`from_json`]. Field names are the Rust ones (snake_case).
+//!
+//! NaN and infinities (e.g. the missing-formant markers of
+//! [`crate::textgrid::IntervalMeasurement`]) are written as `null` and cannot be read
+//! back into `f64` fields.
+
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+
+/// Serializes any result type to JSON.
+pub trait ToJson: Serialize {
+    /// Compact JSON.
+    fn to_json(&self) -> String {
+        serde_json::to_string(self).expect("analysis results serialize to JSON")
+    }
+
+    /// Indented JSON, for files meant to be read by people.
+    fn to_json_pretty(&self) -> String {
+        serde_json::to_string_pretty(self).expect("analysis results serialize to JSON")
+    }
+}
+
+impl<T: Serialize + ?Sized> ToJson for T {}
+
+/// Parses a value written by [`ToJson::to_json`].
+///
+/// # Arguments
+///
+/// * `json` - The JSON text.
+///
+/// # Returns
+///
+/// The value, or a description of where the JSON did not match.
+pub fn from_json<T: DeserializeOwned>(json: &str) -> Result<T, String> {
+    serde_json::from_str(json).map_err(|err| err.to_string())
+}
+
+// ------------------
+// Tests
+// ------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::batch::{BatchConfig, TrackFrame};
+    use crate::quality::Quality;
+
+    #[test]
+    fn results_round_trip_through_json() {
+        let frames = vec![TrackFrame {
+            time: 0.0125,
+            formants: vec![700.0, 1200.0],
+            bandwidths: vec![80.0, 90.0],
+            pitch: -1.0,
+            energy: 0.5,
+            zcr: 0.1,
+            voiced: false,
+            silent: false,
+        }];
+        let json = frames.to_json();
+        assert!(json.starts_with(r#"[{"time":0.0125,"formants":[700.0,1200.0],"#));
+        assert_eq!(from_json::<Vec<TrackFrame>>(&json), Ok(frames));
+
+        let config = BatchConfig {
+            quality: Quality::Fast,
+            ..BatchConfig::default()
+        };
+        assert!(config.to_json_pretty().contains(r#""quality": "fast""#));
+        assert_eq!(from_json(&config.to_json()), Ok(config));
+        assert!(from_json::<BatchConfig>("{}").is_err());
+    }
+}
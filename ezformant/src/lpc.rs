@@ -1,11 +1,15 @@
-use aberth::AberthSolver;
-use rustfft::num_complex::{Complex, ComplexFloat};
+use crate::error::{check_finite, check_positive, EzFormantError, Result};
+use crate::roots::RootSolver;
+use crate::simd::{self, SimdFloat};
+#[cfg(not(feature = "std"))]
+use alloc::{vec, vec::Vec};
+use num_complex::{Complex, ComplexFloat};
 
 /// Applies a pre-emphasis filter to a signal in-place.
 ///
 /// # Arguments
 ///
-/// * `signal` - The signal to be filtered.
+/// * `signal` - The signal to be filtered (`f32` or `f64`).
 /// * `alpha`  - The pre-emphasis coefficient.
 ///
 /// # Example
@@ -14,15 +18,16 @@ use rustfft::num_complex::{Complex, ComplexFloat};
 /// let mut samples = vec![1.0, 2.0, 3.0];
 /// pre_emphasis(&mut samples, 0.95);
 /// ```
-pub fn pre_emphasis(signal: &mut [f64], alpha: f64) {
+pub fn pre_emphasis<T: SimdFloat>(signal: &mut [T], alpha: f64) {
     if signal.is_empty() {
         return;
     }
+    let alpha: T = crate::cast(alpha);
     let mut prev = signal[0];
-    signal[0] = (1.0 - alpha) * prev;
-    for n in 1..signal.len() {
-        let x = signal[n];
-        signal[n] = x - alpha * prev;
+    signal[0] = (T::one() - alpha) * prev;
+    for sample in signal.iter_mut().skip(1) {
+        let x = *sample;
+        *sample = x - alpha * prev;
         prev = x;
     }
 }
@@ -31,29 +36,206 @@ pub fn pre_emphasis(signal: &mut [f64], alpha: f64) {
 ///
 /// # Arguments
 ///
+/// * `signal` - The input signal, `f32` or `f64`; sums accumulate in the same type.
+/// * `maxlag` - The maximum lag for which to compute autocorrelation.
+///
+/// # Returns
+///
+/// A vector containing autocorrelation values from lag 0 to `maxlag`.
+#[tracing::instrument(level = "trace", skip_all, fields(len = signal.len(), maxlag))]
+pub fn autocorrelate<T: SimdFloat>(signal: &[T], maxlag: usize) -> Vec<T> {
+    let mut result = vec![T::zero(); maxlag + 1];
+    autocorrelate_into(signal, &mut result);
+    result
+}
+
+/// Like [`autocorrelate`], writing into a caller-provided buffer so that per-frame
+/// analysis can reuse it.
+///
+/// # Arguments
+///
+/// * `signal` - The input signal, `f32` or `f64`.
+/// * `out`    - Receives the autocorrelation at lags `0..out.len()`.
+pub fn autocorrelate_into<T: SimdFloat>(signal: &[T], out: &mut [T]) {
+    let n = signal.len();
+    for (lag, r) in out.iter_mut().enumerate() {
+        // Only sum while (i + lag) is within the signal’s length
+        *r = if lag < n {
+            T::dot(&signal[..n - lag], &signal[lag..])
+        } else {
+            T::zero()
+        };
+    }
+}
+
+/// Computes the autocorrelation of a signal up to a specified lag using the FFT.
+///
+/// The signal is zero-padded to avoid circular wrap-around, so the result matches
+/// [`autocorrelate`] up to floating-point rounding. Cheaper than the direct sum when
+/// `maxlag` is large.
+///
+/// # Arguments
+///
 /// * `signal` - A slice of f64 representing the input signal.
 /// * `maxlag` - The maximum lag for which to compute autocorrelation.
 ///
 /// # Returns
 ///
 /// A vector containing autocorrelation values from lag 0 to `maxlag`.
-pub fn autocorrelate(signal: &[f64], maxlag: usize) -> Vec<f64> {
+#[cfg(feature = "std")]
+#[tracing::instrument(level = "trace", skip_all, fields(len = signal.len(), maxlag))]
+pub fn autocorrelate_fft(signal: &[f64], maxlag: usize) -> Vec<f64> {
     let n = signal.len();
-    let mut result = Vec::with_capacity(maxlag + 1);
+    if n == 0 {
+        return vec![0.0; maxlag + 1];
+    }
+    let fft_len = (2 * n - 1).next_power_of_two();
 
-    for lag in 0..=maxlag {
-        // Only sum while (i + lag) is within the signal’s length
-        let r: f64 = signal
-            .iter()
-            .enumerate()
-            .take_while(|(i, _)| i + lag < n)
-            .map(|(i, &val)| val * signal[i + lag])
-            .sum();
+    let mut buffer: Vec<Complex<f64>> = signal
+        .iter()
+        .map(|&x| Complex::new(x, 0.0))
+        .chain(std::iter::repeat(Complex::new(0.0, 0.0)))
+        .take(fft_len)
+        .collect();
 
-        result.push(r);
+    crate::fft::forward::<f64>(fft_len).process(&mut buffer);
+    for x in buffer.iter_mut() {
+        *x = Complex::new(x.norm_sqr(), 0.0);
     }
+    crate::fft::inverse::<f64>(fft_len).process(&mut buffer);
 
-    result
+    let scale = 1.0 / fft_len as f64;
+    (0..=maxlag)
+        .map(|lag| if lag < n { buffer[lag].re * scale } else { 0.0 })
+        .collect()
+}
+
+/// Computes the autocorrelation from a one-sided power spectrum by inverse FFT
+/// (Wiener-Khinchin).
+///
+/// Bin `k` of `power` is taken to lie at `k * fs / (2 * power.len())`, the layout of
+/// the Web Audio `AnalyserNode` (no Nyquist bin). The result is the circular
+/// autocorrelation of the frame the spectrum came from, scaled by its FFT length; the
+/// scale does not change the LPC coefficients.
+///
+/// # Arguments
+///
+/// * `power`  - The power spectrum from DC up to (not including) Nyquist.
+/// * `maxlag` - The maximum lag for which to compute autocorrelation.
+///
+/// # Returns
+///
+/// A vector containing autocorrelation values from lag 0 to `maxlag`.
+#[cfg(feature = "std")]
+#[tracing::instrument(level = "trace", skip_all, fields(bins = power.len(), maxlag))]
+pub fn autocorrelate_from_power_spectrum(power: &[f64], maxlag: usize) -> Vec<f64> {
+    let bins = power.len();
+    if bins == 0 {
+        return vec![0.0; maxlag + 1];
+    }
+    let fft_len = 2 * bins;
+
+    // Mirror into a real, even spectrum; the Nyquist bin is unknown and left at zero.
+    let mut buffer = vec![Complex::new(0.0, 0.0); fft_len];
+    for (k, &p) in power.iter().enumerate() {
+        buffer[k] = Complex::new(p, 0.0);
+        if k > 0 {
+            buffer[fft_len - k] = Complex::new(p, 0.0);
+        }
+    }
+    crate::fft::inverse::<f64>(fft_len).process(&mut buffer);
+
+    let scale = 1.0 / fft_len as f64;
+    (0..=maxlag)
+        .map(|lag| buffer.get(lag).map_or(0.0, |x| x.re * scale))
+        .collect()
+}
+
+/// Strategy used to compute the autocorrelation sequence.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum AutocorrelationMethod {
+    /// Direct `O(N * maxlag)` summation (see [`autocorrelate`]).
+    #[default]
+    Direct,
+    /// Zero-padded FFT, `O(N log N)` (see [`autocorrelate_fft`]). Without the `std`
+    /// feature it computes directly.
+    Fft,
+}
+
+impl AutocorrelationMethod {
+    /// Computes the autocorrelation of `signal` from lag 0 to `maxlag` with this method.
+    pub fn autocorrelate(&self, signal: &[f64], maxlag: usize) -> Vec<f64> {
+        match self {
+            AutocorrelationMethod::Direct => autocorrelate(signal, maxlag),
+            #[cfg(feature = "std")]
+            AutocorrelationMethod::Fft => autocorrelate_fft(signal, maxlag),
+            #[cfg(not(feature = "std"))]
+            AutocorrelationMethod::Fft => autocorrelate(signal, maxlag),
+        }
+    }
+}
+
+/// Regularization of the autocorrelation sequence before [`levinson`].
+///
+/// Nearly periodic, noise-free frames (a sustained vowel in a quiet room) give an
+/// almost singular autocorrelation matrix, and the recursion then places poles on the
+/// unit circle at harmonics instead of formants. A little white noise and a lag window
+/// widen the peaks and keep the problem well conditioned. The default does nothing;
+/// `white_noise: 1e-4` (a floor 40 dB below the frame) with a 60 Hz bandwidth is a
+/// common choice in speech coders.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct LagWindow {
+    /// White-noise correction: `r[0]` is multiplied by `1 + white_noise`.
+    pub white_noise: f64,
+    /// Bandwidth in Hz of a Gaussian lag window `exp(-0.5 (2 pi B k / fs)^2)`, which
+    /// smooths the spectrum by a Gaussian of that width. `None` disables it.
+    pub bandwidth: Option<f64>,
+}
+
+impl LagWindow {
+    /// Regularizes `r` in-place.
+    ///
+    /// # Arguments
+    ///
+    /// * `r`           - The autocorrelation sequence, lag 0 first.
+    /// * `sample_rate` - The sampling rate of the analyzed frame.
+    pub fn apply(&self, r: &mut [f64], sample_rate: f64) {
+        if let Some(first) = r.first_mut() {
+            *first *= 1.0 + self.white_noise;
+        }
+        if let Some(bandwidth) = self.bandwidth {
+            let a = 2.0 * core::f64::consts::PI * bandwidth / sample_rate;
+            for (k, value) in r.iter_mut().enumerate().skip(1) {
+                *value *= (-0.5 * (a * k as f64).powi(2)).exp();
+            }
+        }
+    }
+}
+
+/// Full output of the Levinson-Durbin recursion, see [`levinson_full`].
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct LevinsonResult {
+    /// Filter coefficients `[a0, a1, ..., a_order]` (with `a0 = 1.0`).
+    pub coefficients: Vec<f64>,
+    /// Prediction error after each order: `errors[0] = r[0]`, `errors[order]` is the
+    /// final error.
+    pub errors: Vec<f64>,
+    /// Reflection (PARCOR) coefficients `[k1, ..., k_order]`, with the sign convention
+    /// of the recursion (`k_i` is the last coefficient of the order-`i` filter).
+    pub reflection: Vec<f64>,
+}
+
+impl LevinsonResult {
+    /// The final prediction error.
+    pub fn error(&self) -> f64 {
+        *self.errors.last().unwrap()
+    }
+
+    /// Whether the all-pole filter is stable (every `|k_i| < 1`).
+    pub fn is_stable(&self) -> bool {
+        self.reflection.iter().all(|k| k.abs() < 1.0)
+    }
 }
 
 /// Implements the Levinson-Durbin recursion algorithm iteratively.
@@ -62,20 +244,107 @@ pub fn autocorrelate(signal: &[f64], maxlag: usize) -> Vec<f64> {
 ///
 /// * `order`  - The order of the recursion (filter).
 /// * `r`      - A slice of f64 representing the autocorrelation coefficients.
-///              Must have length >= `order + 1`.
+///   Must have length >= `order + 1`.
 ///
 /// # Returns
 ///
 /// A tuple containing:
 /// - A vector of filter coefficients `[a0, a1, ..., a_order]` (with `a0 = 1.0`).
 /// - The final prediction error (`E`).
+///
+/// # Panics
+///
+/// Panics if `r` has `order` or fewer elements; see [`try_levinson`].
 pub fn levinson(order: usize, r: &[f64]) -> (Vec<f64>, f64) {
-    assert!(r.len() >= order + 1, "r too short");
+    assert!(r.len() > order, "r too short");
     let mut a = vec![0.0; order + 1];
-    a[0] = 1.0;
+    let error = levinson_into(r, &mut a);
+    (a, error)
+}
+
+/// Fits an all-pole model to a preprocessed frame by the autocorrelation method.
+///
+/// # Arguments
+///
+/// * `data`      - The frame, already windowed and pre-emphasized.
+/// * `lpc_order` - The LPC order.
+///
+/// # Returns
+///
+/// The coefficients and final prediction error, as from [`levinson`]. Frames of
+/// `lpc_order` samples or fewer get the flat order-0 model `[1.0]`, with no formants,
+/// instead of poles fitted to the zero padding.
+pub fn fit(data: &[f64], lpc_order: usize) -> (Vec<f64>, f64) {
+    let order = if data.len() > lpc_order { lpc_order } else { 0 };
+    levinson(order, &autocorrelate(data, order))
+}
 
-    let mut e = if r[0].abs() < 1e-12 { 1e-12 } else { r[0] };
+/// Like [`levinson`], writing the coefficients into a caller-provided buffer and
+/// updating them in place instead of copying them at every order.
+///
+/// # Arguments
+///
+/// * `r` - The autocorrelation coefficients, at least `a.len()` of them.
+/// * `a` - Receives the coefficients `[1.0, a1, ..., a_order]`; its length sets the
+///   order plus one.
+///
+/// # Returns
+///
+/// The final prediction error.
+///
+/// # Panics
+///
+/// Panics if `r` is shorter than `a`.
+pub fn levinson_into(r: &[f64], a: &mut [f64]) -> f64 {
+    levinson_recursion(r, a, None)
+}
 
+/// Like [`levinson`], but also returns the prediction error and reflection
+/// coefficient of every intermediate order, e.g. for order selection or stability
+/// checks.
+///
+/// # Panics
+///
+/// Panics if `r` has `order` or fewer elements.
+#[tracing::instrument(level = "trace", skip_all, fields(order))]
+pub fn levinson_full(order: usize, r: &[f64]) -> LevinsonResult {
+    assert!(r.len() > order, "r too short");
+    let mut a = vec![0.0; order + 1];
+    let mut errors = Vec::with_capacity(order + 1);
+    let mut reflection = Vec::with_capacity(order);
+    levinson_recursion(r, &mut a, Some((&mut errors, &mut reflection)));
+    LevinsonResult {
+        coefficients: a,
+        errors,
+        reflection,
+    }
+}
+
+/// The recursion behind [`levinson_into`] and [`levinson_full`], updating `a` in place.
+/// With `trace`, pushes the prediction error of every order (order 0 first) to the
+/// first vector and the reflection coefficient of every order above 0 to the second.
+fn levinson_recursion(
+    r: &[f64],
+    a: &mut [f64],
+    mut trace: Option<(&mut Vec<f64>, &mut Vec<f64>)>,
+) -> f64 {
+    let order = a.len().saturating_sub(1);
+    assert!(r.len() > order, "r too short");
+    a.fill(0.0);
+    if a.is_empty() {
+        return r[0];
+    }
+    a[0] = 1.0;
+
+    let mut e = if r[0].abs() < 1e-12 {
+        tracing::debug!(r0 = r[0], "zero-energy frame");
+        1e-12
+    } else {
+        r[0]
+    };
+    if let Some((errors, _)) = &mut trace {
+        errors.push(e);
+    }
     for i in 1..=order {
         let mut acc = r[i];
         for j in 1..i {
@@ -83,19 +352,153 @@ pub fn levinson(order: usize, r: &[f64]) -> (Vec<f64>, f64) {
         }
         let k = -acc / e;
 
-        let mut a_new = a.clone();
-        for j in 1..i {
-            a_new[j] = a[j] + k * a[i - j];
+        // a[j] and a[i - j] depend on each other, so update them in pairs.
+        for j in 1..=i / 2 {
+            let (x, y) = (a[j], a[i - j]);
+            a[j] = x + k * y;
+            if j != i - j {
+                a[i - j] = y + k * x;
+            }
         }
-        a_new[i] = k;
-        a = a_new;
+        a[i] = k;
 
         e *= 1.0 - k * k;
         if e < 1e-12 {
+            tracing::debug!(
+                i,
+                k,
+                "prediction error collapsed; autocorrelation is near-singular"
+            );
             e = 1e-12;
         }
+        if let Some((errors, reflection)) = &mut trace {
+            errors.push(e);
+            reflection.push(k);
+        }
     }
-    (a, e)
+    e
+}
+
+/// Estimates LPC coefficients with the covariance method: minimizes the prediction
+/// error over `signal[order..]` exactly, using the first `order` samples only as
+/// history. Unlike the autocorrelation method it needs no window, so it suits very
+/// short segments such as the closed phase of a glottal cycle, but the resulting
+/// filter is not guaranteed to be stable (see [`stabilize`]).
+///
+/// # Arguments
+///
+/// * `signal` - The segment, including `order` samples of history at the start.
+/// * `order`  - The LPC order.
+///
+/// # Returns
+///
+/// The coefficients `[1.0, a1, ..., a_order]`, or `None` if the segment is shorter
+/// than `2 * order` samples or silent.
+pub fn covariance_method(signal: &[f64], order: usize) -> Option<Vec<f64>> {
+    if signal.len() < 2 * order {
+        return None;
+    }
+    let phi = |i: usize, k: usize| -> f64 {
+        (order..signal.len())
+            .map(|n| signal[n - i] * signal[n - k])
+            .sum()
+    };
+    // Normal equations `Phi a = -psi`, with `Phi[i][k] = phi(i + 1, k + 1)`.
+    let mut matrix: Vec<Vec<f64>> = (1..=order)
+        .map(|i| (1..=order).map(|k| phi(i, k)).collect())
+        .collect();
+    let rhs: Vec<f64> = (1..=order).map(|i| -phi(i, 0)).collect();
+
+    // A tiny ridge keeps noise-free segments, whose `Phi` is rank-deficient when the
+    // order exceeds the number of resonances, solvable.
+    let scale = (0..order).map(|i| matrix[i][i]).fold(0.0, f64::max);
+    if scale == 0.0 {
+        return None;
+    }
+    for (i, row) in matrix.iter_mut().enumerate() {
+        row[i] += 1e-9 * scale;
+    }
+
+    // In-place Cholesky factorization `Phi = L L^T`.
+    for j in 0..order {
+        let diagonal = matrix[j][j] - (0..j).map(|k| matrix[j][k].powi(2)).sum::<f64>();
+        if diagonal <= 0.0 {
+            return None;
+        }
+        matrix[j][j] = diagonal.sqrt();
+        for i in j + 1..order {
+            let dot: f64 = (0..j).map(|k| matrix[i][k] * matrix[j][k]).sum();
+            matrix[i][j] = (matrix[i][j] - dot) / matrix[j][j];
+        }
+    }
+    // Forward then backward substitution.
+    let mut y = vec![0.0; order];
+    for i in 0..order {
+        let dot: f64 = (0..i).map(|k| matrix[i][k] * y[k]).sum();
+        y[i] = (rhs[i] - dot) / matrix[i][i];
+    }
+    let mut a = vec![0.0; order];
+    for i in (0..order).rev() {
+        let dot: f64 = (i + 1..order).map(|k| matrix[k][i] * a[k]).sum();
+        a[i] = (y[i] - dot) / matrix[i][i];
+    }
+
+    let mut coefficients = Vec::with_capacity(order + 1);
+    coefficients.push(1.0);
+    coefficients.extend(a);
+    Some(coefficients)
+}
+
+/// Checks that a frame can be modelled with `order` coefficients: it must be non-empty,
+/// finite and longer than `order`. Shorter frames leave the autocorrelation zero-padded,
+/// and the model fitted to it has evenly spaced poles that look like formants.
+///
+/// # Returns
+///
+/// [`EzFormantError::InputTooShort`] with the number of samples required, or the error
+/// for an empty or non-finite frame.
+pub fn check_frame(frame: &[f64], order: usize) -> Result<()> {
+    check_finite(frame)?;
+    if frame.len() <= order {
+        return Err(EzFormantError::InputTooShort {
+            len: frame.len(),
+            required: order + 1,
+        });
+    }
+    Ok(())
+}
+
+/// Checks LPC coefficients computed elsewhere, e.g. cached or received from another
+/// process, before extracting formants: they must be non-empty and finite, and
+/// `sample_rate` positive.
+pub fn check_coefficients(lpc_coeffs: &[f64], sample_rate: f64) -> Result<()> {
+    check_finite(lpc_coeffs)?;
+    check_positive("sample_rate", sample_rate)
+}
+
+/// Like [`levinson`], but returns an error instead of panicking when `r` is too short,
+/// and rejects non-finite autocorrelation values.
+pub fn try_levinson(order: usize, r: &[f64]) -> Result<(Vec<f64>, f64)> {
+    check_autocorrelation(order, r)?;
+    Ok(levinson(order, r))
+}
+
+/// Like [`levinson_full`], with the checks of [`try_levinson`].
+pub fn try_levinson_full(order: usize, r: &[f64]) -> Result<LevinsonResult> {
+    check_autocorrelation(order, r)?;
+    Ok(levinson_full(order, r))
+}
+
+/// Checks that `r` is finite and has the `order + 1` values the recursion reads.
+fn check_autocorrelation(order: usize, r: &[f64]) -> Result<()> {
+    check_finite(r)?;
+    if r.len() <= order {
+        return Err(EzFormantError::InputTooShort {
+            len: r.len(),
+            required: order + 1,
+        });
+    }
+    Ok(())
 }
 
 /// Implements the Levinson-Durbin recursion algorithm iteratively.
@@ -105,7 +508,7 @@ pub fn levinson(order: usize, r: &[f64]) -> (Vec<f64>, f64) {
 ///
 /// * `order`  - The order of the recursion (filter).
 /// * `r`      - A slice of f64 representing the autocorrelation coefficients.
-///              Must have length >= `order + 1`.
+///   Must have length >= `order + 1`.
 ///
 /// # Returns
 ///
@@ -118,6 +521,154 @@ pub fn levinson_reversed(order: usize, r: &[f64]) -> (Vec<f64>, f64) {
     (a, e)
 }
 
+/// Recovers the reflection coefficients of an LPC polynomial with the step-down
+/// (backward Levinson) recursion.
+///
+/// # Arguments
+///
+/// * `lpc_coeffs` - The LPC coefficients `[1.0, a1, ..., ap]`.
+///
+/// # Returns
+///
+/// `[k1, ..., kp]` with the sign convention of [`LevinsonResult::reflection`], or
+/// `None` if some `|k_i| >= 1`, in which case the lower orders are undefined.
+pub fn reflection_coefficients(lpc_coeffs: &[f64]) -> Option<Vec<f64>> {
+    let order = lpc_coeffs.len().saturating_sub(1);
+    let mut a = lpc_coeffs.to_vec();
+    let mut reflection = vec![0.0; order];
+    for i in (1..=order).rev() {
+        let k = a[i];
+        if k.is_nan() || k.abs() >= 1.0 {
+            return None;
+        }
+        reflection[i - 1] = k;
+        let scale = 1.0 - k * k;
+        a = (0..i)
+            .map(|j| {
+                if j == 0 {
+                    1.0
+                } else {
+                    (a[j] - k * a[i - j]) / scale
+                }
+            })
+            .collect();
+    }
+    Some(reflection)
+}
+
+/// Whether the all-pole filter `1 / A(z)` is stable, i.e. every root of `A` lies
+/// strictly inside the unit circle.
+pub fn is_minimum_phase(lpc_coeffs: &[f64]) -> bool {
+    reflection_coefficients(lpc_coeffs).is_some()
+}
+
+/// Largest pole radius kept by [`stabilize`]; poles on the unit circle are pulled in
+/// to this radius.
+pub const MAX_POLE_RADIUS: f64 = 0.9999;
+
+/// Makes an LPC polynomial minimum-phase by reflecting every pole outside the unit
+/// circle to its conjugate reciprocal `1 / conj(p)`, then pulling any reflected pole
+/// beyond [`MAX_POLE_RADIUS`] in to that radius.
+///
+/// Reflection alone leaves the shape of the magnitude response unchanged (up to a
+/// constant gain), so formant frequencies are preserved while the envelope stays
+/// finite. The pull-in does change the shape: it widens the peaks of poles on or next
+/// to the unit circle, though their frequencies stay put.
+///
+/// # Arguments
+///
+/// * `lpc_coeffs` - The LPC coefficients `[1.0, a1, ..., ap]`.
+/// * `solver`     - The root solver used when the polynomial is unstable.
+///
+/// # Returns
+///
+/// The coefficients unchanged if they are already minimum-phase, otherwise the
+/// coefficients of the stabilized polynomial.
+pub fn stabilize(lpc_coeffs: &[f64], solver: RootSolver) -> Vec<f64> {
+    if is_minimum_phase(lpc_coeffs) {
+        return lpc_coeffs.to_vec();
+    }
+    tracing::debug!(
+        order = lpc_coeffs.len().saturating_sub(1),
+        "reflecting unstable LPC poles"
+    );
+
+    let poles = solver.roots(lpc_coeffs).into_iter().map(|p| {
+        let radius = p.norm();
+        if radius >= 1.0 {
+            let reflected = 1.0 / p.conj();
+            reflected * (reflected.norm().min(MAX_POLE_RADIUS) / reflected.norm())
+        } else {
+            p
+        }
+    });
+
+    // Expand prod (z - p) into descending powers; the imaginary parts cancel for
+    // conjugate pairs.
+    let mut poly = vec![Complex::new(1.0, 0.0)];
+    for pole in poles {
+        poly.push(Complex::new(0.0, 0.0));
+        for j in (1..poly.len()).rev() {
+            let previous = poly[j - 1];
+            poly[j] -= pole * previous;
+        }
+    }
+    poly.into_iter().map(|c| c.re).collect()
+}
+
+/// Converts LPC coefficients to cepstral coefficients of the all-pole model with the
+/// standard recursion
+/// `c_n = -a_n - sum_{k=1}^{n-1} (k / n) c_k a_{n-k}` (with `a_n = 0` for `n > p`).
+///
+/// # Arguments
+///
+/// * `lpc_coeffs` - The LPC coefficients `[1.0, a1, ..., ap]`.
+/// * `n_coeffs`   - The number of cepstral coefficients; may exceed `p`.
+///
+/// # Returns
+///
+/// `[c1, ..., c_n_coeffs]`. The gain term `c0 = ln(E) / 2`, with `E` the prediction
+/// error, is left to the caller since it only depends on the frame energy.
+pub fn lpc_to_cepstrum(lpc_coeffs: &[f64], n_coeffs: usize) -> Vec<f64> {
+    let order = lpc_coeffs.len().saturating_sub(1);
+    let a = |i: usize| if i <= order { lpc_coeffs[i] } else { 0.0 };
+    // c[0] is unused so that c[k] is c_k.
+    let mut c = vec![0.0; n_coeffs + 1];
+    for n in 1..=n_coeffs {
+        let sum: f64 = (n.saturating_sub(order).max(1)..n)
+            .map(|k| k as f64 * c[k] * a(n - k))
+            .sum();
+        c[n] = -a(n) - sum / n as f64;
+    }
+    c.split_off(1)
+}
+
+/// Filters a signal through the LPC inverse filter `A(z)`, giving the prediction
+/// residual (an estimate of the excitation):
+/// `e[n] = x[n] + a1 x[n-1] + ... + ap x[n-p]`, with `x[n] = 0` before the start.
+///
+/// # Arguments
+///
+/// * `signal`     - The signal to filter, normally pre-emphasized like the frame the
+///   coefficients were estimated from, but not windowed.
+/// * `lpc_coeffs` - The LPC coefficients `[1.0, a1, ..., ap]`.
+///
+/// # Returns
+///
+/// The residual, the same length as `signal`.
+pub fn inverse_filter(signal: &[f64], lpc_coeffs: &[f64]) -> Vec<f64> {
+    (0..signal.len())
+        .map(|n| {
+            lpc_coeffs
+                .iter()
+                .take(n + 1)
+                .enumerate()
+                .map(|(k, a)| a * signal[n - k])
+                .sum()
+        })
+        .collect()
+}
+
 /// Computes the frequency response of the LPC filter.
 ///
 /// # Arguments
@@ -134,24 +685,115 @@ pub fn compute_frequency_response(
     sample_rate: f64,
     num_points: usize,
 ) -> Vec<(f64, f64)> {
-    let mut response = Vec::with_capacity(num_points);
-
-    for i in 0..num_points {
-        let freq = i as f64 / num_points as f64 * sample_rate / 2.0;
-        let omega = 2.0 * std::f64::consts::PI * freq / sample_rate;
-        let z = Complex::new(omega.cos(), -omega.sin()); // e^{-j omega}
+    let frequencies: Vec<f64> = (0..num_points)
+        .map(|i| i as f64 / num_points as f64 * sample_rate / 2.0)
+        .collect();
+    frequency_response_at(lpc_coeffs, sample_rate, &frequencies)
+}
 
-        let denominator: Complex<f64> = lpc_coeffs
-            .iter()
-            .enumerate()
-            .map(|(k, &a_k)| z.powi(k as i32) * a_k)
-            .sum();
+/// Computes the frequency response of the LPC filter at the given frequencies, e.g. a
+/// logarithmic grid from [`crate::units::frequency_grid`].
+///
+/// # Arguments
+///
+/// * `lpc_coeffs`  - The LPC coefficients.
+/// * `sample_rate` - The sampling rate of the original signal.
+/// * `frequencies` - The frequencies to evaluate, in Hz, in any order.
+///
+/// # Returns
+///
+/// A vector of `(frequency, magnitude)` pairs, one per requested frequency.
+pub fn frequency_response_at(
+    lpc_coeffs: &[f64],
+    sample_rate: f64,
+    frequencies: &[f64],
+) -> Vec<(f64, f64)> {
+    let mut magnitudes = vec![0.0; frequencies.len()];
+    frequency_response_into(lpc_coeffs, sample_rate, frequencies, &mut magnitudes);
+    frequencies.iter().copied().zip(magnitudes).collect()
+}
 
-        let h = Complex::new(1.0, 0.0) / denominator;
-        response.push((freq, h.norm()));
+/// Like [`frequency_response_at`], writing only the magnitudes into a caller-provided
+/// buffer. Points are evaluated in blocks on the stack, so nothing is allocated.
+///
+/// # Arguments
+///
+/// * `lpc_coeffs`  - The LPC coefficients.
+/// * `sample_rate` - The sampling rate of the original signal.
+/// * `frequencies` - The frequencies to evaluate, in Hz, e.g. computed once per display.
+/// * `out`         - Receives one magnitude per frequency, over the length of the
+///   shorter slice.
+pub fn frequency_response_into(
+    lpc_coeffs: &[f64],
+    sample_rate: f64,
+    frequencies: &[f64],
+    out: &mut [f64],
+) {
+    const BLOCK: usize = 64;
+    let mut points = [Complex::new(0.0, 0.0); BLOCK];
+    let mut values = [Complex::new(0.0, 0.0); BLOCK];
+    for (frequencies, out) in frequencies.chunks(BLOCK).zip(out.chunks_mut(BLOCK)) {
+        let len = frequencies.len().min(out.len());
+        for (point, &freq) in points.iter_mut().zip(&frequencies[..len]) {
+            let omega = 2.0 * core::f64::consts::PI * freq / sample_rate;
+            *point = Complex::new(omega.cos(), -omega.sin()); // e^{-j omega}
+        }
+        simd::evaluate_polynomial_into(lpc_coeffs, &points[..len], &mut values[..len]);
+        for (magnitude, a) in out.iter_mut().zip(&values[..len]) {
+            *magnitude = (Complex::new(1.0, 0.0) / a).norm();
+        }
     }
+}
 
-    response
+/// Computes the model spectrum `sqrt(error) / |A|` at the given frequencies: the LPC
+/// envelope scaled by the model gain, so that it has the level of the unnormalized DFT
+/// magnitude of the analyzed frame.
+///
+/// # Arguments
+///
+/// * `lpc_coeffs`       - The LPC coefficients.
+/// * `prediction_error` - The final prediction error returned by [`levinson`], computed
+///   from the unnormalized autocorrelation of the frame.
+/// * `sample_rate`      - The sampling rate of the original signal.
+/// * `frequencies`      - The frequencies to evaluate, in Hz.
+///
+/// # Returns
+///
+/// A vector of `(frequency, magnitude)` pairs; convert with `20 log10` for dB.
+pub fn model_frequency_response(
+    lpc_coeffs: &[f64],
+    prediction_error: f64,
+    sample_rate: f64,
+    frequencies: &[f64],
+) -> Vec<(f64, f64)> {
+    let gain = prediction_error.max(0.0).sqrt();
+    frequency_response_at(lpc_coeffs, sample_rate, frequencies)
+        .into_iter()
+        .map(|(freq, magnitude)| (freq, gain * magnitude))
+        .collect()
+}
+
+/// Evaluates `sum_k a_k w^k` with Horner's rule: one complex multiply-add per
+/// coefficient and no powers, which keeps high orders accurate.
+pub(crate) fn evaluate_polynomial(coeffs: &[f64], w: Complex<f64>) -> Complex<f64> {
+    coeffs
+        .iter()
+        .rev()
+        .fold(Complex::new(0.0, 0.0), |acc, &a_k| acc * w + a_k)
+}
+
+/// A pole of the LPC filter together with its resonance parameters.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Peak {
+    /// Frequency of the pole angle, in Hz.
+    pub frequency: f64,
+    /// 3 dB bandwidth implied by the pole radius, in Hz.
+    pub bandwidth: f64,
+    /// Magnitude of the pole (`< 1.0` for a stable resonance).
+    pub radius: f64,
+    /// Whether the peak passes the frequency filter of [`formant_detection`].
+    pub is_formant: bool,
 }
 
 /// Detects peaks (roots' angles) given LPC coefficients using the Aberth method.
@@ -165,39 +807,86 @@ pub fn compute_frequency_response(
 ///
 /// A vector of frequencies (in Hz) corresponding to the angles of the polynomial roots.
 pub fn peak_detection(lpc_coeffs: &[f64], sample_rate: f64) -> Vec<f64> {
-    const EPSILON: f64 = 0.001;
-    const MAX_ITERATIONS: u32 = 15;
-
-    let mut poly = lpc_coeffs.to_vec();
-    poly.reverse();
-
-    let mut solver = AberthSolver::new();
-    solver.epsilon = EPSILON;
-    solver.max_iterations = MAX_ITERATIONS;
+    peak_detection_with_solver(lpc_coeffs, sample_rate, RootSolver::default())
+}
 
-    let roots = solver.find_roots(&poly).to_vec();
-    let mut peaks = Vec::with_capacity(roots.len());
+/// Detects peaks (roots' angles) given LPC coefficients using the given root solver.
+///
+/// # Arguments
+///
+/// * `lpc_coeffs`  - The LPC coefficients.
+/// * `sample_rate` - The sampling rate of the original signal.
+/// * `solver`      - The polynomial root finding strategy.
+///
+/// # Returns
+///
+/// A vector of frequencies (in Hz) corresponding to the angles of the polynomial roots.
+pub fn peak_detection_with_solver(
+    lpc_coeffs: &[f64],
+    sample_rate: f64,
+    solver: RootSolver,
+) -> Vec<f64> {
+    peak_detection_with_metadata(lpc_coeffs, sample_rate, solver)
+        .into_iter()
+        .map(|peak| peak.frequency)
+        .collect()
+}
 
-    for root in roots {
-        let theta = root.arg();
+/// Detects peaks given LPC coefficients and reports the resonance parameters of each.
+///
+/// Unstable roots (`|z| > 1`) and the lower half of each conjugate pair are discarded,
+/// exactly as in [`peak_detection`].
+///
+/// # Arguments
+///
+/// * `lpc_coeffs`  - The LPC coefficients.
+/// * `sample_rate` - The sampling rate of the original signal.
+/// * `solver`      - The polynomial root finding strategy.
+///
+/// # Returns
+///
+/// One [`Peak`] per retained root, in the order the solver returned them.
+pub fn peak_detection_with_metadata(
+    lpc_coeffs: &[f64],
+    sample_rate: f64,
+    solver: RootSolver,
+) -> Vec<Peak> {
+    let roots = solver.roots(lpc_coeffs);
+    tracing::trace!(?solver, roots = roots.len(), "polynomial roots");
+    peaks_from_roots(roots, sample_rate)
+}
 
+/// Turns the roots of an LPC polynomial into [`Peak`]s, discarding unstable roots and
+/// the lower half of each conjugate pair.
+pub(crate) fn peaks_from_roots(roots: Vec<Complex<f64>>, sample_rate: f64) -> Vec<Peak> {
+    roots
+        .into_iter()
         // |z|>1.0+ε は非安定 pole
         // Im z >= 0 で共役解の重複を排除
-        if root.norm() > 1.0 + 1e-9 || root.im() < 0.0 {
-            continue;
-        }
+        .filter(|root| root.norm() <= 1.0 + 1e-9 && root.im() >= 0.0)
+        .map(|root| peak_from_root(root, sample_rate))
+        .collect()
+}
 
-        // Convert angle to a frequency in Hz
-        if theta >= 0.0 {
-            peaks.push(theta * sample_rate / (2.0 * std::f64::consts::PI));
-        } else if theta >= -std::f64::consts::PI && theta < 0.0 {
-            // Shift negative angle into [0, 2π)
-            let shifted = theta + 2.0 * std::f64::consts::PI;
-            peaks.push(shifted * sample_rate / (2.0 * std::f64::consts::PI));
-        }
+/// The resonance parameters of one root of an LPC polynomial.
+pub(crate) fn peak_from_root(root: Complex<f64>, sample_rate: f64) -> Peak {
+    let radius = root.norm();
+    // Shift negative angles (real roots at -0.0i) into [0, 2π)
+    let theta = root.arg().rem_euclid(2.0 * core::f64::consts::PI);
+    let frequency = theta * sample_rate / (2.0 * core::f64::consts::PI);
+    Peak {
+        frequency,
+        bandwidth: -radius.ln() * sample_rate / core::f64::consts::PI,
+        radius,
+        is_formant: is_formant_frequency(frequency, sample_rate),
     }
+}
 
-    peaks
+/// Rejects peaks at DC and at the Nyquist frequency, which are not vocal tract resonances.
+fn is_formant_frequency(frequency: f64, sample_rate: f64) -> bool {
+    let low_cutoff = 10.0;
+    let high_cutoff = (sample_rate / 2.0) - 10.0;
+    frequency > low_cutoff && frequency < high_cutoff
 }
 
 /// Performs formant detection from LPC coefficients by selecting valid peaks.
@@ -211,18 +900,40 @@ pub fn peak_detection(lpc_coeffs: &[f64], sample_rate: f64) -> Vec<f64> {
 ///
 /// A vector of formant frequencies in Hz.
 pub fn formant_detection(lpc_coeffs: &[f64], sample_rate: f64) -> Vec<f64> {
-    let mut peaks = peak_detection(lpc_coeffs, sample_rate);
-    let mut formants = Vec::with_capacity(peaks.len());
+    formant_detection_with_solver(lpc_coeffs, sample_rate, RootSolver::default())
+}
 
-    let low_cutoff = 10.0;
-    let high_cutoff = (sample_rate / 2.0) - 10.0;
+/// Like [`formant_detection`], but rejects empty or non-finite coefficients and a
+/// non-positive sampling rate instead of returning meaningless frequencies.
+pub fn try_formant_detection(lpc_coeffs: &[f64], sample_rate: f64) -> Result<Vec<f64>> {
+    check_coefficients(lpc_coeffs, sample_rate)?;
+    Ok(formant_detection(lpc_coeffs, sample_rate))
+}
 
-    for peak in peaks.drain(..) {
-        if peak > low_cutoff && peak < high_cutoff {
-            formants.push(peak);
-        }
-    }
+/// Performs formant detection from LPC coefficients using the given root solver.
+///
+/// # Arguments
+///
+/// * `lpc_coeffs`  - The LPC coefficients.
+/// * `sample_rate` - The sampling rate of the original signal.
+/// * `solver`      - The polynomial root finding strategy.
+///
+/// # Returns
+///
+/// A vector of formant frequencies in Hz.
+#[tracing::instrument(level = "debug", skip_all, fields(order = lpc_coeffs.len().saturating_sub(1), sample_rate))]
+pub fn formant_detection_with_solver(
+    lpc_coeffs: &[f64],
+    sample_rate: f64,
+    solver: RootSolver,
+) -> Vec<f64> {
+    let mut formants: Vec<f64> = peak_detection_with_metadata(lpc_coeffs, sample_rate, solver)
+        .into_iter()
+        .filter(|peak| peak.is_formant)
+        .map(|peak| peak.frequency)
+        .collect();
 
-    formants.sort_by(|a, b| a.partial_cmp(b).unwrap());
+    formants.sort_by(f64::total_cmp);
+    tracing::debug!(?formants, "formants");
     formants
 }
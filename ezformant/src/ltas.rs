@@ -0,0 +1,176 @@
+//! Long-term average spectrum (LTAS) and the band-energy voice measures taken from it.
+//!
+//! The LTAS is a [`welch_psd`] estimate over a whole recording, typically a sustained
+//! vowel or a read passage. [`Ltas::metrics`] reduces it to the usual clinical numbers:
+//! the alpha ratio, the Hammarberg index and the relative energy of the singer's formant
+//! region. Bands reaching past Nyquist are cut at Nyquist.
+
+use crate::error;
+use crate::spectrum::{welch_psd, SpectrumWindow};
+
+/// Low band of the alpha ratio, in Hz.
+pub const ALPHA_LOW_BAND: (f64, f64) = (50.0, 1000.0);
+/// High band of the alpha ratio, in Hz.
+pub const ALPHA_HIGH_BAND: (f64, f64) = (1000.0, 5000.0);
+/// Low band of the Hammarberg index, in Hz.
+pub const HAMMARBERG_LOW_BAND: (f64, f64) = (0.0, 2000.0);
+/// High band of the Hammarberg index, in Hz.
+pub const HAMMARBERG_HIGH_BAND: (f64, f64) = (2000.0, 5000.0);
+/// Band whose share of the total energy is reported as the singer's formant energy, in Hz.
+pub const SINGERS_FORMANT_BAND: (f64, f64) = (1000.0, 3000.0);
+
+/// A long-term average spectrum: power spectral density on a uniform frequency grid.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Ltas {
+    /// Spacing of the bins in Hz; bin `k` is at `k * bin_width`.
+    pub bin_width: f64,
+    /// Density per bin, in units²/Hz.
+    pub psd: Vec<f64>,
+}
+
+/// Band-energy measures of an LTAS, in dB. A measure is `None` when one of its bands
+/// holds no energy (e.g. lies above Nyquist).
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct LtasMetrics {
+    /// Energy in [`ALPHA_HIGH_BAND`] minus energy in [`ALPHA_LOW_BAND`]. Less negative
+    /// values mean a flatter spectral slope (pressed or loud voice).
+    pub alpha_ratio: Option<f64>,
+    /// Strongest level in [`HAMMARBERG_LOW_BAND`] minus the strongest level in
+    /// [`HAMMARBERG_HIGH_BAND`].
+    pub hammarberg_index: Option<f64>,
+    /// Energy in [`SINGERS_FORMANT_BAND`] relative to the energy of the whole spectrum.
+    pub singers_formant: Option<f64>,
+}
+
+impl Ltas {
+    /// Computes the LTAS of a signal from half-overlapping Hann-windowed segments.
+    ///
+    /// # Arguments
+    ///
+    /// * `signal`      - The recording.
+    /// * `sample_rate` - The sampling rate in Hz.
+    /// * `nperseg`     - Samples per segment; the resolution is `sample_rate / nperseg`
+    ///   Hz (e.g. 2048 at 44.1 kHz for about 20 Hz).
+    ///
+    /// # Returns
+    ///
+    /// The LTAS, or an error for a signal shorter than one segment or with NaN/infinity.
+    pub fn compute(signal: &[f64], sample_rate: f64, nperseg: usize) -> error::Result<Self> {
+        let psd = welch_psd(
+            signal,
+            sample_rate,
+            nperseg,
+            nperseg / 2,
+            SpectrumWindow::Hann,
+        )?;
+        Ok(Self {
+            bin_width: sample_rate / nperseg as f64,
+            psd,
+        })
+    }
+
+    /// The frequency of every bin, in Hz.
+    pub fn frequencies(&self) -> Vec<f64> {
+        (0..self.psd.len())
+            .map(|k| k as f64 * self.bin_width)
+            .collect()
+    }
+
+    /// The density of every bin in dB (`10 log10`).
+    pub fn levels_db(&self) -> Vec<f64> {
+        self.psd.iter().map(|p| 10.0 * p.log10()).collect()
+    }
+
+    /// Energy of the bins in `low..high` Hz, in units².
+    pub fn band_energy(&self, low: f64, high: f64) -> f64 {
+        self.band(low, high).iter().sum::<f64>() * self.bin_width
+    }
+
+    /// Energy of the bins in `low..high` Hz in dB, or `None` if the band is empty or
+    /// silent.
+    pub fn band_energy_db(&self, low: f64, high: f64) -> Option<f64> {
+        to_db(self.band_energy(low, high))
+    }
+
+    /// Level of the strongest bin in `low..high` Hz in dB, or `None` if the band is
+    /// empty or silent.
+    pub fn band_peak_db(&self, low: f64, high: f64) -> Option<f64> {
+        to_db(self.band(low, high).iter().fold(0.0, |a: f64, &p| a.max(p)))
+    }
+
+    /// The alpha ratio, Hammarberg index and singer's formant energy.
+    pub fn metrics(&self) -> LtasMetrics {
+        let band_energy = |(low, high): (f64, f64)| self.band_energy_db(low, high);
+        let band_peak = |(low, high): (f64, f64)| self.band_peak_db(low, high);
+        let difference = |a: Option<f64>, b: Option<f64>| Some(a? - b?);
+        LtasMetrics {
+            alpha_ratio: difference(band_energy(ALPHA_HIGH_BAND), band_energy(ALPHA_LOW_BAND)),
+            hammarberg_index: difference(
+                band_peak(HAMMARBERG_LOW_BAND),
+                band_peak(HAMMARBERG_HIGH_BAND),
+            ),
+            singers_formant: difference(
+                band_energy(SINGERS_FORMANT_BAND),
+                band_energy((0.0, f64::INFINITY)),
+            ),
+        }
+    }
+
+    // The densities of the bins in `low..high` Hz.
+    fn band(&self, low: f64, high: f64) -> &[f64] {
+        let first = (low.max(0.0) / self.bin_width).ceil() as usize;
+        let end = (high / self.bin_width).ceil().min(self.psd.len() as f64) as usize;
+        self.psd.get(first..end.max(first)).unwrap_or(&[])
+    }
+}
+
+// ------------------
+// Helpers
+// ------------------
+
+fn to_db(energy: f64) -> Option<f64> {
+    (energy > 0.0).then(|| 10.0 * energy.log10())
+}
+
+// ------------------
+// Tests
+// ------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn white_noise_metrics_follow_band_widths() {
+        let fs = 16000.0;
+        let mut state: u32 = 7;
+        let noise: Vec<f64> = (0..64000)
+            .map(|_| {
+                state = state.wrapping_mul(1664525).wrapping_add(1013904223);
+                (state >> 8) as f64 / (1u32 << 24) as f64 - 0.5
+            })
+            .collect();
+        let ltas = Ltas::compute(&noise, fs, 512).unwrap();
+        assert_eq!(ltas.psd.len(), 257);
+        assert_eq!(ltas.frequencies()[256], 8000.0);
+
+        // A flat spectrum: band energies scale with band widths.
+        let metrics = ltas.metrics();
+        let alpha = metrics.alpha_ratio.unwrap();
+        assert!(
+            (alpha - 10.0 * (4000.0f64 / 950.0).log10()).abs() < 0.5,
+            "{}",
+            alpha
+        );
+        assert!(metrics.hammarberg_index.unwrap().abs() < 3.0);
+        let singers = metrics.singers_formant.unwrap();
+        assert!(
+            (singers - 10.0 * 0.25f64.log10()).abs() < 0.5,
+            "{}",
+            singers
+        );
+        assert_eq!(ltas.band_energy_db(9000.0, 10000.0), None);
+    }
+}
@@ -0,0 +1,227 @@
+//! Speaker normalization of vowel formants.
+//!
+//! Raw formant frequencies differ between speakers with the length of their vocal
+//! tracts, so vowels of different speakers only become comparable after normalization.
+//! Lobanov and Nearey are speaker-extrinsic: they need all of one speaker's vowel
+//! measurements at once and are applied to each speaker separately. The Bark-difference
+//! metric is vowel-intrinsic and works on single tokens.
+
+use crate::units::FrequencyUnit;
+use std::fmt;
+use std::str::FromStr;
+
+/// A vowel normalization procedure.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Normalization {
+    /// z-scores of each formant over the speaker's tokens (Lobanov, 1971).
+    #[default]
+    Lobanov,
+    /// `ln F` minus the speaker's mean `ln` of the same formant (Nearey's individual
+    /// log-mean, "Nearey1" in NORM).
+    Nearey1,
+    /// `ln F` minus the speaker's mean `ln` over all formants (Nearey's shared log-mean,
+    /// "Nearey2" in NORM), which keeps the relative spacing of the formants.
+    Nearey2,
+    /// Bark differences `Z3 - Z1`, `Z3 - Z2` and `Z2 - Z1` of each token (Syrdal &
+    /// Gopal, 1986), using [`FrequencyUnit::Bark`].
+    BarkDifference,
+}
+
+impl Normalization {
+    /// The name accepted by [`FromStr`]: `lobanov`, `nearey1`, `nearey2` or `bark`.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Normalization::Lobanov => "lobanov",
+            Normalization::Nearey1 => "nearey1",
+            Normalization::Nearey2 => "nearey2",
+            Normalization::BarkDifference => "bark",
+        }
+    }
+
+    /// Number of values per token in the output for tokens of `formants` formants.
+    pub fn output_len(&self, formants: usize) -> usize {
+        match self {
+            Normalization::BarkDifference => 3,
+            _ => formants,
+        }
+    }
+}
+
+impl fmt::Display for Normalization {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for Normalization {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "lobanov" | "z" => Ok(Normalization::Lobanov),
+            "nearey1" | "nearey" => Ok(Normalization::Nearey1),
+            "nearey2" => Ok(Normalization::Nearey2),
+            "bark" | "bark-difference" | "barkdifference" => Ok(Normalization::BarkDifference),
+            _ => Err(format!("unknown normalization: {}", s)),
+        }
+    }
+}
+
+/// Normalizes the vowel measurements of one speaker.
+///
+/// # Arguments
+///
+/// * `tokens` - One entry per vowel token, holding F1, F2, ... in Hz. Missing or
+///   non-positive values are `NaN` in the output and do not enter the speaker's means.
+/// * `method` - The procedure.
+///
+/// # Returns
+///
+/// One entry per token with [`Normalization::output_len`] values: unitless z-scores for
+/// Lobanov, log differences for Nearey and Bark for the Bark difference.
+pub fn normalize(tokens: &[Vec<f64>], method: Normalization) -> Vec<Vec<f64>> {
+    match method {
+        Normalization::Lobanov => lobanov(tokens),
+        Normalization::Nearey1 => nearey(tokens, false),
+        Normalization::Nearey2 => nearey(tokens, true),
+        Normalization::BarkDifference => tokens.iter().map(|t| bark_difference(t)).collect(),
+    }
+}
+
+/// z-scores each formant by the mean and (sample) standard deviation of the same
+/// formant over all tokens.
+pub fn lobanov(tokens: &[Vec<f64>]) -> Vec<Vec<f64>> {
+    let stats: Vec<Option<(f64, f64)>> = (0..formant_count(tokens))
+        .map(|i| {
+            let values: Vec<f64> = column(tokens, i).collect();
+            let n = values.len() as f64;
+            if values.len() < 2 {
+                return None;
+            }
+            let mean = values.iter().sum::<f64>() / n;
+            let var = values.iter().map(|v| (v - mean).powi(2)).sum::<f64>() / (n - 1.0);
+            (var > 0.0).then(|| (mean, var.sqrt()))
+        })
+        .collect();
+    map_values(tokens, |i, f| match stats[i] {
+        Some((mean, sd)) => (f - mean) / sd,
+        None => f64::NAN,
+    })
+}
+
+/// Subtracts the speaker's mean log frequency from each log frequency: per formant, or
+/// over all formants when `shared`.
+pub fn nearey(tokens: &[Vec<f64>], shared: bool) -> Vec<Vec<f64>> {
+    let mean_log = |values: &mut dyn Iterator<Item = f64>| {
+        let (sum, n) = values.fold((0.0, 0usize), |(s, n), v| (s + v.ln(), n + 1));
+        (n > 0).then(|| sum / n as f64)
+    };
+    let count = formant_count(tokens);
+    let means: Vec<Option<f64>> = if shared {
+        let all = mean_log(&mut (0..count).flat_map(|i| column(tokens, i)));
+        vec![all; count]
+    } else {
+        (0..count)
+            .map(|i| mean_log(&mut column(tokens, i)))
+            .collect()
+    };
+    map_values(tokens, |i, f| match means[i] {
+        Some(mean) => f.ln() - mean,
+        None => f64::NAN,
+    })
+}
+
+/// The Bark differences `[Z3 - Z1, Z3 - Z2, Z2 - Z1]` of one token's F1-F3, `NaN` where
+/// a formant is missing.
+pub fn bark_difference(formants: &[f64]) -> Vec<f64> {
+    let z = |i: usize| match formants.get(i) {
+        Some(&f) if f > 0.0 => FrequencyUnit::Bark.from_hertz(f),
+        _ => f64::NAN,
+    };
+    let (z1, z2, z3) = (z(0), z(1), z(2));
+    vec![z3 - z1, z3 - z2, z2 - z1]
+}
+
+// ------------------
+// Helpers
+// ------------------
+
+fn formant_count(tokens: &[Vec<f64>]) -> usize {
+    tokens.iter().map(Vec::len).max().unwrap_or(0)
+}
+
+/// The usable (positive, finite) values of formant `i` over all tokens.
+fn column(tokens: &[Vec<f64>], i: usize) -> impl Iterator<Item = f64> + '_ {
+    tokens
+        .iter()
+        .filter_map(move |t| t.get(i).copied())
+        .filter(|f| *f > 0.0 && f.is_finite())
+}
+
+/// Applies `f(formant index, value)` to every usable value, `NaN` elsewhere.
+fn map_values(tokens: &[Vec<f64>], f: impl Fn(usize, f64) -> f64) -> Vec<Vec<f64>> {
+    tokens
+        .iter()
+        .map(|t| {
+            t.iter()
+                .enumerate()
+                .map(|(i, &v)| {
+                    if v > 0.0 && v.is_finite() {
+                        f(i, v)
+                    } else {
+                        f64::NAN
+                    }
+                })
+                .collect()
+        })
+        .collect()
+}
+
+// ------------------
+// Tests
+// ------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn scaled_speakers_normalize_to_the_same_vowels() {
+        // The corner vowels /i/, /a/, /u/ of a speaker, and of one whose vocal tract is
+        // 20% shorter (all formants 1.2x higher).
+        let speaker: Vec<Vec<f64>> = vec![
+            vec![280.0, 2250.0, 2900.0],
+            vec![750.0, 1200.0, 2500.0],
+            vec![310.0, 870.0, 2250.0],
+        ];
+        let shorter: Vec<Vec<f64>> = speaker
+            .iter()
+            .map(|t| t.iter().map(|f| f * 1.2).collect())
+            .collect();
+
+        for method in [
+            Normalization::Lobanov,
+            Normalization::Nearey1,
+            Normalization::Nearey2,
+        ] {
+            let (a, b) = (normalize(&speaker, method), normalize(&shorter, method));
+            for (x, y) in a.iter().flatten().zip(b.iter().flatten()) {
+                assert!((x - y).abs() < 1e-9, "{}: {} vs {}", method, x, y);
+            }
+        }
+
+        let z = normalize(&speaker, Normalization::Lobanov);
+        let f1: Vec<f64> = z.iter().map(|t| t[0]).collect();
+        assert!(f1.iter().sum::<f64>().abs() < 1e-9);
+        assert!((f1.iter().map(|v| v * v).sum::<f64>() - 2.0).abs() < 1e-9);
+
+        // /a/ has the smallest F3 - F1 distance in Bark; a missing F2 only affects the
+        // differences that use it.
+        let bark = normalize(&speaker, Normalization::BarkDifference);
+        assert!(bark[1][0] < bark[0][0] && bark[1][0] < bark[2][0]);
+        let partial = bark_difference(&[300.0, f64::NAN, 2500.0]);
+        assert!(partial[0] > 0.0 && partial[1].is_nan() && partial[2].is_nan());
+        assert_eq!("Nearey2".parse(), Ok(Normalization::Nearey2));
+    }
+}
@@ -1,34 +1,383 @@
+use crate::error::{check_finite, check_positive, Result};
+use crate::lpc::AutocorrelationMethod;
+#[cfg(not(feature = "std"))]
+use alloc::vec::Vec;
+
+#[cfg(feature = "std")]
+pub mod ac;
+#[cfg(feature = "std")]
+pub mod pyin;
+
+/// Result of a single-frame pitch estimate.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct PitchResult {
+    /// Best fundamental frequency candidate in Hz, or `-1.0` if there is none.
+    /// Only meaningful when `voiced` is `true`.
+    pub f0: f64,
+    /// CMND value at the chosen lag: `0.0` for a perfectly periodic frame,
+    /// around `1.0` for noise.
+    pub aperiodicity: f64,
+    /// `1.0 - aperiodicity`, clamped to `[0.0, 1.0]`.
+    pub confidence: f64,
+    /// Whether the CMND dipped below the threshold.
+    pub voiced: bool,
+}
+
+impl PitchResult {
+    /// A result for frames without any pitch candidate (e.g. silence).
+    pub const UNVOICED: PitchResult = PitchResult {
+        f0: -1.0,
+        aperiodicity: 1.0,
+        confidence: 0.0,
+        voiced: false,
+    };
+}
+
+/// Computes the YIN difference function `d(t)` of a signal for a single lag.
+///
+/// # Arguments
+///
+/// * `signal` - The input signal.
+/// * `t`      - The lag (in samples).
+///
+/// # Returns
+///
+/// The sum of squared differences between the signal and its lagged copy.
 pub fn difference_function(signal: &[f64], t: usize) -> f64 {
-    let mut acc = 0.0;
-    for i in 0..signal.len() {
-        if i + t >= signal.len() {
-            break;
-        }
-        acc += (signal[i] - signal[i + t]).powi(2);
+    signal
+        .iter()
+        .zip(signal.iter().skip(t))
+        .map(|(&x, &y)| (x - y).powi(2))
+        .sum()
+}
+
+/// Computes the YIN difference function `d(t)` for all lags `0..t_max` at once.
+///
+/// Expands `d(t)` into energy terms and the autocorrelation `r(t)`, so the cost is
+/// dominated by the autocorrelation `method` (FFT makes this `O(N log N)` instead of
+/// the `O(N * t_max)` of repeated [`difference_function`] calls).
+///
+/// # Arguments
+///
+/// * `signal` - The input signal.
+/// * `t_max`  - The number of lags to compute (exclusive upper bound).
+/// * `method` - The autocorrelation strategy.
+///
+/// # Returns
+///
+/// A vector `d` of length `t_max`.
+pub fn difference_function_all(
+    signal: &[f64],
+    t_max: usize,
+    method: AutocorrelationMethod,
+) -> Vec<f64> {
+    if t_max == 0 {
+        return Vec::new();
+    }
+    let n = signal.len();
+    let r = method.autocorrelate(signal, t_max - 1);
+
+    // cumulative[k] = sum of x_j^2 for j < k
+    let mut cumulative = Vec::with_capacity(n + 1);
+    cumulative.push(0.0);
+    for &x in signal {
+        cumulative.push(cumulative.last().unwrap() + x * x);
     }
 
-    return acc;
+    (0..t_max)
+        .map(|t| {
+            if t >= n {
+                return 0.0;
+            }
+            let head = cumulative[n - t];
+            let tail = cumulative[n] - cumulative[t];
+            (head + tail - 2.0 * r[t]).max(0.0)
+        })
+        .collect()
 }
 
-pub fn cmnd_first_peak(signal: &[f64], t_max: usize, threshold: f64) -> Option<usize> {
+/// Computes the cumulative mean normalized difference function (CMND) of a signal.
+///
+/// # Arguments
+///
+/// * `signal` - The input signal.
+/// * `t_max`  - The number of lags to compute (exclusive upper bound).
+///
+/// # Returns
+///
+/// A vector `d'` of length `t_max` with `d'[0] = 1.0` and
+/// `d'[t] = d(t) * t / sum_{j=1..=t} d(j)` for `t >= 1`.
+pub fn cumulative_mean_normalized_difference(signal: &[f64], t_max: usize) -> Vec<f64> {
+    let d: Vec<f64> = (0..t_max).map(|t| difference_function(signal, t)).collect();
+    normalize_difference(&d)
+}
+
+/// Turns a difference function `d` into its cumulative mean normalized form.
+fn normalize_difference(d: &[f64]) -> Vec<f64> {
+    let mut cmnd = Vec::with_capacity(d.len());
+    if d.is_empty() {
+        return cmnd;
+    }
+    cmnd.push(1.0);
+
     let mut d_sum = 0.0;
-    for t in 1..t_max {
-        let d = difference_function(signal, t);
+    for (t, &d) in d.iter().enumerate().skip(1) {
         d_sum += d;
 
-        let cmnd_val = d * (t as f64) / d_sum;
+        cmnd.push(if d_sum > 0.0 {
+            d * (t as f64) / d_sum
+        } else {
+            1.0
+        });
+    }
+
+    cmnd
+}
+
+/// Finds the first dip of the CMND below `threshold` (absolute threshold step of YIN).
+///
+/// Once the CMND crosses the threshold, the search continues while the function is
+/// still decreasing, so the returned lag is the local minimum that follows the crossing
+/// rather than the crossing point itself.
+///
+/// # Arguments
+///
+/// * `signal`    - The input signal.
+/// * `t_max`     - The largest lag to consider (exclusive).
+/// * `threshold` - The absolute threshold (commonly 0.1–0.15).
+///
+/// # Returns
+///
+/// The integer lag of the selected minimum, or `None` if the CMND never dips below
+/// the threshold.
+pub fn cmnd_first_peak(signal: &[f64], t_max: usize, threshold: f64) -> Option<usize> {
+    let cmnd = cumulative_mean_normalized_difference(signal, t_max);
+    first_minimum_below(&cmnd, threshold, 1)
+}
+
+/// Returns the lag of the local minimum following the first threshold crossing of `cmnd`
+/// at or after lag `start`.
+fn first_minimum_below(cmnd: &[f64], threshold: f64, start: usize) -> Option<usize> {
+    let mut t = (start.max(1)..cmnd.len()).find(|&t| cmnd[t] < threshold)?;
+    while t + 1 < cmnd.len() && cmnd[t + 1] < cmnd[t] {
+        t += 1;
+    }
+    Some(t)
+}
+
+/// Refines the position of an extremum by fitting a parabola through three neighbouring
+/// points.
+///
+/// # Arguments
+///
+/// * `values` - The sampled function.
+/// * `t`      - The index of the extremum to refine.
+///
+/// # Returns
+///
+/// The fractional index of the vertex of the parabola. Falls back to `t` at the edges
+/// of `values` or when the three points are collinear.
+pub fn parabolic_interpolation(values: &[f64], t: usize) -> f64 {
+    parabolic_vertex(values, t).0
+}
+
+/// Returns the `(position, value)` of the parabola through `values[t - 1..=t + 1]`.
+fn parabolic_vertex(values: &[f64], t: usize) -> (f64, f64) {
+    if t == 0 || t + 1 >= values.len() {
+        return (t as f64, values.get(t).copied().unwrap_or(1.0));
+    }
 
-        if cmnd_val < threshold {
-            return Some(t);
-        }
+    let (y0, y1, y2) = (values[t - 1], values[t], values[t + 1]);
+    let denominator = y0 - 2.0 * y1 + y2;
+    if denominator.abs() < f64::EPSILON {
+        return (t as f64, y1);
     }
 
-    return None;
+    let shift = (0.5 * (y0 - y2) / denominator).clamp(-1.0, 1.0);
+    (t as f64 + shift, y1 - 0.25 * (y0 - y2) * shift)
 }
 
+/// Estimates the fundamental period of a signal with the YIN algorithm.
+///
+/// # Arguments
+///
+/// * `signal`    - The input signal.
+/// * `t_max`     - The largest lag to consider (exclusive).
+/// * `threshold` - The absolute CMND threshold.
+///
+/// # Returns
+///
+/// The sub-sample period (in samples), or `None` if no periodicity was found.
+pub fn yin_period(signal: &[f64], t_max: usize, threshold: f64) -> Option<f64> {
+    let cmnd = cumulative_mean_normalized_difference(signal, t_max);
+    let t = first_minimum_below(&cmnd, threshold, 1)?;
+    Some(parabolic_interpolation(&cmnd, t))
+}
+
+/// Estimates the fundamental period of a signal with YIN, computing the difference
+/// function through the given autocorrelation method.
+///
+/// # Arguments
+///
+/// * `signal`    - The input signal.
+/// * `t_max`     - The largest lag to consider (exclusive).
+/// * `threshold` - The absolute CMND threshold.
+/// * `method`    - The autocorrelation strategy.
+///
+/// # Returns
+///
+/// The sub-sample period (in samples), or `None` if no periodicity was found.
+pub fn yin_period_with(
+    signal: &[f64],
+    t_max: usize,
+    threshold: f64,
+    method: AutocorrelationMethod,
+) -> Option<f64> {
+    let cmnd = normalize_difference(&difference_function_all(signal, t_max, method));
+    let t = first_minimum_below(&cmnd, threshold, 1)?;
+    Some(parabolic_interpolation(&cmnd, t))
+}
+
+/// Estimates the fundamental frequency of a signal with the YIN algorithm.
+///
+/// # Arguments
+///
+/// * `signal`        - The input signal.
+/// * `sampling_rate` - The sampling rate of the signal.
+///
+/// # Returns
+///
+/// The fundamental frequency in Hz, or `-1.0` if the signal is not periodic.
 pub fn pitch_detection_yin(signal: &[f64], sampling_rate: f64) -> f64 {
-    return match cmnd_first_peak(signal, signal.len() / 2, 0.1) {
-        None => -1.0,
-        Some(k) => sampling_rate / (k as f64),
+    match yin_period(signal, signal.len() / 2, 0.1) {
+        Some(period) if period > 0.0 => sampling_rate / period,
+        _ => -1.0,
+    }
+}
+
+/// Like [`pitch_detection_yin`], but returns an error for an empty or non-finite signal
+/// or a non-positive sampling rate.
+pub fn try_pitch_detection_yin(signal: &[f64], sampling_rate: f64) -> Result<f64> {
+    check_positive("sampling_rate", sampling_rate)?;
+    check_finite(signal)?;
+    Ok(pitch_detection_yin(signal, sampling_rate))
+}
+
+/// Estimates the fundamental frequency of a signal with YIN, using the given threshold
+/// and autocorrelation method.
+///
+/// # Arguments
+///
+/// * `signal`        - The input signal.
+/// * `sampling_rate` - The sampling rate of the signal.
+/// * `threshold`     - The absolute CMND threshold.
+/// * `method`        - The autocorrelation strategy.
+///
+/// # Returns
+///
+/// The fundamental frequency in Hz, or `-1.0` if the signal is not periodic.
+pub fn pitch_detection_yin_with(
+    signal: &[f64],
+    sampling_rate: f64,
+    threshold: f64,
+    method: AutocorrelationMethod,
+) -> f64 {
+    match yin_period_with(signal, signal.len() / 2, threshold, method) {
+        Some(period) if period > 0.0 => sampling_rate / period,
+        _ => -1.0,
+    }
+}
+
+/// Estimates the fundamental frequency of a frame with YIN and reports how periodic
+/// the frame is.
+///
+/// When the CMND never dips below `threshold`, the global minimum is used as the
+/// candidate and the frame is marked unvoiced.
+///
+/// # Arguments
+///
+/// * `signal`        - The input signal.
+/// * `sampling_rate` - The sampling rate of the signal.
+/// * `threshold`     - The absolute CMND threshold.
+/// * `method`        - The autocorrelation strategy.
+///
+/// # Returns
+///
+/// A [`PitchResult`] with the f0 candidate, its aperiodicity and the voicing decision.
+#[tracing::instrument(level = "debug", skip_all, fields(len = signal.len(), sampling_rate, threshold))]
+pub fn yin(
+    signal: &[f64],
+    sampling_rate: f64,
+    threshold: f64,
+    method: AutocorrelationMethod,
+) -> PitchResult {
+    yin_lags(
+        signal,
+        sampling_rate,
+        threshold,
+        method,
+        1,
+        signal.len() / 2,
+    )
+}
+
+/// Like [`yin`], but only considers periods of f0 between `fmin` and `fmax` Hz, which
+/// rules out octave errors outside a known voice range.
+///
+/// # Arguments
+///
+/// * `signal`        - The input signal; it should hold two periods of `fmin`.
+/// * `sampling_rate` - The sampling rate of the signal.
+/// * `threshold`     - The absolute CMND threshold.
+/// * `method`        - The autocorrelation strategy.
+/// * `fmin`          - Lowest f0 considered, in Hz.
+/// * `fmax`          - Highest f0 considered, in Hz.
+pub fn yin_in_range(
+    signal: &[f64],
+    sampling_rate: f64,
+    threshold: f64,
+    method: AutocorrelationMethod,
+    fmin: f64,
+    fmax: f64,
+) -> PitchResult {
+    let t_min = (sampling_rate / fmax).floor() as usize;
+    // One lag past the longest period, so a minimum there can be refined.
+    let t_max = ((sampling_rate / fmin).ceil() as usize + 2).min(signal.len() / 2);
+    yin_lags(signal, sampling_rate, threshold, method, t_min, t_max)
+}
+
+/// YIN over the lags `t_min..t_max`.
+fn yin_lags(
+    signal: &[f64],
+    sampling_rate: f64,
+    threshold: f64,
+    method: AutocorrelationMethod,
+    t_min: usize,
+    t_max: usize,
+) -> PitchResult {
+    let cmnd = normalize_difference(&difference_function_all(signal, t_max, method));
+
+    let (t, voiced) = match first_minimum_below(&cmnd, threshold, t_min) {
+        Some(t) => (t, true),
+        None => match (t_min.max(1)..cmnd.len()).min_by(|&a, &b| cmnd[a].total_cmp(&cmnd[b])) {
+            Some(t) => (t, false),
+            None => return PitchResult::UNVOICED,
+        },
+    };
+
+    let (period, aperiodicity) = parabolic_vertex(&cmnd, t);
+    if period <= 0.0 {
+        return PitchResult::UNVOICED;
+    }
+    let aperiodicity = aperiodicity.max(0.0);
+
+    let result = PitchResult {
+        f0: sampling_rate / period,
+        aperiodicity,
+        confidence: (1.0 - aperiodicity).clamp(0.0, 1.0),
+        voiced,
     };
+    tracing::debug!(f0 = result.f0, aperiodicity, voiced, "pitch");
+    result
 }
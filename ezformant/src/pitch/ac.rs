@@ -0,0 +1,339 @@
+//! Autocorrelation pitch detection after Boersma (1993), as in Praat's "To Pitch (ac)".
+//!
+//! Each frame is Hanning-windowed and its normalized autocorrelation is divided by the
+//! autocorrelation of the window, which removes the taper bias towards short lags.
+//! Local maxima become voiced candidates next to an unvoiced candidate, and a Viterbi
+//! path finder picks one candidate per frame using Praat's octave and voicing costs.
+
+use super::{parabolic_vertex, PitchResult};
+use rustfft::{num_complex::Complex, FftPlanner};
+
+/// Parameters of the autocorrelation pitch detector. Defaults match Praat.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct AcPitchConfig {
+    /// Time between frames in seconds. `0.0` selects Praat's `0.75 / pitch_floor`.
+    pub time_step: f64,
+    /// Lowest f0 in Hz. The window spans `periods_per_window` periods of this frequency.
+    pub pitch_floor: f64,
+    /// Highest f0 in Hz.
+    pub pitch_ceiling: f64,
+    /// Window length in periods of the pitch floor (Praat uses 3 for pitch, 4.5 for HNR).
+    pub periods_per_window: f64,
+    /// Maximum number of candidates per frame, including the unvoiced one.
+    pub max_candidates: usize,
+    /// Frames whose peak is below this fraction of the global peak tend to be unvoiced.
+    pub silence_threshold: f64,
+    /// Strength a voiced candidate needs to beat the unvoiced candidate.
+    pub voicing_threshold: f64,
+    /// Bias towards higher-frequency candidates, per octave.
+    pub octave_cost: f64,
+    /// Cost of an octave jump between consecutive frames.
+    pub octave_jump_cost: f64,
+    /// Cost of a voiced/unvoiced transition between consecutive frames.
+    pub voiced_unvoiced_cost: f64,
+}
+
+impl Default for AcPitchConfig {
+    fn default() -> Self {
+        Self {
+            time_step: 0.0,
+            pitch_floor: 75.0,
+            pitch_ceiling: 600.0,
+            periods_per_window: 3.0,
+            max_candidates: 15,
+            silence_threshold: 0.03,
+            voicing_threshold: 0.45,
+            octave_cost: 0.01,
+            octave_jump_cost: 0.35,
+            voiced_unvoiced_cost: 0.14,
+        }
+    }
+}
+
+impl AcPitchConfig {
+    /// The hop between frames in seconds.
+    pub fn effective_time_step(&self) -> f64 {
+        if self.time_step > 0.0 {
+            self.time_step
+        } else {
+            0.75 / self.pitch_floor
+        }
+    }
+
+    /// The analysis window length in samples (`periods_per_window` periods of the pitch floor).
+    pub fn window_length(&self, sample_rate: f64) -> usize {
+        (self.periods_per_window * sample_rate / self.pitch_floor).round() as usize
+    }
+}
+
+/// A candidate of one frame: `f0 == 0.0` is the unvoiced candidate.
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Candidate {
+    f0: f64,
+    /// Path-finding score (correlation with octave cost applied).
+    strength: f64,
+    /// Normalized autocorrelation at the candidate lag.
+    correlation: f64,
+}
+
+/// Tracks the pitch of a signal with Praat's autocorrelation method.
+///
+/// Frames start at sample 0 and advance by the time step; a trailing partial frame
+/// is dropped.
+///
+/// # Arguments
+///
+/// * `signal`      - The input signal.
+/// * `sample_rate` - The sampling rate of the signal.
+/// * `config`      - The detector parameters.
+///
+/// # Returns
+///
+/// One [`PitchResult`] per frame. `confidence` is the normalized autocorrelation of the
+/// chosen candidate.
+#[tracing::instrument(level = "debug", skip_all, fields(len = signal.len(), sample_rate))]
+pub fn pitch_ac(signal: &[f64], sample_rate: f64, config: &AcPitchConfig) -> Vec<PitchResult> {
+    let window_length = config.window_length(sample_rate);
+    let hop = ((config.effective_time_step() * sample_rate).round() as usize).max(1);
+    if window_length < 4 || signal.len() < window_length {
+        return Vec::new();
+    }
+
+    let global_peak = signal.iter().fold(0.0f64, |acc, &x| acc.max(x.abs()));
+    let window: Vec<f64> = (0..window_length)
+        .map(|i| {
+            0.5 - 0.5 * (2.0 * std::f64::consts::PI * (i as f64 + 0.5) / window_length as f64).cos()
+        })
+        .collect();
+
+    let fft_len = (2 * window_length).next_power_of_two();
+    let mut planner = FftPlanner::<f64>::new();
+    let window_ac = normalized_autocorrelation(&window, fft_len, &mut planner);
+
+    let frames: Vec<Vec<Candidate>> = (0..=signal.len() - window_length)
+        .step_by(hop)
+        .map(|start| {
+            frame_candidates(
+                &signal[start..start + window_length],
+                &window,
+                &window_ac,
+                global_peak,
+                sample_rate,
+                fft_len,
+                &mut planner,
+                config,
+            )
+        })
+        .collect();
+
+    let time_step_correction = 0.01 / config.effective_time_step();
+    find_path(&frames, time_step_correction, config)
+}
+
+#[allow(clippy::too_many_arguments)]
+fn frame_candidates(
+    frame: &[f64],
+    window: &[f64],
+    window_ac: &[f64],
+    global_peak: f64,
+    sample_rate: f64,
+    fft_len: usize,
+    planner: &mut FftPlanner<f64>,
+    config: &AcPitchConfig,
+) -> Vec<Candidate> {
+    let mean = frame.iter().sum::<f64>() / frame.len() as f64;
+    let local_peak = frame
+        .iter()
+        .fold(0.0f64, |acc, &x| acc.max((x - mean).abs()));
+    let windowed: Vec<f64> = frame
+        .iter()
+        .zip(window)
+        .map(|(&x, &w)| (x - mean) * w)
+        .collect();
+
+    let unvoiced_strength = if global_peak > 0.0 {
+        config.voicing_threshold
+            + (2.0
+                - (local_peak / global_peak)
+                    / (config.silence_threshold / (1.0 + config.voicing_threshold)))
+                .max(0.0)
+    } else {
+        config.voicing_threshold + 2.0
+    };
+    let mut candidates = vec![Candidate {
+        f0: 0.0,
+        strength: unvoiced_strength,
+        correlation: 0.0,
+    }];
+    if local_peak == 0.0 {
+        return candidates;
+    }
+
+    let signal_ac = normalized_autocorrelation(&windowed, fft_len, planner);
+    let max_lag = ((sample_rate / config.pitch_floor).ceil() as usize).min(frame.len() / 2);
+    let min_lag = ((sample_rate / config.pitch_ceiling).floor() as usize).max(1);
+    let r: Vec<f64> = (0..=max_lag.min(window_ac.len() - 1))
+        .map(|lag| {
+            if window_ac[lag] > 0.0 {
+                signal_ac[lag] / window_ac[lag]
+            } else {
+                0.0
+            }
+        })
+        .collect();
+
+    let mut voiced: Vec<Candidate> = (min_lag.max(1)..r.len().saturating_sub(1))
+        .filter(|&lag| {
+            r[lag] > 0.5 * config.voicing_threshold && r[lag] > r[lag - 1] && r[lag] >= r[lag + 1]
+        })
+        .map(|lag| {
+            let (position, value) = parabolic_vertex(&r, lag);
+            let correlation = value.min(1.0);
+            Candidate {
+                f0: sample_rate / position,
+                strength: correlation
+                    - config.octave_cost * (config.pitch_floor * position / sample_rate).log2(),
+                correlation,
+            }
+        })
+        .collect();
+
+    voiced.sort_by(|a, b| b.strength.total_cmp(&a.strength));
+    voiced.truncate(config.max_candidates.saturating_sub(1));
+    candidates.extend(voiced);
+    candidates
+}
+
+/// Autocorrelation of `x` via a zero-padded FFT, normalized so that lag 0 equals 1.
+fn normalized_autocorrelation(
+    x: &[f64],
+    fft_len: usize,
+    planner: &mut FftPlanner<f64>,
+) -> Vec<f64> {
+    let mut buffer: Vec<Complex<f64>> = x
+        .iter()
+        .map(|&v| Complex::new(v, 0.0))
+        .chain(std::iter::repeat(Complex::new(0.0, 0.0)))
+        .take(fft_len)
+        .collect();
+    planner.plan_fft_forward(fft_len).process(&mut buffer);
+    for v in buffer.iter_mut() {
+        *v = Complex::new(v.norm_sqr(), 0.0);
+    }
+    planner.plan_fft_inverse(fft_len).process(&mut buffer);
+
+    let r0 = buffer[0].re;
+    buffer[..x.len()]
+        .iter()
+        .map(|v| if r0 > 0.0 { v.re / r0 } else { 0.0 })
+        .collect()
+}
+
+/// Viterbi search maximizing total candidate strength minus transition costs.
+fn find_path(
+    frames: &[Vec<Candidate>],
+    time_step_correction: f64,
+    config: &AcPitchConfig,
+) -> Vec<PitchResult> {
+    if frames.is_empty() {
+        return Vec::new();
+    }
+    let transition_cost = |a: &Candidate, b: &Candidate| -> f64 {
+        let cost = match (a.f0 > 0.0, b.f0 > 0.0) {
+            (false, false) => 0.0,
+            (true, true) => config.octave_jump_cost * (a.f0 / b.f0).log2().abs(),
+            _ => config.voiced_unvoiced_cost,
+        };
+        cost * time_step_correction
+    };
+
+    let mut score: Vec<f64> = frames[0].iter().map(|c| c.strength).collect();
+    let mut backpointers: Vec<Vec<usize>> = vec![Vec::new(); frames.len()];
+    for t in 1..frames.len() {
+        let (previous, current) = (&frames[t - 1], &frames[t]);
+        let mut next = Vec::with_capacity(current.len());
+        let mut pointers = Vec::with_capacity(current.len());
+        for c in current {
+            let (best, value) = previous
+                .iter()
+                .enumerate()
+                .map(|(i, p)| (i, score[i] - transition_cost(p, c)))
+                .max_by(|a, b| a.1.total_cmp(&b.1))
+                .unwrap();
+            next.push(value + c.strength);
+            pointers.push(best);
+        }
+        score = next;
+        backpointers[t] = pointers;
+    }
+
+    let mut index = (0..score.len())
+        .max_by(|&a, &b| score[a].total_cmp(&score[b]))
+        .unwrap();
+    let mut path = vec![0; frames.len()];
+    for t in (0..frames.len()).rev() {
+        path[t] = index;
+        if t > 0 {
+            index = backpointers[t][index];
+        }
+    }
+
+    path.iter()
+        .zip(frames)
+        .map(|(&i, candidates)| {
+            let chosen = candidates[i];
+            if chosen.f0 > 0.0 {
+                let correlation = chosen.correlation.clamp(0.0, 1.0);
+                PitchResult {
+                    f0: chosen.f0,
+                    aperiodicity: 1.0 - correlation,
+                    confidence: correlation,
+                    voiced: true,
+                }
+            } else {
+                PitchResult::UNVOICED
+            }
+        })
+        .collect()
+}
+
+// ------------------
+// Tests
+// ------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::f64::consts::PI;
+
+    #[test]
+    fn pitch_ac_tracks_harmonic_tone() {
+        let fs = 16000.0;
+        let signal: Vec<f64> = (0..8000)
+            .map(|n| {
+                let t = n as f64 / fs;
+                (1..=5)
+                    .map(|h| (2.0 * PI * 180.0 * h as f64 * t).sin() / h as f64)
+                    .sum()
+            })
+            .collect();
+
+        let track = pitch_ac(&signal, fs, &AcPitchConfig::default());
+        assert!(!track.is_empty());
+        for result in track {
+            assert!(result.voiced);
+            assert!((result.f0 - 180.0).abs() < 0.5, "f0 = {}", result.f0);
+        }
+    }
+
+    #[test]
+    fn pitch_ac_silence_is_unvoiced() {
+        let fs = 16000.0;
+        let mut signal = vec![0.0; 4000];
+        signal.extend((0..4000).map(|n| (2.0 * PI * 200.0 * n as f64 / fs).sin()));
+
+        let track = pitch_ac(&signal, fs, &AcPitchConfig::default());
+        assert!(!track.first().unwrap().voiced);
+        assert!(track.last().unwrap().voiced);
+    }
+}
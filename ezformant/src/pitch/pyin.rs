@@ -0,0 +1,339 @@
+//! Probabilistic YIN (Mauch & Dixon, 2014).
+//!
+//! Stage 1 turns each frame's CMND into a set of f0 candidates weighted by a beta
+//! prior over YIN thresholds. Stage 2 smooths the candidates across frames with an
+//! HMM over pitch bins (each with a voiced and an unvoiced state) decoded by Viterbi.
+
+use super::{difference_function_all, normalize_difference, parabolic_vertex, PitchResult};
+use crate::lpc::AutocorrelationMethod;
+
+/// Number of YIN thresholds evaluated per frame (0.01, 0.02, ..., 1.00).
+const N_THRESHOLDS: usize = 100;
+
+/// Parameters of the pYIN tracker.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PyinConfig {
+    /// Lowest f0 considered, in Hz.
+    pub fmin: f64,
+    /// Highest f0 considered, in Hz.
+    pub fmax: f64,
+    /// Frame length in samples. Should cover at least two periods of `fmin`.
+    pub frame_length: usize,
+    /// Hop between frames in samples.
+    pub hop_length: usize,
+    /// HMM pitch resolution.
+    pub bins_per_semitone: usize,
+    /// Largest pitch change between consecutive frames, in semitones.
+    pub max_jump_semitones: f64,
+    /// Probability of switching between voiced and unvoiced between frames.
+    pub switch_probability: f64,
+    /// Weight given to the global CMND minimum when no dip falls below a threshold.
+    pub no_trough_probability: f64,
+    /// Shape parameters `(alpha, beta)` of the beta prior over thresholds.
+    /// `(2.0, 18.0)` has mean 0.1.
+    pub beta_parameters: (f64, f64),
+}
+
+impl Default for PyinConfig {
+    fn default() -> Self {
+        Self {
+            fmin: 60.0,
+            fmax: 1000.0,
+            frame_length: 2048,
+            hop_length: 512,
+            bins_per_semitone: 10,
+            max_jump_semitones: 2.5,
+            switch_probability: 0.01,
+            no_trough_probability: 0.01,
+            beta_parameters: (2.0, 18.0),
+        }
+    }
+}
+
+/// An f0 hypothesis for a single frame.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct PitchCandidate {
+    /// Candidate frequency in Hz.
+    pub f0: f64,
+    /// Probability that the frame is voiced at this frequency.
+    pub probability: f64,
+}
+
+/// Computes the pYIN f0 candidates of a single frame (stage 1).
+///
+/// # Arguments
+///
+/// * `frame`       - The input frame.
+/// * `sample_rate` - The sampling rate of the frame.
+/// * `config`      - The pYIN parameters.
+///
+/// # Returns
+///
+/// The distinct candidates and their probabilities. The probabilities sum to at most 1;
+/// the remainder is the probability that the frame is unvoiced.
+pub fn pyin_candidates(
+    frame: &[f64],
+    sample_rate: f64,
+    config: &PyinConfig,
+) -> Vec<PitchCandidate> {
+    let tau_min = ((sample_rate / config.fmax).floor() as usize).max(2);
+    let tau_max = ((sample_rate / config.fmin).ceil() as usize).min(frame.len() / 2);
+    if tau_min + 1 >= tau_max {
+        return Vec::new();
+    }
+
+    let cmnd = normalize_difference(&difference_function_all(
+        frame,
+        tau_max + 1,
+        AutocorrelationMethod::Fft,
+    ));
+
+    // Local minima of the CMND within the allowed lag range.
+    let troughs: Vec<usize> = (tau_min..tau_max)
+        .filter(|&t| cmnd[t] <= cmnd[t - 1] && cmnd[t] <= cmnd[t + 1])
+        .collect();
+    if troughs.is_empty() {
+        return Vec::new();
+    }
+    let global_min = troughs
+        .iter()
+        .copied()
+        .min_by(|&a, &b| cmnd[a].total_cmp(&cmnd[b]))
+        .unwrap();
+
+    let prior = threshold_prior(config.beta_parameters);
+    let mut weights = vec![0.0; troughs.len()];
+    for (i, &w) in prior.iter().enumerate() {
+        let threshold = (i + 1) as f64 / N_THRESHOLDS as f64;
+        match troughs.iter().position(|&t| cmnd[t] < threshold) {
+            Some(k) => weights[k] += w,
+            None => {
+                let k = troughs.iter().position(|&t| t == global_min).unwrap();
+                weights[k] += config.no_trough_probability * w;
+            }
+        }
+    }
+
+    troughs
+        .iter()
+        .zip(weights)
+        .filter(|(_, w)| *w > 0.0)
+        .map(|(&t, probability)| PitchCandidate {
+            f0: sample_rate / parabolic_vertex(&cmnd, t).0,
+            probability,
+        })
+        .collect()
+}
+
+/// Smooths per-frame candidates into a pitch track with Viterbi decoding (stage 2).
+///
+/// # Arguments
+///
+/// * `candidates` - The candidates of each frame, as returned by [`pyin_candidates`].
+/// * `config`     - The pYIN parameters.
+///
+/// # Returns
+///
+/// One [`PitchResult`] per frame. `confidence` is the frame's total voiced probability.
+pub fn pyin_decode(candidates: &[Vec<PitchCandidate>], config: &PyinConfig) -> Vec<PitchResult> {
+    if candidates.is_empty() {
+        return Vec::new();
+    }
+
+    let bins_per_octave = 12.0 * config.bins_per_semitone as f64;
+    let n_bins = ((config.fmax / config.fmin).log2() * bins_per_octave).floor() as usize + 1;
+    let bin_of = |f0: f64| -> Option<usize> {
+        if !(f0 >= config.fmin && f0 <= config.fmax) {
+            return None;
+        }
+        Some((((f0 / config.fmin).log2() * bins_per_octave).round() as usize).min(n_bins - 1))
+    };
+    let bin_freq = |bin: usize| config.fmin * 2f64.powf(bin as f64 / bins_per_octave);
+
+    // Triangular pitch transition, log-domain, indexed by |jump|.
+    let width =
+        ((config.max_jump_semitones * config.bins_per_semitone as f64).round() as usize).max(1);
+    let triangle: Vec<f64> = (0..=width).map(|d| (width + 1 - d) as f64).collect();
+    let stay = (1.0 - config.switch_probability).ln();
+    let switch = config.switch_probability.max(f64::MIN_POSITIVE).ln();
+    // Row normalization of the truncated triangle near the edges.
+    let row_norm: Vec<f64> = (0..n_bins)
+        .map(|i| {
+            let lo = i.saturating_sub(width);
+            let hi = (i + width).min(n_bins - 1);
+            (lo..=hi).map(|j| triangle[i.abs_diff(j)]).sum::<f64>().ln()
+        })
+        .collect();
+
+    let n_states = 2 * n_bins;
+    let observation = |frame: &[PitchCandidate]| -> (Vec<f64>, f64) {
+        let mut obs = vec![0.0; n_states];
+        for c in frame {
+            if let Some(bin) = bin_of(c.f0) {
+                obs[bin] += c.probability;
+            }
+        }
+        let voiced: f64 = obs[..n_bins].iter().sum::<f64>().clamp(0.0, 1.0);
+        for o in obs[n_bins..].iter_mut() {
+            *o = (1.0 - voiced) / n_bins as f64;
+        }
+        (
+            obs.into_iter().map(|o| o.max(1e-300).ln()).collect(),
+            voiced,
+        )
+    };
+
+    let n_frames = candidates.len();
+    let mut backpointers = vec![vec![0usize; n_states]; n_frames];
+    let mut voiced_probs = Vec::with_capacity(n_frames);
+
+    let (obs, voiced) = observation(&candidates[0]);
+    voiced_probs.push(voiced);
+    let uniform = -(n_states as f64).ln();
+    let mut score: Vec<f64> = obs.iter().map(|o| o + uniform).collect();
+
+    for (t, frame) in candidates.iter().enumerate().skip(1) {
+        let (obs, voiced) = observation(frame);
+        voiced_probs.push(voiced);
+
+        let mut next = vec![f64::NEG_INFINITY; n_states];
+        for j in 0..n_states {
+            let (bin_j, voiced_j) = (j % n_bins, j < n_bins);
+            let lo = bin_j.saturating_sub(width);
+            let hi = (bin_j + width).min(n_bins - 1);
+            for bin_i in lo..=hi {
+                let pitch = triangle[bin_i.abs_diff(bin_j)].ln() - row_norm[bin_i];
+                for voiced_i in [true, false] {
+                    let i = if voiced_i { bin_i } else { bin_i + n_bins };
+                    let s = score[i] + pitch + if voiced_i == voiced_j { stay } else { switch };
+                    if s > next[j] {
+                        next[j] = s;
+                        backpointers[t][j] = i;
+                    }
+                }
+            }
+            next[j] += obs[j];
+        }
+        score = next;
+    }
+
+    let mut state = (0..n_states)
+        .max_by(|&a, &b| score[a].total_cmp(&score[b]))
+        .unwrap();
+    let mut path = vec![0; n_frames];
+    for t in (0..n_frames).rev() {
+        path[t] = state;
+        state = backpointers[t][state];
+    }
+
+    path.iter()
+        .zip(candidates)
+        .zip(voiced_probs)
+        .map(|((&state, frame), voiced_prob)| {
+            let bin = state % n_bins;
+            let target = bin_freq(bin);
+            // Prefer the refined candidate frequency over the bin centre.
+            let f0 = frame
+                .iter()
+                .filter(|c| bin_of(c.f0) == Some(bin))
+                .map(|c| c.f0)
+                .min_by(|a, b| (a - target).abs().total_cmp(&(b - target).abs()))
+                .unwrap_or(target);
+            PitchResult {
+                f0,
+                aperiodicity: 1.0 - voiced_prob,
+                confidence: voiced_prob,
+                voiced: state < n_bins,
+            }
+        })
+        .collect()
+}
+
+/// Tracks the pitch of a whole signal with pYIN.
+///
+/// Frames start at sample 0 and advance by `config.hop_length`; a trailing partial
+/// frame is dropped.
+///
+/// # Arguments
+///
+/// * `signal`      - The input signal.
+/// * `sample_rate` - The sampling rate of the signal.
+/// * `config`      - The pYIN parameters.
+///
+/// # Returns
+///
+/// One [`PitchResult`] per frame.
+#[tracing::instrument(level = "debug", skip_all, fields(len = signal.len(), sample_rate))]
+pub fn pyin(signal: &[f64], sample_rate: f64, config: &PyinConfig) -> Vec<PitchResult> {
+    if signal.len() < config.frame_length || config.hop_length == 0 {
+        return Vec::new();
+    }
+    let candidates: Vec<Vec<PitchCandidate>> = (0..=signal.len() - config.frame_length)
+        .step_by(config.hop_length)
+        .map(|start| {
+            pyin_candidates(
+                &signal[start..start + config.frame_length],
+                sample_rate,
+                config,
+            )
+        })
+        .collect();
+
+    pyin_decode(&candidates, config)
+}
+
+/// Normalized beta distribution weights for the thresholds 0.01, ..., 1.00.
+fn threshold_prior((alpha, beta): (f64, f64)) -> Vec<f64> {
+    let pdf: Vec<f64> = (1..=N_THRESHOLDS)
+        .map(|i| {
+            // Evaluate at bin centres to avoid the endpoints.
+            let x = (i as f64 - 0.5) / N_THRESHOLDS as f64;
+            x.powf(alpha - 1.0) * (1.0 - x).powf(beta - 1.0)
+        })
+        .collect();
+    let total: f64 = pdf.iter().sum();
+    pdf.into_iter().map(|p| p / total).collect()
+}
+
+// ------------------
+// Tests
+// ------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::f64::consts::PI;
+
+    #[test]
+    fn pyin_tracks_steady_tone() {
+        let fs = 16000.0;
+        let signal: Vec<f64> = (0..16000)
+            .map(|n| {
+                let t = n as f64 / fs;
+                (2.0 * PI * 220.0 * t).sin() + 0.4 * (2.0 * PI * 440.0 * t).sin()
+            })
+            .collect();
+
+        let track = pyin(&signal, fs, &PyinConfig::default());
+        assert!(!track.is_empty());
+        for result in track {
+            assert!(result.voiced);
+            assert!((result.f0 - 220.0).abs() < 1.0, "f0 = {}", result.f0);
+        }
+    }
+
+    #[test]
+    fn pyin_marks_silence_unvoiced() {
+        let fs = 16000.0;
+        let config = PyinConfig::default();
+        let mut signal = vec![0.0; 8000];
+        signal.extend((0..8000).map(|n| (2.0 * PI * 150.0 * n as f64 / fs).sin()));
+
+        let track = pyin(&signal, fs, &config);
+        assert!(!track.first().unwrap().voiced);
+        let last = track.last().unwrap();
+        assert!(last.voiced);
+        assert!((last.f0 - 150.0).abs() < 1.0, "f0 = {}", last.f0);
+    }
+}
@@ -0,0 +1,165 @@
+//! Python bindings (`python` feature).
+//!
+//! Build a wheel with `maturin build --release` from `ezformant/` (see
+//! `pyproject.toml`). Signals are 1-D numpy `float64` arrays; frequencies are in Hz.
+//!
+//! ```python
+//! import ezformant, soundfile
+//! x, fs = soundfile.read("vowel.wav")
+//! tracks = ezformant.analyze(x, fs, max_formant=5000.0)
+//! tracks["formants"][:, 0]  # F1 per frame, NaN where missing
+//! ```
+
+use crate::analyzer::Analyzer;
+use crate::batch::{analyze_signal, BatchConfig};
+use crate::lpc;
+use crate::quality::Quality;
+use numpy::{PyArray1, PyArray2, PyArrayMethods, PyReadonlyArray1};
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+use pyo3::types::PyDict;
+
+/// Formant frequencies of one frame, sorted ascending.
+#[pyfunction]
+#[pyo3(signature = (frame, sample_rate, lpc_order = 10, max_formant = 5500.0, quality = "accurate"))]
+fn formants(
+    frame: PyReadonlyArray1<'_, f64>,
+    sample_rate: f64,
+    lpc_order: usize,
+    max_formant: f64,
+    quality: &str,
+) -> PyResult<Vec<f64>> {
+    let analyzer = Analyzer::new(lpc_order, sample_rate)
+        .with_quality(parse_quality(quality)?)
+        .with_max_formant(max_formant);
+    analyzer
+        .try_formants(&samples(&frame))
+        .map_err(|err| PyValueError::new_err(err.to_string()))
+}
+
+/// Fundamental frequency of one frame, or `None` if it is not periodic.
+#[pyfunction]
+#[pyo3(signature = (frame, sample_rate, quality = "balanced"))]
+fn pitch(
+    frame: PyReadonlyArray1<'_, f64>,
+    sample_rate: f64,
+    quality: &str,
+) -> PyResult<Option<f64>> {
+    let result = Analyzer::new(0, sample_rate)
+        .with_quality(parse_quality(quality)?)
+        .try_pitch_result(&samples(&frame))
+        .map_err(|err| PyValueError::new_err(err.to_string()))?;
+    Ok(result.voiced.then_some(result.f0))
+}
+
+/// Autocorrelation-method LPC coefficients `[1, a1, ..., ap]` of a frame, as is
+/// (no window or pre-emphasis).
+#[pyfunction]
+fn lpc_coefficients<'py>(
+    py: Python<'py>,
+    frame: PyReadonlyArray1<'py, f64>,
+    order: usize,
+) -> PyResult<Bound<'py, PyArray1<f64>>> {
+    let frame = samples(&frame);
+    lpc::check_frame(&frame, order).map_err(|err| PyValueError::new_err(err.to_string()))?;
+    let r = lpc::autocorrelate(&frame, order);
+    let (coefficients, _) = lpc::levinson(order, &r);
+    Ok(PyArray1::from_vec(py, coefficients))
+}
+
+/// Frame-by-frame analysis of a recording.
+///
+/// Returns a dict of numpy arrays: `time`, `f0` (NaN where unvoiced), `energy`,
+/// `voiced`, and `formants` / `bandwidths` of shape `(frames, num_formants)` padded
+/// with NaN.
+#[pyfunction]
+#[pyo3(signature = (
+    signal,
+    sample_rate,
+    lpc_order = 10,
+    max_formant = 5500.0,
+    frame_duration = 0.025,
+    time_step = 0.01,
+    quality = "accurate",
+    num_formants = 4,
+))]
+#[allow(clippy::too_many_arguments)]
+fn analyze<'py>(
+    py: Python<'py>,
+    signal: PyReadonlyArray1<'py, f64>,
+    sample_rate: f64,
+    lpc_order: usize,
+    max_formant: f64,
+    frame_duration: f64,
+    time_step: f64,
+    quality: &str,
+    num_formants: usize,
+) -> PyResult<Bound<'py, PyDict>> {
+    let config = BatchConfig {
+        lpc_order,
+        max_formant,
+        quality: parse_quality(quality)?,
+        frame_duration,
+        time_step,
+        vad: None,
+    };
+    let frames = analyze_signal(&samples(&signal), sample_rate, &config);
+    let padded = |values: &[f64]| -> Vec<f64> {
+        (0..num_formants)
+            .map(|i| values.get(i).copied().unwrap_or(f64::NAN))
+            .collect()
+    };
+    let formants: Vec<Vec<f64>> = frames.iter().map(|f| padded(&f.formants)).collect();
+    let bandwidths: Vec<Vec<f64>> = frames.iter().map(|f| padded(&f.bandwidths)).collect();
+    let column = |field: fn(&crate::batch::TrackFrame) -> f64| -> Vec<f64> {
+        frames.iter().map(field).collect()
+    };
+
+    let tracks = PyDict::new(py);
+    tracks.set_item("time", PyArray1::from_vec(py, column(|f| f.time)))?;
+    tracks.set_item(
+        "f0",
+        PyArray1::from_vec(py, column(|f| if f.voiced { f.pitch } else { f64::NAN })),
+    )?;
+    tracks.set_item("energy", PyArray1::from_vec(py, column(|f| f.energy)))?;
+    tracks.set_item(
+        "voiced",
+        PyArray1::from_vec(py, frames.iter().map(|f| f.voiced).collect()),
+    )?;
+    tracks.set_item("formants", to_array2(py, &formants, num_formants)?)?;
+    tracks.set_item("bandwidths", to_array2(py, &bandwidths, num_formants)?)?;
+    Ok(tracks)
+}
+
+#[pymodule]
+#[pyo3(name = "ezformant")]
+fn python_module(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    m.add_function(wrap_pyfunction!(formants, m)?)?;
+    m.add_function(wrap_pyfunction!(pitch, m)?)?;
+    m.add_function(wrap_pyfunction!(lpc_coefficients, m)?)?;
+    m.add_function(wrap_pyfunction!(analyze, m)?)?;
+    Ok(())
+}
+
+// ------------------
+// Helpers
+// ------------------
+
+/// Copies a (possibly strided) numpy array.
+fn samples(array: &PyReadonlyArray1<'_, f64>) -> Vec<f64> {
+    array.as_array().to_vec()
+}
+
+fn parse_quality(quality: &str) -> PyResult<Quality> {
+    quality.parse().map_err(PyValueError::new_err)
+}
+
+/// Rows of equal length as a 2-D array; an empty track gives shape `(0, columns)`.
+fn to_array2<'py>(
+    py: Python<'py>,
+    rows: &[Vec<f64>],
+    columns: usize,
+) -> PyResult<Bound<'py, PyArray2<f64>>> {
+    let flat: Vec<f64> = rows.iter().flatten().copied().collect();
+    PyArray1::from_vec(py, flat).reshape([rows.len(), columns])
+}
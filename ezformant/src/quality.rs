@@ -0,0 +1,460 @@
+use crate::error::{self, check_finite, check_positive, EzFormantError};
+use crate::lpc::{self, AutocorrelationMethod, LagWindow};
+use crate::pitch::ac::{self, AcPitchConfig};
+use crate::pitch::pyin::{self, PyinConfig};
+use crate::pitch::{self, PitchResult};
+use crate::roots::RootSolver;
+use crate::simd::SimdFloat;
+use crate::spectrum::SpectralFloor;
+use crate::units::{self, FrequencyUnit};
+use crate::warped;
+use crate::{WindowFunction, DEFAULT_PRE_EMPHASIS};
+use std::str::FromStr;
+
+/// A single knob trading CPU time for accuracy.
+///
+/// Each tier maps to a concrete set of algorithm choices (see [`QualitySettings`]),
+/// so callers don't need to know the individual algorithms.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "lowercase"))]
+pub enum Quality {
+    /// Cheapest choices, suitable for real-time display on slow devices.
+    Fast,
+    /// Sensible defaults for real-time use.
+    #[default]
+    Balanced,
+    /// Most robust choices, for offline analysis.
+    Accurate,
+}
+
+impl Quality {
+    /// Returns the concrete algorithm choices for this tier.
+    pub fn settings(self) -> QualitySettings {
+        match self {
+            Quality::Fast => QualitySettings {
+                autocorrelation: AutocorrelationMethod::Fft,
+                root_solver: RootSolver::Aberth {
+                    epsilon: 1e-3,
+                    max_iterations: 15,
+                },
+                pitch_method: PitchMethod::Yin,
+                window: WindowFunction::Hamming,
+                pre_emphasis: DEFAULT_PRE_EMPHASIS,
+                lag_window: LagWindow::default(),
+                warping: 0.0,
+                pitch_range: None,
+                smoothing: false,
+            },
+            Quality::Balanced => QualitySettings {
+                autocorrelation: AutocorrelationMethod::Fft,
+                root_solver: RootSolver::Aberth {
+                    epsilon: 1e-6,
+                    max_iterations: 50,
+                },
+                pitch_method: PitchMethod::Yin,
+                window: WindowFunction::Hamming,
+                pre_emphasis: DEFAULT_PRE_EMPHASIS,
+                lag_window: LagWindow::default(),
+                warping: 0.0,
+                pitch_range: None,
+                smoothing: false,
+            },
+            Quality::Accurate => QualitySettings {
+                autocorrelation: AutocorrelationMethod::Direct,
+                root_solver: RootSolver::Eigen,
+                pitch_method: PitchMethod::Pyin,
+                window: WindowFunction::Hamming,
+                pre_emphasis: DEFAULT_PRE_EMPHASIS,
+                lag_window: LagWindow::default(),
+                warping: 0.0,
+                pitch_range: None,
+                smoothing: true,
+            },
+        }
+    }
+}
+
+impl FromStr for Quality {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "fast" => Ok(Quality::Fast),
+            "balanced" => Ok(Quality::Balanced),
+            "accurate" => Ok(Quality::Accurate),
+            _ => Err(format!("unknown quality: {}", s)),
+        }
+    }
+}
+
+/// Pitch tracking algorithm used across frames.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum PitchMethod {
+    /// Independent YIN estimate per frame.
+    #[default]
+    Yin,
+    /// Probabilistic YIN with Viterbi smoothing across frames.
+    Pyin,
+    /// Praat's autocorrelation method with path finding. The window length is
+    /// derived from the pitch floor; only the hop is taken from the caller.
+    Autocorrelation,
+}
+
+/// The LPC fit of a frame and its model spectrum in dB, from
+/// [`QualitySettings::gain_envelope_db`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct GainEnvelope {
+    /// The LPC coefficients `[1.0, a1, ..., a_order]`.
+    pub lpc_coeffs: Vec<f64>,
+    /// The sampling rate the frame was analyzed at, after downsampling.
+    pub sample_rate: f64,
+    /// The frequencies of the envelope points, in Hz.
+    pub frequencies: Vec<f64>,
+    /// The model spectrum at `frequencies`, in dB.
+    pub magnitudes_db: Vec<f64>,
+}
+
+/// Concrete algorithm choices behind a [`Quality`] tier.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct QualitySettings {
+    /// Autocorrelation used by the YIN difference function. LPC orders are small, so
+    /// LPC autocorrelation is always computed directly.
+    pub autocorrelation: AutocorrelationMethod,
+    /// Polynomial root finder used to turn LPC coefficients into formants.
+    pub root_solver: RootSolver,
+    /// Pitch tracker used by [`QualitySettings::track_pitch`]. Single-frame estimates
+    /// always use YIN.
+    pub pitch_method: PitchMethod,
+    /// Window applied to frames before LPC analysis.
+    pub window: WindowFunction,
+    /// Pre-emphasis coefficient applied to frames before LPC analysis.
+    pub pre_emphasis: f64,
+    /// Regularization of the LPC autocorrelation (none by default).
+    pub lag_window: LagWindow,
+    /// Allpass coefficient of warped LPC (see [`crate::warped`]); `0.0` analyzes on the
+    /// linear frequency axis.
+    pub warping: f64,
+    /// Lowest and highest f0 considered, in Hz. `None` searches every period that fits
+    /// twice in the frame (YIN) or uses the tracker's own defaults.
+    pub pitch_range: Option<(f64, f64)>,
+    /// Whether whole recordings get their formant tracks smoothed after analysis (see
+    /// [`crate::smoothing::smooth_frames`]). Streams are analyzed frame by frame and are
+    /// never smoothed.
+    pub smoothing: bool,
+}
+
+impl Default for QualitySettings {
+    fn default() -> Self {
+        Quality::default().settings()
+    }
+}
+
+impl QualitySettings {
+    /// Preprocesses a frame and detects its formants.
+    ///
+    /// # Arguments
+    ///
+    /// * `data`        - The input frame, `f32` or `f64`. Preprocessing and the
+    ///   autocorrelation run in this precision; the recursion and root solving always
+    ///   use `f64`.
+    /// * `lpc_order`   - The LPC order.
+    /// * `sample_rate` - The sampling rate of the frame.
+    ///
+    /// # Returns
+    ///
+    /// A vector of formant frequencies in Hz.
+    pub fn detect_formants<T: SimdFloat>(
+        &self,
+        data: &[T],
+        lpc_order: usize,
+        sample_rate: f64,
+    ) -> Vec<f64> {
+        self.detect_formant_peaks(data, lpc_order, sample_rate)
+            .into_iter()
+            .map(|peak| peak.frequency)
+            .collect()
+    }
+
+    /// Like [`QualitySettings::detect_formants`], but keeps the bandwidth and pole
+    /// radius of each formant.
+    ///
+    /// # Returns
+    ///
+    /// The peaks that pass the formant filter, sorted by frequency; none for frames of
+    /// `lpc_order` samples or fewer (see [`lpc::check_frame`]).
+    pub fn detect_formant_peaks<T: SimdFloat>(
+        &self,
+        data: &[T],
+        lpc_order: usize,
+        sample_rate: f64,
+    ) -> Vec<lpc::Peak> {
+        if data.len() <= lpc_order {
+            return Vec::new();
+        }
+        let mut data = data.to_vec();
+        crate::preprocess_signal_with(&mut data, self.pre_emphasis, self.window);
+        let widen = |x: T| x.to_f64().unwrap_or(f64::NAN);
+
+        let mut peaks = if self.warping != 0.0 {
+            let data: Vec<f64> = data.into_iter().map(widen).collect();
+            let mut r = warped::warped_autocorrelate(&data, lpc_order, self.warping);
+            self.lag_window.apply(&mut r, sample_rate);
+            let (lpc_coeff, _) = lpc::levinson(lpc_order, &r);
+            warped::warped_peak_detection(&lpc_coeff, sample_rate, self.warping, self.root_solver)
+        } else {
+            let mut r: Vec<f64> = lpc::autocorrelate(&data, lpc_order)
+                .into_iter()
+                .map(widen)
+                .collect();
+            self.lag_window.apply(&mut r, sample_rate);
+            let (lpc_coeff, _) = lpc::levinson(lpc_order, &r);
+            lpc::peak_detection_with_metadata(&lpc_coeff, sample_rate, self.root_solver)
+        };
+
+        peaks.retain(|peak| peak.is_formant);
+        peaks.sort_by(|a, b| a.frequency.total_cmp(&b.frequency));
+        tracing::debug!(formants = ?peaks.iter().map(|p| p.frequency).collect::<Vec<_>>(), "formants");
+        peaks
+    }
+
+    /// Detects formants from the power spectrum of a frame instead of its samples.
+    ///
+    /// The spectrum is assumed to be windowed already, so [`QualitySettings::window`]
+    /// and [`QualitySettings::warping`] are not used; pre-emphasis is applied as the
+    /// gain `|1 - alpha e^{-jw}|^2` of each bin.
+    ///
+    /// # Arguments
+    ///
+    /// * `power`       - The power spectrum from DC up to (not including) Nyquist (see
+    ///   [`lpc::autocorrelate_from_power_spectrum`]).
+    /// * `lpc_order`   - The LPC order.
+    /// * `sample_rate` - The sampling rate the spectrum was computed at.
+    ///
+    /// # Returns
+    ///
+    /// A vector of formant frequencies in Hz; empty if the spectrum stands for a frame
+    /// of `lpc_order` samples or fewer (two per bin).
+    pub fn detect_formants_from_spectrum(
+        &self,
+        power: &[f64],
+        lpc_order: usize,
+        sample_rate: f64,
+    ) -> Vec<f64> {
+        if 2 * power.len() <= lpc_order {
+            return Vec::new();
+        }
+        let alpha = self.pre_emphasis;
+        let bins = power.len() as f64;
+        let emphasized: Vec<f64> = power
+            .iter()
+            .enumerate()
+            .map(|(k, p)| {
+                let omega = std::f64::consts::PI * k as f64 / bins;
+                p * (1.0 + alpha * alpha - 2.0 * alpha * omega.cos())
+            })
+            .collect();
+
+        let mut r = lpc::autocorrelate_from_power_spectrum(&emphasized, lpc_order);
+        self.lag_window.apply(&mut r, sample_rate);
+        let (lpc_coeff, _) = lpc::levinson(lpc_order, &r);
+
+        lpc::formant_detection_with_solver(&lpc_coeff, sample_rate, self.root_solver)
+    }
+
+    /// Fits LPC to a frame and returns the model spectrum in dB, scaled by the LPC gain
+    /// with the window's energy loss, the pre-emphasis and the downsampling undone, so
+    /// that it overlays the DFT magnitude of the original frame in dB.
+    ///
+    /// The frame is preprocessed with [`QualitySettings::window`] and
+    /// [`QualitySettings::pre_emphasis`]; the envelope is that of the stabilized filter.
+    ///
+    /// # Arguments
+    ///
+    /// * `data`                 - The input frame.
+    /// * `lpc_order`            - The LPC order.
+    /// * `original_sample_rate` - The sampling rate of `data`.
+    /// * `downsample_factor`    - The factor to [`decimate`](crate::decimate) by first.
+    /// * `num_points`           - Number of linear frequency steps up to the analysis
+    ///   Nyquist frequency.
+    /// * `floor`                - Floor the magnitudes are clamped to before conversion.
+    pub fn gain_envelope_db(
+        &self,
+        data: &[f64],
+        lpc_order: usize,
+        original_sample_rate: f64,
+        downsample_factor: usize,
+        num_points: usize,
+        floor: SpectralFloor,
+    ) -> GainEnvelope {
+        let downsample_factor = downsample_factor.max(1);
+        let sample_rate = original_sample_rate / downsample_factor as f64;
+        let mut data = crate::decimate(data, downsample_factor);
+        let energy = |x: &[f64]| x.iter().map(|v| v * v).sum::<f64>();
+
+        crate::subtract_mean_in_place(&mut data);
+        let unwindowed = energy(&data);
+        crate::apply_window_in_place(&mut data, self.window);
+        let windowed = energy(&data);
+        crate::pre_emphasize_in_place(&mut data, self.pre_emphasis);
+
+        let (lpc_coeffs, error) = lpc::fit(&data, lpc_order);
+        let envelope_coeffs = lpc::stabilize(&lpc_coeffs, RootSolver::Eigen);
+        // DFT magnitudes grow with the frame length, hence the squared factor.
+        let scale = if windowed > 0.0 {
+            (downsample_factor * downsample_factor) as f64 * unwindowed / windowed
+        } else {
+            0.0
+        };
+
+        let alpha = self.pre_emphasis;
+        let grid = units::frequency_axis(num_points, sample_rate, FrequencyUnit::Hertz);
+        let (frequencies, mut magnitudes): (Vec<f64>, Vec<f64>) =
+            lpc::model_frequency_response(&envelope_coeffs, error * scale, sample_rate, &grid)
+                .into_iter()
+                .map(|(freq, magnitude)| {
+                    let omega = 2.0 * std::f64::consts::PI * freq / sample_rate;
+                    let emphasis = (1.0 + alpha * alpha - 2.0 * alpha * omega.cos()).sqrt();
+                    (freq, magnitude / emphasis)
+                })
+                .unzip();
+        floor.apply(&mut magnitudes);
+
+        GainEnvelope {
+            lpc_coeffs,
+            sample_rate,
+            frequencies,
+            magnitudes_db: magnitudes.iter().map(|m| 20.0 * m.log10()).collect(),
+        }
+    }
+
+    /// Like [`QualitySettings::gain_envelope_db`], but returns an error for a
+    /// non-positive sampling rate, a zero downsample factor, or a frame that is empty,
+    /// non-finite or too short for `lpc_order` after downsampling.
+    pub fn try_gain_envelope_db(
+        &self,
+        data: &[f64],
+        lpc_order: usize,
+        original_sample_rate: f64,
+        downsample_factor: usize,
+        num_points: usize,
+        floor: SpectralFloor,
+    ) -> error::Result<GainEnvelope> {
+        check_positive("sample_rate", original_sample_rate)?;
+        if downsample_factor == 0 {
+            return Err(EzFormantError::InvalidParameter {
+                name: "downsample_factor",
+                value: 0.0,
+            });
+        }
+        check_finite(data)?;
+        let required = lpc_order * downsample_factor + 1;
+        if data.len() < required {
+            return Err(EzFormantError::InputTooShort {
+                len: data.len(),
+                required,
+            });
+        }
+        Ok(self.gain_envelope_db(
+            data,
+            lpc_order,
+            original_sample_rate,
+            downsample_factor,
+            num_points,
+            floor,
+        ))
+    }
+
+    /// Detects the fundamental frequency of a frame.
+    ///
+    /// # Arguments
+    ///
+    /// * `signal`        - The input frame.
+    /// * `sampling_rate` - The sampling rate of the frame.
+    ///
+    /// # Returns
+    ///
+    /// The fundamental frequency in Hz, or `-1.0` if the frame is not periodic.
+    pub fn detect_pitch(&self, signal: &[f64], sampling_rate: f64) -> f64 {
+        let result = self.estimate_pitch(signal, sampling_rate);
+        if result.voiced {
+            result.f0
+        } else {
+            -1.0
+        }
+    }
+
+    /// Estimates the fundamental frequency of a frame along with its voicing.
+    ///
+    /// # Arguments
+    ///
+    /// * `signal`        - The input frame.
+    /// * `sampling_rate` - The sampling rate of the frame.
+    pub fn estimate_pitch(&self, signal: &[f64], sampling_rate: f64) -> PitchResult {
+        match self.pitch_range {
+            Some((fmin, fmax)) => {
+                pitch::yin_in_range(signal, sampling_rate, 0.1, self.autocorrelation, fmin, fmax)
+            }
+            None => pitch::yin(signal, sampling_rate, 0.1, self.autocorrelation),
+        }
+    }
+
+    /// Tracks the pitch of a whole signal frame by frame.
+    ///
+    /// # Arguments
+    ///
+    /// * `signal`       - The input signal.
+    /// * `sample_rate`  - The sampling rate of the signal.
+    /// * `frame_length` - Frame length in samples.
+    /// * `hop_length`   - Hop between frames in samples.
+    ///
+    /// # Returns
+    ///
+    /// One [`PitchResult`] per full frame.
+    pub fn track_pitch(
+        &self,
+        signal: &[f64],
+        sample_rate: f64,
+        frame_length: usize,
+        hop_length: usize,
+    ) -> Vec<PitchResult> {
+        match self.pitch_method {
+            PitchMethod::Yin => {
+                if signal.len() < frame_length || hop_length == 0 {
+                    return Vec::new();
+                }
+                (0..=signal.len() - frame_length)
+                    .step_by(hop_length)
+                    .map(|start| {
+                        self.estimate_pitch(&signal[start..start + frame_length], sample_rate)
+                    })
+                    .collect()
+            }
+            PitchMethod::Pyin => {
+                let defaults = PyinConfig::default();
+                let (fmin, fmax) = self.pitch_range.unwrap_or((defaults.fmin, defaults.fmax));
+                let config = PyinConfig {
+                    fmin,
+                    fmax,
+                    frame_length,
+                    hop_length,
+                    ..defaults
+                };
+                pyin::pyin(signal, sample_rate, &config)
+            }
+            PitchMethod::Autocorrelation => {
+                let defaults = AcPitchConfig::default();
+                let (pitch_floor, pitch_ceiling) = self
+                    .pitch_range
+                    .unwrap_or((defaults.pitch_floor, defaults.pitch_ceiling));
+                let config = AcPitchConfig {
+                    time_step: hop_length as f64 / sample_rate,
+                    pitch_floor,
+                    pitch_ceiling,
+                    ..defaults
+                };
+                ac::pitch_ac(signal, sample_rate, &config)
+            }
+        }
+    }
+}
@@ -0,0 +1,335 @@
+//! Sample rate conversion.
+//!
+//! [`Resampler`] is the extension point; [`PolyphaseResampler`] is the built-in
+//! anti-aliased backend used by [`decimate`](crate::decimate), [`Decimator`] keeps
+//! the "every n-th sample" behaviour of [`downsample`](crate::downsample), and
+//! `RubatoResampler` (feature `rubato`) offers a high-quality backend for offline work.
+
+use std::f64::consts::PI;
+
+/// Phase tables larger than this are not cached; weights are computed per sample.
+const MAX_CACHED_PHASES: usize = 1024;
+
+/// Converts a signal from one sampling rate to another.
+pub trait Resampler {
+    /// Resamples `input` from `from_rate` to `to_rate`.
+    ///
+    /// # Arguments
+    ///
+    /// * `input`     - The input signal.
+    /// * `from_rate` - The sampling rate of `input`.
+    /// * `to_rate`   - The desired sampling rate.
+    ///
+    /// # Returns
+    ///
+    /// About `input.len() * to_rate / from_rate` samples, aligned so that output sample
+    /// `0` coincides with input sample `0`.
+    fn resample(&self, input: &[f64], from_rate: f64, to_rate: f64) -> Vec<f64>;
+}
+
+/// Picks the nearest input sample for each output sample, without filtering.
+///
+/// Cheap, but everything above the new Nyquist frequency aliases into the band.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Decimator;
+
+impl Resampler for Decimator {
+    fn resample(&self, input: &[f64], from_rate: f64, to_rate: f64) -> Vec<f64> {
+        let (up, down) = rational_ratio(from_rate, to_rate);
+        (0..output_length(input.len(), up, down))
+            .map(|n| input[((n * down + up / 2) / up).min(input.len() - 1)])
+            .collect()
+    }
+}
+
+/// Rational-ratio resampler with a Kaiser-windowed sinc low-pass filter.
+///
+/// The rates are reduced to a ratio `up / down` of whole numbers; each of the `up`
+/// filter phases is precomputed once per call.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PolyphaseResampler {
+    /// Zero crossings of the sinc on each side of the centre tap.
+    pub zero_crossings: usize,
+    /// Filter cutoff relative to the lower of the two Nyquist frequencies.
+    pub cutoff: f64,
+    /// Kaiser window shape; larger values trade transition width for stopband attenuation.
+    pub kaiser_beta: f64,
+}
+
+impl Default for PolyphaseResampler {
+    fn default() -> Self {
+        Self {
+            zero_crossings: 16,
+            cutoff: 0.95,
+            kaiser_beta: 8.0,
+        }
+    }
+}
+
+impl PolyphaseResampler {
+    /// Filter weights for an output sample `frac` input samples past input `k0`,
+    /// applied to inputs `k0 + 1 - half ..= k0 + half`.
+    fn weights(&self, frac: f64, scale: f64, half: usize) -> Vec<f64> {
+        let i0_beta = bessel_i0(self.kaiser_beta);
+        let mut weights: Vec<f64> = (0..2 * half)
+            .map(|j| {
+                let x = frac + half as f64 - 1.0 - j as f64;
+                let ratio = x / half as f64;
+                let window = if ratio.abs() < 1.0 {
+                    bessel_i0(self.kaiser_beta * (1.0 - ratio * ratio).sqrt()) / i0_beta
+                } else {
+                    0.0
+                };
+                scale * sinc(scale * x) * window
+            })
+            .collect();
+        // Unity gain at DC for every phase.
+        let sum: f64 = weights.iter().sum();
+        if sum.abs() > f64::EPSILON {
+            weights.iter_mut().for_each(|w| *w /= sum);
+        }
+        weights
+    }
+}
+
+impl Resampler for PolyphaseResampler {
+    #[tracing::instrument(level = "trace", skip_all, fields(len = input.len(), from_rate, to_rate))]
+    fn resample(&self, input: &[f64], from_rate: f64, to_rate: f64) -> Vec<f64> {
+        let (up, down) = rational_ratio(from_rate, to_rate);
+        if up == down {
+            return input.to_vec();
+        }
+
+        let scale = (up as f64 / down as f64).min(1.0) * self.cutoff;
+        let half = ((self.zero_crossings as f64 / scale).ceil() as usize).max(1);
+        let table: Vec<Vec<f64>> = if up <= MAX_CACHED_PHASES {
+            (0..up)
+                .map(|p| self.weights(p as f64 / up as f64, scale, half))
+                .collect()
+        } else {
+            Vec::new()
+        };
+
+        (0..output_length(input.len(), up, down))
+            .map(|n| {
+                let (k0, phase) = ((n * down) / up, (n * down) % up);
+                let computed;
+                let weights = match table.get(phase) {
+                    Some(weights) => weights,
+                    None => {
+                        computed = self.weights(phase as f64 / up as f64, scale, half);
+                        &computed
+                    }
+                };
+                weights
+                    .iter()
+                    .enumerate()
+                    .filter_map(|(j, &w)| {
+                        let k = (k0 + 1 + j).checked_sub(half)?;
+                        input.get(k).map(|&x| w * x)
+                    })
+                    .sum()
+            })
+            .collect()
+    }
+}
+
+/// High-quality asynchronous sinc resampling backed by the `rubato` crate.
+#[cfg(feature = "rubato")]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RubatoResampler {
+    /// Length of the sinc filter.
+    pub sinc_len: usize,
+    /// Filter cutoff relative to the lower of the two Nyquist frequencies.
+    pub cutoff: f32,
+    /// Number of precomputed intermediate filter positions.
+    pub oversampling_factor: usize,
+}
+
+#[cfg(feature = "rubato")]
+impl Default for RubatoResampler {
+    fn default() -> Self {
+        Self {
+            sinc_len: 256,
+            cutoff: 0.95,
+            oversampling_factor: 256,
+        }
+    }
+}
+
+#[cfg(feature = "rubato")]
+impl Resampler for RubatoResampler {
+    #[tracing::instrument(level = "trace", skip_all, fields(len = input.len(), from_rate, to_rate))]
+    fn resample(&self, input: &[f64], from_rate: f64, to_rate: f64) -> Vec<f64> {
+        use rubato::{
+            Resampler as _, SincFixedIn, SincInterpolationParameters, SincInterpolationType,
+            WindowFunction,
+        };
+
+        if input.is_empty() {
+            return Vec::new();
+        }
+        let ratio = to_rate / from_rate;
+        let expected = (input.len() as f64 * ratio).ceil() as usize;
+        let parameters = SincInterpolationParameters {
+            sinc_len: self.sinc_len,
+            f_cutoff: self.cutoff,
+            oversampling_factor: self.oversampling_factor,
+            interpolation: SincInterpolationType::Cubic,
+            window: WindowFunction::BlackmanHarris2,
+        };
+
+        let mut resampler = match SincFixedIn::<f64>::new(ratio, 1.0, parameters, input.len(), 1) {
+            Ok(resampler) => resampler,
+            Err(error) => {
+                tracing::warn!(%error, "could not construct rubato resampler");
+                return Vec::new();
+            }
+        };
+        let delay = resampler.output_delay();
+
+        let mut output = Vec::with_capacity(delay + expected);
+        match resampler.process(&[input], None) {
+            Ok(mut channels) => output.append(&mut channels[0]),
+            Err(error) => {
+                tracing::warn!(%error, "rubato resampling failed");
+                return Vec::new();
+            }
+        }
+        // Flush the filter delay.
+        while output.len() < delay + expected {
+            match resampler.process_partial::<&[f64]>(None, None) {
+                Ok(mut channels) if !channels[0].is_empty() => output.append(&mut channels[0]),
+                _ => break,
+            }
+        }
+
+        output.drain(..delay.min(output.len()));
+        output.truncate(expected);
+        output
+    }
+}
+
+/// Reduces `to_rate / from_rate` to a ratio `up / down` of whole numbers.
+///
+/// Rates are rounded to whole Hz (or whole units, for ratios like `4.0 -> 1.0`).
+fn rational_ratio(from_rate: f64, to_rate: f64) -> (usize, usize) {
+    let from = from_rate.round() as usize;
+    let to = to_rate.round() as usize;
+    assert!(
+        from > 0 && to > 0,
+        "sampling rates must be positive (got {from_rate} -> {to_rate})"
+    );
+    let g = gcd(from, to);
+    (to / g, from / g)
+}
+
+fn output_length(input_len: usize, up: usize, down: usize) -> usize {
+    (input_len * up).div_ceil(down)
+}
+
+fn gcd(mut a: usize, mut b: usize) -> usize {
+    while b != 0 {
+        (a, b) = (b, a % b);
+    }
+    a
+}
+
+fn sinc(x: f64) -> f64 {
+    if x.abs() < 1e-12 {
+        1.0
+    } else {
+        (PI * x).sin() / (PI * x)
+    }
+}
+
+/// Zeroth-order modified Bessel function of the first kind (power series).
+fn bessel_i0(x: f64) -> f64 {
+    let mut sum = 1.0;
+    let mut term = 1.0;
+    let half_x = 0.5 * x;
+    for k in 1..50 {
+        term *= (half_x / k as f64).powi(2);
+        sum += term;
+        if term < 1e-12 * sum {
+            break;
+        }
+    }
+    sum
+}
+
+// ------------------
+// Tests
+// ------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tone(frequency: f64, sample_rate: f64, num_samples: usize) -> Vec<f64> {
+        (0..num_samples)
+            .map(|n| (2.0 * PI * frequency * n as f64 / sample_rate).sin())
+            .collect()
+    }
+
+    fn rms(signal: &[f64]) -> f64 {
+        (signal.iter().map(|x| x * x).sum::<f64>() / signal.len() as f64).sqrt()
+    }
+
+    #[test]
+    fn polyphase_preserves_passband_and_rejects_aliases() {
+        let resampler = PolyphaseResampler::default();
+
+        // 1 kHz survives 44.1 kHz -> 16 kHz.
+        let passband = resampler.resample(&tone(1000.0, 44100.0, 44100), 44100.0, 16000.0);
+        assert_eq!(passband.len(), 16000);
+        let expected = tone(1000.0, 16000.0, 16000);
+        let error: Vec<f64> = passband[500..15500]
+            .iter()
+            .zip(&expected[500..15500])
+            .map(|(a, b)| a - b)
+            .collect();
+        assert!(rms(&error) < 1e-3, "passband error = {}", rms(&error));
+
+        // 12 kHz lies above the new Nyquist frequency and would alias to 4 kHz.
+        let stopband = resampler.resample(&tone(12000.0, 44100.0, 44100), 44100.0, 16000.0);
+        assert!(rms(&stopband[500..15500]) < 1e-3);
+        let aliased = Decimator.resample(&tone(12000.0, 48000.0, 48000), 48000.0, 16000.0);
+        assert!(rms(&aliased) > 0.5);
+    }
+
+    #[test]
+    fn integer_factor_matches_decimator_length() {
+        let signal = tone(300.0, 16000.0, 1001);
+        for factor in 1..=5 {
+            let from = factor as f64;
+            assert_eq!(
+                PolyphaseResampler::default()
+                    .resample(&signal, from, 1.0)
+                    .len(),
+                Decimator.resample(&signal, from, 1.0).len()
+            );
+            assert_eq!(
+                Decimator.resample(&signal, from, 1.0),
+                signal.iter().step_by(factor).copied().collect::<Vec<_>>()
+            );
+        }
+    }
+
+    #[cfg(feature = "rubato")]
+    #[test]
+    fn rubato_preserves_passband_and_rejects_aliases() {
+        let resampler = RubatoResampler::default();
+
+        let passband = resampler.resample(&tone(1000.0, 44100.0, 44100), 44100.0, 16000.0);
+        assert_eq!(passband.len(), 16000);
+        let level = rms(&passband[500..15500]);
+        assert!(
+            (level - 0.5f64.sqrt()).abs() < 1e-2,
+            "passband rms = {level}"
+        );
+
+        let stopband = resampler.resample(&tone(12000.0, 44100.0, 44100), 44100.0, 16000.0);
+        assert!(rms(&stopband[500..15500]) < 1e-3);
+    }
+}
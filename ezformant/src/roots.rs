@@ -0,0 +1,326 @@
+#[cfg(feature = "std")]
+use aberth::AberthSolver;
+#[cfg(not(feature = "std"))]
+use alloc::{vec, vec::Vec};
+use num_complex::Complex;
+
+/// Polynomial root finding strategy used for LPC pole extraction.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum RootSolver {
+    /// Simultaneous Aberth–Ehrlich iteration. Cheap, but may stop before full
+    /// convergence when `max_iterations` is small. Without the `std` feature it falls
+    /// back to [`RootSolver::Eigen`].
+    Aberth { epsilon: f64, max_iterations: u32 },
+    /// Eigenvalues of the (balanced) companion matrix via Hessenberg QR.
+    /// Slower, but robust for ill-conditioned or high-order polynomials.
+    Eigen,
+}
+
+impl Default for RootSolver {
+    fn default() -> Self {
+        RootSolver::Aberth {
+            epsilon: 0.001,
+            max_iterations: 15,
+        }
+    }
+}
+
+impl RootSolver {
+    /// Finds the roots of `z^p + a1 z^(p-1) + ... + ap` given LPC coefficients.
+    ///
+    /// # Arguments
+    ///
+    /// * `lpc_coeffs` - The LPC coefficients `[1.0, a1, ..., ap]`.
+    ///
+    /// # Returns
+    ///
+    /// The `p` complex roots (the poles of the all-pole filter).
+    pub fn roots(&self, lpc_coeffs: &[f64]) -> Vec<Complex<f64>> {
+        match *self {
+            RootSolver::Aberth {
+                epsilon,
+                max_iterations,
+            } => aberth_roots(lpc_coeffs, epsilon, max_iterations),
+            RootSolver::Eigen => companion_roots(lpc_coeffs).unwrap_or_else(|| {
+                tracing::warn!("companion matrix QR did not converge; falling back to Aberth");
+                aberth_roots(lpc_coeffs, 1e-12, 100)
+            }),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+fn aberth_roots(lpc_coeffs: &[f64], epsilon: f64, max_iterations: u32) -> Vec<Complex<f64>> {
+    let mut poly = lpc_coeffs.to_vec();
+    poly.reverse();
+
+    let mut solver = AberthSolver::new();
+    solver.epsilon = epsilon;
+    solver.max_iterations = max_iterations;
+
+    solver.find_roots(&poly).to_vec()
+}
+
+// The companion matrix, the one solver available without `std`.
+#[cfg(not(feature = "std"))]
+fn aberth_roots(lpc_coeffs: &[f64], _epsilon: f64, _max_iterations: u32) -> Vec<Complex<f64>> {
+    companion_roots(lpc_coeffs).unwrap_or_default()
+}
+
+/// Computes the roots of a polynomial as eigenvalues of its companion matrix.
+///
+/// # Arguments
+///
+/// * `coeffs` - Polynomial coefficients in descending powers, `[c0, c1, ..., cp]`
+///   with `c0 != 0` (for LPC coefficients `c0 = 1.0`).
+///
+/// # Returns
+///
+/// The `p` complex roots, or `None` if the QR iteration failed to converge.
+pub fn companion_roots(coeffs: &[f64]) -> Option<Vec<Complex<f64>>> {
+    if coeffs.len() < 2 || coeffs[0] == 0.0 {
+        return Some(Vec::new());
+    }
+    let n = coeffs.len() - 1;
+
+    // 1-based (n + 1) x (n + 1) upper Hessenberg companion matrix.
+    let mut a = vec![vec![0.0; n + 1]; n + 1];
+    for k in 1..=n {
+        a[1][k] = -coeffs[k] / coeffs[0];
+    }
+    for j in 2..=n {
+        a[j][j - 1] = 1.0;
+    }
+
+    balance(&mut a, n);
+    hessenberg_qr(&mut a, n)
+}
+
+/// Balances a 1-based square matrix in-place to improve eigenvalue accuracy.
+#[allow(clippy::needless_range_loop)]
+fn balance(a: &mut [Vec<f64>], n: usize) {
+    const RADIX: f64 = 2.0;
+    const SQRDX: f64 = RADIX * RADIX;
+
+    let mut done = false;
+    while !done {
+        done = true;
+        for i in 1..=n {
+            let mut r = 0.0;
+            let mut c = 0.0;
+            for j in 1..=n {
+                if j != i {
+                    c += a[j][i].abs();
+                    r += a[i][j].abs();
+                }
+            }
+            if c == 0.0 || r == 0.0 {
+                continue;
+            }
+
+            let s = c + r;
+            let mut f = 1.0;
+            let mut g = r / RADIX;
+            while c < g {
+                f *= RADIX;
+                c *= SQRDX;
+            }
+            g = r * RADIX;
+            while c > g {
+                f /= RADIX;
+                c /= SQRDX;
+            }
+
+            if (c + r) / f < 0.95 * s {
+                done = false;
+                let g = 1.0 / f;
+                for j in 1..=n {
+                    a[i][j] *= g;
+                }
+                for j in 1..=n {
+                    a[j][i] *= f;
+                }
+            }
+        }
+    }
+}
+
+/// Finds all eigenvalues of a 1-based upper Hessenberg matrix with the
+/// Francis double-shift QR algorithm. The matrix is destroyed.
+#[allow(clippy::many_single_char_names, clippy::needless_range_loop)]
+fn hessenberg_qr(a: &mut [Vec<f64>], n: usize) -> Option<Vec<Complex<f64>>> {
+    const MAX_ITERATIONS: usize = 60;
+
+    let mut wr = vec![0.0; n + 1];
+    let mut wi = vec![0.0; n + 1];
+
+    let mut anorm = 0.0;
+    for i in 1..=n {
+        for j in i.saturating_sub(1).max(1)..=n {
+            anorm += a[i][j].abs();
+        }
+    }
+
+    let mut nn = n;
+    let mut t = 0.0;
+    let (mut p, mut q, mut r): (f64, f64, f64);
+    let (mut x, mut y, mut z, mut w): (f64, f64, f64, f64);
+
+    while nn >= 1 {
+        let mut its = 0;
+        loop {
+            // Look for a single small subdiagonal element.
+            let mut l = nn;
+            while l >= 2 {
+                let mut s = a[l - 1][l - 1].abs() + a[l][l].abs();
+                if s == 0.0 {
+                    s = anorm;
+                }
+                if a[l][l - 1].abs() + s == s {
+                    a[l][l - 1] = 0.0;
+                    break;
+                }
+                l -= 1;
+            }
+
+            x = a[nn][nn];
+            if l == nn {
+                // One root found.
+                wr[nn] = x + t;
+                wi[nn] = 0.0;
+                nn -= 1;
+                break;
+            }
+
+            y = a[nn - 1][nn - 1];
+            w = a[nn][nn - 1] * a[nn - 1][nn];
+            if l == nn - 1 {
+                // Two roots found.
+                p = 0.5 * (y - x);
+                q = p * p + w;
+                z = q.abs().sqrt();
+                x += t;
+                if q >= 0.0 {
+                    z = p + z.copysign(p);
+                    wr[nn - 1] = x + z;
+                    wr[nn] = x + z;
+                    if z != 0.0 {
+                        wr[nn] = x - w / z;
+                    }
+                    wi[nn - 1] = 0.0;
+                    wi[nn] = 0.0;
+                } else {
+                    wr[nn - 1] = x + p;
+                    wr[nn] = x + p;
+                    wi[nn - 1] = -z;
+                    wi[nn] = z;
+                }
+                nn -= 2;
+                break;
+            }
+
+            if its == MAX_ITERATIONS {
+                return None;
+            }
+            if its == 10 || its == 20 {
+                // Exceptional shift.
+                t += x;
+                for i in 1..=nn {
+                    a[i][i] -= x;
+                }
+                let s = a[nn][nn - 1].abs() + a[nn - 1][nn - 2].abs();
+                x = 0.75 * s;
+                y = x;
+                w = -0.4375 * s * s;
+            }
+            its += 1;
+
+            // Form shift and look for two consecutive small subdiagonal elements.
+            let mut m = nn - 2;
+            loop {
+                z = a[m][m];
+                r = x - z;
+                let s = y - z;
+                p = (r * s - w) / a[m + 1][m] + a[m][m + 1];
+                q = a[m + 1][m + 1] - z - r - s;
+                r = a[m + 2][m + 1];
+                let s = p.abs() + q.abs() + r.abs();
+                p /= s;
+                q /= s;
+                r /= s;
+                if m == l {
+                    break;
+                }
+                let u = a[m][m - 1].abs() * (q.abs() + r.abs());
+                let v = p.abs() * (a[m - 1][m - 1].abs() + z.abs() + a[m + 1][m + 1].abs());
+                if u + v == v {
+                    break;
+                }
+                m -= 1;
+            }
+
+            for i in (m + 2)..=nn {
+                a[i][i - 2] = 0.0;
+                if i != m + 2 {
+                    a[i][i - 3] = 0.0;
+                }
+            }
+
+            // Double QR step on rows l..nn and columns m..nn.
+            for k in m..nn {
+                if k != m {
+                    p = a[k][k - 1];
+                    q = a[k + 1][k - 1];
+                    r = 0.0;
+                    if k != nn - 1 {
+                        r = a[k + 2][k - 1];
+                    }
+                    x = p.abs() + q.abs() + r.abs();
+                    if x != 0.0 {
+                        p /= x;
+                        q /= x;
+                        r /= x;
+                    }
+                }
+                let s = (p * p + q * q + r * r).sqrt().copysign(p);
+                if s == 0.0 {
+                    continue;
+                }
+                if k == m {
+                    if l != m {
+                        a[k][k - 1] = -a[k][k - 1];
+                    }
+                } else {
+                    a[k][k - 1] = -s * x;
+                }
+                p += s;
+                x = p / s;
+                y = q / s;
+                z = r / s;
+                q /= p;
+                r /= p;
+                for j in k..=nn {
+                    p = a[k][j] + q * a[k + 1][j];
+                    if k != nn - 1 {
+                        p += r * a[k + 2][j];
+                        a[k + 2][j] -= p * z;
+                    }
+                    a[k + 1][j] -= p * y;
+                    a[k][j] -= p * x;
+                }
+                let mmin = nn.min(k + 3);
+                for row in a.iter_mut().take(mmin + 1).skip(l) {
+                    p = x * row[k] + y * row[k + 1];
+                    if k != nn - 1 {
+                        p += z * row[k + 2];
+                        row[k + 2] -= p * r;
+                    }
+                    row[k + 1] -= p * q;
+                    row[k] -= p;
+                }
+            }
+        }
+    }
+
+    Some((1..=n).map(|i| Complex::new(wr[i], wi[i])).collect())
+}
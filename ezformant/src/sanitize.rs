@@ -0,0 +1,102 @@
+//! Repair of non-finite and out-of-range samples.
+//!
+//! Browsers occasionally hand over NaN samples, e.g. right after an audio device
+//! switch, and a single NaN spreads to every autocorrelation lag of its frame. The
+//! sanitization pass replaces NaN with silence and clamps infinities and huge values to
+//! a limit, and counts what it repaired so callers can report the glitch instead of
+//! silently analyzing doctored audio.
+
+/// Default magnitude limit: far above normalized (`[-1, 1]`) and 16-bit integer audio,
+/// and low enough that sums of squares over a frame cannot overflow.
+pub const DEFAULT_LIMIT: f64 = 1e6;
+
+/// Counts of samples repaired by [`sanitize_in_place`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SanitizeReport {
+    /// NaN samples replaced by `0.0` and infinities replaced by `±limit`.
+    pub non_finite: usize,
+    /// Finite samples beyond `±limit` clamped to it.
+    pub clamped: usize,
+}
+
+impl SanitizeReport {
+    /// Total number of samples changed.
+    pub fn repaired(&self) -> usize {
+        self.non_finite + self.clamped
+    }
+
+    /// Repairs one sample and counts it if it changed.
+    ///
+    /// # Returns
+    ///
+    /// `0.0` for NaN, the sample clamped to `[-limit, limit]` otherwise.
+    pub fn repair(&mut self, sample: f64, limit: f64) -> f64 {
+        if sample.is_nan() {
+            self.non_finite += 1;
+            0.0
+        } else if sample.abs() > limit {
+            if sample.is_infinite() {
+                self.non_finite += 1;
+            } else {
+                self.clamped += 1;
+            }
+            limit.copysign(sample)
+        } else {
+            sample
+        }
+    }
+}
+
+impl std::ops::AddAssign for SanitizeReport {
+    fn add_assign(&mut self, other: Self) {
+        self.non_finite += other.non_finite;
+        self.clamped += other.clamped;
+    }
+}
+
+/// Replaces NaN samples with `0.0` and clamps the others to `[-limit, limit]`.
+///
+/// # Arguments
+///
+/// * `samples` - The signal, repaired in place.
+/// * `limit`   - Largest magnitude kept, e.g. [`DEFAULT_LIMIT`].
+///
+/// # Returns
+///
+/// How many samples were repaired, by kind.
+pub fn sanitize_in_place(samples: &mut [f64], limit: f64) -> SanitizeReport {
+    let mut report = SanitizeReport::default();
+    for sample in samples.iter_mut() {
+        *sample = report.repair(*sample, limit);
+    }
+    report
+}
+
+// ------------------
+// Tests
+// ------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn repairs_and_counts_bad_samples() {
+        let mut samples = [0.5, f64::NAN, f64::INFINITY, -3.0, f64::NEG_INFINITY, -0.25];
+        let report = sanitize_in_place(&mut samples, 2.0);
+        assert_eq!(samples, [0.5, 0.0, 2.0, -2.0, -2.0, -0.25]);
+        assert_eq!(
+            report,
+            SanitizeReport {
+                non_finite: 3,
+                clamped: 1
+            }
+        );
+        assert_eq!(report.repaired(), 4);
+
+        let mut total = report;
+        total += sanitize_in_place(&mut samples, 2.0);
+        assert_eq!(total, report);
+    }
+}
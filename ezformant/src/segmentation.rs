@@ -0,0 +1,246 @@
+//! Silence / voiced / unvoiced segmentation.
+//!
+//! [`segment_signal`] labels every frame of a recording with the voice activity
+//! detector ([`crate::vad`]) and the pitch tracker's voicing decision, then merges runs
+//! of equal labels into time-stamped [`Segment`]s. [`SegmentDurations`] sums them up for
+//! clinical statistics such as the unvoiced fraction of speech.
+
+use crate::batch::{BatchConfig, TrackFrame};
+use crate::vad::Vad;
+use std::fmt;
+
+/// What a segment contains.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum SegmentLabel {
+    /// Rejected by voice activity detection.
+    Silence,
+    /// Active and periodic.
+    Voiced,
+    /// Active but aperiodic (fricatives, whisper, breathy noise).
+    Unvoiced,
+}
+
+impl SegmentLabel {
+    /// The label as written to TextGrids and JSON: `silence`, `voiced` or `unvoiced`.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SegmentLabel::Silence => "silence",
+            SegmentLabel::Voiced => "voiced",
+            SegmentLabel::Unvoiced => "unvoiced",
+        }
+    }
+
+    /// The label of an analyzed frame.
+    pub fn of_frame(frame: &TrackFrame) -> Self {
+        if frame.silent {
+            SegmentLabel::Silence
+        } else if frame.voiced {
+            SegmentLabel::Voiced
+        } else {
+            SegmentLabel::Unvoiced
+        }
+    }
+}
+
+impl fmt::Display for SegmentLabel {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// A run of frames with the same label.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Segment {
+    /// Start time, in seconds.
+    pub start: f64,
+    /// End time, in seconds.
+    pub end: f64,
+    /// What the segment contains.
+    pub label: SegmentLabel,
+}
+
+impl Segment {
+    /// Length of the segment, in seconds.
+    pub fn duration(&self) -> f64 {
+        self.end - self.start
+    }
+}
+
+/// Total time per label, in seconds.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SegmentDurations {
+    /// Time in silent segments.
+    pub silence: f64,
+    /// Time in voiced segments.
+    pub voiced: f64,
+    /// Time in unvoiced segments.
+    pub unvoiced: f64,
+}
+
+impl SegmentDurations {
+    /// Sums the durations of `segments` by label.
+    pub fn of(segments: &[Segment]) -> Self {
+        let mut durations = Self::default();
+        for segment in segments {
+            *match segment.label {
+                SegmentLabel::Silence => &mut durations.silence,
+                SegmentLabel::Voiced => &mut durations.voiced,
+                SegmentLabel::Unvoiced => &mut durations.unvoiced,
+            } += segment.duration();
+        }
+        durations
+    }
+
+    /// Fraction of the active (non-silent) time that is unvoiced, or `None` if nothing
+    /// is active.
+    pub fn unvoiced_fraction(&self) -> Option<f64> {
+        let active = self.voiced + self.unvoiced;
+        (active > 0.0).then(|| self.unvoiced / active)
+    }
+}
+
+/// Segments a recording into silence, voiced and unvoiced regions.
+///
+/// Frames follow `config` as in [`crate::batch::analyze_signal`], but only the pitch
+/// tracker runs (no formants). `config.vad` sets the detector, or its defaults when
+/// `None`.
+///
+/// # Arguments
+///
+/// * `signal`      - The recording.
+/// * `sample_rate` - The sampling rate of the recording.
+/// * `config`      - Framing, pitch quality and detector parameters.
+///
+/// # Returns
+///
+/// The segments in time order, tiling the span of the analyzed frames; empty if the
+/// recording is shorter than one frame.
+pub fn segment_signal(signal: &[f64], sample_rate: f64, config: &BatchConfig) -> Vec<Segment> {
+    let frame_len = ((config.frame_duration * sample_rate).round() as usize).max(1);
+    let hop = ((config.time_step * sample_rate).round() as usize).max(1);
+    if signal.len() < frame_len {
+        return Vec::new();
+    }
+    let analyzer = config.analyzer(sample_rate);
+    let mut vad = Vad::for_recording(config.vad.unwrap_or_default(), signal, frame_len, hop);
+    let labels: Vec<SegmentLabel> = (0..=signal.len() - frame_len)
+        .step_by(hop)
+        .map(|start| {
+            let frame = &signal[start..start + frame_len];
+            if !vad.is_active(frame) {
+                SegmentLabel::Silence
+            } else if analyzer.pitch_result(frame).voiced {
+                SegmentLabel::Voiced
+            } else {
+                SegmentLabel::Unvoiced
+            }
+        })
+        .collect();
+    let first_centre = frame_len as f64 / 2.0 / sample_rate;
+    merge_runs(&labels, first_centre, hop as f64 / sample_rate)
+}
+
+/// Segments already analyzed frames (e.g. from [`crate::batch::analyze_signal`] with a
+/// VAD) by their `silent` and `voiced` flags.
+///
+/// # Arguments
+///
+/// * `frames` - Frames in time order at a constant hop.
+///
+/// # Returns
+///
+/// The segments in time order; each frame covers one hop around its centre.
+pub fn segment_frames(frames: &[TrackFrame]) -> Vec<Segment> {
+    let Some(first) = frames.first() else {
+        return Vec::new();
+    };
+    let hop = match frames.get(1) {
+        Some(second) => second.time - first.time,
+        None => 0.0,
+    };
+    let labels: Vec<SegmentLabel> = frames.iter().map(SegmentLabel::of_frame).collect();
+    merge_runs(&labels, first.time, hop)
+}
+
+// ------------------
+// Helpers
+// ------------------
+
+/// Merges consecutive equal labels of frames centred at `first_centre + i * hop`.
+fn merge_runs(labels: &[SegmentLabel], first_centre: f64, hop: f64) -> Vec<Segment> {
+    let edge = |i: usize| first_centre + (i as f64 - 0.5) * hop;
+    let mut segments: Vec<Segment> = Vec::new();
+    for (i, &label) in labels.iter().enumerate() {
+        match segments.last_mut() {
+            Some(last) if last.label == label => last.end = edge(i + 1),
+            _ => segments.push(Segment {
+                start: edge(i),
+                end: edge(i + 1),
+                label,
+            }),
+        }
+    }
+    segments
+}
+
+// ------------------
+// Tests
+// ------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::f64::consts::PI;
+
+    #[test]
+    fn labels_silence_vowel_and_fricative() {
+        // 0.3 s of faint noise, a 0.4 s vowel, 0.3 s of loud noise, 0.3 s of faint noise.
+        let fs = 16000.0;
+        let mut state: u32 = 11;
+        let mut noise = || {
+            state = state.wrapping_mul(1664525).wrapping_add(1013904223);
+            (state >> 8) as f64 / (1u32 << 24) as f64 - 0.5
+        };
+        let signal: Vec<f64> = (0..20800)
+            .map(|n| {
+                let t = n as f64 / fs;
+                let floor = 0.001 * noise();
+                if (0.3..0.7).contains(&t) {
+                    floor + 0.5 * (2.0 * PI * 150.0 * t).sin() + 0.2 * (2.0 * PI * 300.0 * t).sin()
+                } else if (0.7..1.0).contains(&t) {
+                    floor + 0.3 * noise()
+                } else {
+                    floor
+                }
+            })
+            .collect();
+
+        let config = BatchConfig {
+            frame_duration: 0.04,
+            ..BatchConfig::default()
+        };
+        let segments = segment_signal(&signal, fs, &config);
+        let label_at = |time: f64| {
+            segments
+                .iter()
+                .find(|s| (s.start..s.end).contains(&time))
+                .map(|s| s.label)
+        };
+        assert_eq!(label_at(0.1), Some(SegmentLabel::Silence));
+        assert_eq!(label_at(0.5), Some(SegmentLabel::Voiced));
+        assert_eq!(label_at(0.85), Some(SegmentLabel::Unvoiced));
+        assert_eq!(label_at(1.25), Some(SegmentLabel::Silence));
+        assert!(segments
+            .windows(2)
+            .all(|w| w[0].end == w[1].start && w[0].label != w[1].label));
+
+        // The detector's hangover adds a little unvoiced time after the noise.
+        let durations = SegmentDurations::of(&segments);
+        assert!((durations.voiced - 0.4).abs() < 0.05, "{:?}", durations);
+        let fraction = durations.unvoiced_fraction().unwrap();
+        assert!((0.4..0.6).contains(&fraction), "{}", fraction);
+    }
+}
@@ -0,0 +1,263 @@
+//! Vectorized inner loops.
+//!
+//! With the `simd` feature on a `wasm32` build with SIMD128 enabled
+//! (`RUSTFLAGS="-C target-feature=+simd128"`), the dot products of the autocorrelation,
+//! window multiplications and the polynomial evaluations of the LPC frequency response
+//! run on 128-bit `core::arch::wasm32` lanes: four `f32` or two `f64` at a time.
+//! Everywhere else the same functions run scalar loops. Vectorized dot products sum in
+//! a different order, so results can differ from the scalar ones by rounding.
+
+#[cfg(not(feature = "std"))]
+use alloc::{vec, vec::Vec};
+use num_complex::Complex;
+use num_traits::Float;
+
+#[cfg(not(all(feature = "simd", target_arch = "wasm32", target_feature = "simd128")))]
+use scalar as kernels;
+#[cfg(all(feature = "simd", target_arch = "wasm32", target_feature = "simd128"))]
+use wasm as kernels;
+
+/// Sample types with vectorized kernels: `f32` and `f64`.
+pub trait SimdFloat: Float {
+    /// `sum(a[i] * b[i])` over the length of the shorter slice.
+    fn dot(a: &[Self], b: &[Self]) -> Self;
+
+    /// Multiplies `data` by `weights` element by element, over the length of the
+    /// shorter slice.
+    fn mul_assign(data: &mut [Self], weights: &[Self]);
+}
+
+impl SimdFloat for f32 {
+    fn dot(a: &[f32], b: &[f32]) -> f32 {
+        kernels::dot_f32(a, b)
+    }
+
+    fn mul_assign(data: &mut [f32], weights: &[f32]) {
+        kernels::mul_assign_f32(data, weights)
+    }
+}
+
+impl SimdFloat for f64 {
+    fn dot(a: &[f64], b: &[f64]) -> f64 {
+        kernels::dot_f64(a, b)
+    }
+
+    fn mul_assign(data: &mut [f64], weights: &[f64]) {
+        kernels::mul_assign_f64(data, weights)
+    }
+}
+
+/// Evaluates the polynomial `sum_k coeffs[k] w^k` at every point with Horner's rule
+/// (see [`crate::lpc::frequency_response_at`]).
+///
+/// # Returns
+///
+/// One value per point, in order. The vectorized version evaluates two points at a time
+/// with the same operations, so it matches the scalar one exactly.
+pub fn evaluate_polynomial_at(coeffs: &[f64], points: &[Complex<f64>]) -> Vec<Complex<f64>> {
+    let mut values = vec![Complex::new(0.0, 0.0); points.len()];
+    evaluate_polynomial_into(coeffs, points, &mut values);
+    values
+}
+
+/// Like [`evaluate_polynomial_at`], writing the values into `out` instead of a new
+/// vector, over the length of the shorter slice.
+pub fn evaluate_polynomial_into(coeffs: &[f64], points: &[Complex<f64>], out: &mut [Complex<f64>]) {
+    let len = points.len().min(out.len());
+    kernels::evaluate_polynomial_into(coeffs, &points[..len], &mut out[..len])
+}
+
+// ------------------
+// Helpers
+// ------------------
+
+/// Plain loops, for every other target.
+#[cfg(not(all(feature = "simd", target_arch = "wasm32", target_feature = "simd128")))]
+mod scalar {
+    use num_complex::Complex;
+    use num_traits::Float;
+
+    fn dot<T: Float>(a: &[T], b: &[T]) -> T {
+        a.iter().zip(b).fold(T::zero(), |acc, (&x, &y)| acc + x * y)
+    }
+
+    fn mul_assign<T: Float>(data: &mut [T], weights: &[T]) {
+        for (x, &w) in data.iter_mut().zip(weights) {
+            *x = *x * w;
+        }
+    }
+
+    pub(super) fn dot_f32(a: &[f32], b: &[f32]) -> f32 {
+        dot(a, b)
+    }
+
+    pub(super) fn dot_f64(a: &[f64], b: &[f64]) -> f64 {
+        dot(a, b)
+    }
+
+    pub(super) fn mul_assign_f32(data: &mut [f32], weights: &[f32]) {
+        mul_assign(data, weights)
+    }
+
+    pub(super) fn mul_assign_f64(data: &mut [f64], weights: &[f64]) {
+        mul_assign(data, weights)
+    }
+
+    pub(super) fn evaluate_polynomial_into(
+        coeffs: &[f64],
+        points: &[Complex<f64>],
+        out: &mut [Complex<f64>],
+    ) {
+        for (value, &w) in out.iter_mut().zip(points) {
+            *value = crate::lpc::evaluate_polynomial(coeffs, w);
+        }
+    }
+}
+
+/// SIMD128 kernels. Loads and stores are unaligned, as `v128_load` and `v128_store`
+/// allow; the tails shorter than a vector fall back to scalar code.
+#[cfg(all(feature = "simd", target_arch = "wasm32", target_feature = "simd128"))]
+mod wasm {
+    use core::arch::wasm32::*;
+    use num_complex::Complex;
+
+    pub(super) fn dot_f32(a: &[f32], b: &[f32]) -> f32 {
+        let len = a.len().min(b.len());
+        let split = len - len % 4;
+        let mut acc = f32x4_splat(0.0);
+        for i in (0..split).step_by(4) {
+            // SAFETY: `i + 4 <= split <= len` for both slices.
+            let (x, y) = unsafe {
+                (
+                    v128_load(a.as_ptr().add(i) as *const v128),
+                    v128_load(b.as_ptr().add(i) as *const v128),
+                )
+            };
+            acc = f32x4_add(acc, f32x4_mul(x, y));
+        }
+        let head = f32x4_extract_lane::<0>(acc)
+            + f32x4_extract_lane::<1>(acc)
+            + f32x4_extract_lane::<2>(acc)
+            + f32x4_extract_lane::<3>(acc);
+        a[split..len]
+            .iter()
+            .zip(&b[split..len])
+            .fold(head, |acc, (&x, &y)| acc + x * y)
+    }
+
+    pub(super) fn dot_f64(a: &[f64], b: &[f64]) -> f64 {
+        let len = a.len().min(b.len());
+        let split = len - len % 2;
+        let mut acc = f64x2_splat(0.0);
+        for i in (0..split).step_by(2) {
+            // SAFETY: `i + 2 <= split <= len` for both slices.
+            let (x, y) = unsafe {
+                (
+                    v128_load(a.as_ptr().add(i) as *const v128),
+                    v128_load(b.as_ptr().add(i) as *const v128),
+                )
+            };
+            acc = f64x2_add(acc, f64x2_mul(x, y));
+        }
+        let head = f64x2_extract_lane::<0>(acc) + f64x2_extract_lane::<1>(acc);
+        a[split..len]
+            .iter()
+            .zip(&b[split..len])
+            .fold(head, |acc, (&x, &y)| acc + x * y)
+    }
+
+    pub(super) fn mul_assign_f32(data: &mut [f32], weights: &[f32]) {
+        let len = data.len().min(weights.len());
+        let split = len - len % 4;
+        for i in (0..split).step_by(4) {
+            // SAFETY: `i + 4 <= split <= len` for both slices.
+            unsafe {
+                let x = v128_load(data.as_ptr().add(i) as *const v128);
+                let w = v128_load(weights.as_ptr().add(i) as *const v128);
+                v128_store(data.as_mut_ptr().add(i) as *mut v128, f32x4_mul(x, w));
+            }
+        }
+        for (x, &w) in data[split..len].iter_mut().zip(&weights[split..len]) {
+            *x *= w;
+        }
+    }
+
+    pub(super) fn mul_assign_f64(data: &mut [f64], weights: &[f64]) {
+        let len = data.len().min(weights.len());
+        let split = len - len % 2;
+        for i in (0..split).step_by(2) {
+            // SAFETY: `i + 2 <= split <= len` for both slices.
+            unsafe {
+                let x = v128_load(data.as_ptr().add(i) as *const v128);
+                let w = v128_load(weights.as_ptr().add(i) as *const v128);
+                v128_store(data.as_mut_ptr().add(i) as *mut v128, f64x2_mul(x, w));
+            }
+        }
+        for (x, &w) in data[split..len].iter_mut().zip(&weights[split..len]) {
+            *x *= w;
+        }
+    }
+
+    /// `points` and `out` have the same length.
+    pub(super) fn evaluate_polynomial_into(
+        coeffs: &[f64],
+        points: &[Complex<f64>],
+        out: &mut [Complex<f64>],
+    ) {
+        let mut pairs = points.chunks_exact(2);
+        let mut values = out.chunks_exact_mut(2);
+        for (pair, value) in (&mut pairs).zip(&mut values) {
+            let (w_re, w_im) = (f64x2(pair[0].re, pair[1].re), f64x2(pair[0].im, pair[1].im));
+            let (mut re, mut im) = (f64x2_splat(0.0), f64x2_splat(0.0));
+            // Horner's rule on both points, with the operations of `Complex` arithmetic.
+            for &a_k in coeffs.iter().rev() {
+                let next_re = f64x2_sub(f64x2_mul(re, w_re), f64x2_mul(im, w_im));
+                let next_im = f64x2_add(f64x2_mul(re, w_im), f64x2_mul(im, w_re));
+                re = f64x2_add(next_re, f64x2_splat(a_k));
+                im = next_im;
+            }
+            value[0] = Complex::new(f64x2_extract_lane::<0>(re), f64x2_extract_lane::<0>(im));
+            value[1] = Complex::new(f64x2_extract_lane::<1>(re), f64x2_extract_lane::<1>(im));
+        }
+        for (value, &w) in values.into_remainder().iter_mut().zip(pairs.remainder()) {
+            *value = crate::lpc::evaluate_polynomial(coeffs, w);
+        }
+    }
+}
+
+// ------------------
+// Tests
+// ------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn kernels_match_plain_loops() {
+        let a: Vec<f64> = (0..11).map(|i| (i as f64 * 0.7).sin()).collect();
+        let b: Vec<f64> = (0..13).map(|i| (i as f64 * 0.3).cos()).collect();
+        let expected: f64 = a.iter().zip(&b).map(|(x, y)| x * y).sum();
+        assert!((f64::dot(&a, &b) - expected).abs() < 1e-12);
+        let (a32, b32): (Vec<f32>, Vec<f32>) = (
+            a.iter().map(|&x| x as f32).collect(),
+            b.iter().map(|&x| x as f32).collect(),
+        );
+        assert!((f32::dot(&a32, &b32) as f64 - expected).abs() < 1e-5);
+
+        let mut data = a.clone();
+        f64::mul_assign(&mut data, &b);
+        for i in 0..a.len() {
+            assert_eq!(data[i], a[i] * b[i]);
+        }
+
+        let coeffs = [1.0, -0.9, 0.4, 0.1];
+        let points: Vec<Complex<f64>> = (0..5)
+            .map(|i| Complex::from_polar(1.0, -(i as f64) * 0.6))
+            .collect();
+        let values = evaluate_polynomial_at(&coeffs, &points);
+        for (value, &w) in values.iter().zip(&points) {
+            assert_eq!(*value, crate::lpc::evaluate_polynomial(&coeffs, w));
+        }
+    }
+}
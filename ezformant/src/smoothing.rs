@@ -0,0 +1,652 @@
+//! Post-processing of per-frame tracks.
+//!
+//! Raw per-frame formants jump whenever the root solver swaps or misses a pole. The
+//! smoothers here work on one track at a time (one formant across frames), with `NaN`
+//! marking frames without a value, the same convention as `FormantBatch.formants` in the
+//! web demo. [`smooth_formant_tracks`] runs the whole chain on analyzed frames: outlier
+//! rejection against a running median, a median filter and an optional constant-velocity
+//! Kalman filter. [`smooth_pitch`] cleans up f0 contours: octave-jump correction,
+//! median smoothing and interpolation across short unvoiced gaps. [`DisplaySmoother`]
+//! steadies live readouts with one-euro filters that hold the last value through brief
+//! dropouts.
+
+use crate::batch::TrackFrame;
+
+/// Parameters of [`smooth_formant_tracks`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct FormantSmoothing {
+    /// Frames in the running median (rounded up to an odd number); `0` or `1` disables
+    /// both the outlier rejection and the median filter.
+    pub median_width: usize,
+    /// Values further than this fraction from the running median are dropped (e.g.
+    /// `0.25` for ±25 %). `f64::INFINITY` keeps every value.
+    pub max_deviation: f64,
+    /// Kalman filter run after the median filter, if any.
+    pub kalman: Option<KalmanConfig>,
+}
+
+impl Default for FormantSmoothing {
+    fn default() -> Self {
+        Self {
+            median_width: 5,
+            max_deviation: 0.25,
+            kalman: None,
+        }
+    }
+}
+
+/// Noise levels of the constant-velocity model behind [`FormantKalman`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct KalmanConfig {
+    /// Standard deviation of the unmodelled acceleration, in Hz/s². Larger values
+    /// follow fast transitions more closely.
+    pub acceleration: f64,
+    /// Standard deviation of a measurement, in Hz.
+    pub measurement: f64,
+    /// Frames without a value after which the filter starts over.
+    pub max_gap: usize,
+}
+
+impl Default for KalmanConfig {
+    fn default() -> Self {
+        Self {
+            acceleration: 1e5,
+            measurement: 50.0,
+            max_gap: 5,
+        }
+    }
+}
+
+/// Constant-velocity Kalman filter over one track, usable frame by frame on a live
+/// stream.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FormantKalman {
+    config: KalmanConfig,
+    dt: f64,
+    // Value and rate of change, and their covariance.
+    state: Option<([f64; 2], [[f64; 2]; 2])>,
+    gap: usize,
+}
+
+impl FormantKalman {
+    /// Creates a filter for frames `time_step` seconds apart.
+    pub fn new(config: KalmanConfig, time_step: f64) -> Self {
+        Self {
+            config,
+            dt: time_step,
+            state: None,
+            gap: 0,
+        }
+    }
+
+    /// Feeds the next frame's measurement (`NaN` if it has none).
+    ///
+    /// # Returns
+    ///
+    /// The filtered value, or `NaN` for a frame without a measurement.
+    pub fn update(&mut self, measurement: f64) -> f64 {
+        if !measurement.is_finite() {
+            self.gap += 1;
+            if self.gap > self.config.max_gap {
+                self.state = None;
+            } else if let Some((x, p)) = self.state {
+                self.state = Some(self.predict(x, p));
+            }
+            return f64::NAN;
+        }
+        self.gap = 0;
+        let r = self.config.measurement * self.config.measurement;
+        let (x, p) = match self.state {
+            None => ([measurement, 0.0], [[r, 0.0], [0.0, 1e6 * r]]),
+            Some((x, p)) => {
+                let (x, p) = self.predict(x, p);
+                let innovation = measurement - x[0];
+                let s = p[0][0] + r;
+                let k = [p[0][0] / s, p[1][0] / s];
+                (
+                    [x[0] + k[0] * innovation, x[1] + k[1] * innovation],
+                    [
+                        [(1.0 - k[0]) * p[0][0], (1.0 - k[0]) * p[0][1]],
+                        [p[1][0] - k[1] * p[0][0], p[1][1] - k[1] * p[0][1]],
+                    ],
+                )
+            }
+        };
+        self.state = Some((x, p));
+        x[0]
+    }
+
+    /// Forgets the track.
+    pub fn reset(&mut self) {
+        self.state = None;
+        self.gap = 0;
+    }
+
+    // Advances the state by one frame.
+    fn predict(&self, x: [f64; 2], p: [[f64; 2]; 2]) -> ([f64; 2], [[f64; 2]; 2]) {
+        let dt = self.dt;
+        let q = self.config.acceleration * self.config.acceleration;
+        let x = [x[0] + dt * x[1], x[1]];
+        // F P F^T + Q with F = [[1, dt], [0, 1]] and the white-acceleration Q.
+        let p00 = p[0][0] + dt * (p[0][1] + p[1][0]) + dt * dt * p[1][1] + q * dt.powi(4) / 4.0;
+        let p01 = p[0][1] + dt * p[1][1] + q * dt.powi(3) / 2.0;
+        let p10 = p[1][0] + dt * p[1][1] + q * dt.powi(3) / 2.0;
+        let p11 = p[1][1] + q * dt * dt;
+        (x, [[p00, p01], [p10, p11]])
+    }
+}
+
+/// Parameters of [`smooth_pitch`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct PitchSmoothing {
+    /// Frames in the running median (rounded up to an odd number); `0` or `1` disables
+    /// it.
+    pub median_width: usize,
+    /// Longest unvoiced gap, in frames, bridged by interpolation; `0` leaves gaps alone.
+    pub max_gap: usize,
+    /// Frames around each value that define the local contour for octave correction;
+    /// `0` disables the correction.
+    pub octave_window: usize,
+}
+
+impl Default for PitchSmoothing {
+    fn default() -> Self {
+        Self {
+            median_width: 5,
+            max_gap: 5,
+            octave_window: 15,
+        }
+    }
+}
+
+/// Parameters of [`DisplaySmoother`] and [`OneEuroFilter`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct DisplaySmoothing {
+    /// Cutoff of the low-pass filter when the value is steady, in Hz. Lower values
+    /// remove more jitter.
+    pub min_cutoff: f64,
+    /// Increase of the cutoff per unit of speed (value units per second), so fast
+    /// movements lag less. `0` gives a plain exponential smoother.
+    pub beta: f64,
+    /// Cutoff of the low-pass filter on the speed estimate, in Hz.
+    pub derivative_cutoff: f64,
+    /// How long the last value stays on display after the input drops out, in seconds.
+    pub hold_time: f64,
+}
+
+impl Default for DisplaySmoothing {
+    fn default() -> Self {
+        Self {
+            min_cutoff: 1.0,
+            beta: 0.01,
+            derivative_cutoff: 1.0,
+            hold_time: 0.15,
+        }
+    }
+}
+
+/// The one-euro filter (Casiez, Roussel & Vogel, 2012): an exponential smoother whose
+/// cutoff rises with the speed of the signal.
+#[derive(Debug, Clone, PartialEq)]
+pub struct OneEuroFilter {
+    config: DisplaySmoothing,
+    // Smoothed value and smoothed speed.
+    state: Option<(f64, f64)>,
+}
+
+impl OneEuroFilter {
+    /// Creates a filter; `config.hold_time` is not used here.
+    pub fn new(config: DisplaySmoothing) -> Self {
+        Self {
+            config,
+            state: None,
+        }
+    }
+
+    /// Filters the next value, `dt` seconds after the previous one.
+    pub fn filter(&mut self, value: f64, dt: f64) -> f64 {
+        let smoothed = match self.state {
+            Some((previous, speed)) if dt > 0.0 => {
+                let raw_speed = (value - previous) / dt;
+                let speed = lerp(speed, raw_speed, alpha(self.config.derivative_cutoff, dt));
+                let cutoff = self.config.min_cutoff + self.config.beta * speed.abs();
+                (lerp(previous, value, alpha(cutoff, dt)), speed)
+            }
+            Some(state) => state,
+            None => (value, 0.0),
+        };
+        self.state = Some(smoothed);
+        smoothed.0
+    }
+
+    /// The last output, if any.
+    pub fn value(&self) -> Option<f64> {
+        self.state.map(|(value, _)| value)
+    }
+
+    /// Forgets the signal, so the next value passes through unchanged.
+    pub fn reset(&mut self) {
+        self.state = None;
+    }
+}
+
+/// Smooths a set of live values for display (e.g. f0 and the formants of each frame),
+/// each through its own [`OneEuroFilter`].
+///
+/// A missing value (`NaN` or non-positive, like the `-1.0` of an unvoiced f0) keeps the
+/// last smoothed value on display for up to [`DisplaySmoothing::hold_time`]; after
+/// that the channel is cleared and the next value starts it afresh instead of gliding
+/// in from the old one.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DisplaySmoother {
+    config: DisplaySmoothing,
+    // Filter and seconds since its last input, per channel.
+    channels: Vec<(OneEuroFilter, f64)>,
+}
+
+impl DisplaySmoother {
+    /// Creates a smoother; channels are added as values arrive.
+    pub fn new(config: DisplaySmoothing) -> Self {
+        Self {
+            config,
+            channels: Vec::new(),
+        }
+    }
+
+    /// Smooths the next set of values in place.
+    ///
+    /// # Arguments
+    ///
+    /// * `values` - One value per channel, in a fixed order. Missing values are replaced
+    ///   by the held value, or left as they are once the hold has run out.
+    /// * `dt`     - Seconds since the previous call.
+    pub fn update(&mut self, values: &mut [f64], dt: f64) {
+        if self.channels.len() < values.len() {
+            let fresh = (OneEuroFilter::new(self.config), f64::INFINITY);
+            self.channels.resize(values.len(), fresh);
+        }
+        for (value, (filter, missing_for)) in values.iter_mut().zip(&mut self.channels) {
+            if *value > 0.0 && value.is_finite() {
+                let step = if missing_for.is_finite() {
+                    *missing_for + dt
+                } else {
+                    dt
+                };
+                *value = filter.filter(*value, step);
+                *missing_for = 0.0;
+            } else {
+                *missing_for += dt;
+                match filter.value() {
+                    Some(held) if *missing_for <= self.config.hold_time => *value = held,
+                    _ => filter.reset(),
+                }
+            }
+        }
+    }
+
+    /// Clears every channel.
+    pub fn reset(&mut self) {
+        self.channels.clear();
+    }
+}
+
+/// Running median of a track, ignoring `NaN`s in each window.
+///
+/// # Arguments
+///
+/// * `track` - Values per frame, `NaN` where missing.
+/// * `width` - Frames per window, rounded up to an odd number; the window shrinks at
+///   the ends.
+///
+/// # Returns
+///
+/// The filtered track; frames that had no value stay `NaN`.
+pub fn median_filter(track: &[f64], width: usize) -> Vec<f64> {
+    let half = width / 2;
+    let mut window = Vec::with_capacity(2 * half + 1);
+    (0..track.len())
+        .map(|i| {
+            if track[i].is_nan() {
+                return f64::NAN;
+            }
+            window.clear();
+            let end = (i + half + 1).min(track.len());
+            window.extend(
+                track[i.saturating_sub(half)..end]
+                    .iter()
+                    .filter(|x| !x.is_nan()),
+            );
+            median(&mut window)
+        })
+        .collect()
+}
+
+/// Drops values further than `max_deviation` (a fraction) from the running median of
+/// `width` frames around them.
+///
+/// # Returns
+///
+/// The track with outliers replaced by `NaN`.
+pub fn reject_outliers(track: &[f64], width: usize, max_deviation: f64) -> Vec<f64> {
+    median_filter(track, width)
+        .iter()
+        .zip(track)
+        .map(|(&m, &x)| {
+            if (x - m).abs() <= max_deviation * m.abs() {
+                x
+            } else {
+                f64::NAN
+            }
+        })
+        .collect()
+}
+
+/// Runs a [`FormantKalman`] over a whole track.
+pub fn kalman_filter(track: &[f64], time_step: f64, config: &KalmanConfig) -> Vec<f64> {
+    let mut kalman = FormantKalman::new(*config, time_step);
+    track.iter().map(|&x| kalman.update(x)).collect()
+}
+
+/// Smooths one track with outlier rejection, the median filter and the Kalman filter,
+/// as configured.
+pub fn smooth_track(track: &[f64], time_step: f64, config: &FormantSmoothing) -> Vec<f64> {
+    let mut track = track.to_vec();
+    if config.median_width > 1 {
+        track = reject_outliers(&track, config.median_width, config.max_deviation);
+        track = median_filter(&track, config.median_width);
+    }
+    match &config.kalman {
+        Some(kalman) => kalman_filter(&track, time_step, kalman),
+        None => track,
+    }
+}
+
+/// Halves or doubles values that sit an octave away from the local contour, the typical
+/// error of autocorrelation pitch trackers.
+///
+/// # Arguments
+///
+/// * `track`  - f0 per frame in Hz, `NaN` where unvoiced.
+/// * `window` - Frames around each value whose median defines the local contour.
+///
+/// # Returns
+///
+/// The corrected track.
+pub fn correct_octave_jumps(track: &[f64], window: usize) -> Vec<f64> {
+    let reference = median_filter(track, window);
+    track
+        .iter()
+        .zip(&reference)
+        .map(|(&f0, &reference)| {
+            // Within a quarter octave of twice or half the contour.
+            let octaves = (f0 / reference).log2();
+            if (octaves - 1.0).abs() < 0.25 {
+                f0 / 2.0
+            } else if (octaves + 1.0).abs() < 0.25 {
+                f0 * 2.0
+            } else {
+                f0
+            }
+        })
+        .collect()
+}
+
+/// Fills unvoiced gaps of at most `max_gap` frames between two voiced frames,
+/// interpolating linearly in log frequency (semitones). Longer gaps and the ends of the
+/// track stay `NaN`.
+pub fn interpolate_gaps(track: &[f64], max_gap: usize) -> Vec<f64> {
+    let mut out = track.to_vec();
+    let mut previous: Option<usize> = None;
+    for (i, &f0) in track.iter().enumerate() {
+        if f0.is_nan() {
+            continue;
+        }
+        if let Some(p) = previous.filter(|&p| i - p > 1 && i - p - 1 <= max_gap) {
+            let (a, b) = (track[p].ln(), f0.ln());
+            for (k, value) in out.iter_mut().enumerate().take(i).skip(p + 1) {
+                let frac = (k - p) as f64 / (i - p) as f64;
+                *value = (a + frac * (b - a)).exp();
+            }
+        }
+        previous = Some(i);
+    }
+    out
+}
+
+/// Cleans up an f0 contour: octave-jump correction, then the median filter, then
+/// interpolation across short unvoiced gaps.
+///
+/// # Arguments
+///
+/// * `track`  - f0 per frame in Hz; `NaN` or non-positive values (such as the `-1.0`
+///   of [`TrackFrame::pitch`]) mark unvoiced frames.
+/// * `config` - Which steps to run.
+///
+/// # Returns
+///
+/// The smoothed contour, `NaN` where still unvoiced.
+pub fn smooth_pitch(track: &[f64], config: &PitchSmoothing) -> Vec<f64> {
+    let mut track: Vec<f64> = track
+        .iter()
+        .map(|&f0| if f0 > 0.0 { f0 } else { f64::NAN })
+        .collect();
+    if config.octave_window > 1 {
+        track = correct_octave_jumps(&track, config.octave_window);
+    }
+    if config.median_width > 1 {
+        track = median_filter(&track, config.median_width);
+    }
+    if config.max_gap > 0 {
+        track = interpolate_gaps(&track, config.max_gap);
+    }
+    track
+}
+
+/// Smooths the first `count` formant tracks of analyzed frames.
+///
+/// # Arguments
+///
+/// * `frames` - Frames in time order at a constant hop.
+/// * `count`  - Number of formants (F1 to F`count`) to smooth.
+/// * `config` - The smoothing chain.
+///
+/// # Returns
+///
+/// For each frame, `count` smoothed formant frequencies, `NaN` where the frame had no
+/// such formant or its value was rejected.
+pub fn smooth_formant_tracks(
+    frames: &[TrackFrame],
+    count: usize,
+    config: &FormantSmoothing,
+) -> Vec<Vec<f64>> {
+    let time_step = match frames {
+        [first, second, ..] => second.time - first.time,
+        _ => 0.0,
+    };
+    let tracks: Vec<Vec<f64>> = (0..count)
+        .map(|i| {
+            let track: Vec<f64> = frames
+                .iter()
+                .map(|f| f.formants.get(i).copied().unwrap_or(f64::NAN))
+                .collect();
+            smooth_track(&track, time_step, config)
+        })
+        .collect();
+    (0..frames.len())
+        .map(|j| tracks.iter().map(|track| track[j]).collect())
+        .collect()
+}
+
+/// Smooths the formants of analyzed frames in place with [`smooth_formant_tracks`].
+///
+/// Frames keep their number of formants: a value the chain rejects takes the running
+/// median of its track instead, and one without neighbours keeps its raw value.
+///
+/// # Arguments
+///
+/// * `frames` - Frames in time order at a constant hop.
+/// * `config` - The smoothing chain.
+pub fn smooth_frames(frames: &mut [TrackFrame], config: &FormantSmoothing) {
+    let count = frames.iter().map(|f| f.formants.len()).max().unwrap_or(0);
+    let smoothed = smooth_formant_tracks(frames, count, config);
+    let median = FormantSmoothing {
+        max_deviation: f64::INFINITY,
+        kalman: None,
+        ..*config
+    };
+    let medians = smooth_formant_tracks(frames, count, &median);
+    for ((frame, smoothed), medians) in frames.iter_mut().zip(smoothed).zip(medians) {
+        for ((formant, value), median) in frame.formants.iter_mut().zip(smoothed).zip(medians) {
+            if value.is_finite() {
+                *formant = value;
+            } else if median.is_finite() {
+                *formant = median;
+            }
+        }
+    }
+}
+
+// ------------------
+// Helpers
+// ------------------
+
+/// Smoothing factor of an exponential smoother with the given cutoff, in Hz.
+fn alpha(cutoff: f64, dt: f64) -> f64 {
+    let tau = 1.0 / (2.0 * std::f64::consts::PI * cutoff);
+    1.0 / (1.0 + tau / dt)
+}
+
+fn lerp(from: f64, to: f64, alpha: f64) -> f64 {
+    from + alpha * (to - from)
+}
+
+/// Median of the values (reordered in place), `NaN` if empty.
+fn median(values: &mut [f64]) -> f64 {
+    if values.is_empty() {
+        return f64::NAN;
+    }
+    values.sort_by(f64::total_cmp);
+    let mid = values.len() / 2;
+    if values.len().is_multiple_of(2) {
+        0.5 * (values[mid - 1] + values[mid])
+    } else {
+        values[mid]
+    }
+}
+
+// ------------------
+// Tests
+// ------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rejects_spikes_and_follows_a_glide() {
+        // F1 gliding from 500 to 700 Hz with a root-solver glitch and a missing frame.
+        let mut track: Vec<f64> = (0..50).map(|i| 500.0 + 4.0 * i as f64).collect();
+        track[20] = 1600.0;
+        track[30] = f64::NAN;
+        let config = FormantSmoothing {
+            kalman: Some(KalmanConfig::default()),
+            ..FormantSmoothing::default()
+        };
+        let smoothed = smooth_track(&track, 0.01, &config);
+        assert!(smoothed[20].is_nan());
+        assert!(smoothed[30].is_nan());
+        for i in (5..50).filter(|&i| i != 20 && i != 30) {
+            let expected = 500.0 + 4.0 * i as f64;
+            assert!(
+                (smoothed[i] - expected).abs() < 10.0,
+                "{}: {}",
+                i,
+                smoothed[i]
+            );
+        }
+
+        let frames: Vec<TrackFrame> = (0..3)
+            .map(|i| TrackFrame {
+                time: 0.01 * i as f64,
+                formants: vec![500.0, 1500.0 + i as f64],
+                bandwidths: vec![],
+                pitch: -1.0,
+                energy: 0.0,
+                zcr: 0.0,
+                voiced: false,
+                silent: false,
+            })
+            .collect();
+        let tracks = smooth_formant_tracks(&frames, 3, &FormantSmoothing::default());
+        assert_eq!(tracks[1][..2], [500.0, 1501.0]);
+        assert!(tracks[1][2].is_nan());
+
+        // In place, the spike is replaced by the median while frames keep their count.
+        let mut frames: Vec<TrackFrame> = (0..5)
+            .map(|i| TrackFrame {
+                formants: vec![if i == 2 { 900.0 } else { 500.0 }],
+                ..frames[0].clone()
+            })
+            .collect();
+        frames[4].formants.clear();
+        smooth_frames(&mut frames, &FormantSmoothing::default());
+        assert_eq!(frames[2].formants, [500.0]);
+        assert!(frames[4].formants.is_empty());
+    }
+
+    #[test]
+    fn pitch_octave_errors_and_short_gaps_are_repaired() {
+        // A 200 Hz contour with an octave-up error, an octave-down error, a 3-frame
+        // gap and a 10-frame gap.
+        let mut track = vec![200.0; 40];
+        track[8] = 400.0;
+        track[12] = 100.0;
+        track[20..23].fill(-1.0);
+        track[28..38].fill(-1.0);
+        let smoothed = smooth_pitch(&track, &PitchSmoothing::default());
+        assert_eq!(smoothed[8], 200.0);
+        assert_eq!(smoothed[12], 200.0);
+        assert!(smoothed[20..23].iter().all(|&f0| (f0 - 200.0).abs() < 1e-9));
+        assert!(smoothed[28..38].iter().all(|f0| f0.is_nan()));
+
+        // Gaps are bridged on a semitone scale: halfway between 100 and 400 Hz is 200.
+        let bridged = interpolate_gaps(&[100.0, f64::NAN, 400.0], 1);
+        assert!((bridged[1] - 200.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn display_smoother_steadies_values_and_holds_through_dropouts() {
+        let dt = 0.01;
+        let mut smoother = DisplaySmoother::new(DisplaySmoothing::default());
+        // f0 and F1, with ±20 Hz frame-to-frame jitter on F1.
+        let mut last = [0.0; 2];
+        for i in 0..100 {
+            let jitter = if i % 2 == 0 { 20.0 } else { -20.0 };
+            let mut values = [200.0, 500.0 + jitter];
+            smoother.update(&mut values, dt);
+            last = values;
+        }
+        assert_eq!(last[0], 200.0);
+        assert!((last[1] - 500.0).abs() < 5.0, "{}", last[1]);
+
+        // An unvoiced dropout: held for 0.15 s, then left as -1.
+        let held: Vec<f64> = (0..20)
+            .map(|_| {
+                let mut values = [-1.0, f64::NAN];
+                smoother.update(&mut values, dt);
+                values[0]
+            })
+            .collect();
+        assert!(held[..15].iter().all(|&v| v == 200.0), "{:?}", held);
+        assert!(held[15..].iter().all(|&v| v == -1.0), "{:?}", held);
+
+        // After the hold the next value is shown as is rather than glided into.
+        let mut values = [300.0, 700.0];
+        smoother.update(&mut values, dt);
+        assert_eq!(values, [300.0, 700.0]);
+    }
+}
@@ -0,0 +1,284 @@
+//! Running per-speaker statistics.
+//!
+//! [`SpeakerStats`] ingests analyzed frames one at a time and keeps, per formant and
+//! for f0, the running mean and standard deviation (Welford's algorithm) and a 1 Hz
+//! histogram for percentiles, plus the convex hull of the F1/F2 points for the vowel
+//! space area. Memory stays bounded however long the session runs, so a live display
+//! can show "your average F1/F2" without keeping every frame.
+
+use crate::batch::TrackFrame;
+
+/// Frequencies above this are counted in the top histogram bin, in Hz.
+pub const MAX_HISTOGRAM_HZ: f64 = 20000.0;
+
+/// Running statistics of one speaker's voiced frames.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct SpeakerStats {
+    formants: Vec<RunningStats>,
+    f0: RunningStats,
+    // Counterclockwise, F1 on the x axis and F2 on the y axis.
+    hull: Vec<(f64, f64)>,
+    frames: usize,
+}
+
+impl SpeakerStats {
+    /// Creates empty statistics.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds one frame's measurements.
+    ///
+    /// # Arguments
+    ///
+    /// * `formants` - F1, F2, ... in Hz; `NaN` or non-positive values are skipped.
+    /// * `f0`       - The fundamental frequency in Hz, or `-1.0` (any non-positive value
+    ///   or `NaN`) if unknown.
+    pub fn push(&mut self, formants: &[f64], f0: f64) {
+        self.frames += 1;
+        if self.formants.len() < formants.len() {
+            self.formants
+                .resize_with(formants.len(), RunningStats::default);
+        }
+        for (stats, &f) in self.formants.iter_mut().zip(formants) {
+            stats.push(f);
+        }
+        self.f0.push(f0);
+        if let [f1, f2, ..] = *formants {
+            if usable(f1) && usable(f2) {
+                self.extend_hull((f1, f2));
+            }
+        }
+    }
+
+    /// Adds an analyzed frame. Silent and unvoiced frames are skipped, as their
+    /// formants do not belong to a vowel.
+    pub fn push_frame(&mut self, frame: &TrackFrame) {
+        if frame.voiced && !frame.silent {
+            self.push(&frame.formants, frame.pitch);
+        }
+    }
+
+    /// Number of frames added.
+    pub fn frames(&self) -> usize {
+        self.frames
+    }
+
+    /// Mean of formant `index` (0 for F1) in Hz, or `None` before any value.
+    pub fn mean_formant(&self, index: usize) -> Option<f64> {
+        self.formants.get(index)?.mean()
+    }
+
+    /// Means of the formants seen so far, F1 upwards, `NaN` where none was measured.
+    pub fn mean_formants(&self) -> Vec<f64> {
+        self.formants
+            .iter()
+            .map(|s| s.mean().unwrap_or(f64::NAN))
+            .collect()
+    }
+
+    /// Sample standard deviation of formant `index` in Hz, or `None` with fewer than two
+    /// values.
+    pub fn formant_std_dev(&self, index: usize) -> Option<f64> {
+        self.formants.get(index)?.std_dev()
+    }
+
+    /// The `percentile` (0-100) of formant `index`, to the nearest Hz, or `None` before
+    /// any value.
+    pub fn formant_percentile(&self, index: usize, percentile: f64) -> Option<f64> {
+        self.formants.get(index)?.percentile(percentile)
+    }
+
+    /// Mean f0 in Hz, or `None` before any voiced frame.
+    pub fn mean_f0(&self) -> Option<f64> {
+        self.f0.mean()
+    }
+
+    /// Sample standard deviation of f0 in Hz, or `None` with fewer than two values.
+    pub fn f0_std_dev(&self) -> Option<f64> {
+        self.f0.std_dev()
+    }
+
+    /// The `percentile` (0-100) of f0, to the nearest Hz, or `None` before any value.
+    pub fn f0_percentile(&self, percentile: f64) -> Option<f64> {
+        self.f0.percentile(percentile)
+    }
+
+    /// Corners of the convex hull of all F1/F2 points, as `(F1, F2)` in Hz,
+    /// counterclockwise in the F1-F2 plane.
+    pub fn vowel_space_hull(&self) -> &[(f64, f64)] {
+        &self.hull
+    }
+
+    /// Area of [`SpeakerStats::vowel_space_hull`], in Hz². A single outlier frame
+    /// widens the hull for good, so feed only frames with trustworthy formants.
+    pub fn vowel_space_area(&self) -> f64 {
+        let n = self.hull.len();
+        let twice: f64 = (0..n)
+            .map(|i| {
+                let ((x0, y0), (x1, y1)) = (self.hull[i], self.hull[(i + 1) % n]);
+                x0 * y1 - x1 * y0
+            })
+            .sum();
+        twice.abs() / 2.0
+    }
+
+    /// Forgets everything.
+    pub fn reset(&mut self) {
+        *self = Self::default();
+    }
+
+    fn extend_hull(&mut self, point: (f64, f64)) {
+        let inside = self.hull.len() >= 3
+            && (0..self.hull.len())
+                .all(|i| cross(self.hull[i], self.hull[(i + 1) % self.hull.len()], point) >= 0.0);
+        if !inside {
+            let mut points = std::mem::take(&mut self.hull);
+            points.push(point);
+            self.hull = convex_hull(points);
+        }
+    }
+}
+
+// ------------------
+// Helpers
+// ------------------
+
+/// Mean, variance and 1 Hz histogram of a stream of frequencies.
+#[derive(Debug, Clone, PartialEq, Default)]
+struct RunningStats {
+    count: usize,
+    mean: f64,
+    m2: f64,
+    histogram: Vec<u32>,
+}
+
+impl RunningStats {
+    fn push(&mut self, value: f64) {
+        if !usable(value) {
+            return;
+        }
+        self.count += 1;
+        let delta = value - self.mean;
+        self.mean += delta / self.count as f64;
+        self.m2 += delta * (value - self.mean);
+
+        let bin = value.min(MAX_HISTOGRAM_HZ).round() as usize;
+        if self.histogram.len() <= bin {
+            self.histogram.resize(bin + 1, 0);
+        }
+        self.histogram[bin] += 1;
+    }
+
+    fn mean(&self) -> Option<f64> {
+        (self.count > 0).then_some(self.mean)
+    }
+
+    fn std_dev(&self) -> Option<f64> {
+        (self.count > 1).then(|| (self.m2 / (self.count - 1) as f64).sqrt())
+    }
+
+    // Nearest-rank percentile.
+    fn percentile(&self, percentile: f64) -> Option<f64> {
+        if self.count == 0 {
+            return None;
+        }
+        let rank =
+            ((percentile.clamp(0.0, 100.0) / 100.0 * self.count as f64).ceil() as usize).max(1);
+        let mut seen = 0;
+        self.histogram.iter().enumerate().find_map(|(hz, &n)| {
+            seen += n as usize;
+            (seen >= rank).then_some(hz as f64)
+        })
+    }
+}
+
+fn usable(value: f64) -> bool {
+    value > 0.0 && value.is_finite()
+}
+
+/// z component of `(b - a) x (c - a)`; positive when `c` is left of `a -> b`.
+fn cross(a: (f64, f64), b: (f64, f64), c: (f64, f64)) -> f64 {
+    (b.0 - a.0) * (c.1 - a.1) - (b.1 - a.1) * (c.0 - a.0)
+}
+
+/// Counterclockwise convex hull by Andrew's monotone chain, without collinear points.
+fn convex_hull(mut points: Vec<(f64, f64)>) -> Vec<(f64, f64)> {
+    points.sort_by(|a, b| a.0.total_cmp(&b.0).then(a.1.total_cmp(&b.1)));
+    points.dedup();
+    if points.len() < 3 {
+        return points;
+    }
+    // The last point of each chain starts the other one.
+    let mut hull = half_hull(points.iter());
+    let mut upper = half_hull(points.iter().rev());
+    hull.pop();
+    upper.pop();
+    hull.extend(upper);
+    hull
+}
+
+/// The chain of left turns through points sorted along the x axis.
+fn half_hull<'a>(points: impl Iterator<Item = &'a (f64, f64)>) -> Vec<(f64, f64)> {
+    let mut chain: Vec<(f64, f64)> = Vec::new();
+    for &p in points {
+        while let [.., a, b] = chain[..] {
+            if cross(a, b, p) > 0.0 {
+                break;
+            }
+            chain.pop();
+        }
+        chain.push(p);
+    }
+    chain
+}
+
+// ------------------
+// Tests
+// ------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn running_stats_and_vowel_space_of_corner_vowels() {
+        let mut stats = SpeakerStats::new();
+        // Corners of a 400 x 1000 Hz rectangle, points inside it and an unvoiced frame.
+        let points = [
+            (300.0, 2300.0),
+            (700.0, 2300.0),
+            (700.0, 1300.0),
+            (300.0, 1300.0),
+            (500.0, 1800.0),
+            (400.0, 2000.0),
+        ];
+        for (i, &(f1, f2)) in points.iter().enumerate() {
+            stats.push(&[f1, f2, 2500.0], if i % 2 == 0 { 200.0 } else { -1.0 });
+        }
+        stats.push(&[f64::NAN, 1000.0], -1.0);
+
+        assert_eq!(stats.frames(), 7);
+        assert_eq!(stats.mean_formant(0), Some(2900.0 / 6.0));
+        assert_eq!(stats.mean_formants()[2], 2500.0);
+        assert_eq!(stats.formant_percentile(1, 50.0), Some(1800.0));
+        assert_eq!(stats.formant_percentile(1, 0.0), Some(1000.0));
+        assert_eq!(stats.formant_percentile(0, 100.0), Some(700.0));
+        assert_eq!(stats.mean_f0(), Some(200.0));
+        assert_eq!(stats.f0_std_dev(), Some(0.0));
+
+        assert_eq!(stats.vowel_space_hull().len(), 4);
+        assert_eq!(stats.vowel_space_area(), 400.0 * 1000.0);
+        // A point outside the rectangle extends the hull.
+        stats.push(&[500.0, 2800.0], 210.0);
+        assert_eq!(stats.vowel_space_hull().len(), 5);
+        assert_eq!(
+            stats.vowel_space_area(),
+            400.0 * 1000.0 + 400.0 * 500.0 / 2.0
+        );
+
+        stats.reset();
+        assert_eq!(stats.mean_f0(), None);
+        assert_eq!(stats.vowel_space_area(), 0.0);
+    }
+}
@@ -0,0 +1,235 @@
+//! Scrolling spectrogram for live displays.
+//!
+//! [`Spectrogram`] keeps the most recent spectra in a ring buffer of dB columns and
+//! renders them straight to RGBA pixels through a [`Colormap`], oldest column on the
+//! left and the lowest bin at the bottom, so a display only has to blit the result.
+
+use crate::colormap::Colormap;
+use crate::spectrum::SpectralFloor;
+
+/// A fixed number of spectrum columns, overwritten oldest first.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Spectrogram {
+    bins: usize,
+    capacity: usize,
+    columns: Vec<f32>,
+    write: usize,
+    filled: usize,
+    min_db: f32,
+    max_db: f32,
+    colormap: Colormap,
+}
+
+impl Spectrogram {
+    /// Creates an empty grayscale spectrogram showing -100 to 0 dB.
+    ///
+    /// # Arguments
+    ///
+    /// * `bins`     - Values per column; longer spectra are truncated, shorter ones padded.
+    /// * `capacity` - Number of columns kept (the width of the time axis).
+    pub fn new(bins: usize, capacity: usize) -> Self {
+        let bins = bins.max(1);
+        let capacity = capacity.max(1);
+        Self {
+            bins,
+            capacity,
+            columns: vec![f32::NEG_INFINITY; bins * capacity],
+            write: 0,
+            filled: 0,
+            min_db: -100.0,
+            max_db: 0.0,
+            colormap: Colormap::default(),
+        }
+    }
+
+    /// Sets the dB range spanned by the colormap; values outside are clamped.
+    pub fn with_range(mut self, min_db: f32, max_db: f32) -> Self {
+        self.set_range(min_db, max_db);
+        self
+    }
+
+    /// Changes the displayed dB range without touching the stored columns.
+    pub fn set_range(&mut self, min_db: f32, max_db: f32) {
+        self.min_db = min_db.min(max_db);
+        self.max_db = max_db.max(min_db);
+    }
+
+    /// Sets the colormap used by [`Spectrogram::render_into`].
+    pub fn with_colormap(mut self, colormap: Colormap) -> Self {
+        self.colormap = colormap;
+        self
+    }
+
+    /// Changes the colormap without touching the stored columns.
+    pub fn set_colormap(&mut self, colormap: Colormap) {
+        self.colormap = colormap;
+    }
+
+    /// The colormap used for rendering.
+    pub fn colormap(&self) -> Colormap {
+        self.colormap
+    }
+
+    /// The displayed dB range.
+    pub fn range(&self) -> (f32, f32) {
+        (self.min_db, self.max_db)
+    }
+
+    /// Values per column.
+    pub fn bins(&self) -> usize {
+        self.bins
+    }
+
+    /// Maximum number of columns kept.
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Number of columns pushed so far, up to [`Spectrogram::capacity`].
+    pub fn len(&self) -> usize {
+        self.filled
+    }
+
+    /// Whether no column has been pushed since creation or [`Spectrogram::clear`].
+    pub fn is_empty(&self) -> bool {
+        self.filled == 0
+    }
+
+    /// Appends a column of linear magnitudes (e.g. from [`crate::fourier_trans`]).
+    /// Magnitudes are clamped to the default [`SpectralFloor`] first, so empty bins
+    /// stay finite.
+    pub fn push_magnitudes(&mut self, magnitudes: &[f32]) {
+        let len = magnitudes.len().min(self.bins);
+        let column = &mut self.push_with(magnitudes, |m| m)[..len];
+        SpectralFloor::default().apply(column);
+        for m in column.iter_mut() {
+            *m = 20.0 * m.log10();
+        }
+    }
+
+    /// Appends a column already in dB (e.g. from `AnalyserNode.getFloatFrequencyData`).
+    pub fn push_db(&mut self, db: &[f32]) {
+        self.push_with(db, |x| x);
+    }
+
+    /// The column pushed `age` columns ago (0 is the newest), in dB.
+    pub fn column(&self, age: usize) -> Option<&[f32]> {
+        if age >= self.filled {
+            return None;
+        }
+        let index = (self.write + self.capacity - 1 - age) % self.capacity;
+        Some(&self.columns[index * self.bins..(index + 1) * self.bins])
+    }
+
+    /// Forgets every column.
+    pub fn clear(&mut self) {
+        self.columns.fill(f32::NEG_INFINITY);
+        self.write = 0;
+        self.filled = 0;
+    }
+
+    /// Renders the spectrogram as RGBA, scaled to `width` x `height` pixels with
+    /// nearest-neighbour sampling. Columns not yet pushed get the colormap's lowest color.
+    ///
+    /// # Arguments
+    ///
+    /// * `out`    - Row-major RGBA bytes, at least `width * height * 4` long (as in
+    ///   `ImageData.data`).
+    /// * `width`  - Pixels along time; the newest column is at the right edge.
+    /// * `height` - Pixels along frequency; bin 0 is the bottom row.
+    pub fn render_into(&self, out: &mut [u8], width: usize, height: usize) {
+        for y in 0..height {
+            let bin = (height - 1 - y) * self.bins / height;
+            let row = &mut out[y * width * 4..(y + 1) * width * 4];
+            for (x, pixel) in row.chunks_exact_mut(4).enumerate() {
+                let age = self.capacity - 1 - x * self.capacity / width;
+                let color = match self.column(age) {
+                    Some(column) => self.colormap.rgba_db(column[bin], self.min_db, self.max_db),
+                    None => self.colormap.rgba(0.0),
+                };
+                pixel.copy_from_slice(&color);
+            }
+        }
+    }
+
+    // Writes one column, converting each value with `to_db`.
+    // Writes the next column and returns it.
+    fn push_with(&mut self, values: &[f32], to_db: impl Fn(f32) -> f32) -> &mut [f32] {
+        let start = self.write * self.bins;
+        self.write = (self.write + 1) % self.capacity;
+        self.filled = (self.filled + 1).min(self.capacity);
+        let column = &mut self.columns[start..start + self.bins];
+        for (i, slot) in column.iter_mut().enumerate() {
+            *slot = values.get(i).map_or(f32::NEG_INFINITY, |&v| to_db(v));
+        }
+        column
+    }
+}
+
+/// The dB magnitude spectrum of a frame as a spectrogram column of `bins` values
+/// spanning 0 Hz to Nyquist.
+///
+/// The frame is Hamming-windowed and zero-padded to `2 * bins` samples; longer frames
+/// keep only their central `2 * bins` samples. Magnitudes are clamped to the default
+/// [`SpectralFloor`] before the dB conversion.
+pub fn frame_column(frame: &[f64], bins: usize) -> Vec<f32> {
+    let fft_len = 2 * bins.max(1);
+    let skip = frame.len().saturating_sub(fft_len) / 2;
+    let mut windowed: Vec<f64> = frame.iter().skip(skip).take(fft_len).copied().collect();
+    crate::apply_hamming_window_in_place(&mut windowed);
+    let mut padded: Vec<f32> = windowed.iter().map(|&x| x as f32).collect();
+    padded.resize(fft_len, 0.0);
+    let mut magnitudes = crate::fourier_trans(padded);
+    SpectralFloor::default().apply(&mut magnitudes);
+    magnitudes.into_iter().map(|m| 20.0 * m.log10()).collect()
+}
+
+// ------------------
+// Tests
+// ------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn scrolls_and_renders_newest_column_on_the_right() {
+        let mut spectrogram = Spectrogram::new(2, 3).with_range(-60.0, 0.0);
+        spectrogram.push_magnitudes(&[1.0, 0.001]);
+        spectrogram.push_db(&[-30.0]);
+        assert_eq!(spectrogram.len(), 2);
+        assert_eq!(spectrogram.column(0), Some(&[-30.0, f32::NEG_INFINITY][..]));
+        assert_eq!(spectrogram.column(1), Some(&[0.0, -60.0][..]));
+        assert_eq!(spectrogram.column(2), None);
+
+        let mut pixels = vec![0u8; 3 * 2 * 4];
+        spectrogram.render_into(&mut pixels, 3, 2);
+        let gray: Vec<u8> = pixels.chunks(4).map(|p| p[0]).collect();
+        // Top row is bin 1, bottom row bin 0; the left column is still empty.
+        assert_eq!(gray, [0, 0, 0, 0, 255, 128]);
+        assert!(pixels.chunks(4).all(|p| p[3] == 255));
+
+        spectrogram.push_db(&[0.0, 0.0]);
+        spectrogram.push_db(&[0.0, 0.0]);
+        assert_eq!(spectrogram.len(), 3);
+        assert_eq!(spectrogram.column(2), Some(&[-30.0, f32::NEG_INFINITY][..]));
+
+        spectrogram.set_colormap(Colormap::Inferno);
+        spectrogram.render_into(&mut pixels, 3, 2);
+        assert_eq!(pixels[..4], Colormap::Inferno.rgba(0.0));
+    }
+
+    #[test]
+    fn empty_bins_are_floored_not_infinite() {
+        let floor_db = 20.0 * (SpectralFloor::DEFAULT_ABSOLUTE as f32).log10();
+        let mut spectrogram = Spectrogram::new(3, 2);
+        spectrogram.push_magnitudes(&[0.0, f32::NAN]);
+        assert_eq!(
+            spectrogram.column(0),
+            Some(&[floor_db, floor_db, f32::NEG_INFINITY][..])
+        );
+
+        let silence = frame_column(&[0.0; 64], 16);
+        assert!(silence.iter().all(|&db| db == floor_db));
+    }
+}
@@ -0,0 +1,280 @@
+use crate::error::{self, check_finite, check_positive, EzFormantError};
+use crate::fft;
+use num_complex::Complex;
+use num_traits::Float;
+
+/// Lower bound applied to magnitude spectra so they can be converted to dB.
+///
+/// The same floor should be used for the FFT spectrum and the LPC envelope of a frame,
+/// so that both displays bottom out at the same level.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SpectralFloor {
+    /// Clamp magnitudes to at least this value.
+    Absolute(f64),
+    /// Clamp magnitudes to at least this many dB below the largest magnitude of the
+    /// frame. Silent frames fall back to [`SpectralFloor::DEFAULT_ABSOLUTE`].
+    RelativeDb(f64),
+}
+
+impl Default for SpectralFloor {
+    fn default() -> Self {
+        SpectralFloor::Absolute(Self::DEFAULT_ABSOLUTE)
+    }
+}
+
+impl SpectralFloor {
+    /// The absolute floor used by default (-200 dB).
+    pub const DEFAULT_ABSOLUTE: f64 = 1e-10;
+
+    /// The floor value for a frame whose largest magnitude is `peak`.
+    pub fn threshold(&self, peak: f64) -> f64 {
+        match *self {
+            SpectralFloor::Absolute(floor) => floor,
+            SpectralFloor::RelativeDb(db) if peak > 0.0 => {
+                (peak * 10f64.powf(-db.abs() / 20.0)).max(f64::MIN_POSITIVE)
+            }
+            SpectralFloor::RelativeDb(_) => Self::DEFAULT_ABSOLUTE,
+        }
+    }
+
+    /// Clamps `magnitudes` to the floor in-place.
+    ///
+    /// # Arguments
+    ///
+    /// * `magnitudes` - A magnitude spectrum (`f32` or `f64`).
+    pub fn apply<T: Float>(&self, magnitudes: &mut [T]) {
+        let peak = magnitudes
+            .iter()
+            .fold(0.0f64, |acc, x| acc.max(x.to_f64().unwrap_or(0.0)));
+        let floor = T::from(self.threshold(peak)).unwrap_or_else(T::min_positive_value);
+        let floor = floor.max(T::min_positive_value());
+        for x in magnitudes.iter_mut() {
+            // Also replaces NaN with the floor.
+            *x = x.max(floor);
+        }
+    }
+}
+
+/// Window applied to a frame before its FFT in [`crate::fourier_trans_with`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SpectrumWindow {
+    /// No window: the frame as is (heavy leakage from frame edges).
+    #[default]
+    Rectangular,
+    /// Hann window.
+    Hann,
+    /// Hamming window.
+    Hamming,
+    /// Blackman window; the lowest sidelobes, the widest peaks.
+    Blackman,
+}
+
+impl SpectrumWindow {
+    /// Multiplies `data` (`f32` or `f64`) by the (symmetric) window in-place.
+    pub fn apply<T: Float>(&self, data: &mut [T]) {
+        if *self == SpectrumWindow::Rectangular {
+            return;
+        }
+        let coefficients = self.coefficients(data.len());
+        for (x, w) in data.iter_mut().zip(coefficients) {
+            *x = *x * T::from(w).unwrap_or_else(T::one);
+        }
+    }
+
+    /// The `len` window coefficients.
+    pub fn coefficients(&self, len: usize) -> Vec<f64> {
+        let (a0, a1, a2) = match self {
+            SpectrumWindow::Rectangular => return vec![1.0; len],
+            SpectrumWindow::Hann => (0.5, 0.5, 0.0),
+            SpectrumWindow::Hamming => (0.54, 0.46, 0.0),
+            SpectrumWindow::Blackman => (0.42, 0.5, 0.08),
+        };
+        let span = (len.max(2) - 1) as f64;
+        (0..len)
+            .map(|i| {
+                let phase = 2.0 * std::f64::consts::PI * i as f64 / span;
+                a0 - a1 * phase.cos() + a2 * (2.0 * phase).cos()
+            })
+            .collect()
+    }
+}
+
+impl std::str::FromStr for SpectrumWindow {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "rectangular" | "none" => Ok(SpectrumWindow::Rectangular),
+            "hann" | "hanning" => Ok(SpectrumWindow::Hann),
+            "hamming" => Ok(SpectrumWindow::Hamming),
+            "blackman" => Ok(SpectrumWindow::Blackman),
+            _ => Err(format!("unknown window: {}", s)),
+        }
+    }
+}
+
+/// How the values of an externally supplied spectrum are scaled.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SpectrumScale {
+    /// Squared magnitudes.
+    #[default]
+    Power,
+    /// Magnitudes.
+    Magnitude,
+    /// Magnitudes in dB (`20 log10 |X|`), as returned by the Web Audio
+    /// `AnalyserNode.getFloatFrequencyData`.
+    Decibels,
+}
+
+impl SpectrumScale {
+    /// Converts `values` in this scale to power. Non-finite results (e.g. from NaN
+    /// input) become zero.
+    pub fn to_power(&self, values: &[f64]) -> Vec<f64> {
+        values
+            .iter()
+            .map(|&v| match self {
+                SpectrumScale::Power => v,
+                SpectrumScale::Magnitude => v * v,
+                SpectrumScale::Decibels => 10f64.powf(v / 10.0),
+            })
+            .map(|p| if p.is_finite() { p.max(0.0) } else { 0.0 })
+            .collect()
+    }
+}
+
+impl std::str::FromStr for SpectrumScale {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "power" => Ok(SpectrumScale::Power),
+            "magnitude" => Ok(SpectrumScale::Magnitude),
+            "db" | "decibels" => Ok(SpectrumScale::Decibels),
+            _ => Err(format!("unknown spectrum scale: {}", s)),
+        }
+    }
+}
+
+/// Estimates the one-sided power spectral density of a signal with Welch's method:
+/// the periodograms of overlapping windowed segments, averaged.
+///
+/// Each segment has its mean removed before windowing. The density is scaled as in
+/// `scipy.signal.welch` (units²/Hz), so summing it times the bin spacing gives the
+/// signal's variance; convert to dB with `10 log10`.
+///
+/// # Arguments
+///
+/// * `signal`      - The input samples.
+/// * `sample_rate` - The sampling rate in Hz.
+/// * `nperseg`     - Samples per segment, which sets the resolution: bin `k` is at
+///   `k * sample_rate / nperseg` Hz.
+/// * `noverlap`    - Samples shared by consecutive segments (`nperseg / 2` is usual).
+/// * `window`      - Window applied to each segment; [`SpectrumWindow::Hann`] is usual.
+///
+/// # Returns
+///
+/// `nperseg / 2 + 1` densities for 0 Hz to Nyquist, or an error if the signal is
+/// shorter than a segment, contains NaN/infinity, or `noverlap >= nperseg`.
+pub fn welch_psd(
+    signal: &[f64],
+    sample_rate: f64,
+    nperseg: usize,
+    noverlap: usize,
+    window: SpectrumWindow,
+) -> error::Result<Vec<f64>> {
+    check_finite(signal)?;
+    check_positive("sample_rate", sample_rate)?;
+    if nperseg == 0 || noverlap >= nperseg {
+        return Err(EzFormantError::InvalidParameter {
+            name: "noverlap",
+            value: noverlap as f64,
+        });
+    }
+    if signal.len() < nperseg {
+        return Err(EzFormantError::InputTooShort {
+            len: signal.len(),
+            required: nperseg,
+        });
+    }
+
+    let coefficients = window.coefficients(nperseg);
+    let step = nperseg - noverlap;
+    let segments = 1 + (signal.len() - nperseg) / step;
+    let fft = fft::real_forward::<f64>(nperseg);
+    let mut buffer = vec![0.0; nperseg];
+    let mut spectrum = vec![Complex::new(0.0, 0.0); fft.complex_len()];
+    let mut psd = vec![0.0; fft.complex_len()];
+    for segment in 0..segments {
+        let samples = &signal[segment * step..segment * step + nperseg];
+        let mean = samples.iter().sum::<f64>() / nperseg as f64;
+        for ((b, &x), &w) in buffer.iter_mut().zip(samples).zip(&coefficients) {
+            *b = (x - mean) * w;
+        }
+        fft.process(&mut buffer, &mut spectrum)
+            .expect("buffers sized by the plan");
+        for (p, x) in psd.iter_mut().zip(&spectrum) {
+            *p += x.norm_sqr();
+        }
+    }
+
+    // Every bin but DC (and Nyquist for even lengths) also stands for its negative
+    // frequency.
+    let scale = 1.0 / (sample_rate * coefficients.iter().map(|w| w * w).sum::<f64>());
+    let last = psd.len() - 1;
+    for (k, p) in psd.iter_mut().enumerate() {
+        let one_sided = if k == 0 || (k == last && nperseg.is_multiple_of(2)) {
+            1.0
+        } else {
+            2.0
+        };
+        *p *= one_sided * scale / segments as f64;
+    }
+    Ok(psd)
+}
+
+// ------------------
+// Tests
+// ------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn floors_clamp_magnitudes() {
+        let mut absolute = vec![0.0f32, 1e-12, 0.5, 2.0];
+        SpectralFloor::default().apply(&mut absolute);
+        assert_eq!(absolute, vec![1e-10, 1e-10, 0.5, 2.0]);
+
+        // 40 dB below a peak of 2.0 is 0.02.
+        let mut relative = vec![0.0f64, 0.01, 0.5, 2.0];
+        SpectralFloor::RelativeDb(40.0).apply(&mut relative);
+        assert!((relative[0] - 0.02).abs() < 1e-12);
+        assert!((relative[1] - 0.02).abs() < 1e-12);
+        assert_eq!(&relative[2..], &[0.5, 2.0]);
+
+        let mut silent = vec![0.0f64; 4];
+        SpectralFloor::RelativeDb(40.0).apply(&mut silent);
+        assert!(silent.iter().all(|&x| x == SpectralFloor::DEFAULT_ABSOLUTE));
+    }
+
+    #[test]
+    fn welch_psd_integrates_to_variance() {
+        // A 1 kHz sine of amplitude 2 (variance 2) at 8 kHz, bin 32 of 256.
+        let fs = 8000.0;
+        let signal: Vec<f64> = (0..4000)
+            .map(|n| 2.0 * (2.0 * std::f64::consts::PI * 1000.0 * n as f64 / fs).sin())
+            .collect();
+        let psd = welch_psd(&signal, fs, 256, 128, SpectrumWindow::Hann).unwrap();
+        assert_eq!(psd.len(), 129);
+        let peak = (0..psd.len())
+            .max_by(|&a, &b| psd[a].total_cmp(&psd[b]))
+            .unwrap();
+        assert_eq!(peak, 32);
+        let power: f64 = psd.iter().sum::<f64>() * fs / 256.0;
+        assert!((power - 2.0).abs() < 0.05, "{}", power);
+
+        assert!(welch_psd(&signal, fs, 256, 256, SpectrumWindow::Hann).is_err());
+        assert!(welch_psd(&signal[..100], fs, 256, 128, SpectrumWindow::Hann).is_err());
+    }
+}
@@ -0,0 +1,147 @@
+//! Aggregate measures over formant tracks.
+//!
+//! Formant dispersion (Fitch, 1997) and formant spacing (Reby & McComb, 2003) both
+//! estimate the average distance between adjacent formants, which scales inversely
+//! with vocal tract length. Density counts formants per kHz.
+
+/// Speed of sound used by [`vocal_tract_length`], in cm/s.
+pub const SPEED_OF_SOUND: f64 = 35000.0;
+
+/// Summary of a formant track, computed from the per-formant means.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct FormantTrackStats {
+    /// Mean of each formant over the frames used.
+    pub mean_formants: Vec<f64>,
+    /// Number of frames that had all requested formants.
+    pub frames: usize,
+    /// [`dispersion`] of the mean formants, in Hz.
+    pub dispersion: f64,
+    /// [`spacing`] of the mean formants, in Hz.
+    pub spacing: f64,
+    /// [`density`] of the mean formants up to the highest one, in formants per kHz.
+    pub density: f64,
+}
+
+/// Formant dispersion: the mean difference between adjacent formants,
+/// `(F_n - F_1) / (n - 1)`.
+///
+/// # Returns
+///
+/// The dispersion in Hz, or `None` with fewer than two formants.
+pub fn dispersion(formants: &[f64]) -> Option<f64> {
+    match formants {
+        [first, .., last] => Some((last - first) / (formants.len() - 1) as f64),
+        _ => None,
+    }
+}
+
+/// Formant spacing: the least-squares `ΔF` for a uniform tube closed at one end,
+/// whose formants lie at `F_i = (2i - 1) / 2 · ΔF`.
+///
+/// # Returns
+///
+/// The spacing in Hz, or `None` if `formants` is empty.
+pub fn spacing(formants: &[f64]) -> Option<f64> {
+    if formants.is_empty() {
+        return None;
+    }
+    let (numerator, denominator) =
+        formants
+            .iter()
+            .enumerate()
+            .fold((0.0, 0.0), |(num, den), (i, &f)| {
+                let position = i as f64 + 0.5;
+                (num + f * position, den + position * position)
+            });
+    Some(numerator / denominator)
+}
+
+/// Formant density: formants per kHz below `max_frequency`.
+///
+/// # Arguments
+///
+/// * `formants`      - The formant frequencies, in Hz.
+/// * `max_frequency` - The upper edge of the counted band, in Hz.
+pub fn density(formants: &[f64], max_frequency: f64) -> f64 {
+    if max_frequency <= 0.0 {
+        return 0.0;
+    }
+    let count = formants.iter().filter(|&&f| f <= max_frequency).count();
+    count as f64 / (max_frequency / 1000.0)
+}
+
+/// Apparent vocal tract length for a formant spacing, `c / (2 ΔF)`, in cm.
+pub fn vocal_tract_length(spacing: f64) -> f64 {
+    SPEED_OF_SOUND / (2.0 * spacing)
+}
+
+/// Summarizes a formant track.
+///
+/// # Arguments
+///
+/// * `track`        - Formants per frame, e.g. from
+///   [`Analyzer::formants`](crate::analyzer::Analyzer::formants).
+/// * `num_formants` - How many formants (F1 upwards) to use. Frames with fewer are
+///   skipped.
+///
+/// # Returns
+///
+/// The statistics, or `None` if no frame has `num_formants` formants or
+/// `num_formants < 2`.
+pub fn formant_track_stats(track: &[Vec<f64>], num_formants: usize) -> Option<FormantTrackStats> {
+    if num_formants < 2 {
+        return None;
+    }
+    let frames: Vec<&[f64]> = track
+        .iter()
+        .filter(|f| f.len() >= num_formants)
+        .map(|f| &f[..num_formants])
+        .collect();
+    if frames.is_empty() {
+        return None;
+    }
+
+    let mean_formants: Vec<f64> = (0..num_formants)
+        .map(|i| frames.iter().map(|f| f[i]).sum::<f64>() / frames.len() as f64)
+        .collect();
+    Some(FormantTrackStats {
+        frames: frames.len(),
+        dispersion: dispersion(&mean_formants)?,
+        spacing: spacing(&mean_formants)?,
+        density: density(&mean_formants, mean_formants[num_formants - 1]),
+        mean_formants,
+    })
+}
+
+// ------------------
+// Tests
+// ------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn uniform_tube_measures() {
+        // A 17.5 cm tube: formants at 500, 1500, 2500, 3500 Hz.
+        let tube = [500.0, 1500.0, 2500.0, 3500.0];
+        assert_eq!(dispersion(&tube), Some(1000.0));
+        assert!((spacing(&tube).unwrap() - 1000.0).abs() < 1e-9);
+        assert!((vocal_tract_length(1000.0) - 17.5).abs() < 1e-9);
+        assert_eq!(density(&tube, 2000.0), 1.0);
+        assert_eq!(dispersion(&[500.0]), None);
+
+        let track = vec![
+            vec![480.0, 1480.0, 2480.0, 3480.0],
+            vec![520.0, 1520.0, 2520.0, 3520.0, 4500.0],
+            vec![700.0, 1200.0],
+        ];
+        let stats = formant_track_stats(&track, 4).unwrap();
+        assert_eq!(stats.frames, 2);
+        assert_eq!(stats.mean_formants, tube.to_vec());
+        assert_eq!(stats.dispersion, 1000.0);
+        assert!((stats.density - 4.0 / 3.5).abs() < 1e-12);
+        assert_eq!(formant_track_stats(&track[2..], 4), None);
+    }
+}
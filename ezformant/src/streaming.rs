@@ -0,0 +1,380 @@
+//! Streaming analysis of audio that arrives in arbitrary chunks, such as 128-sample
+//! AudioWorklet render quanta.
+//!
+//! [`StreamingAnalyzer`] keeps the most recent frame in a ring buffer and analyzes it
+//! every hop, so callers only forward the samples they receive. Frames match those of
+//! [`crate::batch::analyze_frames`] over the whole stream.
+//!
+//! Frames that cannot be analyzed (non-finite samples without
+//! [`StreamingAnalyzer::with_sanitize`], or too few for the LPC order) come back
+//! without formants or f0 and are counted as dropped in [`StreamingAnalyzer::counters`].
+//!
+//! [`StreamingAnalyzer::push_overlay`] also feeds each frame's spectrum into a
+//! [`Spectrogram`], so a display gets spectrogram columns, formants and f0 for the
+//! same frames in one call.
+
+use crate::analyzer::Analyzer;
+use crate::batch::TrackFrame;
+use crate::counters::{DropReason, FrameCounters, FrameCountersSnapshot};
+use crate::sanitize::SanitizeReport;
+use crate::spectrogram::{self, Spectrogram};
+use crate::vad::{Vad, VadConfig};
+
+/// A frame's tracks together with its spectrogram column.
+#[derive(Debug, Clone, PartialEq)]
+pub struct OverlayFrame {
+    /// Time, formants, f0 and energy of the frame.
+    pub track: TrackFrame,
+    /// The frame's magnitude spectrum in dB, as pushed to the spectrogram.
+    pub spectrum: Vec<f32>,
+}
+
+/// Accumulates a stream into overlapping frames and analyzes each one.
+#[derive(Debug, Clone)]
+pub struct StreamingAnalyzer {
+    analyzer: Analyzer,
+    ring: Vec<f64>,
+    frame: Vec<f64>,
+    write: usize,
+    filled: usize,
+    since_analysis: usize,
+    received: u64,
+    hop: usize,
+    vad: Option<Vad>,
+    sanitize_limit: Option<f64>,
+    repairs: SanitizeReport,
+    counters: FrameCounters,
+}
+
+impl StreamingAnalyzer {
+    /// Creates a streaming analyzer.
+    ///
+    /// # Arguments
+    ///
+    /// * `analyzer`  - The per-frame analysis, at the stream's sampling rate.
+    /// * `frame_len` - Samples per analyzed frame.
+    /// * `hop`       - Samples between the starts of consecutive frames.
+    pub fn new(analyzer: Analyzer, frame_len: usize, hop: usize) -> Self {
+        let frame_len = frame_len.max(1);
+        let hop = hop.max(1);
+        Self {
+            analyzer,
+            ring: vec![0.0; frame_len],
+            frame: vec![0.0; frame_len],
+            write: 0,
+            filled: 0,
+            since_analysis: first_analysis_offset(frame_len, hop),
+            received: 0,
+            hop,
+            vad: None,
+            sanitize_limit: None,
+            repairs: SanitizeReport::default(),
+            counters: FrameCounters::new(),
+        }
+    }
+
+    /// Skips the analysis of frames that voice activity detection rejects, returning
+    /// them as [`TrackFrame::silent`]. The noise floor is learned from the stream, so it
+    /// should start with background noise.
+    pub fn with_vad(mut self, config: VadConfig) -> Self {
+        self.set_vad(Some(config));
+        self
+    }
+
+    /// Enables voice activity detection with `config`, or disables it with `None`.
+    pub fn set_vad(&mut self, config: Option<VadConfig>) {
+        self.vad = config.map(Vad::new);
+    }
+
+    /// Repairs incoming samples before buffering them: NaN becomes `0.0` and other
+    /// values are clamped to `[-limit, limit]` (see [`crate::sanitize`]).
+    pub fn with_sanitize(mut self, limit: f64) -> Self {
+        self.set_sanitize(Some(limit));
+        self
+    }
+
+    /// Enables sample repair with `limit`, or disables it with `None`.
+    pub fn set_sanitize(&mut self, limit: Option<f64>) {
+        self.sanitize_limit = limit;
+    }
+
+    /// Samples repaired since creation or the last [`StreamingAnalyzer::reset`].
+    pub fn sanitize_report(&self) -> SanitizeReport {
+        self.repairs
+    }
+
+    /// Frames analyzed and dropped as invalid since creation or the last
+    /// [`StreamingAnalyzer::reset`]. Frames rejected by voice activity detection are
+    /// neither.
+    pub fn counters(&self) -> FrameCountersSnapshot {
+        self.counters.snapshot()
+    }
+
+    /// The per-frame analysis configuration.
+    pub fn analyzer(&self) -> &Analyzer {
+        &self.analyzer
+    }
+
+    /// Samples per analyzed frame.
+    pub fn frame_len(&self) -> usize {
+        self.ring.len()
+    }
+
+    /// Samples between consecutive frames.
+    pub fn hop(&self) -> usize {
+        self.hop
+    }
+
+    /// Appends a chunk of samples and analyzes every frame completed by it.
+    ///
+    /// The first frame is analyzed once `frame_len` samples have arrived, then one
+    /// every `hop` samples. Returns an empty vector (without allocating) when no frame
+    /// completes.
+    ///
+    /// # Arguments
+    ///
+    /// * `chunk` - The next samples of the stream, of any length (`f32` or `f64`).
+    pub fn push<T: Copy + Into<f64>>(&mut self, chunk: &[T]) -> Vec<TrackFrame> {
+        let mut frames = Vec::new();
+        self.push_with(chunk, |_, frame| frames.push(frame));
+        frames
+    }
+
+    /// Like [`StreamingAnalyzer::push`], but hands each frame to `on_frame` instead of
+    /// collecting them, for callers that copy what they need into buffers of their own.
+    ///
+    /// # Arguments
+    ///
+    /// * `chunk`    - The next samples of the stream.
+    /// * `on_frame` - Called with every frame the chunk completes, in time order.
+    pub fn push_each<T: Copy + Into<f64>>(
+        &mut self,
+        chunk: &[T],
+        mut on_frame: impl FnMut(TrackFrame),
+    ) {
+        self.push_with(chunk, |_, frame| on_frame(frame));
+    }
+
+    /// Like [`StreamingAnalyzer::push`], and pushes the spectrum of every analyzed frame
+    /// to `spectrogram` (see [`spectrogram::frame_column`]), so its columns line up with
+    /// the returned frames one to one.
+    ///
+    /// # Arguments
+    ///
+    /// * `chunk`       - The next samples of the stream.
+    /// * `spectrogram` - Receives one column per frame.
+    pub fn push_overlay<T: Copy + Into<f64>>(
+        &mut self,
+        chunk: &[T],
+        spectrogram: &mut Spectrogram,
+    ) -> Vec<OverlayFrame> {
+        let mut frames = Vec::new();
+        self.push_with(chunk, |samples, track| {
+            let spectrum = spectrogram::frame_column(samples, spectrogram.bins());
+            spectrogram.push_db(&spectrum);
+            frames.push(OverlayFrame { track, spectrum });
+        });
+        frames
+    }
+
+    // Buffers `chunk` and calls `on_frame` with the samples and analysis of every frame
+    // it completes.
+    fn push_with<T: Copy + Into<f64>>(
+        &mut self,
+        chunk: &[T],
+        mut on_frame: impl FnMut(&[f64], TrackFrame),
+    ) {
+        let len = self.ring.len();
+        for &sample in chunk {
+            let mut sample = sample.into();
+            if let Some(limit) = self.sanitize_limit {
+                sample = self.repairs.repair(sample, limit);
+            }
+            self.ring[self.write] = sample;
+            self.write = (self.write + 1) % len;
+            self.received += 1;
+            self.filled = (self.filled + 1).min(len);
+            self.since_analysis += 1;
+            if self.filled == len && self.since_analysis >= self.hop {
+                self.since_analysis = 0;
+                let frame = self.analyze();
+                on_frame(&self.frame, frame);
+            }
+        }
+    }
+
+    /// Forgets the buffered samples, the learned noise floor, the repair and frame
+    /// counts, and restarts the clock at zero.
+    pub fn reset(&mut self) {
+        self.repairs = SanitizeReport::default();
+        self.counters.reset();
+        self.write = 0;
+        self.filled = 0;
+        self.since_analysis = first_analysis_offset(self.ring.len(), self.hop);
+        self.received = 0;
+        if let Some(vad) = &mut self.vad {
+            vad.reset();
+        }
+    }
+
+    // Analyzes the frame ending at the most recent sample. Frames the analyzer rejects
+    // are counted as dropped and keep only their energy and zero-crossing rate.
+    fn analyze(&mut self) -> TrackFrame {
+        let (older, newer) = self.ring.split_at(self.write);
+        self.frame[..newer.len()].copy_from_slice(newer);
+        self.frame[newer.len()..].copy_from_slice(older);
+
+        let start = self.received - self.ring.len() as u64;
+        let sample_rate = self.analyzer.sample_rate();
+        if !self.analyzer.accepts(&self.frame) {
+            self.counters.record_dropped(DropReason::InvalidInput);
+            return TrackFrame {
+                silent: false,
+                ..TrackFrame::silent(&self.frame, start, sample_rate)
+            };
+        }
+        let active = match &mut self.vad {
+            Some(vad) => vad.is_active(&self.frame),
+            None => true,
+        };
+        if !active {
+            return TrackFrame::silent(&self.frame, start, sample_rate);
+        }
+        self.counters.record_analyzed();
+        TrackFrame::analyze(&self.analyzer, &self.frame, start)
+    }
+}
+
+// ------------------
+// Helpers
+// ------------------
+
+// The initial hop counter, chosen so that the first frame is analyzed as soon as it is
+// full even when `hop` is longer than the frame.
+fn first_analysis_offset(frame_len: usize, hop: usize) -> usize {
+    hop.saturating_sub(frame_len)
+}
+
+// ------------------
+// Tests
+// ------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn chunk_size_does_not_change_the_frames() {
+        let fs = 16000.0;
+        let signal: Vec<f64> = (0..8000)
+            .map(|n| (n as f64 * 0.07).sin() + 0.3 * (n as f64 * 0.41).sin())
+            .collect();
+        let analyzer = Analyzer::new(12, fs).with_downsample_factor(2);
+
+        let mut whole = StreamingAnalyzer::new(analyzer.clone(), 1024, 256);
+        let expected = whole.push(&signal);
+        // (8000 - 1024) / 256 + 1 frames.
+        assert_eq!(expected.len(), 28);
+        assert_eq!(expected[0].time, 512.0 / fs);
+        assert_eq!(expected[0].formants, analyzer.formants(&signal[..1024]));
+        assert_eq!(
+            expected[27].formants,
+            analyzer.formants(&signal[6912..7936])
+        );
+        assert_eq!(
+            expected,
+            crate::batch::analyze_frames(&signal, &analyzer, 1024, 256)
+        );
+
+        let mut chunked = StreamingAnalyzer::new(analyzer, 1024, 256);
+        let quanta: Vec<f32> = signal.iter().map(|&x| x as f32).collect();
+        let frames: Vec<TrackFrame> = quanta.chunks(128).flat_map(|c| chunked.push(c)).collect();
+        assert_eq!(frames.len(), expected.len());
+        for (a, b) in frames.iter().zip(&expected) {
+            assert_eq!(a.time, b.time);
+            assert_eq!(a.formants.len(), b.formants.len());
+        }
+    }
+
+    #[test]
+    fn sanitize_repairs_a_glitched_chunk() {
+        let fs = 8000.0;
+        let mut signal: Vec<f64> = (0..1200).map(|n| (n as f64 * 0.3).sin()).collect();
+        let mut clean = signal.clone();
+        clean[300..310].fill(0.0);
+        signal[300..310].fill(f64::NAN);
+
+        let analyzer = Analyzer::new(8, fs);
+        let expected = StreamingAnalyzer::new(analyzer.clone(), 200, 100).push(&clean);
+        let mut stream = StreamingAnalyzer::new(analyzer, 200, 100).with_sanitize(1e6);
+        assert_eq!(stream.push(&signal), expected);
+        assert_eq!(stream.sanitize_report().non_finite, 10);
+        stream.reset();
+        assert_eq!(stream.sanitize_report().repaired(), 0);
+    }
+
+    #[test]
+    fn hop_longer_than_the_frame_starts_at_the_first_full_frame() {
+        let fs = 8000.0;
+        let signal: Vec<f64> = (0..2000).map(|n| (n as f64 * 0.3).sin()).collect();
+        let analyzer = Analyzer::new(8, fs);
+
+        let mut stream = StreamingAnalyzer::new(analyzer.clone(), 200, 500);
+        let frames = stream.push(&signal);
+        // Frames start at 0, 500, 1000 and 1500.
+        assert_eq!(frames.len(), 4);
+        assert_eq!(frames[0].time, 100.0 / fs);
+        assert_eq!(
+            frames,
+            crate::batch::analyze_frames(&signal, &analyzer, 200, 500)
+        );
+
+        stream.reset();
+        assert_eq!(stream.push(&signal), frames);
+    }
+
+    #[test]
+    fn counters_track_analyzed_and_invalid_frames() {
+        let fs = 8000.0;
+        let mut signal: Vec<f64> = (0..1200).map(|n| (n as f64 * 0.3).sin()).collect();
+        signal[300] = f64::NAN;
+
+        let mut stream = StreamingAnalyzer::new(Analyzer::new(8, fs), 200, 100);
+        let frames = stream.push(&signal);
+        // Two of the 11 frames cover sample 300.
+        assert_eq!(frames.len(), 11);
+        let counts = stream.counters();
+        assert_eq!((counts.analyzed, counts.dropped_invalid), (9, 2));
+        for frame in &frames[2..4] {
+            assert!(frame.formants.is_empty() && frame.pitch == -1.0 && !frame.silent);
+        }
+
+        stream.reset();
+        assert_eq!(stream.counters(), FrameCountersSnapshot::default());
+    }
+
+    #[test]
+    fn overlay_pushes_one_aligned_column_per_frame() {
+        let fs = 8000.0;
+        // 1 kHz tone: bin 1000 / (8000 / 256) = 32 of a 128-bin column.
+        let signal: Vec<f64> = (0..2000)
+            .map(|n| (2.0 * std::f64::consts::PI * 1000.0 * n as f64 / fs).sin())
+            .collect();
+        let mut stream = StreamingAnalyzer::new(Analyzer::new(8, fs), 200, 80);
+        let mut spectrogram = Spectrogram::new(128, 50);
+        let frames = stream.push_overlay(&signal, &mut spectrogram);
+
+        assert_eq!(frames.len(), 23);
+        assert_eq!(spectrogram.len(), frames.len());
+        let newest = frames.last().unwrap();
+        assert_eq!(spectrogram.column(0), Some(&newest.spectrum[..]));
+        let peak = (0..128)
+            .max_by(|&a, &b| newest.spectrum[a].total_cmp(&newest.spectrum[b]))
+            .unwrap();
+        assert_eq!(peak, 32);
+        assert_eq!(
+            frames.iter().map(|f| f.track.clone()).collect::<Vec<_>>(),
+            StreamingAnalyzer::new(Analyzer::new(8, fs), 200, 80).push(&signal)
+        );
+    }
+}
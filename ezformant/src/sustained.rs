@@ -0,0 +1,394 @@
+//! Measurements for sustained-vowel tasks.
+//!
+//! Clinical protocols ("sustain /a/ for as long as you can") measure a stable middle
+//! portion and discard the onset and offset. [`find_sustained_vowel`] tracks f0 and the
+//! first two formants frame by frame, slides a window over the fully voiced frames,
+//! and picks the window whose tracks vary least. [`phonation`] reports how long the
+//! vowel was held (maximum phonation time) and where voicing broke off along the way.
+
+use crate::analyzer::Analyzer;
+use crate::hnr::{hnr, mean_hnr, HnrConfig};
+use crate::pitch::ac::AcPitchConfig;
+use crate::units::FrequencyUnit;
+use crate::voice_quality::{glottal_periods, jitter, shimmer, Jitter, Shimmer};
+
+/// Frame and window lengths for [`find_sustained_vowel`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SustainedVowelConfig {
+    /// Length of the measured region, in seconds.
+    pub window_duration: f64,
+    /// Analysis frame length, in seconds.
+    pub frame_duration: f64,
+    /// Hop between frames, in seconds.
+    pub time_step: f64,
+}
+
+impl Default for SustainedVowelConfig {
+    fn default() -> Self {
+        Self {
+            window_duration: 1.0,
+            frame_duration: 0.04,
+            time_step: 0.01,
+        }
+    }
+}
+
+/// Measurements over the steadiest region of a recording.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SustainedVowel {
+    /// Start of the region, in seconds.
+    pub start: f64,
+    /// End of the region, in seconds.
+    pub end: f64,
+    /// Mean fundamental frequency, in Hz.
+    pub f0: f64,
+    /// Mean F1, F2 and F3, in Hz.
+    pub formants: [f64; 3],
+    /// Sum of the coefficients of variation of f0, F1 and F2 over the region; lower is
+    /// steadier.
+    pub variation: f64,
+    /// Jitter of the region, if enough periods were found.
+    pub jitter: Option<Jitter>,
+    /// Shimmer of the region, if enough periods were found.
+    pub shimmer: Option<Shimmer>,
+    /// Mean harmonics-to-noise ratio of the region's voiced frames, in dB.
+    pub hnr: Option<f64>,
+}
+
+/// Finds the steadiest voiced region of a recording and measures it.
+///
+/// # Arguments
+///
+/// * `signal`   - The recording, at `analyzer.sample_rate()`.
+/// * `analyzer` - Formant and pitch settings used for the frame-wise tracks.
+/// * `config`   - Frame and region lengths.
+///
+/// # Returns
+///
+/// The measurements, or `None` if no window of `config.window_duration` seconds has
+/// every frame voiced with at least three formants, or if a duration or the time step
+/// of `config` is not positive.
+#[tracing::instrument(level = "debug", skip_all, fields(len = signal.len()))]
+pub fn find_sustained_vowel(
+    signal: &[f64],
+    analyzer: &Analyzer,
+    config: &SustainedVowelConfig,
+) -> Option<SustainedVowel> {
+    let durations = [
+        config.window_duration,
+        config.frame_duration,
+        config.time_step,
+    ];
+    if !durations.iter().all(|&d| d > 0.0 && d.is_finite()) {
+        return None;
+    }
+    // Variation and means are computed in Hz.
+    let analyzer = &analyzer.clone().with_units(FrequencyUnit::Hertz);
+    let sample_rate = analyzer.sample_rate();
+    let frame_length = (config.frame_duration * sample_rate).round() as usize;
+    if frame_length == 0 {
+        return None;
+    }
+    let hop = ((config.time_step * sample_rate).round() as usize).max(1);
+    let window_frames = ((config.window_duration - config.frame_duration) / config.time_step)
+        .round()
+        .max(0.0) as usize
+        + 1;
+
+    // Per frame: [f0, F1, F2, F3], or `None` if unvoiced or short of formants.
+    let pitch = analyzer
+        .quality()
+        .track_pitch(signal, sample_rate, frame_length, hop);
+    let tracks: Vec<Option<[f64; 4]>> = pitch
+        .iter()
+        .enumerate()
+        .map(|(i, p)| {
+            let frame = signal.get(i * hop..i * hop + frame_length)?;
+            let formants = analyzer.formants(frame);
+            (p.voiced && formants.len() >= 3).then(|| [p.f0, formants[0], formants[1], formants[2]])
+        })
+        .collect();
+    if tracks.len() < window_frames {
+        return None;
+    }
+
+    let (first, variation) = (0..=tracks.len() - window_frames)
+        .filter_map(|first| {
+            let window: Option<Vec<[f64; 4]>> = tracks[first..first + window_frames]
+                .iter()
+                .copied()
+                .collect();
+            let window = window?;
+            let variation: f64 = (0..3).map(|j| coefficient_of_variation(&window, j)).sum();
+            Some((first, variation))
+        })
+        .min_by(|a, b| a.1.total_cmp(&b.1))?;
+
+    let window = &tracks[first..first + window_frames];
+    let mean = |j: usize| window.iter().flatten().map(|t| t[j]).sum::<f64>() / window_frames as f64;
+    let start = first * hop;
+    let end = ((first + window_frames - 1) * hop + frame_length).min(signal.len());
+    let region = &signal[start..end];
+
+    let periods = glottal_periods(region, sample_rate, &AcPitchConfig::default());
+    Some(SustainedVowel {
+        start: start as f64 / sample_rate,
+        end: end as f64 / sample_rate,
+        f0: mean(0),
+        formants: [mean(1), mean(2), mean(3)],
+        variation,
+        jitter: jitter(&periods),
+        shimmer: shimmer(&periods),
+        hnr: mean_hnr(&hnr(region, sample_rate, &HnrConfig::default())),
+    })
+}
+
+/// Frame lengths and thresholds for [`phonation`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PhonationConfig {
+    /// Analysis frame length, in seconds.
+    pub frame_duration: f64,
+    /// Hop between frames, in seconds.
+    pub time_step: f64,
+    /// Frames quieter than this, in dB relative to the loudest frame, count as silent
+    /// even when the pitch tracker reports them voiced.
+    pub silence_threshold_db: f64,
+    /// Shortest unvoiced gap counted as a phonation break, in seconds.
+    pub min_break_duration: f64,
+}
+
+impl Default for PhonationConfig {
+    fn default() -> Self {
+        Self {
+            frame_duration: 0.04,
+            time_step: 0.01,
+            silence_threshold_db: -40.0,
+            min_break_duration: 0.05,
+        }
+    }
+}
+
+/// An interruption of voicing within a phonation.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct PhonationBreak {
+    /// Start of the break, in seconds.
+    pub start: f64,
+    /// Length of the break, in seconds.
+    pub duration: f64,
+}
+
+/// Duration and continuity of a sustained phonation.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Phonation {
+    /// Start of the first voiced frame, in seconds.
+    pub onset: f64,
+    /// End of the last voiced frame, in seconds.
+    pub offset: f64,
+    /// Maximum phonation time, `offset - onset`, in seconds.
+    pub maximum_phonation_time: f64,
+    /// Time actually voiced between onset and offset (excluding breaks), in seconds.
+    pub voiced_duration: f64,
+    /// Unvoiced gaps of at least `min_break_duration` between onset and offset.
+    pub breaks: Vec<PhonationBreak>,
+}
+
+/// Measures the maximum phonation time and the phonation breaks of a recording.
+///
+/// A frame is voiced when the pitch tracker says so and its level is within
+/// `silence_threshold_db` of the loudest frame. The phonation runs from the first to
+/// the last voiced frame; unvoiced gaps shorter than `min_break_duration` are treated
+/// as tracker dropouts and ignored.
+///
+/// # Arguments
+///
+/// * `signal`   - The recording, at `analyzer.sample_rate()`.
+/// * `analyzer` - Pitch settings used for the voicing track.
+/// * `config`   - Frame lengths and thresholds.
+///
+/// # Returns
+///
+/// The measurements, or `None` if no frame is voiced, or if the frame duration or the
+/// time step of `config` is not positive.
+#[tracing::instrument(level = "debug", skip_all, fields(len = signal.len()))]
+pub fn phonation(
+    signal: &[f64],
+    analyzer: &Analyzer,
+    config: &PhonationConfig,
+) -> Option<Phonation> {
+    let durations = [config.frame_duration, config.time_step];
+    if !durations.iter().all(|&d| d > 0.0 && d.is_finite()) {
+        return None;
+    }
+    let sample_rate = analyzer.sample_rate();
+    let frame_length = (config.frame_duration * sample_rate).round() as usize;
+    if frame_length == 0 {
+        return None;
+    }
+    let hop = ((config.time_step * sample_rate).round() as usize).max(1);
+
+    let pitch = analyzer
+        .quality()
+        .track_pitch(signal, sample_rate, frame_length, hop);
+    let levels: Vec<f64> = (0..pitch.len())
+        .map(|i| {
+            let frame = signal.get(i * hop..i * hop + frame_length).unwrap_or(&[]);
+            frame.iter().map(|x| x * x).sum::<f64>() / frame_length.max(1) as f64
+        })
+        .collect();
+    let loudest = levels.iter().copied().fold(0.0, f64::max);
+    let threshold = loudest * 10f64.powf(config.silence_threshold_db / 10.0);
+    let voiced: Vec<bool> = pitch
+        .iter()
+        .zip(&levels)
+        .map(|(p, &level)| p.voiced && level > threshold && level > 0.0)
+        .collect();
+
+    let first = voiced.iter().position(|&v| v)?;
+    let last = voiced.iter().rposition(|&v| v)?;
+    // Frame `i` covers `[i * hop, i * hop + frame_length)`; report the frame centres'
+    // hop-wide cells so that adjacent frames tile the time axis.
+    let centre = |i: usize| (i * hop) as f64 + 0.5 * frame_length as f64;
+    let onset = (centre(first) - 0.5 * hop as f64) / sample_rate;
+    let offset = (centre(last) + 0.5 * hop as f64) / sample_rate;
+
+    let mut breaks = Vec::new();
+    let mut i = first;
+    while i <= last {
+        if voiced[i] {
+            i += 1;
+            continue;
+        }
+        let end = i + voiced[i..].iter().position(|&v| v).unwrap_or(0);
+        let duration = (end - i) as f64 * hop as f64 / sample_rate;
+        if duration >= config.min_break_duration {
+            breaks.push(PhonationBreak {
+                start: (centre(i) - 0.5 * hop as f64) / sample_rate,
+                duration,
+            });
+        }
+        i = end;
+    }
+
+    let maximum_phonation_time = offset - onset;
+    Some(Phonation {
+        onset,
+        offset,
+        maximum_phonation_time,
+        voiced_duration: maximum_phonation_time - breaks.iter().map(|b| b.duration).sum::<f64>(),
+        breaks,
+    })
+}
+
+/// Standard deviation over mean of column `j`.
+fn coefficient_of_variation(window: &[[f64; 4]], j: usize) -> f64 {
+    let n = window.len() as f64;
+    let mean = window.iter().map(|t| t[j]).sum::<f64>() / n;
+    let variance = window.iter().map(|t| (t[j] - mean).powi(2)).sum::<f64>() / n;
+    variance.sqrt() / mean
+}
+
+// ------------------
+// Tests
+// ------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_signals::{pulse_train, resonate, VOWEL_FORMANTS};
+
+    // `duration` seconds of the shared test vowel with f0 following `f0(t)`.
+    fn vowel(sample_rate: f64, duration: f64, f0: impl Fn(f64) -> f64) -> Vec<f64> {
+        let mut signal = pulse_train(sample_rate, (sample_rate * duration) as usize, f0);
+        resonate(&mut signal, sample_rate, &VOWEL_FORMANTS);
+        signal
+    }
+
+    #[test]
+    fn picks_the_steady_part_of_a_glide() {
+        let sample_rate = 8000.0;
+        // f0 glides from 200 Hz down to 120 Hz over the first second, then holds.
+        let signal = vowel(sample_rate, 2.0, |t| 200.0 - 80.0 * t.min(1.0));
+        let analyzer = Analyzer::new(8, sample_rate);
+        let config = SustainedVowelConfig {
+            window_duration: 0.5,
+            ..SustainedVowelConfig::default()
+        };
+
+        let vowel = find_sustained_vowel(&signal, &analyzer, &config).unwrap();
+        assert!(vowel.start >= 0.95, "start = {}", vowel.start);
+        assert!((vowel.end - vowel.start - 0.5).abs() < 0.02);
+        assert!((vowel.f0 - 120.0).abs() < 2.0, "f0 = {}", vowel.f0);
+        assert!((vowel.formants[0] - 700.0).abs() < 100.0);
+        assert!(vowel.jitter.unwrap().local < 0.01);
+
+        let too_long = SustainedVowelConfig {
+            window_duration: 3.0,
+            ..config
+        };
+        assert_eq!(find_sustained_vowel(&signal, &analyzer, &too_long), None);
+
+        for time_step in [0.0, -0.01, f64::NAN] {
+            let config = SustainedVowelConfig {
+                time_step,
+                ..config
+            };
+            assert_eq!(find_sustained_vowel(&signal, &analyzer, &config), None);
+            let config = PhonationConfig {
+                time_step,
+                ..PhonationConfig::default()
+            };
+            assert_eq!(phonation(&signal, &analyzer, &config), None);
+        }
+        let no_frame = SustainedVowelConfig {
+            frame_duration: 0.0,
+            ..config
+        };
+        assert_eq!(find_sustained_vowel(&signal, &analyzer, &no_frame), None);
+    }
+
+    #[test]
+    fn measures_phonation_time_and_breaks() {
+        let sample_rate = 8000.0;
+        let silence = |duration: f64| vec![0.0; (sample_rate * duration) as usize];
+        // 0.2 s silence, 1.0 s vowel, 0.15 s gap, 0.5 s vowel, 0.3 s silence.
+        let signal: Vec<f64> = [
+            silence(0.2),
+            vowel(sample_rate, 1.0, |_| 150.0),
+            silence(0.15),
+            vowel(sample_rate, 0.5, |_| 150.0),
+            silence(0.3),
+        ]
+        .concat();
+
+        let analyzer = Analyzer::new(8, sample_rate);
+        let result = phonation(&signal, &analyzer, &PhonationConfig::default()).unwrap();
+        assert!(
+            (result.onset - 0.2).abs() < 0.03,
+            "onset = {}",
+            result.onset
+        );
+        assert!(
+            (result.offset - 1.85).abs() < 0.03,
+            "offset = {}",
+            result.offset
+        );
+        assert_eq!(result.breaks.len(), 1);
+        let gap = result.breaks[0];
+        assert!((gap.start - 1.2).abs() < 0.03, "break at {}", gap.start);
+        // Frames straddling the edges of the gap may also read unvoiced.
+        assert!(
+            (gap.duration - 0.15).abs() <= 0.05,
+            "break of {}",
+            gap.duration
+        );
+        assert!((result.voiced_duration - 1.5).abs() <= 0.1, "{:?}", result);
+
+        assert_eq!(
+            phonation(&silence(1.0), &analyzer, &PhonationConfig::default()),
+            None
+        );
+    }
+}
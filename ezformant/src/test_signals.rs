@@ -0,0 +1,62 @@
+//! Synthetic signals for the unit tests of this crate and of the `webapp` bindings.
+//!
+//! Not part of the public API: outside this crate's tests it only exists with the
+//! `test-signals` feature, which the bindings enable from their dev-dependencies, and
+//! it may change at any time.
+
+use std::f64::consts::PI;
+
+/// Resonances of [`vowel`], as `(frequency, bandwidth)` in Hz.
+pub const VOWEL_FORMANTS: [(f64, f64); 3] = [(700.0, 80.0), (1200.0, 90.0), (2600.0, 120.0)];
+
+/// Unit impulses at `f0(t)` Hz, with `t` in seconds; the first impulse is at sample 0.
+///
+/// # Arguments
+///
+/// * `sample_rate` - The sampling rate.
+/// * `len`         - Number of samples.
+/// * `f0`          - The pulse rate at each time.
+pub fn pulse_train(sample_rate: f64, len: usize, f0: impl Fn(f64) -> f64) -> Vec<f64> {
+    let mut phase = 1.0;
+    (0..len)
+        .map(|n| {
+            let pulse = phase >= 1.0;
+            if pulse {
+                phase -= 1.0;
+            }
+            phase += f0(n as f64 / sample_rate) / sample_rate;
+            if pulse {
+                1.0
+            } else {
+                0.0
+            }
+        })
+        .collect()
+}
+
+/// Filters `signal` in place through two-pole resonators in series.
+///
+/// # Arguments
+///
+/// * `signal`      - The excitation, replaced by the output.
+/// * `sample_rate` - The sampling rate.
+/// * `resonances`  - `(frequency, bandwidth)` of each resonator, in Hz.
+pub fn resonate(signal: &mut [f64], sample_rate: f64, resonances: &[(f64, f64)]) {
+    for &(frequency, bandwidth) in resonances {
+        let r = (-PI * bandwidth / sample_rate).exp();
+        let (a1, a2) = (2.0 * r * (2.0 * PI * frequency / sample_rate).cos(), -r * r);
+        let (mut y1, mut y2) = (0.0, 0.0);
+        for x in signal.iter_mut() {
+            let y = *x + a1 * y1 + a2 * y2;
+            (y2, y1) = (y1, y);
+            *x = y;
+        }
+    }
+}
+
+/// A pulse train at `f0` Hz through the resonances of [`VOWEL_FORMANTS`].
+pub fn vowel(sample_rate: f64, f0: f64, len: usize) -> Vec<f64> {
+    let mut signal = pulse_train(sample_rate, len, |_| f0);
+    resonate(&mut signal, sample_rate, &VOWEL_FORMANTS);
+    signal
+}
@@ -0,0 +1,467 @@
+//! Praat TextGrid annotations and per-interval measurements.
+//!
+//! [`TextGrid`] reads both the long ("text file") and short text formats, in UTF-8 or
+//! the UTF-16 Praat writes for non-ASCII labels. [`measure_intervals`] then summarizes
+//! formant and f0 tracks over each labeled interval, e.g. every vowel of a segmented
+//! recording:
+//!
+//! ```no_run
+//! # use ezformant::batch::{analyze_signal, BatchConfig};
+//! # use ezformant::textgrid::{measure_intervals, TextGrid};
+//! # let (signal, sample_rate) = (vec![0.0; 16000], 16000.0);
+//! let grid = TextGrid::from_bytes(&std::fs::read("speaker1.TextGrid").unwrap()).unwrap();
+//! let frames = analyze_signal(&signal, sample_rate, &BatchConfig::default());
+//! let vowels = grid.interval_tier("vowels").unwrap();
+//! for m in measure_intervals(&frames, vowels, 3) {
+//!     println!("{} {:?} {:?}", m.label, m.median_f0, m.median_formants);
+//! }
+//! ```
+
+use crate::batch::TrackFrame;
+use std::str::FromStr;
+
+/// A labeled stretch of time on an interval tier.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Interval {
+    /// Start time, in seconds.
+    pub start: f64,
+    /// End time, in seconds.
+    pub end: f64,
+    /// The label; empty for unlabeled gaps.
+    pub label: String,
+}
+
+/// A labeled instant on a point tier.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Point {
+    /// Time, in seconds.
+    pub time: f64,
+    /// The label.
+    pub label: String,
+}
+
+/// One tier of a TextGrid.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Tier {
+    /// An `IntervalTier`.
+    Intervals {
+        /// Name of the tier.
+        name: String,
+        /// Intervals in time order.
+        intervals: Vec<Interval>,
+    },
+    /// A `TextTier` (point tier).
+    Points {
+        /// Name of the tier.
+        name: String,
+        /// Points in time order.
+        points: Vec<Point>,
+    },
+}
+
+impl Tier {
+    /// Name of the tier.
+    pub fn name(&self) -> &str {
+        match self {
+            Tier::Intervals { name, .. } | Tier::Points { name, .. } => name,
+        }
+    }
+}
+
+/// A Praat TextGrid.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct TextGrid {
+    /// Start of the time domain, in seconds.
+    pub xmin: f64,
+    /// End of the time domain, in seconds.
+    pub xmax: f64,
+    /// Tiers in file order.
+    pub tiers: Vec<Tier>,
+}
+
+impl TextGrid {
+    /// Parses a TextGrid file's bytes, detecting UTF-16 from its byte order mark.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, String> {
+        let text = match bytes {
+            [0xFE, 0xFF, rest @ ..] => decode_utf16(rest, u16::from_be_bytes),
+            [0xFF, 0xFE, rest @ ..] => decode_utf16(rest, u16::from_le_bytes),
+            [0xEF, 0xBB, 0xBF, rest @ ..] => String::from_utf8(rest.to_vec()).ok(),
+            _ => String::from_utf8(bytes.to_vec()).ok(),
+        };
+        text.ok_or_else(|| "TextGrid is not valid UTF-8 or UTF-16".to_string())?
+            .parse()
+    }
+
+    /// Finds the first tier named `name`.
+    pub fn tier(&self, name: &str) -> Option<&Tier> {
+        self.tiers.iter().find(|tier| tier.name() == name)
+    }
+
+    /// The intervals of the first interval tier named `name`.
+    pub fn interval_tier(&self, name: &str) -> Option<&[Interval]> {
+        self.tiers.iter().find_map(|tier| match tier {
+            Tier::Intervals {
+                name: tier_name,
+                intervals,
+            } if tier_name == name => Some(intervals.as_slice()),
+            _ => None,
+        })
+    }
+}
+
+impl FromStr for TextGrid {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut tokens = Tokens::new(s);
+        if tokens.string()? != "ooTextFile" || tokens.string()? != "TextGrid" {
+            return Err("not a TextGrid".to_string());
+        }
+        let xmin = tokens.number()?;
+        let xmax = tokens.number()?;
+        if tokens.next() != Some(Token::Exists) {
+            return Ok(TextGrid {
+                xmin,
+                xmax,
+                tiers: Vec::new(),
+            });
+        }
+
+        let count = tokens.size()?;
+        let mut tiers = Vec::with_capacity(count);
+        for _ in 0..count {
+            let class = tokens.string()?;
+            let name = tokens.string()?;
+            tokens.number()?;
+            tokens.number()?;
+            let size = tokens.size()?;
+            let tier = match class.as_str() {
+                "IntervalTier" => Tier::Intervals {
+                    name,
+                    intervals: (0..size)
+                        .map(|_| {
+                            Ok(Interval {
+                                start: tokens.number()?,
+                                end: tokens.number()?,
+                                label: tokens.string()?,
+                            })
+                        })
+                        .collect::<Result<_, String>>()?,
+                },
+                "TextTier" => Tier::Points {
+                    name,
+                    points: (0..size)
+                        .map(|_| {
+                            Ok(Point {
+                                time: tokens.number()?,
+                                label: tokens.string()?,
+                            })
+                        })
+                        .collect::<Result<_, String>>()?,
+                },
+                other => return Err(format!("unknown tier class: {}", other)),
+            };
+            tiers.push(tier);
+        }
+        Ok(TextGrid { xmin, xmax, tiers })
+    }
+}
+
+/// Summary of the frames inside one labeled interval.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct IntervalMeasurement {
+    /// Start of the interval, in seconds.
+    pub start: f64,
+    /// End of the interval, in seconds.
+    pub end: f64,
+    /// The interval's label.
+    pub label: String,
+    /// Number of frames centred inside the interval.
+    pub frames: usize,
+    /// Mean of F1, F2, ... over the frames that have each formant (NaN if none do).
+    pub mean_formants: Vec<f64>,
+    /// Median of F1, F2, ... over the frames that have each formant (NaN if none do).
+    pub median_formants: Vec<f64>,
+    /// Mean f0 over the voiced frames.
+    pub mean_f0: Option<f64>,
+    /// Median f0 over the voiced frames.
+    pub median_f0: Option<f64>,
+}
+
+/// Measures formants and f0 over every labeled interval.
+///
+/// # Arguments
+///
+/// * `frames`       - Tracks of the annotated recording, e.g. from
+///   [`crate::batch::analyze_signal`].
+/// * `intervals`    - An interval tier; intervals with empty (or whitespace) labels are
+///   skipped.
+/// * `num_formants` - How many formants (F1 upwards) to summarize.
+///
+/// # Returns
+///
+/// One measurement per labeled interval, in tier order. A frame belongs to the
+/// interval its centre falls in.
+pub fn measure_intervals(
+    frames: &[TrackFrame],
+    intervals: &[Interval],
+    num_formants: usize,
+) -> Vec<IntervalMeasurement> {
+    intervals
+        .iter()
+        .filter(|interval| !interval.label.trim().is_empty())
+        .map(|interval| {
+            let inside: Vec<&TrackFrame> = frames
+                .iter()
+                .filter(|f| f.time >= interval.start && f.time < interval.end)
+                .collect();
+            let formant = |i: usize| -> Vec<f64> {
+                inside
+                    .iter()
+                    .filter_map(|f| f.formants.get(i).copied())
+                    .collect()
+            };
+            let f0: Vec<f64> = inside
+                .iter()
+                .filter(|f| f.voiced)
+                .map(|f| f.pitch)
+                .collect();
+            IntervalMeasurement {
+                start: interval.start,
+                end: interval.end,
+                label: interval.label.clone(),
+                frames: inside.len(),
+                mean_formants: (0..num_formants)
+                    .map(|i| mean(&formant(i)).unwrap_or(f64::NAN))
+                    .collect(),
+                median_formants: (0..num_formants)
+                    .map(|i| median(formant(i)).unwrap_or(f64::NAN))
+                    .collect(),
+                mean_f0: mean(&f0),
+                median_f0: median(f0),
+            }
+        })
+        .collect()
+}
+
+// ------------------
+// Helpers
+// ------------------
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Number(f64),
+    Text(String),
+    Exists,
+}
+
+/// The values of a TextGrid in either text format. Long-format keys (`xmin =`,
+/// `intervals [1]:`, ...) are skipped, which leaves the same sequence as the short
+/// format.
+struct Tokens<'a> {
+    rest: &'a str,
+}
+
+impl<'a> Tokens<'a> {
+    fn new(text: &'a str) -> Self {
+        Self { rest: text }
+    }
+
+    fn string(&mut self) -> Result<String, String> {
+        match self.next() {
+            Some(Token::Text(text)) => Ok(text),
+            other => Err(format!("expected a string, found {:?}", other)),
+        }
+    }
+
+    fn number(&mut self) -> Result<f64, String> {
+        match self.next() {
+            Some(Token::Number(value)) => Ok(value),
+            other => Err(format!("expected a number, found {:?}", other)),
+        }
+    }
+
+    fn size(&mut self) -> Result<usize, String> {
+        let value = self.number()?;
+        if value >= 0.0 && value.fract() == 0.0 {
+            Ok(value as usize)
+        } else {
+            Err(format!("expected a count, found {}", value))
+        }
+    }
+}
+
+impl Iterator for Tokens<'_> {
+    type Item = Token;
+
+    fn next(&mut self) -> Option<Token> {
+        loop {
+            self.rest = self.rest.trim_start();
+            if let Some(quoted) = self.rest.strip_prefix('"') {
+                // Strings run to the next lone quote; `""` is an escaped quote.
+                let mut text = String::new();
+                let mut chars = quoted.char_indices().peekable();
+                while let Some((i, c)) = chars.next() {
+                    if c != '"' {
+                        text.push(c);
+                    } else if chars.peek().map(|&(_, c)| c) == Some('"') {
+                        text.push('"');
+                        chars.next();
+                    } else {
+                        self.rest = &quoted[i + 1..];
+                        return Some(Token::Text(text));
+                    }
+                }
+                self.rest = "";
+                return Some(Token::Text(text));
+            }
+
+            let end = self
+                .rest
+                .find(char::is_whitespace)
+                .unwrap_or(self.rest.len());
+            if end == 0 {
+                return None;
+            }
+            let (word, rest) = self.rest.split_at(end);
+            self.rest = rest;
+            if word == "<exists>" {
+                return Some(Token::Exists);
+            }
+            if let Ok(value) = word.parse() {
+                return Some(Token::Number(value));
+            }
+        }
+    }
+}
+
+fn decode_utf16(bytes: &[u8], from_bytes: fn([u8; 2]) -> u16) -> Option<String> {
+    let units: Vec<u16> = bytes
+        .chunks_exact(2)
+        .map(|pair| from_bytes([pair[0], pair[1]]))
+        .collect();
+    String::from_utf16(&units).ok()
+}
+
+fn mean(values: &[f64]) -> Option<f64> {
+    (!values.is_empty()).then(|| values.iter().sum::<f64>() / values.len() as f64)
+}
+
+fn median(mut values: Vec<f64>) -> Option<f64> {
+    if values.is_empty() {
+        return None;
+    }
+    values.sort_by(f64::total_cmp);
+    let mid = values.len() / 2;
+    Some(if values.len().is_multiple_of(2) {
+        (values[mid - 1] + values[mid]) / 2.0
+    } else {
+        values[mid]
+    })
+}
+
+// ------------------
+// Tests
+// ------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const LONG: &str = r#"File type = "ooTextFile"
+Object class = "TextGrid"
+
+xmin = 0
+xmax = 0.3
+tiers? <exists>
+size = 2
+item []:
+    item [1]:
+        class = "IntervalTier"
+        name = "vowels"
+        xmin = 0
+        xmax = 0.3
+        intervals: size = 3
+        intervals [1]:
+            xmin = 0
+            xmax = 0.1
+            text = ""
+        intervals [2]:
+            xmin = 0.1
+            xmax = 0.2
+            text = "a ""open"""
+        intervals [3]:
+            xmin = 0.2
+            xmax = 0.3
+            text = "i"
+    item [2]:
+        class = "TextTier"
+        name = "events"
+        xmin = 0
+        xmax = 0.3
+        points: size = 1
+        points [1]:
+            number = 0.15
+            mark = "click"
+"#;
+
+    const SHORT: &str = "File type = \"ooTextFile\"\nObject class = \"TextGrid\"\n\n0\n0.3\n\
+        <exists>\n2\n\"IntervalTier\"\n\"vowels\"\n0\n0.3\n3\n0\n0.1\n\"\"\n0.1\n0.2\n\
+        \"a \"\"open\"\"\"\n0.2\n0.3\n\"i\"\n\"TextTier\"\n\"events\"\n0\n0.3\n1\n0.15\n\"click\"\n";
+
+    #[test]
+    fn parses_both_formats_and_measures_intervals() {
+        let grid: TextGrid = LONG.parse().unwrap();
+        assert_eq!(SHORT.parse(), Ok(grid.clone()));
+        let utf16: Vec<u8> = [0xFF, 0xFE]
+            .into_iter()
+            .chain(SHORT.encode_utf16().flat_map(u16::to_le_bytes))
+            .collect();
+        assert_eq!(TextGrid::from_bytes(&utf16), Ok(grid.clone()));
+        assert_eq!(grid.xmax, 0.3);
+        assert_eq!(grid.tier("events").unwrap().name(), "events");
+        let vowels = grid.interval_tier("vowels").unwrap();
+        assert_eq!(vowels[1].label, "a \"open\"");
+        assert!(grid.interval_tier("events").is_none());
+
+        let frames: Vec<TrackFrame> = (0..30)
+            .map(|i| {
+                let time = 0.005 + i as f64 * 0.01;
+                let open = (0.1..0.2).contains(&time);
+                TrackFrame {
+                    time,
+                    formants: if open {
+                        vec![700.0 + i as f64, 1200.0]
+                    } else {
+                        vec![300.0]
+                    },
+                    bandwidths: vec![],
+                    pitch: if i % 2 == 0 { 200.0 } else { -1.0 },
+                    energy: 1.0,
+                    zcr: 0.1,
+                    voiced: i % 2 == 0,
+                    silent: false,
+                }
+            })
+            .collect();
+        let measurements = measure_intervals(&frames, vowels, 2);
+        assert_eq!(measurements.len(), 2);
+        let a = &measurements[0];
+        assert_eq!((a.label.as_str(), a.frames), ("a \"open\"", 10));
+        assert_eq!(a.mean_formants, [714.5, 1200.0]);
+        assert_eq!(a.median_formants, [714.5, 1200.0]);
+        assert_eq!((a.mean_f0, a.median_f0), (Some(200.0), Some(200.0)));
+        let i = &measurements[1];
+        assert_eq!(i.median_formants[0], 300.0);
+        assert!(i.median_formants[1].is_nan());
+
+        assert!("File type = \"ooTextFile\"\nObject class = \"Pitch 1\"\n"
+            .parse::<TextGrid>()
+            .is_err());
+    }
+}
@@ -0,0 +1,25 @@
+//! Native `tracing` backend.
+//!
+//! The analysis pipeline emits `tracing` spans and events unconditionally; they are
+//! free when no subscriber is installed. This module installs a `fmt` subscriber for
+//! native builds. Browser builds use `webapp::init_tracing` instead.
+
+use tracing_subscriber::EnvFilter;
+
+/// Installs a global subscriber printing spans and events to stderr.
+///
+/// The filter is read from `RUST_LOG` and defaults to `ezformant=info`.
+///
+/// # Returns
+///
+/// `false` if a global subscriber was already installed.
+pub fn init_fmt() -> bool {
+    let filter =
+        EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new("ezformant=info"));
+
+    tracing_subscriber::fmt()
+        .with_env_filter(filter)
+        .with_writer(std::io::stderr)
+        .try_init()
+        .is_ok()
+}
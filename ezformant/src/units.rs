@@ -0,0 +1,219 @@
+//! Perceptual and musical frequency scales for formant and pitch outputs.
+
+use std::str::FromStr;
+
+/// Reference frequency of [`FrequencyUnit::Semitones`], as in Praat.
+pub const SEMITONE_REFERENCE_HZ: f64 = 100.0;
+
+/// Frequency of MIDI note 69 (A4) for [`FrequencyUnit::Midi`].
+pub const MIDI_A4_HZ: f64 = 440.0;
+
+/// Unit in which frequencies are reported.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum FrequencyUnit {
+    /// Hertz (no conversion).
+    #[default]
+    Hertz,
+    /// Bark, using Traunmüller's (1990) formula.
+    Bark,
+    /// ERB-rate (number of ERBs below the frequency), after Glasberg & Moore (1990).
+    Erb,
+    /// Mel, using O'Shaughnessy's formula `2595 log10(1 + f / 700)`.
+    Mel,
+    /// Semitones relative to [`SEMITONE_REFERENCE_HZ`].
+    Semitones,
+    /// Cents (hundredths of a semitone) relative to `reference_hz`, e.g. a tonic or a
+    /// speaker's mean f0.
+    Cents {
+        /// The frequency at 0 cents, in Hz.
+        reference_hz: f64,
+    },
+    /// MIDI note number, fractional between notes: 69 is A4 at [`MIDI_A4_HZ`], 60 is
+    /// middle C.
+    Midi,
+}
+
+impl FrequencyUnit {
+    /// Converts a frequency in Hz to this unit.
+    pub fn from_hertz(self, hz: f64) -> f64 {
+        match self {
+            FrequencyUnit::Hertz => hz,
+            FrequencyUnit::Bark => 26.81 * hz / (1960.0 + hz) - 0.53,
+            FrequencyUnit::Erb => 21.4 * (1.0 + 0.00437 * hz).log10(),
+            FrequencyUnit::Mel => 2595.0 * (1.0 + hz / 700.0).log10(),
+            FrequencyUnit::Semitones => 12.0 * (hz / SEMITONE_REFERENCE_HZ).log2(),
+            FrequencyUnit::Cents { reference_hz } => 1200.0 * (hz / reference_hz).log2(),
+            FrequencyUnit::Midi => 69.0 + 12.0 * (hz / MIDI_A4_HZ).log2(),
+        }
+    }
+
+    /// Converts a value in this unit back to Hz.
+    pub fn to_hertz(self, value: f64) -> f64 {
+        match self {
+            FrequencyUnit::Hertz => value,
+            FrequencyUnit::Bark => 1960.0 * (value + 0.53) / (26.28 - value),
+            FrequencyUnit::Erb => (10f64.powf(value / 21.4) - 1.0) / 0.00437,
+            FrequencyUnit::Mel => 700.0 * (10f64.powf(value / 2595.0) - 1.0),
+            FrequencyUnit::Semitones => SEMITONE_REFERENCE_HZ * 2f64.powf(value / 12.0),
+            FrequencyUnit::Cents { reference_hz } => reference_hz * 2f64.powf(value / 1200.0),
+            FrequencyUnit::Midi => MIDI_A4_HZ * 2f64.powf((value - 69.0) / 12.0),
+        }
+    }
+
+    /// Converts frequencies in Hz to this unit in-place.
+    ///
+    /// Non-positive values, such as the `-1.0` "no pitch" marker, are left unchanged.
+    pub fn convert_in_place(self, values: &mut [f64]) {
+        for value in values.iter_mut().filter(|v| **v > 0.0) {
+            *value = self.from_hertz(*value);
+        }
+    }
+}
+
+impl FromStr for FrequencyUnit {
+    type Err = String;
+
+    /// Parses `hz`, `bark`, `erb`, `mel`, `st`, `midi` or `cents:<reference Hz>` (e.g.
+    /// `cents:220`), ignoring case.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.to_ascii_lowercase();
+        if let Some(reference) = s.strip_prefix("cents:") {
+            return match reference.trim().parse::<f64>() {
+                Ok(reference_hz) if reference_hz > 0.0 && reference_hz.is_finite() => {
+                    Ok(FrequencyUnit::Cents { reference_hz })
+                }
+                _ => Err(format!("invalid cents reference: {}", reference)),
+            };
+        }
+        match s.as_str() {
+            "hz" | "hertz" => Ok(FrequencyUnit::Hertz),
+            "bark" => Ok(FrequencyUnit::Bark),
+            "erb" => Ok(FrequencyUnit::Erb),
+            "mel" => Ok(FrequencyUnit::Mel),
+            "st" | "semitones" => Ok(FrequencyUnit::Semitones),
+            "midi" => Ok(FrequencyUnit::Midi),
+            _ => Err(format!("unknown unit: {}", s)),
+        }
+    }
+}
+
+/// Frequencies of the points returned by
+/// [`compute_frequency_response`](crate::lpc::compute_frequency_response), in `unit`.
+///
+/// # Arguments
+///
+/// * `num_points`  - The number of points.
+/// * `sample_rate` - The sampling rate; the axis spans `0..sample_rate / 2`.
+/// * `unit`        - The unit of the returned values.
+///
+/// # Returns
+///
+/// `num_points` frequencies in ascending order. The first point is 0 Hz, which is
+/// `-inf` in semitones.
+pub fn frequency_axis(num_points: usize, sample_rate: f64, unit: FrequencyUnit) -> Vec<f64> {
+    (0..num_points)
+        .map(|i| unit.from_hertz(i as f64 / num_points as f64 * sample_rate / 2.0))
+        .collect()
+}
+
+/// A grid of frequencies evenly spaced in `spacing`, for sampling a response on a
+/// perceptual or logarithmic axis (see [`crate::lpc::frequency_response_at`]).
+/// [`FrequencyUnit::Semitones`] gives a logarithmic grid and [`FrequencyUnit::Hertz`]
+/// a linear one.
+///
+/// # Arguments
+///
+/// * `min_hz`     - The first frequency, in Hz. Must be positive for semitones.
+/// * `max_hz`     - The last frequency, in Hz.
+/// * `num_points` - The number of points, including both ends.
+/// * `spacing`    - The unit in which the points are evenly spaced.
+///
+/// # Returns
+///
+/// `num_points` frequencies in Hz.
+pub fn frequency_grid(
+    min_hz: f64,
+    max_hz: f64,
+    num_points: usize,
+    spacing: FrequencyUnit,
+) -> Vec<f64> {
+    let (low, high) = (spacing.from_hertz(min_hz), spacing.from_hertz(max_hz));
+    let step = (high - low) / num_points.saturating_sub(1).max(1) as f64;
+    (0..num_points)
+        .map(|i| spacing.to_hertz(low + i as f64 * step))
+        .collect()
+}
+
+// ------------------
+// Tests
+// ------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn conversions_round_trip_and_match_reference_values() {
+        let units = [
+            FrequencyUnit::Hertz,
+            FrequencyUnit::Bark,
+            FrequencyUnit::Erb,
+            FrequencyUnit::Mel,
+            FrequencyUnit::Semitones,
+            FrequencyUnit::Cents {
+                reference_hz: 220.0,
+            },
+            FrequencyUnit::Midi,
+        ];
+        for unit in units {
+            for hz in [50.0, 500.0, 1000.0, 5000.0] {
+                assert!((unit.to_hertz(unit.from_hertz(hz)) - hz).abs() < 1e-9);
+            }
+        }
+        assert!((FrequencyUnit::Bark.from_hertz(1000.0) - 8.527).abs() < 1e-3);
+        assert!((FrequencyUnit::Erb.from_hertz(1000.0) - 15.62).abs() < 1e-2);
+        assert!((FrequencyUnit::Mel.from_hertz(1000.0) - 1000.0).abs() < 0.1);
+        assert_eq!(FrequencyUnit::Semitones.from_hertz(200.0), 12.0);
+
+        let mut pitch = [-1.0, 400.0];
+        FrequencyUnit::Semitones.convert_in_place(&mut pitch);
+        assert_eq!(pitch, [-1.0, 24.0]);
+        assert_eq!("ST".parse(), Ok(FrequencyUnit::Semitones));
+    }
+
+    #[test]
+    fn musical_units_use_their_references() {
+        let midi = FrequencyUnit::Midi;
+        assert_eq!(midi.from_hertz(440.0), 69.0);
+        assert!((midi.from_hertz(261.6256) - 60.0).abs() < 1e-4);
+        assert!((midi.from_hertz(450.0) - 69.389).abs() < 1e-3);
+
+        let cents: FrequencyUnit = "Cents:220".parse().unwrap();
+        assert_eq!(
+            cents,
+            FrequencyUnit::Cents {
+                reference_hz: 220.0
+            }
+        );
+        assert_eq!(cents.from_hertz(440.0), 1200.0);
+        assert!((cents.from_hertz(233.0819) - 100.0).abs() < 1e-3);
+        assert!("cents:0".parse::<FrequencyUnit>().is_err());
+        assert!("cents".parse::<FrequencyUnit>().is_err());
+    }
+
+    #[test]
+    fn frequency_grids_are_evenly_spaced_in_their_unit() {
+        let log = frequency_grid(100.0, 6400.0, 7, FrequencyUnit::Semitones);
+        for (f, expected) in log
+            .iter()
+            .zip([100.0, 200.0, 400.0, 800.0, 1600.0, 3200.0, 6400.0])
+        {
+            assert!((f - expected).abs() < 1e-9, "{:?}", log);
+        }
+        assert_eq!(
+            frequency_grid(0.0, 300.0, 4, FrequencyUnit::Hertz),
+            vec![0.0, 100.0, 200.0, 300.0]
+        );
+        assert_eq!(frequency_grid(50.0, 500.0, 1, FrequencyUnit::Mel).len(), 1);
+    }
+}
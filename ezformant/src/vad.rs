@@ -0,0 +1,212 @@
+//! Energy and zero-crossing based voice activity detection.
+//!
+//! [`Vad`] tracks the background level of a stream of frames and marks a frame active
+//! when it rises far enough above it. Frames with a high zero-crossing rate (broadband
+//! noise, hiss) need a larger margin, and a short hangover keeps word endings and brief
+//! dips active. Batch and streaming analysis use it to skip the LPC root solver on
+//! frames that only hold room noise.
+
+use crate::{frame_energy, zcr};
+
+/// Thresholds and time constants for [`Vad`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct VadConfig {
+    /// Margin above the noise floor, in dB, for a frame to count as active.
+    pub threshold_db: f64,
+    /// Frames quieter than this mean-square level, in dB (0 dB is a full-scale square
+    /// wave), are always inactive.
+    pub min_energy_db: f64,
+    /// Zero-crossing rate above which a frame needs twice the margin.
+    pub max_zcr: f64,
+    /// Inactive-looking frames kept active after the last active one.
+    pub hangover_frames: usize,
+    /// Fraction of the distance to an inactive frame's level by which the noise floor
+    /// moves per frame. It follows quieter frames at once and active ones 20 times
+    /// slower.
+    pub noise_adaptation: f64,
+}
+
+impl Default for VadConfig {
+    fn default() -> Self {
+        Self {
+            threshold_db: 10.0,
+            min_energy_db: -70.0,
+            max_zcr: 0.4,
+            hangover_frames: 8,
+            noise_adaptation: 0.05,
+        }
+    }
+}
+
+/// Voice activity detector over consecutive frames.
+///
+/// A live detector's noise floor starts at the level of the first frame, so the stream
+/// should begin with background noise; [`Vad::for_recording`] avoids this for whole
+/// recordings.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Vad {
+    config: VadConfig,
+    noise_floor_db: Option<f64>,
+    hangover: usize,
+}
+
+impl Vad {
+    /// Creates a detector with no noise estimate yet.
+    pub fn new(config: VadConfig) -> Self {
+        Self {
+            config,
+            noise_floor_db: None,
+            hangover: 0,
+        }
+    }
+
+    /// Creates a detector for a whole recording, with the noise floor starting at the
+    /// level of its quietest tenth of frames, so that speech at the very start is still
+    /// detected and a short stretch of digital silence does not pull the floor down.
+    ///
+    /// # Arguments
+    ///
+    /// * `config`    - Detector thresholds.
+    /// * `signal`    - The recording.
+    /// * `frame_len` - Samples per frame.
+    /// * `hop`       - Samples between the starts of consecutive frames.
+    pub fn for_recording(config: VadConfig, signal: &[f64], frame_len: usize, hop: usize) -> Self {
+        let frame_len = frame_len.max(1);
+        let mut levels: Vec<f64> = (0..signal.len().saturating_sub(frame_len - 1))
+            .step_by(hop.max(1))
+            .map(|start| level_db(&signal[start..start + frame_len]))
+            .collect();
+        levels.sort_by(f64::total_cmp);
+        Self {
+            noise_floor_db: levels.get(levels.len() / 10).copied(),
+            ..Self::new(config)
+        }
+    }
+
+    /// The thresholds in use.
+    pub fn config(&self) -> &VadConfig {
+        &self.config
+    }
+
+    /// The current noise floor estimate in dB, once a frame has been seen.
+    pub fn noise_floor_db(&self) -> Option<f64> {
+        self.noise_floor_db
+    }
+
+    /// Classifies the next frame and updates the noise floor.
+    ///
+    /// # Returns
+    ///
+    /// Whether the frame is active (speech, or within the hangover after it).
+    pub fn is_active(&mut self, frame: &[f64]) -> bool {
+        let level = level_db(frame);
+        let floor = *self.noise_floor_db.get_or_insert(level);
+        let margin = if zcr(frame) > self.config.max_zcr {
+            2.0 * self.config.threshold_db
+        } else {
+            self.config.threshold_db
+        };
+        let speech = level >= self.config.min_energy_db && level > floor + margin;
+
+        let rate = if speech {
+            self.config.noise_adaptation / 20.0
+        } else {
+            self.config.noise_adaptation
+        };
+        self.noise_floor_db = Some(if level < floor {
+            level
+        } else {
+            floor + rate * (level - floor)
+        });
+
+        if speech {
+            self.hangover = self.config.hangover_frames;
+            true
+        } else if self.hangover > 0 {
+            self.hangover -= 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Forgets the noise floor and the hangover.
+    pub fn reset(&mut self) {
+        self.noise_floor_db = None;
+        self.hangover = 0;
+    }
+}
+
+/// Classifies the frames of a recording.
+///
+/// # Arguments
+///
+/// * `signal`    - The recording.
+/// * `frame_len` - Samples per frame.
+/// * `hop`       - Samples between the starts of consecutive frames.
+/// * `config`    - Detector thresholds.
+///
+/// # Returns
+///
+/// One flag per full frame (as in [`crate::batch::analyze_frames`]), `true` where active.
+pub fn detect(signal: &[f64], frame_len: usize, hop: usize, config: &VadConfig) -> Vec<bool> {
+    let frame_len = frame_len.max(1);
+    if signal.len() < frame_len {
+        return Vec::new();
+    }
+    let mut vad = Vad::for_recording(*config, signal, frame_len, hop);
+    (0..=signal.len() - frame_len)
+        .step_by(hop.max(1))
+        .map(|start| vad.is_active(&signal[start..start + frame_len]))
+        .collect()
+}
+
+// ------------------
+// Helpers
+// ------------------
+
+/// Mean-square level of a frame in dB, bottoming out at -200 dB.
+fn level_db(frame: &[f64]) -> f64 {
+    10.0 * frame_energy(frame).max(1e-20).log10()
+}
+
+// ------------------
+// Tests
+// ------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tone_bursts_in_noise_are_active_with_hangover() {
+        // 0.3 s of faint noise, 0.3 s of 200 Hz tone over it, 0.4 s of noise again.
+        let fs = 8000.0;
+        let mut state: u32 = 3;
+        let signal: Vec<f64> = (0..8000)
+            .map(|n| {
+                state = state.wrapping_mul(1664525).wrapping_add(1013904223);
+                let noise = 0.002 * ((state >> 8) as f64 / (1u32 << 24) as f64 - 0.5);
+                let t = n as f64 / fs;
+                let tone = if (0.3..0.6).contains(&t) {
+                    0.5 * (2.0 * std::f64::consts::PI * 200.0 * t).sin()
+                } else {
+                    0.0
+                };
+                noise + tone
+            })
+            .collect();
+        let config = VadConfig::default();
+        let active = detect(&signal, 200, 80, &config);
+        assert_eq!(active.len(), 98);
+
+        let first = active.iter().position(|&a| a).unwrap();
+        let last = active.iter().rposition(|&a| a).unwrap();
+        // Frame i covers [80 i, 80 i + 200): the tone spans samples 2400..4800.
+        assert!((28..=30).contains(&first), "{}", first);
+        assert!(active[first..=57].iter().all(|&a| a));
+        assert_eq!(last, 59 + config.hangover_frames, "{:?}", active);
+        assert!(!active[..first].iter().any(|&a| a));
+    }
+}
@@ -0,0 +1,227 @@
+//! Vibrato rate and extent of f0 contours.
+//!
+//! Each voiced run of a contour is converted to cents around its own mean and
+//! detrended, so slow intonation drift does not count as vibrato. The strongest
+//! autocorrelation peak within the vibrato rate range gives the rate, its height the
+//! regularity, and the RMS deviation the extent.
+
+use crate::batch::TrackFrame;
+
+/// Rate range and thresholds for [`vibrato`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct VibratoConfig {
+    /// Slowest rate searched, in Hz.
+    pub min_rate: f64,
+    /// Fastest rate searched, in Hz.
+    pub max_rate: f64,
+    /// Shortest voiced run measured, in seconds. It should span at least two cycles at
+    /// `min_rate`.
+    pub min_duration: f64,
+    /// Smallest extent reported, in cents; flatter runs count as straight tone.
+    pub min_extent: f64,
+}
+
+impl Default for VibratoConfig {
+    fn default() -> Self {
+        Self {
+            min_rate: 3.0,
+            max_rate: 10.0,
+            min_duration: 0.7,
+            min_extent: 10.0,
+        }
+    }
+}
+
+/// Vibrato of one voiced run.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Vibrato {
+    /// Start of the run, in seconds.
+    pub start: f64,
+    /// End of the run, in seconds.
+    pub end: f64,
+    /// Oscillations per second, in Hz.
+    pub rate: f64,
+    /// Amplitude of the oscillation (half its peak-to-peak swing), in cents.
+    pub extent: f64,
+    /// Normalized autocorrelation at one vibrato period, in `[0, 1]`; close to 1 for an
+    /// even, sinusoidal vibrato.
+    pub regularity: f64,
+}
+
+/// Measures the vibrato of every voiced run of an f0 contour.
+///
+/// # Arguments
+///
+/// * `track`     - f0 per frame in Hz; `NaN` or non-positive values mark unvoiced
+///   frames. Smoothing it first ([`crate::smoothing::smooth_pitch`]) removes octave
+///   errors and bridges short dropouts.
+/// * `time_step` - Time between frames, in seconds; frame `i` is at `i * time_step`.
+/// * `config`    - Rate range and thresholds.
+///
+/// # Returns
+///
+/// One entry per run long enough to measure that oscillates within the rate range, in
+/// time order. Runs with a straight or aperiodic contour are left out.
+pub fn vibrato(track: &[f64], time_step: f64, config: &VibratoConfig) -> Vec<Vibrato> {
+    let times: Vec<f64> = (0..track.len()).map(|i| i as f64 * time_step).collect();
+    measure_runs(&times, track, time_step, config)
+}
+
+/// Measures the vibrato of analyzed frames, from their `pitch` (`-1` where unvoiced)
+/// and `time`.
+///
+/// # Arguments
+///
+/// * `frames` - Frames in time order at a constant hop.
+/// * `config` - Rate range and thresholds.
+pub fn vibrato_frames(frames: &[TrackFrame], config: &VibratoConfig) -> Vec<Vibrato> {
+    let [first, second, ..] = frames else {
+        return Vec::new();
+    };
+    let times: Vec<f64> = frames.iter().map(|f| f.time).collect();
+    let track: Vec<f64> = frames.iter().map(|f| f.pitch).collect();
+    measure_runs(&times, &track, second.time - first.time, config)
+}
+
+// ------------------
+// Helpers
+// ------------------
+
+/// Splits the contour into voiced runs and measures each.
+fn measure_runs(
+    times: &[f64],
+    track: &[f64],
+    time_step: f64,
+    config: &VibratoConfig,
+) -> Vec<Vibrato> {
+    if time_step <= 0.0 {
+        return Vec::new();
+    }
+    let min_len = (config.min_duration / time_step).ceil().max(3.0) as usize;
+    let mut out = Vec::new();
+    let voiced = |f0: f64| f0 > 0.0;
+    let mut i = 0;
+    while i < track.len() {
+        if !voiced(track[i]) {
+            i += 1;
+            continue;
+        }
+        let start = i;
+        while i < track.len() && voiced(track[i]) {
+            i += 1;
+        }
+        if i - start < min_len {
+            continue;
+        }
+        if let Some((rate, extent, regularity)) = measure(&track[start..i], time_step, config) {
+            out.push(Vibrato {
+                start: times[start],
+                end: times[i - 1] + time_step,
+                rate,
+                extent,
+                regularity,
+            });
+        }
+    }
+    out
+}
+
+/// Rate, extent and regularity of one voiced run, or `None` if it is too flat or has no
+/// autocorrelation peak in the rate range.
+fn measure(run: &[f64], time_step: f64, config: &VibratoConfig) -> Option<(f64, f64, f64)> {
+    // Cents around the mean, minus the least-squares line.
+    let log: Vec<f64> = run.iter().map(|f0| 1200.0 * f0.log2()).collect();
+    let n = log.len() as f64;
+    let mean_t = (n - 1.0) / 2.0;
+    let mean = log.iter().sum::<f64>() / n;
+    let (mut sxy, mut sxx) = (0.0, 0.0);
+    for (t, &c) in log.iter().enumerate() {
+        sxy += (t as f64 - mean_t) * (c - mean);
+        sxx += (t as f64 - mean_t).powi(2);
+    }
+    let slope = sxy / sxx;
+    let cents: Vec<f64> = log
+        .iter()
+        .enumerate()
+        .map(|(t, &c)| c - mean - slope * (t as f64 - mean_t))
+        .collect();
+    let rms = (cents.iter().map(|c| c * c).sum::<f64>() / n).sqrt();
+    let extent = std::f64::consts::SQRT_2 * rms;
+    if extent < config.min_extent {
+        return None;
+    }
+
+    let min_lag = ((1.0 / (config.max_rate * time_step)).floor() as usize).max(1);
+    let max_lag = ((1.0 / (config.min_rate * time_step)).ceil() as usize).min(cents.len() - 2);
+    if min_lag + 1 > max_lag {
+        return None;
+    }
+    let r: Vec<f64> = (min_lag - 1..=max_lag + 1)
+        .map(|lag| autocorrelation(&cents, lag))
+        .collect();
+    // The highest local maximum strictly inside the range.
+    let (k, &peak) = r
+        .iter()
+        .enumerate()
+        .skip(1)
+        .take(r.len() - 2)
+        .filter(|&(k, &v)| v > 0.0 && v >= r[k - 1] && v >= r[k + 1])
+        .max_by(|a, b| a.1.total_cmp(b.1))?;
+    let (left, right) = (r[k - 1], r[k + 1]);
+    let denom = left - 2.0 * peak + right;
+    let shift = if denom.abs() > f64::EPSILON {
+        (0.5 * (left - right) / denom).clamp(-0.5, 0.5)
+    } else {
+        0.0
+    };
+    let period = (min_lag - 1 + k) as f64 + shift;
+    Some((1.0 / (period * time_step), extent, peak.min(1.0)))
+}
+
+/// Correlation coefficient between `x` and `x` delayed by `lag`.
+fn autocorrelation(x: &[f64], lag: usize) -> f64 {
+    let (a, b) = (&x[..x.len() - lag], &x[lag..]);
+    let dot: f64 = a.iter().zip(b).map(|(p, q)| p * q).sum();
+    let energy = a.iter().map(|p| p * p).sum::<f64>() * b.iter().map(|q| q * q).sum::<f64>();
+    if energy > 0.0 {
+        dot / energy.sqrt()
+    } else {
+        0.0
+    }
+}
+
+// ------------------
+// Tests
+// ------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::f64::consts::PI;
+
+    #[test]
+    fn measures_a_sung_vibrato_and_skips_a_straight_tone() {
+        // 2 s of 5.5 Hz, ±50 cent vibrato on a slowly rising A3, a 0.2 s break, then
+        // 1 s of straight tone.
+        let dt = 0.01;
+        let mut track: Vec<f64> = (0..200)
+            .map(|i| {
+                let t = i as f64 * dt;
+                let cents = 20.0 * t + 50.0 * (2.0 * PI * 5.5 * t).sin();
+                220.0 * 2f64.powf(cents / 1200.0)
+            })
+            .collect();
+        track.extend([-1.0; 20]);
+        track.extend([330.0; 100]);
+
+        let found = vibrato(&track, dt, &VibratoConfig::default());
+        assert_eq!(found.len(), 1, "{:?}", found);
+        let v = found[0];
+        assert_eq!((v.start, v.end), (0.0, 2.0));
+        assert!((v.rate - 5.5).abs() < 0.1, "{}", v.rate);
+        assert!((v.extent - 50.0).abs() < 3.0, "{}", v.extent);
+        assert!(v.regularity > 0.9, "{}", v.regularity);
+    }
+}
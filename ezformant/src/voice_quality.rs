@@ -0,0 +1,322 @@
+//! Period-to-period perturbation measures (jitter and shimmer), following Praat's
+//! definitions.
+//!
+//! Glottal periods are found by tracking f0 with [`pitch_ac`] and stepping from one
+//! waveform maximum to the next, one period at a time. Consecutive periods form a
+//! run; runs are broken at unvoiced frames and wherever neighbouring periods differ
+//! by more than [`MAX_PERIOD_FACTOR`], and no measure looks across a break.
+
+use crate::pitch::{
+    ac::{pitch_ac, AcPitchConfig},
+    parabolic_interpolation,
+};
+
+/// Largest ratio between consecutive periods that still counts as the same run.
+pub const MAX_PERIOD_FACTOR: f64 = 1.3;
+
+/// A single glottal cycle.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct GlottalPeriod {
+    /// Start time (the waveform maximum opening the cycle), in seconds.
+    pub start: f64,
+    /// Length of the cycle, in seconds.
+    pub duration: f64,
+    /// Peak-to-peak amplitude within the cycle.
+    pub amplitude: f64,
+}
+
+impl GlottalPeriod {
+    fn end(&self) -> f64 {
+        self.start + self.duration
+    }
+}
+
+/// Period perturbation measures. All but `local_absolute` are fractions (multiply by
+/// 100 for Praat's percentages).
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Jitter {
+    /// Mean absolute difference of consecutive periods over the mean period.
+    pub local: f64,
+    /// Mean absolute difference of consecutive periods, in seconds.
+    pub local_absolute: f64,
+    /// Relative average perturbation (three-period smoothing).
+    pub rap: f64,
+    /// Five-point period perturbation quotient.
+    pub ppq5: f64,
+}
+
+/// Amplitude perturbation measures. All but `local_db` are fractions.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Shimmer {
+    /// Mean absolute difference of consecutive amplitudes over the mean amplitude.
+    pub local: f64,
+    /// Mean absolute base-10 log ratio of consecutive amplitudes, in dB.
+    pub local_db: f64,
+    /// Three-point amplitude perturbation quotient.
+    pub apq3: f64,
+    /// Five-point amplitude perturbation quotient.
+    pub apq5: f64,
+}
+
+/// Finds the glottal periods of the voiced parts of a signal.
+///
+/// # Arguments
+///
+/// * `signal`      - The input signal.
+/// * `sample_rate` - The sampling rate of the signal.
+/// * `config`      - The pitch tracker parameters; `pitch_floor` and `pitch_ceiling`
+///   also bound the accepted period lengths.
+///
+/// # Returns
+///
+/// The periods in time order. A period whose `start` differs from the previous
+/// period's end begins a new run.
+#[tracing::instrument(level = "debug", skip_all, fields(len = signal.len(), sample_rate))]
+pub fn glottal_periods(
+    signal: &[f64],
+    sample_rate: f64,
+    config: &AcPitchConfig,
+) -> Vec<GlottalPeriod> {
+    let track = pitch_ac(signal, sample_rate, config);
+    if track.is_empty() {
+        return Vec::new();
+    }
+    let hop = ((config.effective_time_step() * sample_rate).round() as usize).max(1);
+    let centre = config.window_length(sample_rate) / 2;
+    let f0_at = |n: usize| -> Option<f64> {
+        let frame = (n.saturating_sub(centre) / hop).min(track.len() - 1);
+        track[frame].voiced.then_some(track[frame].f0)
+    };
+    let min_period = sample_rate / config.pitch_ceiling;
+    let max_period = sample_rate / config.pitch_floor;
+
+    let mut periods = Vec::new();
+    // Position (in samples, fractional) and index of the current cycle's maximum.
+    let mut anchor: Option<(f64, usize)> = None;
+    let mut n = 0;
+    while n < signal.len() {
+        let Some(f0) = f0_at(n) else {
+            anchor = None;
+            n += hop;
+            continue;
+        };
+        let period = sample_rate / f0;
+
+        let (lo, hi) = match anchor {
+            // Start of a run: the largest maximum within one period.
+            None => (n, n + period.ceil() as usize),
+            Some((_, index)) => (
+                index + (0.8 * period).floor() as usize,
+                index + (1.25 * period).ceil() as usize,
+            ),
+        };
+        if hi >= signal.len() {
+            break;
+        }
+        let peak = (lo..=hi)
+            .max_by(|&a, &b| signal[a].total_cmp(&signal[b]))
+            .unwrap();
+        let position = parabolic_interpolation(signal, peak);
+
+        if let Some((previous, index)) = anchor {
+            let length = position - previous;
+            if (min_period..=max_period).contains(&length) {
+                let (low, high) = signal[index..=peak]
+                    .iter()
+                    .fold((f64::INFINITY, f64::NEG_INFINITY), |(lo, hi), &x| {
+                        (lo.min(x), hi.max(x))
+                    });
+                periods.push(GlottalPeriod {
+                    start: previous / sample_rate,
+                    duration: length / sample_rate,
+                    amplitude: high - low,
+                });
+            }
+        }
+        anchor = Some((position, peak));
+        n = peak + 1;
+    }
+    periods
+}
+
+/// Computes jitter from a sequence of glottal periods.
+///
+/// # Arguments
+///
+/// * `periods` - The periods, e.g. from [`glottal_periods`].
+///
+/// # Returns
+///
+/// The jitter measures, or `None` if no run has the five periods PPQ5 needs.
+pub fn jitter(periods: &[GlottalPeriod]) -> Option<Jitter> {
+    let runs = runs(periods, |p| p.duration);
+    let mean = mean_of(&runs)?;
+    let local_absolute = perturbation(&runs, 0)?;
+    Some(Jitter {
+        local: local_absolute / mean,
+        local_absolute,
+        rap: perturbation(&runs, 1)? / mean,
+        ppq5: perturbation(&runs, 2)? / mean,
+    })
+}
+
+/// Computes shimmer from a sequence of glottal periods.
+///
+/// # Arguments
+///
+/// * `periods` - The periods, e.g. from [`glottal_periods`].
+///
+/// # Returns
+///
+/// The shimmer measures, or `None` if no run has the five periods APQ5 needs.
+pub fn shimmer(periods: &[GlottalPeriod]) -> Option<Shimmer> {
+    let runs = runs(periods, |p| p.amplitude);
+    let mean = mean_of(&runs)?;
+
+    let ratios: Vec<f64> = runs
+        .iter()
+        .flat_map(|run| run.windows(2))
+        .filter(|pair| pair[0] > 0.0 && pair[1] > 0.0)
+        .map(|pair| (20.0 * (pair[1] / pair[0]).log10()).abs())
+        .collect();
+    if ratios.is_empty() {
+        return None;
+    }
+
+    Some(Shimmer {
+        local: perturbation(&runs, 0)? / mean,
+        local_db: ratios.iter().sum::<f64>() / ratios.len() as f64,
+        apq3: perturbation(&runs, 1)? / mean,
+        apq5: perturbation(&runs, 2)? / mean,
+    })
+}
+
+/// Splits `periods` into runs of contiguous, similar periods and extracts `value`.
+fn runs(periods: &[GlottalPeriod], value: impl Fn(&GlottalPeriod) -> f64) -> Vec<Vec<f64>> {
+    let mut runs: Vec<Vec<f64>> = Vec::new();
+    for (i, period) in periods.iter().enumerate() {
+        let continues = i > 0 && {
+            let previous = &periods[i - 1];
+            let ratio = period.duration / previous.duration;
+            (period.start - previous.end()).abs() < 1e-6
+                && (1.0 / MAX_PERIOD_FACTOR..=MAX_PERIOD_FACTOR).contains(&ratio)
+        };
+        match runs.last_mut() {
+            Some(run) if continues => run.push(value(period)),
+            _ => runs.push(vec![value(period)]),
+        }
+    }
+    runs
+}
+
+fn mean_of(runs: &[Vec<f64>]) -> Option<f64> {
+    let count: usize = runs.iter().map(Vec::len).sum();
+    (count > 0).then(|| runs.iter().flatten().sum::<f64>() / count as f64)
+}
+
+/// Mean absolute deviation of each value from the average of its `2k + 1`
+/// neighbourhood; `k == 0` compares consecutive values instead.
+fn perturbation(runs: &[Vec<f64>], k: usize) -> Option<f64> {
+    let deviations: Vec<f64> = if k == 0 {
+        runs.iter()
+            .flat_map(|run| run.windows(2))
+            .map(|pair| (pair[1] - pair[0]).abs())
+            .collect()
+    } else {
+        runs.iter()
+            .flat_map(|run| run.windows(2 * k + 1))
+            .map(|window| {
+                let average = window.iter().sum::<f64>() / window.len() as f64;
+                (window[k] - average).abs()
+            })
+            .collect()
+    };
+    if deviations.is_empty() {
+        None
+    } else {
+        Some(deviations.iter().sum::<f64>() / deviations.len() as f64)
+    }
+}
+
+// ------------------
+// Tests
+// ------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::f64::consts::PI;
+
+    fn contiguous(durations: &[f64], amplitudes: &[f64]) -> Vec<GlottalPeriod> {
+        let mut start = 0.0;
+        durations
+            .iter()
+            .zip(amplitudes)
+            .map(|(&duration, &amplitude)| {
+                let period = GlottalPeriod {
+                    start,
+                    duration,
+                    amplitude,
+                };
+                start += duration;
+                period
+            })
+            .collect()
+    }
+
+    #[test]
+    fn alternating_periods_give_known_perturbation() {
+        // Periods alternate 9.9 ms / 10.1 ms, amplitudes 0.9 / 1.1.
+        let durations: Vec<f64> = (0..20)
+            .map(|i| if i % 2 == 0 { 0.0099 } else { 0.0101 })
+            .collect();
+        let amplitudes: Vec<f64> = (0..20)
+            .map(|i| if i % 2 == 0 { 0.9 } else { 1.1 })
+            .collect();
+        let periods = contiguous(&durations, &amplitudes);
+
+        let j = jitter(&periods).unwrap();
+        assert!((j.local - 0.02).abs() < 1e-9);
+        assert!((j.local_absolute - 0.0002).abs() < 1e-12);
+        // |T_i - mean of three| = 2 * 0.0002 / 3 for every interior period.
+        assert!((j.rap - 0.04 / 3.0).abs() < 1e-9);
+
+        let s = shimmer(&periods).unwrap();
+        assert!((s.local - 0.2).abs() < 1e-9);
+        assert!((s.local_db - 20.0 * (1.1f64 / 0.9).log10()).abs() < 1e-9);
+
+        // A gap splits the runs; too few periods per run for PPQ5.
+        let mut broken = contiguous(&durations[..4], &amplitudes[..4]);
+        broken.extend(
+            contiguous(&durations[..4], &amplitudes[..4])
+                .into_iter()
+                .map(|p| GlottalPeriod {
+                    start: p.start + 1.0,
+                    ..p
+                }),
+        );
+        assert_eq!(jitter(&broken), None);
+    }
+
+    #[test]
+    fn steady_tone_has_negligible_perturbation() {
+        let fs = 16000.0;
+        let signal: Vec<f64> = (0..16000)
+            .map(|n| {
+                let t = n as f64 / fs;
+                (2.0 * PI * 125.0 * t).sin() + 0.3 * (2.0 * PI * 250.0 * t).sin()
+            })
+            .collect();
+
+        let periods = glottal_periods(&signal, fs, &AcPitchConfig::default());
+        assert!(periods.len() > 100);
+        for period in &periods {
+            assert!((period.duration - 0.008).abs() < 1e-5);
+        }
+        assert!(jitter(&periods).unwrap().local < 1e-3);
+        assert!(shimmer(&periods).unwrap().local < 1e-3);
+    }
+}
@@ -0,0 +1,185 @@
+//! Warped linear prediction (Strube 1980, Härmä et al. 2000).
+//!
+//! The unit delays of the predictor are replaced by first-order allpass sections
+//! `D(z) = (z^-1 - λ) / (1 - λ z^-1)`, which stretch the low frequencies over more of
+//! the unit circle. With `λ` near the Bark warping for the sampling rate, a low-order
+//! model spends its poles where the formants are, so 44.1 kHz audio can be analyzed
+//! without downsampling.
+//!
+//! Roots of the warped polynomial are mapped back with `z = (z̃ + λ) / (1 + λ z̃)`
+//! before their frequencies and bandwidths are read off. The mapping keeps the unit
+//! disc in place, so stable warped models stay stable.
+
+use crate::lpc::{self, Peak};
+use crate::roots::RootSolver;
+use num_complex::Complex;
+
+/// Warping coefficient that best approximates the Bark scale at the given sampling
+/// rate (Smith & Abel 1999), e.g. about 0.756 at 44.1 kHz and 0.576 at 16 kHz.
+///
+/// # Arguments
+///
+/// * `sample_rate` - The sampling rate of the signal.
+///
+/// # Returns
+///
+/// The allpass coefficient `λ`.
+pub fn bark_warping_coefficient(sample_rate: f64) -> f64 {
+    let khz = sample_rate / 1000.0;
+    1.0674 * (2.0 / std::f64::consts::PI * (0.06583 * khz).atan()).sqrt() - 0.1916
+}
+
+/// Computes the warped autocorrelation `r[k] = sum_n x[n] x_k[n]`, where `x_k` is the
+/// signal after `k` allpass sections.
+///
+/// # Arguments
+///
+/// * `signal`  - The (windowed) frame.
+/// * `maxlag`  - The LPC order.
+/// * `warping` - The allpass coefficient `λ`, in `(-1, 1)`; `0.0` gives the ordinary
+///   autocorrelation.
+///
+/// # Returns
+///
+/// `maxlag + 1` warped autocorrelation values, ready for [`lpc::levinson`].
+pub fn warped_autocorrelate(signal: &[f64], maxlag: usize, warping: f64) -> Vec<f64> {
+    let mut r = Vec::with_capacity(maxlag + 1);
+    let mut current = signal.to_vec();
+    r.push(signal.iter().map(|x| x * x).sum());
+    for _ in 0..maxlag {
+        // y[n] = -λ x[n] + x[n-1] + λ y[n-1]
+        let (mut x1, mut y1) = (0.0, 0.0);
+        for v in current.iter_mut() {
+            let y = -warping * *v + x1 + warping * y1;
+            x1 = *v;
+            y1 = y;
+            *v = y;
+        }
+        r.push(signal.iter().zip(&current).map(|(x, y)| x * y).sum());
+    }
+    r
+}
+
+/// Detects peaks of a warped LPC model and reports them on the linear frequency axis.
+///
+/// # Arguments
+///
+/// * `lpc_coeffs`  - The warped LPC coefficients `[1.0, a1, ..., ap]`.
+/// * `sample_rate` - The sampling rate of the original signal.
+/// * `warping`     - The allpass coefficient used in the analysis.
+/// * `solver`      - The polynomial root finding strategy.
+///
+/// # Returns
+///
+/// One [`Peak`] per retained root, as in [`lpc::peak_detection_with_metadata`].
+pub fn warped_peak_detection(
+    lpc_coeffs: &[f64],
+    sample_rate: f64,
+    warping: f64,
+    solver: RootSolver,
+) -> Vec<Peak> {
+    let roots = solver
+        .roots(lpc_coeffs)
+        .into_iter()
+        .map(|root| (root + warping) / (root * warping + 1.0))
+        .collect();
+    lpc::peaks_from_roots(roots, sample_rate)
+}
+
+/// Performs formant detection from warped LPC coefficients.
+///
+/// # Arguments
+///
+/// * `lpc_coeffs`  - The warped LPC coefficients.
+/// * `sample_rate` - The sampling rate of the original signal.
+/// * `warping`     - The allpass coefficient used in the analysis.
+/// * `solver`      - The polynomial root finding strategy.
+///
+/// # Returns
+///
+/// A vector of formant frequencies in Hz, sorted ascending.
+#[tracing::instrument(level = "debug", skip_all, fields(order = lpc_coeffs.len().saturating_sub(1), sample_rate, warping))]
+pub fn warped_formant_detection(
+    lpc_coeffs: &[f64],
+    sample_rate: f64,
+    warping: f64,
+    solver: RootSolver,
+) -> Vec<f64> {
+    let mut formants: Vec<f64> = warped_peak_detection(lpc_coeffs, sample_rate, warping, solver)
+        .into_iter()
+        .filter(|peak| peak.is_formant)
+        .map(|peak| peak.frequency)
+        .collect();
+
+    formants.sort_by(f64::total_cmp);
+    tracing::debug!(?formants, "formants");
+    formants
+}
+
+/// Computes the frequency response of a warped LPC filter on the linear frequency
+/// axis, like [`lpc::compute_frequency_response`].
+///
+/// # Arguments
+///
+/// * `lpc_coeffs`  - The warped LPC coefficients.
+/// * `sample_rate` - The sampling rate of the original signal.
+/// * `warping`     - The allpass coefficient used in the analysis.
+/// * `num_points`  - The number of frequency points in the response.
+///
+/// # Returns
+///
+/// A vector of `(frequency, magnitude)` pairs.
+pub fn warped_frequency_response(
+    lpc_coeffs: &[f64],
+    sample_rate: f64,
+    warping: f64,
+    num_points: usize,
+) -> Vec<(f64, f64)> {
+    (0..num_points)
+        .map(|i| {
+            let freq = i as f64 / num_points as f64 * sample_rate / 2.0;
+            let omega = 2.0 * std::f64::consts::PI * freq / sample_rate;
+            let z = Complex::new(omega.cos(), -omega.sin()); // e^{-j omega}
+            let delay = (z - warping) / (-z * warping + 1.0);
+            (
+                freq,
+                1.0 / lpc::evaluate_polynomial(lpc_coeffs, delay).norm(),
+            )
+        })
+        .collect()
+}
+
+// ------------------
+// Tests
+// ------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::preprocess_signal;
+    use crate::test_signals::vowel;
+
+    #[test]
+    fn warped_lpc_finds_formants_at_full_rate() {
+        let fs = 44100.0;
+        let mut frame = vowel(fs, 120.0, 2048);
+        preprocess_signal(&mut frame, 0.97);
+        let warping = bark_warping_coefficient(fs);
+        assert!((warping - 0.756).abs() < 1e-3);
+
+        let order = 10;
+        let r = warped_autocorrelate(&frame, order, warping);
+        let (a, _) = lpc::levinson(order, &r);
+        let formants = warped_formant_detection(&a, fs, warping, RootSolver::Eigen);
+        for expected in [700.0, 1200.0, 2600.0] {
+            let found = formants.iter().any(|f| (f - expected).abs() < 60.0);
+            assert!(found, "{} not in {:?}", expected, formants);
+        }
+
+        // Zero warping is ordinary LPC.
+        let plain = lpc::autocorrelate(&frame, order);
+        for (w, p) in warped_autocorrelate(&frame, order, 0.0).iter().zip(&plain) {
+            assert!((w - p).abs() <= 1e-9 * plain[0]);
+        }
+    }
+}
@@ -63,6 +63,7 @@ fn criterion_bench(c: &mut Criterion) {
     }));
 }
 
+#[allow(dead_code)]
 fn pre_emphasis(signal: &mut [f64], alpha: f64) {
     for i in (1..signal.len()).rev() {
         signal[i] -= alpha * signal[i - 1];
@@ -112,6 +113,7 @@ fn autocorrelate(signal: &[f64], maxlag: usize) -> Vec<f64> {
 /// A tuple containing:
 /// - A vector of filter coefficients (`a`).
 /// - The final prediction error (`E`).
+#[allow(dead_code)]
 fn levinson(order: usize, r: &[f64]) -> (Vec<f64>, f64){
     let p = order;
 
@@ -0,0 +1,680 @@
+// Golden-file snapshot of the shapes the wasm API hands to JavaScript: array lengths,
+// object field names and value types, one entry per exported function or method.
+// Numbers themselves are not recorded, so accuracy changes don't fail the test while
+// renamed fields, changed flat layouts and changed lengths do.
+//
+// Functions returning `JsValue` are covered through the frame they serialize, which is
+// exactly what `frames::to_js` hands to `serde-wasm-bindgen`. Variable-length outputs
+// such as formant lists are recorded as `number[]`.
+//
+// After an intentional API change, regenerate the golden file with
+//   UPDATE_SNAPSHOTS=1 cargo test -p webapp api_shapes
+// and review the diff of `snapshots/api_shapes.json`.
+
+use super::*;
+use serde::Serialize;
+use serde_json::{json, Map, Value};
+use std::f64::consts::PI;
+
+const SNAPSHOT: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/snapshots/api_shapes.json");
+
+#[test]
+fn api_shapes() {
+    let actual = serde_json::to_string_pretty(&Value::Object(api_shapes_map())).unwrap() + "\n";
+    if std::env::var_os("UPDATE_SNAPSHOTS").is_some() {
+        std::fs::write(SNAPSHOT, &actual).unwrap();
+        return;
+    }
+    let expected = std::fs::read_to_string(SNAPSHOT).unwrap_or_default();
+    assert!(
+        expected == actual,
+        "wasm API shapes changed; if intentional, rerun with UPDATE_SNAPSHOTS=1.\n\
+         expected:\n{expected}\nactual:\n{actual}"
+    );
+}
+
+// Covers the deprecated flat functions too, so their shapes stay pinned until removal.
+#[allow(deprecated)]
+fn api_shapes_map() -> Map<String, Value> {
+    let fs = 16000.0;
+    let frame = vowel(fs, 0.05);
+    let long = vowel(fs, 1.5);
+    // Two phonations separated by a 150 ms break.
+    let half = vowel(fs, 0.75);
+    let padded: Vec<f64> = [
+        vec![0.0; 8000],
+        half.clone(),
+        vec![0.0; 2400],
+        half,
+        vec![0.0; 8000],
+    ]
+    .concat();
+    let syllables = bursts(fs, 2.0);
+    let frame_f32: Vec<f32> = frame[..512].iter().map(|&x| x as f32).collect();
+    // AnalyserNode-style dB spectrum.
+    let spectrum: Vec<f64> = wasm_fourier(frame_f32.clone())
+        .iter()
+        .map(|&x| 20.0 * (x as f64).max(1e-10).log10())
+        .collect();
+
+    let mut shapes = Map::new();
+    let mut record = |name: &str, shape: Value| {
+        shapes.insert(name.to_string(), shape);
+    };
+
+    // Spectra
+    record("wasm_fourier", fixed(&wasm_fourier(frame_f32.clone())));
+    record(
+        "wasm_fourier_with_options(two_sided, complex)",
+        fixed(&wasm_fourier_with_options(
+            frame_f32.clone(),
+            true,
+            true,
+            1,
+            0.0,
+        )),
+    );
+    record(
+        "wasm_fourier_windowed(hann, fft_size)",
+        fixed(&wasm_fourier_windowed(frame_f32.clone(), "hann", true, 1024, true, 0.0).unwrap()),
+    );
+    record(
+        "wasm_fourier_complex(polar)",
+        fixed(&wasm_fourier_complex(frame_f32.clone(), "hann", 0, true).unwrap()),
+    );
+    record(
+        "welch_psd",
+        fixed(&welch_psd(&long, fs, 256, 128, "hann").unwrap()),
+    );
+
+    // LPC
+    record(
+        "lpc_filter_freq_response_with_downsampling",
+        fixed(&lpc_filter_freq_response_with_downsampling(
+            frame.clone(),
+            12,
+            fs,
+            2,
+            64,
+            None,
+        )),
+    );
+    record(
+        "lpc_filter_freq_response",
+        fixed(&lpc_filter_freq_response(frame.clone(), 12, fs, 64, None)),
+    );
+    record(
+        "lpc_filter_freq_response_with_peaks",
+        fixed(&lpc_filter_freq_response_with_peaks(
+            frame.clone(),
+            12,
+            fs,
+            64,
+            None,
+        )),
+    );
+    record(
+        "formant_frame",
+        shape(&FormantFrame::analyze(
+            frame.clone(),
+            12,
+            fs,
+            4,
+            spectral_floor(0.0),
+            DEFAULT_PRE_EMPHASIS,
+        )),
+    );
+    record(
+        "formant_frame_with_downsampling",
+        shape(&FormantFrame::analyze_downsampled(
+            &frame,
+            12,
+            fs,
+            2,
+            4,
+            spectral_floor(0.0),
+            DEFAULT_PRE_EMPHASIS,
+        )),
+    );
+    record(
+        "formant_frame_f32",
+        shape(&FormantFrame::analyze_downsampled(
+            &widen(&frame_f32),
+            12,
+            fs,
+            2,
+            4,
+            spectral_floor(0.0),
+            DEFAULT_PRE_EMPHASIS,
+        )),
+    );
+    record(
+        "formant_frame_gain_db",
+        shape(&FormantFrame::analyze_gain_db(
+            &frame,
+            12,
+            fs,
+            2,
+            4,
+            spectral_floor(60.0),
+            &quality::QualitySettings::default(),
+        )),
+    );
+    record(
+        "formant_frame_on_grid",
+        shape(&FormantFrame::analyze_on_grid(
+            downsample(&frame, 2),
+            12,
+            fs / 2.0,
+            &frequency_grid(50.0, 4000.0, 48, "semitones").unwrap(),
+            spectral_floor(0.0),
+            DEFAULT_PRE_EMPHASIS,
+        )),
+    );
+    record(
+        "lpc_diagnostics",
+        shape(&LevinsonFrame::analyze(
+            frame.clone(),
+            4,
+            DEFAULT_PRE_EMPHASIS,
+        )),
+    );
+    record(
+        "lpc_cepstrum",
+        fixed(&lpc_cepstrum(frame.clone(), 12, 16, None)),
+    );
+    record(
+        "lpc_residual",
+        fixed(&lpc_residual(frame.clone(), 12, None)),
+    );
+    let mut custom = frame.clone();
+    subtract_mean(&mut custom);
+    record("subtract_mean", fixed(&custom));
+    apply_window(&mut custom, "gaussian").unwrap();
+    record("apply_window", fixed(&custom));
+    pre_emphasis(&mut custom, DEFAULT_PRE_EMPHASIS);
+    record("pre_emphasis", fixed(&custom));
+    let r = autocorrelate(&custom, 12);
+    record("autocorrelate", fixed(&r));
+    let levinson = lpc::try_levinson_full(12, &r).unwrap();
+    record(
+        "compute_frequency_response",
+        fixed(&compute_frequency_response(&levinson.coefficients, fs, 512)),
+    );
+    record(
+        "formants_from_coefficients",
+        variable(&formants_from_coefficients(&levinson.coefficients, fs).unwrap()),
+    );
+    record(
+        "formant_frame_from_coefficients",
+        shape(&FormantFrame::from_coefficients(
+            &levinson.coefficients,
+            fs,
+            &units::frequency_axis(64, fs, units::FrequencyUnit::Hertz),
+            spectrum::SpectralFloor::default(),
+        )),
+    );
+    record(
+        "poles_from_coefficients",
+        shape(&PoleFrame::from_coefficients(&levinson.coefficients, fs)),
+    );
+    record(
+        "lpc_poles",
+        shape(&PoleFrame::analyze(
+            frame.clone(),
+            12,
+            fs,
+            DEFAULT_PRE_EMPHASIS,
+        )),
+    );
+    record(
+        "peak_frames_from_coefficients",
+        shape(&PeakFrame::from_coefficients(&levinson.coefficients, fs)),
+    );
+    record("levinson", shape(&LevinsonFrame::from(levinson)));
+    record(
+        "resynthesize",
+        fixed(&resynthesize(&frame, fs, 12, "impulses", 120.0, None)),
+    );
+    record(
+        "peak_frames",
+        shape(&PeakFrame::analyze(
+            frame.clone(),
+            12,
+            fs,
+            DEFAULT_PRE_EMPHASIS,
+        )),
+    );
+    let peaks = peak_detection_with_metadata(frame.clone(), 12, fs, None);
+    assert_eq!(peaks.len() % 4, 0);
+    record("peak_detection_with_metadata", json!("number[4n]"));
+
+    // Formants
+    let formant_lists = [
+        (
+            "formant_detection",
+            formant_detection(frame.clone(), 12, fs, None),
+        ),
+        (
+            "formant_detection_with_downsampling",
+            formant_detection_with_downsampling(frame.clone(), 12, fs, 2, None),
+        ),
+        (
+            "formant_detection_f32",
+            formant_detection_f32(&frame_f32, 12, fs, 2, None),
+        ),
+        (
+            "formant_detection_with_quality",
+            formant_detection_with_quality(frame.clone(), 12, fs, 2, "fast", None).unwrap(),
+        ),
+        (
+            "formant_detection_f32_with_quality",
+            formant_detection_f32_with_quality(&frame_f32, 12, fs, 1, "fast", None).unwrap(),
+        ),
+        (
+            "formant_detection_with_lag_window",
+            formant_detection_with_lag_window(frame.clone(), 12, fs, 2, 1e-4, Some(60.0), None),
+        ),
+        (
+            "formant_detection_warped",
+            formant_detection_warped(frame.clone(), 12, fs, None, None),
+        ),
+        (
+            "formant_detection_with_window",
+            formant_detection_with_window(frame.clone(), 12, fs, 2, "gaussian", None).unwrap(),
+        ),
+        (
+            "formant_detection_with_max_formant",
+            formant_detection_with_max_formant(frame.clone(), 12, fs, 5500.0, None).unwrap(),
+        ),
+        (
+            "formant_detection_from_spectrum",
+            formant_detection_from_spectrum(spectrum.clone(), "db", 12, fs, Some(5500.0), None)
+                .unwrap(),
+        ),
+        (
+            "formant_detection_with_units",
+            formant_detection_with_units(frame.clone(), 12, fs, 2, "bark", None).unwrap(),
+        ),
+        (
+            "try_formant_detection",
+            try_formant_detection(&frame, 12, fs, 2, "balanced", None).unwrap(),
+        ),
+    ];
+    for (name, formants) in formant_lists {
+        record(name, variable(&formants));
+    }
+
+    // Pitch
+    let scalars = [
+        ("pitch_detection", pitch_detection(&frame, fs)),
+        (
+            "pitch_detection_with_threshold",
+            pitch_detection_with_threshold(&frame, fs, 0.1),
+        ),
+        (
+            "pitch_detection_with_quality",
+            pitch_detection_with_quality(&frame, fs, "fast").unwrap(),
+        ),
+        (
+            "pitch_detection_with_units",
+            pitch_detection_with_units(&frame, fs, "st").unwrap(),
+        ),
+        (
+            "pitch_detection_f32",
+            pitch_detection_f32(&frame_f32, fs, 0.1),
+        ),
+        (
+            "pitch_detection_cepstrum",
+            pitch_detection_cepstrum(&frame, fs),
+        ),
+        (
+            "estimate_channel_lag",
+            estimate_channel_lag(&frame, &frame, 8),
+        ),
+        (
+            "pre_emphasis_from_frequency",
+            pre_emphasis_from_frequency(50.0, fs),
+        ),
+        ("formant_dispersion", formant_dispersion(&[500.0, 1500.0])),
+        ("formant_spacing", formant_spacing(&[500.0, 1500.0])),
+        ("hz_to_bark", hz_to_bark(1000.0)),
+        ("bark_to_hz", bark_to_hz(8.0)),
+        ("hz_to_erb", hz_to_erb(1000.0)),
+        ("erb_to_hz", erb_to_hz(15.0)),
+        ("hz_to_mel", hz_to_mel(1000.0)),
+        ("mel_to_hz", mel_to_hz(1000.0)),
+        ("hz_to_semitones", hz_to_semitones(200.0)),
+        ("semitones_to_hz", semitones_to_hz(12.0)),
+        ("hz_to_midi", hz_to_midi(440.0)),
+        ("midi_to_hz", midi_to_hz(60.0)),
+        ("hz_to_cents", hz_to_cents(440.0, 220.0)),
+        ("cents_to_hz", cents_to_hz(100.0, 220.0)),
+    ];
+    for (name, value) in scalars {
+        record(name, shape(&value));
+    }
+    record(
+        "pitch_detection_with_voicing",
+        fixed(&pitch_detection_with_voicing(&frame, fs, 0.1)),
+    );
+    record("pitch_frame", shape(&PitchFrame::analyze(&frame, fs, 0.1)));
+    record(
+        "try_pitch_frame",
+        shape(&PitchFrame::from(
+            analyzer::Analyzer::new(0, fs)
+                .try_pitch_result(&frame)
+                .unwrap(),
+        )),
+    );
+    record(
+        "frequency_axis",
+        fixed(&frequency_axis(16, fs, "mel").unwrap()),
+    );
+    record(
+        "frequency_grid",
+        fixed(&frequency_grid(50.0, 4000.0, 16, "semitones").unwrap()),
+    );
+    let mut converted = vec![-1.0, 100.0, 200.0];
+    convert_frequencies(&mut converted, "st").unwrap();
+    record("convert_frequencies", fixed(&converted));
+
+    // Voice quality
+    record("hnr", fixed(&hnr(&long, fs)));
+    record("jitter_shimmer", fixed(&jitter_shimmer(&long, fs)));
+    record(
+        "voice_quality_frame",
+        shape(&VoiceQualityFrame::analyze(&long, fs)),
+    );
+    record(
+        "segment_recording",
+        shape(&segmentation_frame(&padded, fs, 0.04, 0.01, None)),
+    );
+    let contour: Vec<f64> = (0..100)
+        .map(|i| 220.0 * (1.0 + 0.03 * (i as f64 * 0.35).sin()))
+        .collect();
+    record(
+        "vibrato_segments",
+        shape(
+            &vibrato::vibrato(&contour, 0.01, &vibrato::VibratoConfig::default())
+                .into_iter()
+                .map(VibratoFrame::from)
+                .collect::<Vec<_>>(),
+        ),
+    );
+    record(
+        "ltas_frame",
+        shape(&LtasFrame::from(
+            ltas::Ltas::compute(&long, fs, 512).unwrap(),
+        )),
+    );
+    record(
+        "sustained_vowel",
+        shape(&SustainedVowelFrame::analyze(&long, 12, fs, 1.0)),
+    );
+    record(
+        "glottal_flow",
+        shape(&GlottalFlowFrame::analyze(&frame, fs)),
+    );
+    record(
+        "glottal_closure_instants",
+        variable(&glottal_closure_instants(&long, fs)),
+    );
+    let synchronous: Vec<SynchronousFormantFrame> = synchronous_formants(
+        &long,
+        &analyzer::Analyzer::new(12, fs).with_downsample_factor(2),
+        None,
+        None,
+    )
+    .into_iter()
+    .map(SynchronousFormantFrame::from)
+    .collect();
+    record("pitch_synchronous_formants", shape(&synchronous));
+    record("phonation", shape(&PhonationFrame::analyze(&padded, fs)));
+    record("ddk", shape(&DdkFrame::analyze(&syllables, fs)));
+    record("ddk(silence)", shape(&DdkFrame::analyze(&[0.0; 8000], fs)));
+
+    // Classes
+    let monitor = FrameMonitor::new();
+    record(
+        "FrameMonitor.formant_detection_with_downsampling",
+        variable(&monitor.formant_detection_with_downsampling(frame.clone(), 12, fs, 2, None)),
+    );
+    record(
+        "FrameMonitor.formant_detection_f32",
+        variable(&monitor.formant_detection_f32(&frame_f32, 12, fs, 2, None)),
+    );
+    record("FrameMonitor.counters", fixed(&monitor.counters()));
+
+    let mut filter = HarmonicFilter::new(3, 3);
+    record("HarmonicFilter.process", fixed(&filter.process(&frame_f32)));
+
+    let mut stream = StreamingAnalyzer::new(fs, 12, 2, 0.025, 0.01, None);
+    record(
+        "StreamingAnalyzer.push",
+        shape(&stream.push_frames(&frame_f32)),
+    );
+    let mut overlay = StreamingAnalyzer::new(fs, 12, 2, 0.025, 0.01, None);
+    let mut columns = Spectrogram::new(128, 10, -100.0, 0.0);
+    record(
+        "StreamingAnalyzer.push_overlay",
+        shape(&overlay.overlay_frames(&frame_f32, &mut columns)),
+    );
+    let mut gated = StreamingAnalyzer::new(fs, 12, 2, 0.025, 0.01, None);
+    gated.set_vad(true, None);
+    record(
+        "StreamingAnalyzer.set_vad",
+        shape(&gated.push_frames(&frame_f32)),
+    );
+    record("StreamingAnalyzer.counters", fixed(&stream.counters()));
+
+    let mut worklet = WorkletAnalyzer::new(fs, 12, 2, 512, 256, 4, None);
+    record("WorkletAnalyzer.input", shape(&worklet.input_len()));
+    worklet.input[..512].copy_from_slice(&frame_f32);
+    record(
+        "WorkletAnalyzer.analyze_in_place",
+        shape(&worklet.analyze_in_place(512)),
+    );
+    record("WorkletAnalyzer.output", fixed(&worklet.output));
+
+    let analyzer = FrameAnalyzer::new(fs, 12, 2, 80.0, None);
+    record(
+        "FrameAnalyzer.analyze",
+        shape(&analyzer.analysis_frame(&frame_f32)),
+    );
+    // `analyze_node` and `FormantBatch.from_audio_buffer` take browser objects; they
+    // serialize the same frames, and the buffer's channels go through `downmix`.
+    record(
+        "FrameAnalyzer.analyze_node",
+        shape(&analyzer.analysis_frame(&frame_f32)),
+    );
+    let mut gaussian = FrameAnalyzer::new(fs, 12, 2, 80.0, None);
+    gaussian.set_window("gaussian").unwrap();
+    record(
+        "FrameAnalyzer.set_window",
+        shape(&gaussian.analysis_frame(&frame_f32)),
+    );
+    let stereo = downmix(2, frame_f32.len(), None, |_, out| {
+        out.copy_from_slice(&frame_f32);
+        Ok(())
+    });
+    record(
+        "FormantBatch.from_audio_buffer(downmix)",
+        fixed(&stereo.unwrap()),
+    );
+
+    let mut spectrogram = Spectrogram::new(256, 100, -100.0, 0.0);
+    spectrogram.push(&wasm_fourier(frame_f32.clone()));
+    spectrogram.render(64, 32);
+    record("Spectrogram.render(64, 32)", fixed(&spectrogram.pixels));
+    record("Spectrogram.pixels_len", shape(&spectrogram.pixels_len()));
+    record(
+        "colormap_row",
+        fixed(&colormap_row(&wasm_fourier(frame_f32.clone()), "viridis", -100.0, 0.0).unwrap()),
+    );
+
+    let mut batch = FormantBatch::new(long.clone(), fs, 12, 2, 0.025, 0.01, None);
+    batch.set_frames_per_step(1000);
+    record("FormantBatch.step", shape(&batch.step()));
+    record("FormantBatch.total_frames", shape(&batch.total_frames()));
+    record("FormantBatch.times", fixed(&batch.times()));
+    record("FormantBatch.formants", fixed(&batch.formants(3)));
+    record(
+        "FormantBatch.smoothed_formants",
+        fixed(&batch.smoothed_formants(3, 5, 0.25, true)),
+    );
+    record("FormantBatch.pitch", fixed(&batch.pitch()));
+    record("FormantBatch.vibrato", shape(&batch.vibrato_frames()));
+    record(
+        "normalize_formants",
+        fixed(&normalize_formants(&batch.formants(3), 3, "lobanov").unwrap()),
+    );
+    let mut smoother = DisplaySmoother::new(1.0, 0.01, 0.15);
+    let mut smoothed = [200.0, 500.0, 1500.0];
+    smoother.update(&mut smoothed, 0.01);
+    record("DisplaySmoother.update", fixed(&smoothed));
+    let mut heatmap = FormantHeatmap::new(150.0, 1200.0, 500.0, 3000.0, 16, 24);
+    heatmap.push_batch(&batch);
+    heatmap.push(500.0, 1500.0);
+    record("FormantHeatmap.total", shape(&heatmap.total()));
+    record("FormantHeatmap.intensity", fixed(&heatmap.intensity(1.0)));
+    heatmap.render(1.0);
+    record("FormantHeatmap.render", fixed(&heatmap.pixels));
+    let mut speaker_stats = SpeakerStats::new();
+    speaker_stats.push_batch(&batch);
+    speaker_stats.push(&[300.0, 2300.0, 2900.0], 210.0);
+    record("SpeakerStats.frames", shape(&speaker_stats.frames()));
+    record(
+        "SpeakerStats.summary",
+        shape(&SpeakerStatsFrame::from(&speaker_stats.inner)),
+    );
+    record(
+        "FormantBatch.smoothed_pitch",
+        fixed(&batch.smoothed_pitch(5, 5, true)),
+    );
+    record(
+        "smooth_pitch_contour",
+        fixed(&smooth_pitch_contour(
+            &[200.0, 400.0, -1.0, 200.0, 100.0, 200.0],
+            1,
+            2,
+            true,
+        )),
+    );
+    record("FormantBatch.energy", fixed(&batch.energy()));
+    record("FormantBatch.voicing", fixed(&batch.voicing()));
+    record("FormantBatch.frames", shape(&batch.track_frames()));
+    record("FormantBatch.to_json", shape(&batch.to_json().is_empty()));
+
+    let mut gated = FormantBatch::new(padded.clone(), fs, 12, 2, 0.025, 0.01, None);
+    gated.set_vad(true, Some(12.0));
+    gated.set_frames_per_step(1000);
+    gated.step();
+    record("FormantBatch.set_vad", shape(&gated.track_frames()));
+
+    let mut config = AnalysisConfig::from_json(r#"{"num_formants":3}"#).unwrap();
+    config.set_quality("fast").unwrap();
+    config.set_window(Some("gaussian".into())).unwrap();
+    config.set_pre_emphasis(Some(0.9));
+    config.set_lpc_order(None);
+    config.set_max_formant(5000.0);
+    config.set_num_formants(4);
+    config.set_pitch_range(75.0, 500.0);
+    config.set_framing(0.025, 0.01);
+    config.set_vad(false, None);
+    config.set_sanitize(true, None);
+    record("AnalysisConfig.validate", shape(&config.validate().is_ok()));
+    record("AnalysisConfig.lpc_order", shape(&config.lpc_order()));
+    record("AnalysisConfig.to_json", shape(&config.to_json()));
+    record(
+        "AnalysisConfig.analyze_frame",
+        shape(&config.track_frame(&frame, fs)),
+    );
+    record(
+        "AnalysisConfig.analyze",
+        shape(&config.track_frames(&long, fs)),
+    );
+    let mut configured = FormantBatch::from_config(long.clone(), fs, &config);
+    configured.set_frames_per_step(1000);
+    configured.step();
+    record("FormantBatch.from_config", fixed(&configured.times()));
+    let mut configured = StreamingAnalyzer::from_config(fs, &config);
+    record(
+        "StreamingAnalyzer.from_config",
+        shape(&configured.push_frames(&frame_f32)),
+    );
+
+    let mut glitched = frame_f32.clone();
+    glitched[..4].fill(f32::NAN);
+    record(
+        "sanitize_samples",
+        shape(&sanitize_frame(&mut glitched, None)),
+    );
+    let mut repairing = StreamingAnalyzer::new(fs, 12, 2, 0.025, 0.01, None);
+    repairing.set_sanitize(true, Some(1.0));
+    repairing.push_frames(&glitched);
+    record(
+        "StreamingAnalyzer.sanitize_report",
+        shape(&SanitizeFrame::from(repairing.inner.sanitize_report())),
+    );
+
+    shapes
+}
+
+// ------------------
+// Helpers
+// ------------------
+
+// Shape of a serialized value: numbers, booleans and strings become their type name,
+// arrays the shape of their first element, objects a map of field shapes.
+fn shape<T: Serialize>(value: &T) -> Value {
+    fn describe(value: &Value) -> Value {
+        match value {
+            Value::Null => json!("null"),
+            Value::Bool(_) => json!("boolean"),
+            Value::Number(_) => json!("number"),
+            Value::String(_) => json!("string"),
+            Value::Array(items) => match items.first() {
+                Some(first) => json!([describe(first)]),
+                None => json!([]),
+            },
+            Value::Object(fields) => Value::Object(
+                fields
+                    .iter()
+                    .map(|(key, value)| (key.clone(), describe(value)))
+                    .collect(),
+            ),
+        }
+    }
+    describe(&serde_json::to_value(value).unwrap())
+}
+
+// A flat array whose length is part of the contract.
+fn fixed<T>(values: &[T]) -> Value {
+    json!(format!("number[{}]", values.len()))
+}
+
+// A flat array whose length depends on the input (e.g. the number of formants found).
+fn variable<T>(_values: &[T]) -> Value {
+    json!("number[]")
+}
+
+// `duration` seconds of the core's test vowel at 120 Hz.
+fn vowel(sample_rate: f64, duration: f64) -> Vec<f64> {
+    ezformant::test_signals::vowel(sample_rate, 120.0, (sample_rate * duration) as usize)
+}
+
+// 80 ms tone bursts at 5 per second.
+fn bursts(sample_rate: f64, duration: f64) -> Vec<f64> {
+    (0..(sample_rate * duration) as usize)
+        .map(|n| {
+            let t = n as f64 / sample_rate;
+            let phase = (t - 0.1).rem_euclid(0.2);
+            if t >= 0.1 && phase < 0.08 {
+                (PI * phase / 0.08).sin().powi(2) * (2.0 * PI * 220.0 * t).sin()
+            } else {
+                0.0
+            }
+        })
+        .collect()
+}
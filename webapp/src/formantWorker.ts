@@ -10,6 +10,7 @@ type CalcMessage = {
 		lpcOrder: number;
 		sampleRate: number;
 		downsampleFactor: number;
+		requestedAt: number;
 	};
 };
 
@@ -19,21 +20,29 @@ type WorkerResponse =
 	| { type: "init"; status: "success" | "error"; error?: string }
 	| {
 			type: "calcFormants";
-			status: "success" | "error";
+			status: "success" | "skipped" | "error";
 			formants?: Float64Array;
 			pitch?: number;
+			counters?: Float64Array;
 			error?: string;
 	  };
 
+// Requests older than this were queued behind slower frames; skip them.
+const STALE_FRAME_MS = 250;
+
 const workerScope: DedicatedWorkerGlobalScope =
 	self as DedicatedWorkerGlobalScope;
 let wasm: WasmBindings | null = null;
+let monitor: InstanceType<WasmBindings["FrameMonitor"]> | null = null;
 
 async function ensureWasmLoaded(): Promise<WasmBindings> {
 	if (wasm) return wasm;
 	const wasmUrl = new URL("../pkg/webapp.js", import.meta.url).href;
 	wasm = (await import(/* @vite-ignore */ wasmUrl)) as WasmBindings;
 	await wasm.default();
+	wasm.set_panic_hook();
+	wasm.init_tracing("warn");
+	monitor = new wasm.FrameMonitor();
 	return wasm;
 }
 
@@ -59,21 +68,41 @@ workerScope.onmessage = async (event: MessageEvent<WorkerMessage>) => {
 	if (message.type === "calcFormants") {
 		try {
 			const bindings = await ensureWasmLoaded();
-			const input = Float64Array.from(message.data.audioData);
+			const frameMonitor = monitor ?? new bindings.FrameMonitor();
+			monitor = frameMonitor;
+
+			const age =
+				performance.timeOrigin + performance.now() - message.data.requestedAt;
+			if (age > STALE_FRAME_MS) {
+				frameMonitor.record_overload();
+				workerScope.postMessage({
+					type: "calcFormants",
+					status: "skipped",
+					counters: frameMonitor.counters(),
+				} satisfies WorkerResponse);
+				return;
+			}
+
+			const input = Float32Array.from(message.data.audioData);
 
-			const formants = bindings.formant_detection_with_downsampling(
+			const formants = frameMonitor.formant_detection_f32(
 				input,
 				message.data.lpcOrder,
 				message.data.sampleRate,
 				message.data.downsampleFactor,
 			);
-			const pitch = bindings.pitch_detection(input, message.data.sampleRate);
+			const pitch = bindings.pitch_detection_f32(
+				input,
+				message.data.sampleRate,
+				0.1,
+			);
 
 			workerScope.postMessage({
 				type: "calcFormants",
 				status: "success",
 				formants,
 				pitch,
+				counters: frameMonitor.counters(),
 			} satisfies WorkerResponse);
 		} catch (error) {
 			workerScope.postMessage({
@@ -0,0 +1,894 @@
+// Structured results returned to JS through `serde-wasm-bindgen`.
+//
+// Field names are camelCase on the JS side. The TypeScript interfaces below are
+// appended to the generated `webapp.d.ts`.
+
+use ezformant::*;
+use serde::Serialize;
+use wasm_bindgen::prelude::*;
+
+#[wasm_bindgen(typescript_custom_section)]
+const TS_FRAMES: &'static str = r#"
+export interface SpectrumFrame {
+    frequencies: number[];
+    magnitudes: number[];
+}
+
+export interface FormantFrame {
+    formants: number[];
+    envelope?: SpectrumFrame;
+}
+
+export interface PitchFrame {
+    f0: number;
+    aperiodicity: number;
+    voiced: boolean;
+}
+
+export interface PeakFrame {
+    frequency: number;
+    bandwidth: number;
+    radius: number;
+    isFormant: boolean;
+}
+
+export interface PoleFrame {
+    roots: number[];
+    radii: number[];
+    frequencies: number[];
+}
+
+export interface VoiceQualityFrame {
+    jitterLocal: number | null;
+    jitterRap: number | null;
+    jitterPpq5: number | null;
+    shimmerLocal: number | null;
+    shimmerDb: number | null;
+    shimmerApq3: number | null;
+    shimmerApq5: number | null;
+}
+
+export interface SustainedVowelFrame {
+    start: number;
+    end: number;
+    f0: number;
+    formants: number[];
+    variation: number;
+    voiceQuality: VoiceQualityFrame;
+    hnr: number | null;
+}
+
+export interface PhonationBreakFrame {
+    start: number;
+    duration: number;
+}
+
+export interface PhonationFrame {
+    onset: number;
+    offset: number;
+    maximumPhonationTime: number;
+    voicedDuration: number;
+    breaks: PhonationBreakFrame[];
+}
+
+export interface LevinsonFrame {
+    coefficients: number[];
+    errors: number[];
+    reflection: number[];
+}
+
+export interface GlottalFlowFrame {
+    flow: number[];
+    derivative: number[];
+}
+
+export interface SynchronousFormantFrame {
+    time: number;
+    period: number;
+    formants: number[];
+}
+
+export interface AnalysisFrame {
+    formants: number[];
+    bandwidths: number[];
+    pitch: PitchFrame;
+    spectrum: SpectrumFrame;
+    envelope: SpectrumFrame;
+}
+
+export interface TrackFrame {
+    time: number;
+    formants: number[];
+    bandwidths: number[];
+    pitch: number;
+    energy: number;
+    zcr: number;
+    voiced: boolean;
+    silent: boolean;
+}
+
+export interface OverlayFrame extends TrackFrame {
+    spectrum: number[];
+}
+
+export interface DdkFrame {
+    syllables: number[];
+    rate: number;
+    meanInterval: number;
+    intervalCv: number;
+}
+
+export interface SegmentFrame {
+    start: number;
+    end: number;
+    label: "silence" | "voiced" | "unvoiced";
+}
+
+export interface SegmentationFrame {
+    segments: SegmentFrame[];
+    silenceDuration: number;
+    voicedDuration: number;
+    unvoicedDuration: number;
+    unvoicedFraction: number | null;
+}
+
+export interface LtasFrame {
+    frequencies: number[];
+    levelsDb: number[];
+    alphaRatio: number | null;
+    hammarbergIndex: number | null;
+    singersFormant: number | null;
+}
+
+export interface SpeakerStatsFrame {
+    frames: number;
+    meanF0: number | null;
+    f0StdDev: number | null;
+    medianF0: number | null;
+    meanFormants: number[];
+    formantStdDevs: number[];
+    medianFormants: number[];
+    vowelSpaceArea: number;
+    vowelSpaceHull: [number, number][];
+}
+
+export interface VibratoFrame {
+    start: number;
+    end: number;
+    rate: number;
+    extent: number;
+    regularity: number;
+}
+
+export interface SanitizeFrame {
+    nonFinite: number;
+    clamped: number;
+}
+"#;
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SpectrumFrame {
+    pub frequencies: Vec<f64>,
+    pub magnitudes: Vec<f64>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FormantFrame {
+    pub formants: Vec<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub envelope: Option<SpectrumFrame>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PitchFrame {
+    pub f0: f64,
+    pub aperiodicity: f64,
+    pub voiced: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PeakFrame {
+    pub frequency: f64,
+    pub bandwidth: f64,
+    pub radius: f64,
+    pub is_formant: bool,
+}
+
+// Every root of the LPC polynomial, unstable ones and both halves of each conjugate
+// pair included, sorted by angle from -pi to pi.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PoleFrame {
+    // Interleaved [re0, im0, re1, im1, ...].
+    pub roots: Vec<f64>,
+    pub radii: Vec<f64>,
+    // Frequency of each root's angle in Hz, negative below the real axis.
+    pub frequencies: Vec<f64>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VoiceQualityFrame {
+    pub jitter_local: Option<f64>,
+    pub jitter_rap: Option<f64>,
+    pub jitter_ppq5: Option<f64>,
+    pub shimmer_local: Option<f64>,
+    pub shimmer_db: Option<f64>,
+    pub shimmer_apq3: Option<f64>,
+    pub shimmer_apq5: Option<f64>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SustainedVowelFrame {
+    pub start: f64,
+    pub end: f64,
+    pub f0: f64,
+    pub formants: Vec<f64>,
+    pub variation: f64,
+    pub voice_quality: VoiceQualityFrame,
+    pub hnr: Option<f64>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PhonationBreakFrame {
+    pub start: f64,
+    pub duration: f64,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PhonationFrame {
+    pub onset: f64,
+    pub offset: f64,
+    pub maximum_phonation_time: f64,
+    pub voiced_duration: f64,
+    pub breaks: Vec<PhonationBreakFrame>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DdkFrame {
+    pub syllables: Vec<f64>,
+    pub rate: f64,
+    pub mean_interval: f64,
+    pub interval_cv: f64,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GlottalFlowFrame {
+    pub flow: Vec<f64>,
+    pub derivative: Vec<f64>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SynchronousFormantFrame {
+    pub time: f64,
+    pub period: f64,
+    pub formants: Vec<f64>,
+}
+
+// Everything the live display draws for one frame. `spectrum` and `envelope` are both
+// in dB on the same scale, as in `FormantFrame::analyze_gain_db`.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AnalysisFrame {
+    pub formants: Vec<f64>,
+    pub bandwidths: Vec<f64>,
+    pub pitch: PitchFrame,
+    pub spectrum: SpectrumFrame,
+    pub envelope: SpectrumFrame,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TrackFrame {
+    pub time: f64,
+    pub formants: Vec<f64>,
+    pub bandwidths: Vec<f64>,
+    pub pitch: f64,
+    pub energy: f64,
+    pub zcr: f64,
+    pub voiced: bool,
+    pub silent: bool,
+}
+
+// A `TrackFrame` with the dB spectrum column pushed to the spectrogram for it.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OverlayFrame {
+    pub time: f64,
+    pub formants: Vec<f64>,
+    pub bandwidths: Vec<f64>,
+    pub pitch: f64,
+    pub energy: f64,
+    pub zcr: f64,
+    pub voiced: bool,
+    pub silent: bool,
+    pub spectrum: Vec<f32>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LevinsonFrame {
+    pub coefficients: Vec<f64>,
+    pub errors: Vec<f64>,
+    pub reflection: Vec<f64>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SegmentFrame {
+    pub start: f64,
+    pub end: f64,
+    pub label: &'static str,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SegmentationFrame {
+    pub segments: Vec<SegmentFrame>,
+    pub silence_duration: f64,
+    pub voiced_duration: f64,
+    pub unvoiced_duration: f64,
+    pub unvoiced_fraction: Option<f64>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LtasFrame {
+    pub frequencies: Vec<f64>,
+    pub levels_db: Vec<f64>,
+    pub alpha_ratio: Option<f64>,
+    pub hammarberg_index: Option<f64>,
+    pub singers_formant: Option<f64>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SpeakerStatsFrame {
+    pub frames: usize,
+    pub mean_f0: Option<f64>,
+    pub f0_std_dev: Option<f64>,
+    pub median_f0: Option<f64>,
+    pub mean_formants: Vec<f64>,
+    pub formant_std_devs: Vec<f64>,
+    pub median_formants: Vec<f64>,
+    pub vowel_space_area: f64,
+    pub vowel_space_hull: Vec<[f64; 2]>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VibratoFrame {
+    pub start: f64,
+    pub end: f64,
+    pub rate: f64,
+    pub extent: f64,
+    pub regularity: f64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SanitizeFrame {
+    pub non_finite: usize,
+    pub clamped: usize,
+}
+
+impl FormantFrame {
+    // Formants and LPC envelope of a frame that is already at `sample_rate`. The
+    // envelope magnitudes are clamped to `floor`.
+    pub fn analyze(
+        data: Vec<f64>,
+        lpc_order: usize,
+        sample_rate: f64,
+        num_points: usize,
+        floor: spectrum::SpectralFloor,
+        pre_emphasis: f64,
+    ) -> Self {
+        let grid = units::frequency_axis(num_points, sample_rate, units::FrequencyUnit::Hertz);
+        Self::analyze_on_grid(data, lpc_order, sample_rate, &grid, floor, pre_emphasis)
+    }
+
+    // Like `analyze`, but samples the envelope at `frequencies` (in Hz) instead of a
+    // linear grid.
+    pub fn analyze_on_grid(
+        mut data: Vec<f64>,
+        lpc_order: usize,
+        sample_rate: f64,
+        frequencies: &[f64],
+        floor: spectrum::SpectralFloor,
+        pre_emphasis: f64,
+    ) -> Self {
+        // Preprocess signal
+        preprocess_signal(&mut data, pre_emphasis);
+
+        let (lpc_coeff, _) = lpc::fit(&data, lpc_order);
+        Self::from_coefficients(&lpc_coeff, sample_rate, frequencies, floor)
+    }
+
+    // Formants and envelope of LPC coefficients computed elsewhere. The envelope is that
+    // of the stabilized filter, sampled at `frequencies` (in Hz).
+    pub fn from_coefficients(
+        lpc_coeff: &[f64],
+        sample_rate: f64,
+        frequencies: &[f64],
+        floor: spectrum::SpectralFloor,
+    ) -> Self {
+        let envelope_coeff = lpc::stabilize(lpc_coeff, roots::RootSolver::Eigen);
+
+        let (frequencies, mut magnitudes): (Vec<f64>, Vec<f64>) =
+            lpc::frequency_response_at(&envelope_coeff, sample_rate, frequencies)
+                .into_iter()
+                .unzip();
+        floor.apply(&mut magnitudes);
+
+        FormantFrame {
+            formants: lpc::formant_detection(lpc_coeff, sample_rate),
+            envelope: Some(SpectrumFrame {
+                frequencies,
+                magnitudes,
+            }),
+        }
+    }
+
+    // Like `analyze`, but downsamples `data` by `downsample_factor` first, low-pass
+    // filtering it so nothing aliases (see `decimate`).
+    pub fn analyze_downsampled(
+        data: &[f64],
+        lpc_order: usize,
+        original_sample_rate: f64,
+        downsample_factor: usize,
+        num_points: usize,
+        floor: spectrum::SpectralFloor,
+        pre_emphasis: f64,
+    ) -> Self {
+        Self::analyze(
+            decimate(data, downsample_factor),
+            lpc_order,
+            original_sample_rate / downsample_factor as f64,
+            num_points,
+            floor,
+            pre_emphasis,
+        )
+    }
+
+    // Like `analyze_downsampled`, but the envelope is the model spectrum in dB that
+    // overlays `wasm_fourier` of the original frame in dB (see
+    // `QualitySettings::gain_envelope_db`), with the window and pre-emphasis of
+    // `settings`. Magnitudes are clamped to `floor` before conversion.
+    pub fn analyze_gain_db(
+        data: &[f64],
+        lpc_order: usize,
+        original_sample_rate: f64,
+        downsample_factor: usize,
+        num_points: usize,
+        floor: spectrum::SpectralFloor,
+        settings: &quality::QualitySettings,
+    ) -> Self {
+        let envelope = settings.gain_envelope_db(
+            data,
+            lpc_order,
+            original_sample_rate,
+            downsample_factor,
+            num_points,
+            floor,
+        );
+        FormantFrame {
+            formants: lpc::formant_detection(&envelope.lpc_coeffs, envelope.sample_rate),
+            envelope: Some(envelope.into()),
+        }
+    }
+
+    // The envelope magnitudes, as returned by the legacy flat API.
+    pub fn envelope_magnitudes(self) -> Vec<f64> {
+        self.envelope.map(|e| e.magnitudes).unwrap_or_default()
+    }
+}
+
+impl AnalysisFrame {
+    // Formants with bandwidths, f0, FFT spectrum and LPC envelope of one frame at
+    // `sample_rate`. The envelope has one point per FFT bin below the analysis Nyquist
+    // frequency, so both curves share their frequencies.
+    pub fn analyze(
+        data: &[f64],
+        lpc_order: usize,
+        sample_rate: f64,
+        downsample_factor: usize,
+        floor: spectrum::SpectralFloor,
+        settings: &quality::QualitySettings,
+        pitch_threshold: f64,
+    ) -> Self {
+        let downsample_factor = downsample_factor.max(1);
+        let bins = data.len() / 2;
+        let magnitudes = fourier_trans_with(
+            data.iter().map(|&x| x as f32).collect(),
+            SpectrumOptions {
+                floor,
+                decibels: true,
+                ..SpectrumOptions::default()
+            },
+        );
+        let spectrum = SpectrumFrame {
+            frequencies: (0..bins)
+                .map(|k| k as f64 * sample_rate / data.len() as f64)
+                .collect(),
+            magnitudes: magnitudes.iter().map(|&m| m as f64).collect(),
+        };
+
+        let envelope = settings.gain_envelope_db(
+            data,
+            lpc_order,
+            sample_rate,
+            downsample_factor,
+            bins / downsample_factor,
+            floor,
+        );
+        let mut peaks: Vec<lpc::Peak> = lpc::peak_detection_with_metadata(
+            &envelope.lpc_coeffs,
+            envelope.sample_rate,
+            roots::RootSolver::default(),
+        )
+        .into_iter()
+        .filter(|peak| peak.is_formant)
+        .collect();
+        peaks.sort_by(|a, b| a.frequency.total_cmp(&b.frequency));
+        let (formants, bandwidths) = peaks.iter().map(|p| (p.frequency, p.bandwidth)).unzip();
+
+        AnalysisFrame {
+            formants,
+            bandwidths,
+            pitch: PitchFrame::analyze(data, sample_rate, pitch_threshold),
+            spectrum,
+            envelope: envelope.into(),
+        }
+    }
+}
+
+impl PitchFrame {
+    pub fn analyze(signal: &[f64], sampling_rate: f64, threshold: f64) -> Self {
+        pitch::yin(
+            signal,
+            sampling_rate,
+            threshold,
+            lpc::AutocorrelationMethod::Fft,
+        )
+        .into()
+    }
+}
+
+impl From<pitch::PitchResult> for PitchFrame {
+    fn from(result: pitch::PitchResult) -> Self {
+        PitchFrame {
+            f0: result.f0,
+            aperiodicity: result.aperiodicity,
+            voiced: result.voiced,
+        }
+    }
+}
+
+impl PeakFrame {
+    // Retained LPC roots of a frame, sorted by frequency.
+    pub fn analyze(
+        mut data: Vec<f64>,
+        lpc_order: usize,
+        sample_rate: f64,
+        pre_emphasis: f64,
+    ) -> Vec<Self> {
+        // Preprocess signal
+        preprocess_signal(&mut data, pre_emphasis);
+
+        let (lpc_coeff, _) = lpc::fit(&data, lpc_order);
+        Self::from_coefficients(&lpc_coeff, sample_rate)
+    }
+
+    // Retained roots of LPC coefficients computed elsewhere, sorted by frequency.
+    pub fn from_coefficients(lpc_coeff: &[f64], sample_rate: f64) -> Vec<Self> {
+        let mut peaks =
+            lpc::peak_detection_with_metadata(lpc_coeff, sample_rate, roots::RootSolver::default());
+        peaks.sort_by(|a, b| a.frequency.total_cmp(&b.frequency));
+        peaks
+            .into_iter()
+            .map(|peak| PeakFrame {
+                frequency: peak.frequency,
+                bandwidth: peak.bandwidth,
+                radius: peak.radius,
+                is_formant: peak.is_formant,
+            })
+            .collect()
+    }
+}
+
+impl PoleFrame {
+    // Roots of the LPC fit of a frame.
+    pub fn analyze(
+        mut data: Vec<f64>,
+        lpc_order: usize,
+        sample_rate: f64,
+        pre_emphasis: f64,
+    ) -> Self {
+        preprocess_signal(&mut data, pre_emphasis);
+        let (lpc_coeff, _) = lpc::fit(&data, lpc_order);
+        Self::from_coefficients(&lpc_coeff, sample_rate)
+    }
+
+    // Roots of LPC coefficients computed elsewhere, found with the robust eigenvalue
+    // solver so that none is missing.
+    pub fn from_coefficients(lpc_coeff: &[f64], sample_rate: f64) -> Self {
+        let mut roots = roots::RootSolver::Eigen.roots(lpc_coeff);
+        roots.sort_by(|a, b| a.arg().total_cmp(&b.arg()));
+        PoleFrame {
+            roots: roots.iter().flat_map(|root| [root.re, root.im]).collect(),
+            radii: roots.iter().map(|root| root.norm()).collect(),
+            frequencies: roots
+                .iter()
+                .map(|root| root.arg() * sample_rate / (2.0 * std::f64::consts::PI))
+                .collect(),
+        }
+    }
+}
+
+impl VoiceQualityFrame {
+    pub fn analyze(signal: &[f64], sampling_rate: f64) -> Self {
+        let periods = voice_quality::glottal_periods(
+            signal,
+            sampling_rate,
+            &pitch::ac::AcPitchConfig::default(),
+        );
+        Self::from_measures(
+            voice_quality::jitter(&periods),
+            voice_quality::shimmer(&periods),
+        )
+    }
+
+    fn from_measures(
+        jitter: Option<voice_quality::Jitter>,
+        shimmer: Option<voice_quality::Shimmer>,
+    ) -> Self {
+        VoiceQualityFrame {
+            jitter_local: jitter.map(|j| j.local),
+            jitter_rap: jitter.map(|j| j.rap),
+            jitter_ppq5: jitter.map(|j| j.ppq5),
+            shimmer_local: shimmer.map(|s| s.local),
+            shimmer_db: shimmer.map(|s| s.local_db),
+            shimmer_apq3: shimmer.map(|s| s.apq3),
+            shimmer_apq5: shimmer.map(|s| s.apq5),
+        }
+    }
+}
+
+impl SustainedVowelFrame {
+    // Measurements over the steadiest `window_duration` seconds of a recording.
+    pub fn analyze(
+        signal: &[f64],
+        lpc_order: usize,
+        sample_rate: f64,
+        window_duration: f64,
+    ) -> Option<Self> {
+        let analyzer = analyzer::Analyzer::new(lpc_order, sample_rate);
+        let config = sustained::SustainedVowelConfig {
+            window_duration,
+            ..sustained::SustainedVowelConfig::default()
+        };
+        let vowel = sustained::find_sustained_vowel(signal, &analyzer, &config)?;
+        Some(SustainedVowelFrame {
+            start: vowel.start,
+            end: vowel.end,
+            f0: vowel.f0,
+            formants: vowel.formants.to_vec(),
+            variation: vowel.variation,
+            voice_quality: VoiceQualityFrame::from_measures(vowel.jitter, vowel.shimmer),
+            hnr: vowel.hnr,
+        })
+    }
+}
+
+impl PhonationFrame {
+    // Maximum phonation time and breaks of a sustained-vowel recording.
+    pub fn analyze(signal: &[f64], sample_rate: f64) -> Option<Self> {
+        let analyzer = analyzer::Analyzer::new(0, sample_rate);
+        let result =
+            sustained::phonation(signal, &analyzer, &sustained::PhonationConfig::default())?;
+        Some(PhonationFrame {
+            onset: result.onset,
+            offset: result.offset,
+            maximum_phonation_time: result.maximum_phonation_time,
+            voiced_duration: result.voiced_duration,
+            breaks: result
+                .breaks
+                .iter()
+                .map(|b| PhonationBreakFrame {
+                    start: b.start,
+                    duration: b.duration,
+                })
+                .collect(),
+        })
+    }
+}
+
+impl LevinsonFrame {
+    // LPC coefficients with the per-order prediction errors and reflection coefficients.
+    pub fn analyze(mut data: Vec<f64>, lpc_order: usize, pre_emphasis: f64) -> Self {
+        preprocess_signal(&mut data, pre_emphasis);
+        let r = lpc::autocorrelate(&data, lpc_order);
+        LevinsonFrame::from(lpc::levinson_full(lpc_order, &r))
+    }
+}
+
+impl From<lpc::LevinsonResult> for LevinsonFrame {
+    fn from(result: lpc::LevinsonResult) -> Self {
+        LevinsonFrame {
+            coefficients: result.coefficients,
+            errors: result.errors,
+            reflection: result.reflection,
+        }
+    }
+}
+
+impl From<Vec<segmentation::Segment>> for SegmentationFrame {
+    fn from(segments: Vec<segmentation::Segment>) -> Self {
+        let durations = segmentation::SegmentDurations::of(&segments);
+        SegmentationFrame {
+            segments: segments
+                .iter()
+                .map(|s| SegmentFrame {
+                    start: s.start,
+                    end: s.end,
+                    label: s.label.as_str(),
+                })
+                .collect(),
+            silence_duration: durations.silence,
+            voiced_duration: durations.voiced,
+            unvoiced_duration: durations.unvoiced,
+            unvoiced_fraction: durations.unvoiced_fraction(),
+        }
+    }
+}
+
+impl From<ltas::Ltas> for LtasFrame {
+    fn from(ltas: ltas::Ltas) -> Self {
+        let metrics = ltas.metrics();
+        LtasFrame {
+            frequencies: ltas.frequencies(),
+            levels_db: ltas.levels_db(),
+            alpha_ratio: metrics.alpha_ratio,
+            hammarberg_index: metrics.hammarberg_index,
+            singers_formant: metrics.singers_formant,
+        }
+    }
+}
+
+impl From<&speaker::SpeakerStats> for SpeakerStatsFrame {
+    // Per-formant entries are NaN where a formant has too few values.
+    fn from(stats: &speaker::SpeakerStats) -> Self {
+        let mean_formants = stats.mean_formants();
+        let per_formant = |f: &dyn Fn(usize) -> Option<f64>| -> Vec<f64> {
+            (0..mean_formants.len())
+                .map(|i| f(i).unwrap_or(f64::NAN))
+                .collect()
+        };
+        SpeakerStatsFrame {
+            frames: stats.frames(),
+            mean_f0: stats.mean_f0(),
+            f0_std_dev: stats.f0_std_dev(),
+            median_f0: stats.f0_percentile(50.0),
+            formant_std_devs: per_formant(&|i| stats.formant_std_dev(i)),
+            median_formants: per_formant(&|i| stats.formant_percentile(i, 50.0)),
+            mean_formants,
+            vowel_space_area: stats.vowel_space_area(),
+            vowel_space_hull: stats
+                .vowel_space_hull()
+                .iter()
+                .map(|&(f1, f2)| [f1, f2])
+                .collect(),
+        }
+    }
+}
+
+impl From<vibrato::Vibrato> for VibratoFrame {
+    fn from(v: vibrato::Vibrato) -> Self {
+        VibratoFrame {
+            start: v.start,
+            end: v.end,
+            rate: v.rate,
+            extent: v.extent,
+            regularity: v.regularity,
+        }
+    }
+}
+
+impl From<sanitize::SanitizeReport> for SanitizeFrame {
+    fn from(report: sanitize::SanitizeReport) -> Self {
+        SanitizeFrame {
+            non_finite: report.non_finite,
+            clamped: report.clamped,
+        }
+    }
+}
+
+impl GlottalFlowFrame {
+    // Glottal flow and flow derivative of a voiced frame, by IAIF.
+    pub fn analyze(frame: &[f64], sample_rate: f64) -> Self {
+        let result = glottal::iaif(frame, sample_rate, &glottal::IaifConfig::default());
+        GlottalFlowFrame {
+            flow: result.flow,
+            derivative: result.derivative,
+        }
+    }
+}
+
+impl From<pitch_synchronous::SynchronousFrame> for SynchronousFormantFrame {
+    fn from(frame: pitch_synchronous::SynchronousFrame) -> Self {
+        SynchronousFormantFrame {
+            time: frame.time,
+            period: frame.period,
+            formants: frame.formants,
+        }
+    }
+}
+
+impl DdkFrame {
+    // Syllable rate and regularity of a /pa-ta-ka/ style repetition task.
+    pub fn analyze(signal: &[f64], sample_rate: f64) -> Option<Self> {
+        let result = ddk::ddk(signal, sample_rate, &ddk::DdkConfig::default())?;
+        Some(DdkFrame {
+            syllables: result.syllables,
+            rate: result.rate,
+            mean_interval: result.mean_interval,
+            interval_cv: result.interval_cv,
+        })
+    }
+}
+
+impl From<quality::GainEnvelope> for SpectrumFrame {
+    fn from(envelope: quality::GainEnvelope) -> Self {
+        SpectrumFrame {
+            frequencies: envelope.frequencies,
+            magnitudes: envelope.magnitudes_db,
+        }
+    }
+}
+
+impl From<batch::TrackFrame> for TrackFrame {
+    fn from(frame: batch::TrackFrame) -> Self {
+        TrackFrame {
+            time: frame.time,
+            formants: frame.formants,
+            bandwidths: frame.bandwidths,
+            pitch: frame.pitch,
+            energy: frame.energy,
+            zcr: frame.zcr,
+            voiced: frame.voiced,
+            silent: frame.silent,
+        }
+    }
+}
+
+impl From<streaming::OverlayFrame> for OverlayFrame {
+    fn from(frame: streaming::OverlayFrame) -> Self {
+        let track = frame.track;
+        OverlayFrame {
+            time: track.time,
+            formants: track.formants,
+            bandwidths: track.bandwidths,
+            pitch: track.pitch,
+            energy: track.energy,
+            zcr: track.zcr,
+            voiced: track.voiced,
+            silent: track.silent,
+            spectrum: frame.spectrum,
+        }
+    }
+}
+
+// Converts a frame to a plain JS object.
+pub fn to_js<T: Serialize>(frame: &T) -> JsValue {
+    serde_wasm_bindgen::to_value(frame).unwrap_or(JsValue::NULL)
+}
@@ -1,104 +1,232 @@
 use ezformant::*;
 use wasm_bindgen::prelude::*;
 
-#[wasm_bindgen]
-extern "C" {
-    #[wasm_bindgen(js_namespace = console)]
-    fn log(s: &str);
-
-    #[wasm_bindgen(js_namespace = console, js_name = log)]
-    fn log_u32(a: u32);
+#[cfg(test)]
+mod api_snapshot;
+mod frames;
 
-    #[wasm_bindgen(js_namespace = console, js_name = log)]
-    fn log_many(a: &str, b: &str);
-}
+use frames::{
+    AnalysisFrame, DdkFrame, FormantFrame, GlottalFlowFrame, LevinsonFrame, LtasFrame,
+    OverlayFrame, PeakFrame, PhonationFrame, PitchFrame, PoleFrame, SanitizeFrame,
+    SegmentationFrame, SpeakerStatsFrame, SustainedVowelFrame, SynchronousFormantFrame, TrackFrame,
+    VibratoFrame, VoiceQualityFrame,
+};
 
 // ------------------
 // Public API
 // ------------------
 
+// Routes `tracing` spans/events from the analysis pipeline to the browser console.
+// `max_level` is one of "error", "warn", "info", "debug", "trace"; anything else throws.
+// Returns false if a subscriber was already installed.
+#[cfg(feature = "tracing-wasm")]
+#[wasm_bindgen]
+pub fn init_tracing(max_level: &str) -> Result<bool, JsError> {
+    use tracing_subscriber::layer::SubscriberExt;
+    use tracing_wasm::{WASMLayer, WASMLayerConfigBuilder};
+
+    let level: tracing::Level = parse_arg("init_tracing", "max_level", max_level)?;
+    let config = WASMLayerConfigBuilder::new().set_max_level(level).build();
+    let subscriber = tracing_subscriber::Registry::default().with(WASMLayer::new(config));
+
+    Ok(tracing::subscriber::set_global_default(subscriber).is_ok())
+}
+
+// `initThreadPool(numThreads)`: starts the rayon pool that `FormantBatch.step` and
+// `AnalysisConfig.analyze` use with the `threads` feature. Await it once before the
+// first analysis; it needs a cross-origin isolated page (SharedArrayBuffer).
+#[cfg(all(feature = "threads", target_arch = "wasm32"))]
+pub use wasm_bindgen_rayon::init_thread_pool;
+
+// Logs Rust panics with their message and source location to `console.error`; without
+// it a panic only surfaces as an opaque "unreachable executed" `RuntimeError`. Call it
+// once at start-up; later calls do nothing. A no-op when the crate is built without
+// the `console_error_panic_hook` feature.
+#[wasm_bindgen]
+pub fn set_panic_hook() {
+    #[cfg(feature = "console_error_panic_hook")]
+    console_error_panic_hook::set_once();
+}
+
 #[wasm_bindgen]
 pub fn wasm_fourier(data: Vec<f32>) -> Vec<f32> {
     fourier_trans(data)
 }
 
+// Like `wasm_fourier`, but `two_sided` returns all N bins instead of N/2, `complex`
+// returns interleaved [re0, im0, re1, im1, ...] instead of magnitudes, and `segments > 1`
+// averages that many half-overlapping windowed sub-spectra (same output length).
+// `floor_db > 0` clamps magnitudes to that many dB below the frame's peak; otherwise
+// the default absolute floor is used.
+#[wasm_bindgen]
+pub fn wasm_fourier_with_options(
+    data: Vec<f32>,
+    two_sided: bool,
+    complex: bool,
+    segments: usize,
+    floor_db: f64,
+) -> Vec<f32> {
+    fourier_trans_with(
+        data,
+        SpectrumOptions {
+            two_sided,
+            complex,
+            segments,
+            floor: spectral_floor(floor_db),
+            ..SpectrumOptions::default()
+        },
+    )
+}
+
+// Magnitude spectrum of a frame prepared for display: `window` is "rectangular", "hann",
+// "hamming" or "blackman", `remove_mean` subtracts the frame's DC offset, `fft_size`
+// zero-pads to that many samples (0 keeps the frame length) and `decibels` returns
+// 20*log10 values. `floor_db` is as in `wasm_fourier_with_options`.
 #[wasm_bindgen]
+pub fn wasm_fourier_windowed(
+    data: Vec<f32>,
+    window: &str,
+    remove_mean: bool,
+    fft_size: usize,
+    decibels: bool,
+    floor_db: f64,
+) -> Result<Vec<f32>, JsError> {
+    let window: spectrum::SpectrumWindow = parse_arg("wasm_fourier_windowed", "window", window)?;
+    Ok(fourier_trans_with(
+        data,
+        SpectrumOptions {
+            window,
+            remove_mean,
+            fft_size,
+            decibels,
+            floor: spectral_floor(floor_db),
+            ..SpectrumOptions::default()
+        },
+    ))
+}
+
+// Complex spectrum of a frame for phase-based processing (phase vocoders, reassignment):
+// interleaved [re0, im0, re1, im1, ...] for bins 0..N/2, or [mag0, phase0, ...] with
+// phases in radians when `polar`. `window` and `fft_size` are as in
+// `wasm_fourier_windowed`.
+#[wasm_bindgen]
+pub fn wasm_fourier_complex(
+    data: Vec<f32>,
+    window: &str,
+    fft_size: usize,
+    polar: bool,
+) -> Result<Vec<f32>, JsError> {
+    let window: spectrum::SpectrumWindow = parse_arg("wasm_fourier_complex", "window", window)?;
+    Ok(fourier_trans_with(
+        data,
+        SpectrumOptions {
+            complex: true,
+            polar,
+            window,
+            fft_size,
+            ..SpectrumOptions::default()
+        },
+    ))
+}
+
+// Welch power spectral density of a signal (e.g. a long-term average spectrum of a
+// sustained vowel): `nperseg / 2 + 1` densities in units^2/Hz from 0 Hz to Nyquist,
+// scaled as scipy.signal.welch. `window` is as in `wasm_fourier_windowed`. Throws for a
+// signal shorter than one segment or `noverlap >= nperseg`.
+#[wasm_bindgen]
+pub fn welch_psd(
+    signal: &[f64],
+    sample_rate: f64,
+    nperseg: usize,
+    noverlap: usize,
+    window: &str,
+) -> Result<Vec<f64>, JsError> {
+    let window: spectrum::SpectrumWindow = parse_arg("welch_psd", "window", window)?;
+    spectrum::welch_psd(signal, sample_rate, nperseg, noverlap, window)
+        .map_err(|e| js_error("welch_psd", e))
+}
+
+// The LPC entry points below take an optional trailing `pre_emphasis` coefficient
+// (default 0.97, omit it from JS to keep the default). Use `pre_emphasis_from_frequency`
+// to derive it from a frequency as Praat does.
+// The flat functions below predate the structured frames and are kept for existing
+// callers. They are thin wrappers over the frame constructors, so both return the
+// same numbers. The deprecated ones also carry `#[allow(deprecated)]`, which
+// wasm-bindgen copies onto the generated export that calls them.
+
+// Returns the LPC frequency response magnitudes.
+// @deprecated Use `formant_frame_with_downsampling`, which returns a structured
+// `FormantFrame`.
+#[deprecated(note = "use `formant_frame_with_downsampling`")]
+#[wasm_bindgen]
+#[allow(deprecated)]
 pub fn lpc_filter_freq_response_with_downsampling(
     original_data: Vec<f64>,
     lpc_order: usize,
     original_sample_rate: f64,
     downsample_factor: usize,
     num_points: usize,
+    pre_emphasis: Option<f64>,
 ) -> Vec<f64> {
-    // Downsample
-    let mut data = downsample(&original_data, downsample_factor);
-    let sample_rate = original_sample_rate / downsample_factor as f64;
-
-    // Preprocess signal
-    preprocess_signal(&mut data, 0.97);
-
-    // Compute autocorrelation
-    let r = lpc::autocorrelate(&data, lpc_order);
-
-    // Solve for LPC coefficients
-    let (a, _e) = lpc::levinson(lpc_order, &r);
-
-    // Compute frequency response
-    lpc::compute_frequency_response(&a, sample_rate, num_points)
-        .into_iter()
-        .map(|(_, mag)| mag)
-        .collect()
+    FormantFrame::analyze_downsampled(
+        &original_data,
+        lpc_order,
+        original_sample_rate,
+        downsample_factor,
+        num_points,
+        spectrum::SpectralFloor::default(),
+        pre_emphasis.unwrap_or(DEFAULT_PRE_EMPHASIS),
+    )
+    .envelope_magnitudes()
 }
 
+// Returns the LPC frequency response magnitudes.
+// @deprecated Use `formant_frame`, which returns a structured `FormantFrame`.
+#[deprecated(note = "use `formant_frame`")]
 #[wasm_bindgen]
+#[allow(deprecated)]
 pub fn lpc_filter_freq_response(
-    mut data: Vec<f64>,
+    data: Vec<f64>,
     lpc_order: usize,
     sample_rate: f64,
     num_points: usize,
+    pre_emphasis: Option<f64>,
 ) -> Vec<f64> {
-    // Preprocess signal
-    preprocess_signal(&mut data, 0.97);
-
-    // Compute autocorrelation
-    let r = lpc::autocorrelate(&data, lpc_order);
-
-    // Solve for LPC coefficients
-    let (a, _e) = lpc::levinson(lpc_order, &r);
-
-    // Compute frequency response
-    lpc::compute_frequency_response(&a, sample_rate, num_points)
-        .into_iter()
-        .map(|(_, mag)| mag)
-        .collect()
+    FormantFrame::analyze(
+        data,
+        lpc_order,
+        sample_rate,
+        num_points,
+        spectrum::SpectralFloor::default(),
+        pre_emphasis.unwrap_or(DEFAULT_PRE_EMPHASIS),
+    )
+    .envelope_magnitudes()
 }
 
 // Returns [F1, F2, F3, F4, LPC_frequency_response]
+// @deprecated Use `formant_frame`, which returns a structured `FormantFrame`.
+#[deprecated(note = "use `formant_frame`")]
 #[wasm_bindgen]
+#[allow(deprecated)]
 pub fn lpc_filter_freq_response_with_peaks(
-    mut data: Vec<f64>,
+    data: Vec<f64>,
     lpc_order: usize,
     sample_rate: f64,
     num_points: usize,
+    pre_emphasis: Option<f64>,
 ) -> Vec<f64> {
     const FORMANT_NUM: usize = 4;
 
-    // Preprocess signal
-    preprocess_signal(&mut data, 0.97);
-
-    // Compute autocorrelation
-    let r = lpc::autocorrelate(&data, lpc_order);
-    let (lpc_coeff, _) = lpc::levinson(lpc_order, &r);
-
-    // Detect formants
-    let formants = lpc::formant_detection(&lpc_coeff, sample_rate);
-
-    // Compute LPC frequency response
-    let lpc_freq_response: Vec<f64> =
-        lpc::compute_frequency_response(&lpc_coeff, sample_rate, num_points)
-            .into_iter()
-            .map(|(_, mag)| mag)
-            .collect();
+    let frame = FormantFrame::analyze(
+        data,
+        lpc_order,
+        sample_rate,
+        num_points,
+        spectrum::SpectralFloor::default(),
+        pre_emphasis.unwrap_or(DEFAULT_PRE_EMPHASIS),
+    );
+    let formants = frame.formants.clone();
+    let lpc_freq_response = frame.envelope_magnitudes();
 
     // Prepare result
     let mut result = Vec::with_capacity(FORMANT_NUM + lpc_freq_response.len());
@@ -113,128 +241,2969 @@ pub fn lpc_filter_freq_response_with_peaks(
     result
 }
 
+// Returns a `FormantFrame`: the formants and the LPC envelope sampled at `num_points`
+// frequencies. `floor_db` is interpreted as in `wasm_fourier_with_options`, so that the
+// envelope and the spectrum share a noise floor. Throws for an empty or non-finite
+// frame, or one with no more than `lpc_order` samples (after downsampling, in the
+// variants below).
+#[wasm_bindgen(unchecked_return_type = "FormantFrame")]
+pub fn formant_frame(
+    data: Vec<f64>,
+    lpc_order: usize,
+    sample_rate: f64,
+    num_points: usize,
+    floor_db: f64,
+    pre_emphasis: Option<f64>,
+) -> Result<JsValue, JsError> {
+    check_frame("formant_frame", "data", &data, lpc_order, 1)?;
+    Ok(frames::to_js(&FormantFrame::analyze(
+        data,
+        lpc_order,
+        sample_rate,
+        num_points,
+        spectral_floor(floor_db),
+        pre_emphasis.unwrap_or(DEFAULT_PRE_EMPHASIS),
+    )))
+}
+
+// Like `formant_frame`, but downsamples `original_data` by `downsample_factor` first.
+// The signal is low-pass filtered before samples are dropped, so nothing above the new
+// Nyquist frequency aliases onto the formants.
+#[wasm_bindgen(unchecked_return_type = "FormantFrame")]
+pub fn formant_frame_with_downsampling(
+    original_data: &[f64],
+    lpc_order: usize,
+    original_sample_rate: f64,
+    downsample_factor: usize,
+    num_points: usize,
+    floor_db: f64,
+    pre_emphasis: Option<f64>,
+) -> Result<JsValue, JsError> {
+    check_frame(
+        "formant_frame_with_downsampling",
+        "original_data",
+        original_data,
+        lpc_order,
+        downsample_factor,
+    )?;
+    Ok(frames::to_js(&FormantFrame::analyze_downsampled(
+        original_data,
+        lpc_order,
+        original_sample_rate,
+        downsample_factor,
+        num_points,
+        spectral_floor(floor_db),
+        pre_emphasis.unwrap_or(DEFAULT_PRE_EMPHASIS),
+    )))
+}
+
+// Float32Array variant of `formant_frame_with_downsampling`.
+#[wasm_bindgen(unchecked_return_type = "FormantFrame")]
+pub fn formant_frame_f32(
+    original_data: &[f32],
+    lpc_order: usize,
+    original_sample_rate: f64,
+    downsample_factor: usize,
+    num_points: usize,
+    floor_db: f64,
+    pre_emphasis: Option<f64>,
+) -> Result<JsValue, JsError> {
+    formant_frame_with_downsampling(
+        &widen(original_data),
+        lpc_order,
+        original_sample_rate,
+        downsample_factor,
+        num_points,
+        floor_db,
+        pre_emphasis,
+    )
+}
+
+// Like `formant_frame_with_downsampling`, but the envelope magnitudes are in dB and
+// scaled by the LPC gain, so they overlay `20 * log10(wasm_fourier(original_data))`
+// directly, with no vertical offset to tune.
+#[wasm_bindgen(unchecked_return_type = "FormantFrame")]
+pub fn formant_frame_gain_db(
+    original_data: &[f64],
+    lpc_order: usize,
+    original_sample_rate: f64,
+    downsample_factor: usize,
+    num_points: usize,
+    floor_db: f64,
+    pre_emphasis: Option<f64>,
+) -> Result<JsValue, JsError> {
+    check_frame(
+        "formant_frame_gain_db",
+        "original_data",
+        original_data,
+        lpc_order,
+        downsample_factor,
+    )?;
+    Ok(frames::to_js(&FormantFrame::analyze_gain_db(
+        original_data,
+        lpc_order,
+        original_sample_rate,
+        downsample_factor,
+        num_points,
+        spectral_floor(floor_db),
+        &quality::QualitySettings {
+            pre_emphasis: pre_emphasis.unwrap_or(DEFAULT_PRE_EMPHASIS),
+            ..Default::default()
+        },
+    )))
+}
+
+// Like `formant_frame_with_downsampling`, but samples the envelope at `frequencies`
+// (in Hz, e.g. from `frequency_grid`) instead of `num_points` linear steps, so a log
+// frequency axis needs no client-side resampling.
+#[wasm_bindgen(unchecked_return_type = "FormantFrame")]
+pub fn formant_frame_on_grid(
+    original_data: &[f64],
+    lpc_order: usize,
+    original_sample_rate: f64,
+    downsample_factor: usize,
+    frequencies: Vec<f64>,
+    floor_db: f64,
+    pre_emphasis: Option<f64>,
+) -> Result<JsValue, JsError> {
+    check_frame(
+        "formant_frame_on_grid",
+        "original_data",
+        original_data,
+        lpc_order,
+        downsample_factor,
+    )?;
+    Ok(frames::to_js(&FormantFrame::analyze_on_grid(
+        decimate(original_data, downsample_factor),
+        lpc_order,
+        original_sample_rate / downsample_factor as f64,
+        &frequencies,
+        spectral_floor(floor_db),
+        pre_emphasis.unwrap_or(DEFAULT_PRE_EMPHASIS),
+    )))
+}
+
 // returns [F1,f2,f3,f4]
+// @deprecated Use `formant_frame`, which returns a structured `FormantFrame`.
+#[deprecated(note = "use `formant_frame`")]
+#[wasm_bindgen]
+#[allow(deprecated)]
+pub fn formant_detection(
+    data: Vec<f64>,
+    lpc_order: usize,
+    sample_rate: f64,
+    pre_emphasis: Option<f64>,
+) -> Vec<f64> {
+    FormantFrame::analyze(
+        data,
+        lpc_order,
+        sample_rate,
+        0,
+        spectrum::SpectralFloor::default(),
+        pre_emphasis.unwrap_or(DEFAULT_PRE_EMPHASIS),
+    )
+    .formants
+}
+
+// Returns a `LevinsonFrame`: the LPC coefficients plus the prediction error after each
+// order (errors[0] is the frame energy) and the reflection coefficients, for order
+// selection and stability checks. Throws for frames `formant_frame` rejects.
+#[wasm_bindgen(unchecked_return_type = "LevinsonFrame")]
+pub fn lpc_diagnostics(
+    data: Vec<f64>,
+    lpc_order: usize,
+    pre_emphasis: Option<f64>,
+) -> Result<JsValue, JsError> {
+    check_frame("lpc_diagnostics", "data", &data, lpc_order, 1)?;
+    Ok(frames::to_js(&LevinsonFrame::analyze(
+        data,
+        lpc_order,
+        pre_emphasis.unwrap_or(DEFAULT_PRE_EMPHASIS),
+    )))
+}
+
+// Returns the LPC-cepstral coefficients [c1, ..., c_n_coeffs] of a frame, e.g. as
+// features for vowel or speaker classification.
+#[wasm_bindgen]
+pub fn lpc_cepstrum(
+    data: Vec<f64>,
+    lpc_order: usize,
+    n_coeffs: usize,
+    pre_emphasis: Option<f64>,
+) -> Vec<f64> {
+    let frame = LevinsonFrame::analyze(
+        data,
+        lpc_order,
+        pre_emphasis.unwrap_or(DEFAULT_PRE_EMPHASIS),
+    );
+    lpc::lpc_to_cepstrum(&frame.coefficients, n_coeffs)
+}
+
+// Returns the LPC residual (excitation estimate) of a frame: the pre-emphasized frame
+// filtered by the inverse of its own LPC model. Same length as `data`.
+#[wasm_bindgen]
+pub fn lpc_residual(data: Vec<f64>, lpc_order: usize, pre_emphasis: Option<f64>) -> Vec<f64> {
+    let alpha = pre_emphasis.unwrap_or(DEFAULT_PRE_EMPHASIS);
+    let mut emphasized = data.clone();
+    subtract_mean_in_place(&mut emphasized);
+    pre_emphasize_in_place(&mut emphasized, alpha);
+    let frame = LevinsonFrame::analyze(data, lpc_order, alpha);
+    lpc::inverse_filter(&emphasized, &frame.coefficients)
+}
+
+// The building blocks of `formant_frame`, for custom pipelines, e.g. with a window of
+// your own:
+//
+//   const frame = Float64Array.from(samples);
+//   subtract_mean(frame);
+//   myWindow(frame);
+//   pre_emphasis(frame, 0.97);
+//   const { coefficients } = levinson(autocorrelate(frame, 12), 12);
+//   const envelope = compute_frequency_response(coefficients, 16000, 512);
+
+// Subtracts the mean of `data` in place.
+#[wasm_bindgen]
+pub fn subtract_mean(data: &mut [f64]) {
+    subtract_mean_in_place(data);
+}
+
+// Applies the LPC window "hamming" or "gaussian" to `data` in place.
+#[wasm_bindgen]
+pub fn apply_window(data: &mut [f64], window: &str) -> Result<(), JsError> {
+    apply_window_in_place(data, parse_arg("apply_window", "window", window)?);
+    Ok(())
+}
+
+// Applies the pre-emphasis filter y[n] = x[n] - alpha x[n-1] to `data` in place.
+#[wasm_bindgen]
+pub fn pre_emphasis(data: &mut [f64], alpha: f64) {
+    pre_emphasize_in_place(data, alpha);
+}
+
+// Autocorrelation of `data` at lags 0..=max_lag.
+#[wasm_bindgen]
+pub fn autocorrelate(data: &[f64], max_lag: usize) -> Vec<f64> {
+    lpc::autocorrelate(data, max_lag)
+}
+
+// Levinson-Durbin recursion on an autocorrelation: LPC coefficients [1, a1, ...,
+// a_order] with the per-order prediction errors and reflection coefficients. Throws if
+// `r` has `order` values or fewer or is not finite.
+#[wasm_bindgen(unchecked_return_type = "LevinsonFrame")]
+pub fn levinson(r: &[f64], order: usize) -> Result<JsValue, JsError> {
+    let result = lpc::try_levinson_full(order, r).map_err(|e| arg_error("levinson", "r", e))?;
+    Ok(frames::to_js(&LevinsonFrame::from(result)))
+}
+
+// Magnitude of the LPC envelope 1/|A| at `num_points` frequencies from 0 Hz up to (not
+// including) Nyquist; `frequency_axis` gives the matching frequencies.
+#[wasm_bindgen]
+pub fn compute_frequency_response(
+    coefficients: &[f64],
+    sample_rate: f64,
+    num_points: usize,
+) -> Vec<f64> {
+    lpc::compute_frequency_response(coefficients, sample_rate, num_points)
+        .into_iter()
+        .map(|(_, magnitude)| magnitude)
+        .collect()
+}
+
+// Resynthesizes `signal` from its LPC model, block by block. `source` is "residual"
+// (reproduces the input), "impulses" (an impulse train at `f0` Hz) or "noise"
+// (whisper); unknown values select "residual". Play the result to hear what the
+// analysis settings capture.
+#[wasm_bindgen]
+pub fn resynthesize(
+    signal: &[f64],
+    sampling_rate: f64,
+    lpc_order: usize,
+    source: &str,
+    f0: f64,
+    pre_emphasis: Option<f64>,
+) -> Vec<f64> {
+    let source = match source.to_ascii_lowercase().as_str() {
+        "impulses" => synthesis::Source::ImpulseTrain { f0 },
+        "noise" => synthesis::Source::Noise,
+        _ => synthesis::Source::Residual,
+    };
+    let config = synthesis::ResynthesisConfig {
+        lpc_order,
+        pre_emphasis: pre_emphasis.unwrap_or(DEFAULT_PRE_EMPHASIS),
+        source,
+        ..synthesis::ResynthesisConfig::default()
+    };
+    synthesis::resynthesize(signal, sampling_rate, &config)
+}
+
+// Returns [frequency, bandwidth, radius, is_formant (1 or 0)] for each retained LPC root,
+// flattened and sorted by frequency, for custom formant selection on the JS side.
+// @deprecated Use `peak_frames`, which returns structured `PeakFrame`s.
+#[deprecated(note = "use `peak_frames`")]
+#[wasm_bindgen]
+#[allow(deprecated)]
+pub fn peak_detection_with_metadata(
+    data: Vec<f64>,
+    lpc_order: usize,
+    sample_rate: f64,
+    pre_emphasis: Option<f64>,
+) -> Vec<f64> {
+    PeakFrame::analyze(
+        data,
+        lpc_order,
+        sample_rate,
+        pre_emphasis.unwrap_or(DEFAULT_PRE_EMPHASIS),
+    )
+    .iter()
+    .flat_map(|peak| {
+        [
+            peak.frequency,
+            peak.bandwidth,
+            peak.radius,
+            if peak.is_formant { 1.0 } else { 0.0 },
+        ]
+    })
+    .collect()
+}
+
+// Returns a `PeakFrame[]`, one entry per retained LPC root, sorted by frequency.
+// Throws for frames `formant_frame` rejects.
+#[wasm_bindgen(unchecked_return_type = "PeakFrame[]")]
+pub fn peak_frames(
+    data: Vec<f64>,
+    lpc_order: usize,
+    sample_rate: f64,
+    pre_emphasis: Option<f64>,
+) -> Result<JsValue, JsError> {
+    check_frame("peak_frames", "data", &data, lpc_order, 1)?;
+    Ok(frames::to_js(&PeakFrame::analyze(
+        data,
+        lpc_order,
+        sample_rate,
+        pre_emphasis.unwrap_or(DEFAULT_PRE_EMPHASIS),
+    )))
+}
+
+// Returns a `PoleFrame` with every root of the frame's LPC polynomial, for drawing a
+// pole plot against the unit circle: stable poles lie inside, and the formants are the
+// poles near the circle. Throws for frames `formant_frame` rejects.
+//
+//   const { roots, radii } = lpc_poles(frame, 12, 16000);
+//   for (let i = 0; i < radii.length; i++) dot(roots[2 * i], roots[2 * i + 1]);
+#[wasm_bindgen(unchecked_return_type = "PoleFrame")]
+pub fn lpc_poles(
+    data: Vec<f64>,
+    lpc_order: usize,
+    sample_rate: f64,
+    pre_emphasis: Option<f64>,
+) -> Result<JsValue, JsError> {
+    check_frame("lpc_poles", "data", &data, lpc_order, 1)?;
+    Ok(frames::to_js(&PoleFrame::analyze(
+        data,
+        lpc_order,
+        sample_rate,
+        pre_emphasis.unwrap_or(DEFAULT_PRE_EMPHASIS),
+    )))
+}
+
+// The functions below start from LPC coefficients [1, a1, ..., ap] computed once per
+// frame (e.g. by `levinson`) and shared between consumers, instead of raw samples.
+// They throw for empty or non-finite coefficients or a non-positive `sample_rate`.
+
+// Formant frequencies in Hz, sorted, like `formant_frame(...).formants`.
 #[wasm_bindgen]
-pub fn formant_detection(mut data: Vec<f64>, lpc_order: usize, sample_rate: f64) -> Vec<f64> {
-    // Preprocess signal
-    preprocess_signal(&mut data, 0.97);
+pub fn formants_from_coefficients(
+    coefficients: &[f64],
+    sample_rate: f64,
+) -> Result<Vec<f64>, JsError> {
+    check_coefficients("formants_from_coefficients", coefficients, sample_rate)?;
+    Ok(lpc::formant_detection(coefficients, sample_rate))
+}
 
-    // Compute autocorrelation
-    let r = lpc::autocorrelate(&data, lpc_order);
+// Like `formant_frame`, with the envelope of the given coefficients.
+#[wasm_bindgen(unchecked_return_type = "FormantFrame")]
+pub fn formant_frame_from_coefficients(
+    coefficients: &[f64],
+    sample_rate: f64,
+    num_points: usize,
+    floor_db: f64,
+) -> Result<JsValue, JsError> {
+    check_coefficients("formant_frame_from_coefficients", coefficients, sample_rate)?;
+    let grid = units::frequency_axis(num_points, sample_rate, units::FrequencyUnit::Hertz);
+    Ok(frames::to_js(&FormantFrame::from_coefficients(
+        coefficients,
+        sample_rate,
+        &grid,
+        spectral_floor(floor_db),
+    )))
+}
 
-    // Solve for LPC coefficients
-    let (lpc_coeff, _) = lpc::levinson(lpc_order, &r);
+// Like `lpc_poles`, for the roots of the given coefficients.
+#[wasm_bindgen(unchecked_return_type = "PoleFrame")]
+pub fn poles_from_coefficients(coefficients: &[f64], sample_rate: f64) -> Result<JsValue, JsError> {
+    check_coefficients("poles_from_coefficients", coefficients, sample_rate)?;
+    Ok(frames::to_js(&PoleFrame::from_coefficients(
+        coefficients,
+        sample_rate,
+    )))
+}
 
-    // Detect formants
-    lpc::formant_detection(&lpc_coeff, sample_rate)
+// Like `peak_frames`, for the roots of the given coefficients.
+#[wasm_bindgen(unchecked_return_type = "PeakFrame[]")]
+pub fn peak_frames_from_coefficients(
+    coefficients: &[f64],
+    sample_rate: f64,
+) -> Result<JsValue, JsError> {
+    check_coefficients("peak_frames_from_coefficients", coefficients, sample_rate)?;
+    Ok(frames::to_js(&PeakFrame::from_coefficients(
+        coefficients,
+        sample_rate,
+    )))
 }
 
 // returns [F1,f2,f3,f4]
+// @deprecated Use `formant_frame_with_downsampling`, which returns a structured
+// `FormantFrame`.
+#[deprecated(note = "use `formant_frame_with_downsampling`")]
 #[wasm_bindgen]
+#[allow(deprecated)]
 pub fn formant_detection_with_downsampling(
     original_data: Vec<f64>,
     lpc_order: usize,
     original_sample_rate: f64,
     downsample_factor: usize,
+    pre_emphasis: Option<f64>,
+) -> Vec<f64> {
+    FormantFrame::analyze_downsampled(
+        &original_data,
+        lpc_order,
+        original_sample_rate,
+        downsample_factor,
+        0,
+        spectrum::SpectralFloor::default(),
+        pre_emphasis.unwrap_or(DEFAULT_PRE_EMPHASIS),
+    )
+    .formants
+}
+
+// Float32Array variant of `formant_detection_with_downsampling`, so samples from
+// `AnalyserNode.getFloatTimeDomainData` can be passed without conversion.
+#[wasm_bindgen]
+pub fn formant_detection_f32(
+    original_data: &[f32],
+    lpc_order: usize,
+    original_sample_rate: f64,
+    downsample_factor: usize,
+    pre_emphasis: Option<f64>,
 ) -> Vec<f64> {
+    FormantFrame::analyze_downsampled(
+        &widen(original_data),
+        lpc_order,
+        original_sample_rate,
+        downsample_factor,
+        0,
+        spectrum::SpectralFloor::default(),
+        pre_emphasis.unwrap_or(DEFAULT_PRE_EMPHASIS),
+    )
+    .formants
+}
+
+// returns [F1,f2,f3,f4]
+// `quality` is one of "fast", "balanced" or "accurate"; anything else throws.
+#[wasm_bindgen]
+pub fn formant_detection_with_quality(
+    original_data: Vec<f64>,
+    lpc_order: usize,
+    original_sample_rate: f64,
+    downsample_factor: usize,
+    quality: &str,
+    pre_emphasis: Option<f64>,
+) -> Result<Vec<f64>, JsError> {
+    let quality: quality::Quality =
+        parse_arg("formant_detection_with_quality", "quality", quality)?;
+    let settings = quality::QualitySettings {
+        pre_emphasis: pre_emphasis.unwrap_or(DEFAULT_PRE_EMPHASIS),
+        ..quality.settings()
+    };
+
     // Downsample
-    let mut data = downsample(&original_data, downsample_factor);
+    let data = decimate(&original_data, downsample_factor);
     let sample_rate = original_sample_rate / downsample_factor as f64;
 
-    // Preprocess signal
-    preprocess_signal(&mut data, 0.97);
-
-    // Compute autocorrelation
-    let r = lpc::autocorrelate(&data, lpc_order);
+    Ok(settings.detect_formants(&data, lpc_order, sample_rate))
+}
 
-    // Solve for LPC coefficients
-    let (lpc_coeff, _) = lpc::levinson(lpc_order, &r);
+// Float32Array variant of `formant_detection_with_quality` that stays in single
+// precision through preprocessing and the autocorrelation instead of widening the
+// frame, halving its memory traffic. With `downsample_factor > 1` the frame is widened
+// for the resampler as in `formant_detection_f32`.
+#[wasm_bindgen]
+pub fn formant_detection_f32_with_quality(
+    original_data: &[f32],
+    lpc_order: usize,
+    original_sample_rate: f64,
+    downsample_factor: usize,
+    quality: &str,
+    pre_emphasis: Option<f64>,
+) -> Result<Vec<f64>, JsError> {
+    Ok(analyzer::Analyzer::new(lpc_order, original_sample_rate)
+        .with_downsample_factor(downsample_factor)
+        .with_quality(parse_arg(
+            "formant_detection_f32_with_quality",
+            "quality",
+            quality,
+        )?)
+        .with_pre_emphasis(pre_emphasis.unwrap_or(DEFAULT_PRE_EMPHASIS))
+        .formants_f32(original_data))
+}
 
-    // Detect formants
-    lpc::formant_detection(&lpc_coeff, sample_rate)
+// returns [F1,f2,f3,f4]
+// Regularizes the autocorrelation before Levinson: `white_noise` scales r[0] by
+// (1 + white_noise) (1e-4 is typical) and `lag_window_hz` applies a Gaussian lag window
+// of that bandwidth (e.g. 60; omit to disable). Use for clean sustained vowels, where
+// the plain analysis can lock onto harmonics.
+#[wasm_bindgen]
+pub fn formant_detection_with_lag_window(
+    original_data: Vec<f64>,
+    lpc_order: usize,
+    original_sample_rate: f64,
+    downsample_factor: usize,
+    white_noise: f64,
+    lag_window_hz: Option<f64>,
+    pre_emphasis: Option<f64>,
+) -> Vec<f64> {
+    analyzer::Analyzer::new(lpc_order, original_sample_rate)
+        .with_downsample_factor(downsample_factor)
+        .with_pre_emphasis(pre_emphasis.unwrap_or(DEFAULT_PRE_EMPHASIS))
+        .with_lag_window(lpc::LagWindow {
+            white_noise,
+            bandwidth: lag_window_hz,
+        })
+        .formants(&original_data)
 }
 
+// returns [F1,f2,f3,f4]
+// Warped LPC on the full-rate signal, with no downsampling. `warping` is the allpass
+// coefficient; omit it for the Bark warping of `original_sample_rate` (about 0.76 at
+// 44.1 kHz). An order of 10-14 is enough at any rate.
 #[wasm_bindgen]
-pub fn pitch_detection(signal: &[f64], sampling_rate: f64) -> f64 {
-    return pitch::pitch_detection_yin(signal, sampling_rate);
+pub fn formant_detection_warped(
+    original_data: Vec<f64>,
+    lpc_order: usize,
+    original_sample_rate: f64,
+    warping: Option<f64>,
+    pre_emphasis: Option<f64>,
+) -> Vec<f64> {
+    analyzer::Analyzer::new(lpc_order, original_sample_rate)
+        .with_downsample_factor(1)
+        .with_pre_emphasis(pre_emphasis.unwrap_or(DEFAULT_PRE_EMPHASIS))
+        .with_warping(
+            warping.unwrap_or_else(|| warped::bark_warping_coefficient(original_sample_rate)),
+        )
+        .formants(&original_data)
 }
 
-// ------------------
-// Tests
-// ------------------
+// returns [F1,f2,f3,f4]
+// `window` is "hamming" or "gaussian" (Praat's window); anything else throws. To match
+// Praat, pass a frame twice its nominal window length.
+#[wasm_bindgen]
+pub fn formant_detection_with_window(
+    original_data: Vec<f64>,
+    lpc_order: usize,
+    original_sample_rate: f64,
+    downsample_factor: usize,
+    window: &str,
+    pre_emphasis: Option<f64>,
+) -> Result<Vec<f64>, JsError> {
+    Ok(analyzer::Analyzer::new(lpc_order, original_sample_rate)
+        .with_downsample_factor(downsample_factor)
+        .with_window(parse_arg(
+            "formant_detection_with_window",
+            "window",
+            window,
+        )?)
+        .with_pre_emphasis(pre_emphasis.unwrap_or(DEFAULT_PRE_EMPHASIS))
+        .formants(&original_data))
+}
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use std::f64::consts::PI;
+// returns [F1,f2,f3,f4]
+// `max_formant_hz` is the formant ceiling, as in Praat (5000 Hz for adult males, 5500 Hz
+// for adult females): frames are resampled so that it becomes the Nyquist frequency,
+// so no downsample factor is needed. Throws for a non-positive ceiling or sample rate,
+// and for an empty, non-finite or too short frame.
+#[wasm_bindgen]
+pub fn formant_detection_with_max_formant(
+    original_data: Vec<f64>,
+    lpc_order: usize,
+    original_sample_rate: f64,
+    max_formant_hz: f64,
+    pre_emphasis: Option<f64>,
+) -> Result<Vec<f64>, JsError> {
+    analyzer::Analyzer::new(lpc_order, original_sample_rate)
+        .with_max_formant(max_formant_hz)
+        .with_pre_emphasis(pre_emphasis.unwrap_or(DEFAULT_PRE_EMPHASIS))
+        .try_formants(&original_data)
+        .map_err(|e| match e {
+            error::EzFormantError::InvalidParameter { .. } => {
+                js_error("formant_detection_with_max_formant", e)
+            }
+            _ => arg_error("formant_detection_with_max_formant", "original_data", e),
+        })
+}
 
-    /// Helper function to manually downsample the data.
-    fn manual_downsample(data: &[f64], factor: usize) -> Vec<f64> {
-        data.iter().step_by(factor).cloned().collect()
+// returns [F1,f2,f3,f4]
+// Takes a spectrum instead of samples, e.g. AnalyserNode.getFloatFrequencyData with
+// `scale` "db" ("power" and "magnitude" are also accepted; anything else throws). Bin k is
+// at k * sample_rate / (2 * spectrum.length). `max_formant_hz` limits the model to the
+// bins below it (omit to use the whole spectrum).
+#[wasm_bindgen]
+pub fn formant_detection_from_spectrum(
+    spectrum: Vec<f64>,
+    scale: &str,
+    lpc_order: usize,
+    sample_rate: f64,
+    max_formant_hz: Option<f64>,
+    pre_emphasis: Option<f64>,
+) -> Result<Vec<f64>, JsError> {
+    let scale: spectrum::SpectrumScale =
+        parse_arg("formant_detection_from_spectrum", "scale", scale)?;
+    let mut analyzer = analyzer::Analyzer::new(lpc_order, sample_rate)
+        .with_pre_emphasis(pre_emphasis.unwrap_or(DEFAULT_PRE_EMPHASIS));
+    if let Some(max_formant_hz) = max_formant_hz {
+        analyzer = analyzer.with_max_formant(max_formant_hz);
     }
+    Ok(analyzer.formants_from_spectrum(&scale.to_power(&spectrum)))
+}
 
-    #[test]
-    fn test_lpc_filter_freq_response_with_downsampling() {
-        // Parameters for the synthetic test signal
-        let original_sample_rate = 16000.0; // in Hz
-        let frequency = 440.0; // A4 note in Hz
-        let duration = 1.0; // in seconds
-        let num_samples = (original_sample_rate * duration) as usize;
+// returns [F1,f2,f3,f4] in `units`: "hz", "bark", "erb", "mel", "semitones" (re 100 Hz),
+// "midi" (69 = A4 at 440 Hz) or "cents:<reference Hz>" such as "cents:220"; anything
+// else throws.
+#[wasm_bindgen]
+pub fn formant_detection_with_units(
+    original_data: Vec<f64>,
+    lpc_order: usize,
+    original_sample_rate: f64,
+    downsample_factor: usize,
+    units: &str,
+    pre_emphasis: Option<f64>,
+) -> Result<Vec<f64>, JsError> {
+    Ok(analyzer::Analyzer::new(lpc_order, original_sample_rate)
+        .with_downsample_factor(downsample_factor)
+        .with_pre_emphasis(pre_emphasis.unwrap_or(DEFAULT_PRE_EMPHASIS))
+        .with_units(parse_arg("formant_detection_with_units", "units", units)?)
+        .formants(&original_data))
+}
 
-        // Generate a sine wave as the original data
-        let original_data: Vec<f64> = (0..num_samples)
-            .map(|n| (2.0 * PI * frequency * (n as f64) / original_sample_rate).sin())
-            .collect();
+// Like `formant_detection_with_quality`, but throws an `Error` describing the problem
+// for empty, too short or non-finite frames (or an unknown `quality`) instead of
+// returning garbage.
+#[wasm_bindgen]
+pub fn try_formant_detection(
+    original_data: &[f64],
+    lpc_order: usize,
+    original_sample_rate: f64,
+    downsample_factor: usize,
+    quality: &str,
+    pre_emphasis: Option<f64>,
+) -> Result<Vec<f64>, JsError> {
+    let analyzer = analyzer::Analyzer::new(lpc_order, original_sample_rate)
+        .with_downsample_factor(downsample_factor)
+        .with_quality(parse_arg("try_formant_detection", "quality", quality)?)
+        .with_pre_emphasis(pre_emphasis.unwrap_or(DEFAULT_PRE_EMPHASIS));
+    analyzer
+        .try_formants(original_data)
+        .map_err(|e| arg_error("try_formant_detection", "original_data", e))
+}
 
-        // LPC parameters
-        let lpc_order = 10;
-        let downsample_factor = 2;
-        let num_points = 512;
+#[wasm_bindgen]
+pub fn pitch_detection_with_quality(
+    signal: &[f64],
+    sampling_rate: f64,
+    quality: &str,
+) -> Result<f64, JsError> {
+    let quality: quality::Quality = parse_arg("pitch_detection_with_quality", "quality", quality)?;
+    Ok(quality.settings().detect_pitch(signal, sampling_rate))
+}
 
-        // Call the function that includes downsampling
-        let response_with_downsampling = lpc_filter_freq_response_with_downsampling(
-            original_data.clone(),
-            lpc_order,
-            original_sample_rate,
-            downsample_factor,
-            num_points,
-        );
+// Returns a `PitchFrame`, or throws an `Error` for an empty or non-finite frame.
+// `quality` is one of "fast", "balanced" or "accurate"; anything else throws.
+#[wasm_bindgen(unchecked_return_type = "PitchFrame")]
+pub fn try_pitch_frame(
+    signal: &[f64],
+    sampling_rate: f64,
+    quality: &str,
+) -> Result<JsValue, JsError> {
+    let result = analyzer::Analyzer::new(0, sampling_rate)
+        .with_quality(parse_arg("try_pitch_frame", "quality", quality)?)
+        .try_pitch_result(signal)
+        .map_err(|e| arg_error("try_pitch_frame", "signal", e))?;
+    Ok(frames::to_js(&PitchFrame::from(result)))
+}
 
-        // Manually downsample the original data
-        let downsampled_data = manual_downsample(&original_data, downsample_factor);
-        let downsampled_sample_rate = original_sample_rate / (downsample_factor as f64);
+// Returns f0 in `units` (see `formant_detection_with_units`), or -1 if the frame is
+// not periodic.
+#[wasm_bindgen]
+pub fn pitch_detection_with_units(
+    signal: &[f64],
+    sampling_rate: f64,
+    units: &str,
+) -> Result<f64, JsError> {
+    Ok(analyzer::Analyzer::new(0, sampling_rate)
+        .with_units(parse_arg("pitch_detection_with_units", "units", units)?)
+        .pitch(signal))
+}
 
-        // Call the function without downsampling
-        let response_without_downsampling = lpc_filter_freq_response(
-            downsampled_data.clone(),
-            lpc_order,
-            downsampled_sample_rate,
-            num_points,
-        );
+// Returns the frequencies of the `num_points` LPC response points (0 to Nyquist) in
+// `units`, for labelling the x axis consistently with the other outputs.
+#[wasm_bindgen]
+pub fn frequency_axis(
+    num_points: usize,
+    sample_rate: f64,
+    units: &str,
+) -> Result<Vec<f64>, JsError> {
+    let units = parse_arg("frequency_axis", "units", units)?;
+    Ok(units::frequency_axis(num_points, sample_rate, units))
+}
 
-        // Define an acceptable error tolerance
-        let epsilon = 1e-3;
+// Returns `num_points` frequencies in Hz from `min_hz` to `max_hz`, evenly spaced in
+// `spacing`: "hz" (linear), "semitones" (logarithmic; `min_hz` must be positive), "mel",
+// "bark" or "erb". Pass the result to `formant_frame_on_grid`.
+#[wasm_bindgen]
+pub fn frequency_grid(
+    min_hz: f64,
+    max_hz: f64,
+    num_points: usize,
+    spacing: &str,
+) -> Result<Vec<f64>, JsError> {
+    let spacing = parse_arg("frequency_grid", "spacing", spacing)?;
+    Ok(units::frequency_grid(min_hz, max_hz, num_points, spacing))
+}
 
-        // Ensure both responses have the same number of points
-        assert_eq!(
-            response_with_downsampling.len(),
-            response_without_downsampling.len(),
-            "Frequency responses have different lengths"
-        );
+#[wasm_bindgen]
+pub fn pitch_detection(signal: &[f64], sampling_rate: f64) -> f64 {
+    pitch::pitch_detection_yin(signal, sampling_rate)
+}
 
-        // Compare each point in the frequency responses
-        for (i, (resp_ds, resp)) in response_with_downsampling
-            .iter()
-            .zip(response_without_downsampling.iter())
-            .enumerate()
-        {
-            let diff = (resp_ds - resp).abs();
-            if diff > epsilon {
-                panic!(
-                    "Frequency response differs at index {}: with_downsampling = {}, without_downsampling = {}, difference = {} exceeds epsilon = {}",
-                    i, resp_ds, resp, diff, epsilon
-                );
-            }
+// Returns f0 in Hz, or -1 if the frame is not periodic.
+// `threshold` is the YIN absolute threshold (0.1–0.15 is typical).
+#[wasm_bindgen]
+pub fn pitch_detection_with_threshold(signal: &[f64], sampling_rate: f64, threshold: f64) -> f64 {
+    pitch::pitch_detection_yin_with(
+        signal,
+        sampling_rate,
+        threshold,
+        lpc::AutocorrelationMethod::Fft,
+    )
+}
+
+// Returns [f0, aperiodicity, voiced (1 or 0)].
+// f0 is the best candidate even when unvoiced; check `voiced` before plotting it.
+// @deprecated Use `pitch_frame`, which returns a structured `PitchFrame`.
+#[deprecated(note = "use `pitch_frame`")]
+#[wasm_bindgen]
+#[allow(deprecated)]
+pub fn pitch_detection_with_voicing(
+    signal: &[f64],
+    sampling_rate: f64,
+    threshold: f64,
+) -> Vec<f64> {
+    let frame = PitchFrame::analyze(signal, sampling_rate, threshold);
+    vec![
+        frame.f0,
+        frame.aperiodicity,
+        if frame.voiced { 1.0 } else { 0.0 },
+    ]
+}
+
+// Returns a `PitchFrame`. f0 is the best candidate even when unvoiced.
+#[wasm_bindgen(unchecked_return_type = "PitchFrame")]
+pub fn pitch_frame(signal: &[f64], sampling_rate: f64, threshold: f64) -> JsValue {
+    frames::to_js(&PitchFrame::analyze(signal, sampling_rate, threshold))
+}
+
+// Float32Array variant of `pitch_detection_with_threshold`, so samples from
+// `AnalyserNode.getFloatTimeDomainData` can be passed without conversion.
+#[wasm_bindgen]
+pub fn pitch_detection_f32(signal: &[f32], sampling_rate: f64, threshold: f64) -> f64 {
+    pitch_detection_with_threshold(&widen(signal), sampling_rate, threshold)
+}
+
+// Returns the cepstral f0 estimate in Hz (search range 60–1000 Hz), or -1 if none is
+// found. Independent of YIN, so the two can be cross-checked.
+#[wasm_bindgen]
+pub fn pitch_detection_cepstrum(signal: &[f64], sampling_rate: f64) -> f64 {
+    cepstrum::pitch_detection_cepstrum(signal, sampling_rate, 60.0, 1000.0)
+}
+
+// Returns the HNR in dB of each 10 ms frame (Praat defaults), -200 for unvoiced frames.
+#[wasm_bindgen]
+pub fn hnr(signal: &[f64], sampling_rate: f64) -> Vec<f64> {
+    hnr::hnr(signal, sampling_rate, &hnr::HnrConfig::default())
+}
+
+// Returns [jitter_local, jitter_rap, jitter_ppq5, shimmer_local, shimmer_db, shimmer_apq3,
+// shimmer_apq5] as fractions (dB for shimmer_db); NaN where too few periods were found.
+// @deprecated Use `voice_quality_frame`, which returns a structured `VoiceQualityFrame`.
+#[deprecated(note = "use `voice_quality_frame`")]
+#[wasm_bindgen]
+#[allow(deprecated)]
+pub fn jitter_shimmer(signal: &[f64], sampling_rate: f64) -> Vec<f64> {
+    let frame = VoiceQualityFrame::analyze(signal, sampling_rate);
+    [
+        frame.jitter_local,
+        frame.jitter_rap,
+        frame.jitter_ppq5,
+        frame.shimmer_local,
+        frame.shimmer_db,
+        frame.shimmer_apq3,
+        frame.shimmer_apq5,
+    ]
+    .iter()
+    .map(|value| value.unwrap_or(f64::NAN))
+    .collect()
+}
+
+// Returns a `VoiceQualityFrame`; measures are null where too few periods were found.
+#[wasm_bindgen(unchecked_return_type = "VoiceQualityFrame")]
+pub fn voice_quality_frame(signal: &[f64], sampling_rate: f64) -> JsValue {
+    frames::to_js(&VoiceQualityFrame::analyze(signal, sampling_rate))
+}
+
+// Returns a `SegmentationFrame`: the recording cut into silence, voiced and unvoiced
+// segments (times in seconds) with the total time of each and the unvoiced share of the
+// non-silent time. `vad_threshold_db` is as in `StreamingAnalyzer.set_vad`.
+#[wasm_bindgen(unchecked_return_type = "SegmentationFrame")]
+pub fn segment_recording(
+    signal: &[f64],
+    sampling_rate: f64,
+    frame_duration: f64,
+    time_step: f64,
+    vad_threshold_db: Option<f64>,
+) -> JsValue {
+    frames::to_js(&segmentation_frame(
+        signal,
+        sampling_rate,
+        frame_duration,
+        time_step,
+        vad_threshold_db,
+    ))
+}
+
+// Returns an `LtasFrame`: the long-term average spectrum of a recording in dB with its
+// alpha ratio, Hammarberg index and singer's formant energy (dB, null when a band lies
+// above Nyquist). `nperseg` sets the resolution, `sampling_rate / nperseg` Hz. Throws
+// for a recording shorter than `nperseg` samples.
+#[wasm_bindgen(unchecked_return_type = "LtasFrame")]
+pub fn ltas_frame(signal: &[f64], sampling_rate: f64, nperseg: usize) -> Result<JsValue, JsError> {
+    let ltas = ltas::Ltas::compute(signal, sampling_rate, nperseg)
+        .map_err(|e| js_error("ltas_frame", e))?;
+    Ok(frames::to_js(&LtasFrame::from(ltas)))
+}
+
+// Returns a `VibratoFrame` (rate in Hz, extent in cents, regularity in [0, 1]) for each
+// voiced run of an f0 contour (Hz per frame, -1 or NaN where unvoiced, `time_step`
+// seconds apart) that holds at least 0.7 s of 3-10 Hz vibrato. Times count from the
+// first frame.
+#[wasm_bindgen(unchecked_return_type = "VibratoFrame[]")]
+pub fn vibrato_segments(track: &[f64], time_step: f64) -> JsValue {
+    let found = vibrato::vibrato(track, time_step, &vibrato::VibratoConfig::default());
+    let found: Vec<VibratoFrame> = found.into_iter().map(VibratoFrame::from).collect();
+    frames::to_js(&found)
+}
+
+// Returns a `SustainedVowelFrame` for the steadiest `window_duration` seconds of a
+// sustained-vowel recording (clinical "sustained /a/" protocol), or null if no fully
+// voiced stretch that long was found.
+#[wasm_bindgen(unchecked_return_type = "SustainedVowelFrame | null")]
+pub fn sustained_vowel(
+    signal: &[f64],
+    lpc_order: usize,
+    sampling_rate: f64,
+    window_duration: f64,
+) -> JsValue {
+    SustainedVowelFrame::analyze(signal, lpc_order, sampling_rate, window_duration)
+        .map_or(JsValue::NULL, |frame| frames::to_js(&frame))
+}
+
+// Returns a `PhonationFrame` (maximum phonation time and phonation breaks, in
+// seconds) for a sustained-vowel recording, or null if nothing was voiced.
+#[wasm_bindgen(unchecked_return_type = "PhonationFrame | null")]
+pub fn phonation(signal: &[f64], sampling_rate: f64) -> JsValue {
+    PhonationFrame::analyze(signal, sampling_rate)
+        .map_or(JsValue::NULL, |frame| frames::to_js(&frame))
+}
+
+// Returns a `DdkFrame` (syllable times in seconds, syllables per second and interval
+// regularity) for a diadochokinetic task, or null if fewer than two syllables were found.
+#[wasm_bindgen(unchecked_return_type = "DdkFrame | null")]
+pub fn ddk(signal: &[f64], sampling_rate: f64) -> JsValue {
+    DdkFrame::analyze(signal, sampling_rate).map_or(JsValue::NULL, |frame| frames::to_js(&frame))
+}
+
+// Glottal flow estimate of a voiced frame (30-50 ms), by iterative adaptive inverse
+// filtering.
+#[wasm_bindgen(unchecked_return_type = "GlottalFlowFrame")]
+pub fn glottal_flow(frame: &[f64], sampling_rate: f64) -> JsValue {
+    frames::to_js(&GlottalFlowFrame::analyze(frame, sampling_rate))
+}
+
+// Returns the sample indices of glottal closure instants (epochs), found by
+// zero-frequency filtering with a 10 ms trend-removal window.
+#[wasm_bindgen]
+pub fn glottal_closure_instants(signal: &[f64], sampling_rate: f64) -> Vec<u32> {
+    glottal::glottal_closure_instants(signal, sampling_rate, &glottal::GciConfig::default())
+        .into_iter()
+        .map(|n| n as u32)
+        .collect()
+}
+
+// Formants measured once per glottal cycle. With `closed_phase_fraction` (default 0.4)
+// each cycle's LPC covers that fraction of the period after closure; pass 0 to use two
+// whole cycles instead. `gci_window` is the epoch detector's trend window in seconds
+// (default 0.01; use about 1.5 periods for high voices).
+#[wasm_bindgen(unchecked_return_type = "SynchronousFormantFrame[]")]
+pub fn pitch_synchronous_formants(
+    signal: &[f64],
+    lpc_order: usize,
+    sampling_rate: f64,
+    downsample_factor: usize,
+    closed_phase_fraction: Option<f64>,
+    gci_window: Option<f64>,
+    pre_emphasis: Option<f64>,
+) -> JsValue {
+    let analyzer = analyzer::Analyzer::new(lpc_order, sampling_rate)
+        .with_downsample_factor(downsample_factor)
+        .with_pre_emphasis(pre_emphasis.unwrap_or(DEFAULT_PRE_EMPHASIS));
+    let frames: Vec<SynchronousFormantFrame> =
+        synchronous_formants(signal, &analyzer, closed_phase_fraction, gci_window)
+            .into_iter()
+            .map(SynchronousFormantFrame::from)
+            .collect();
+    frames::to_js(&frames)
+}
+
+// Returns the lag of `other` relative to `reference` in samples (fractional; positive
+// when `other` is late), searched within +-`max_lag`, or NaN if either channel is
+// silent. For a positive lag, `reference[n]` lines up with `other[n + lag]`.
+#[wasm_bindgen]
+pub fn estimate_channel_lag(reference: &[f64], other: &[f64], max_lag: usize) -> f64 {
+    align::estimate_lag(reference, other, max_lag).map_or(f64::NAN, |a| a.fractional_lag)
+}
+
+// Formant detection that keeps counts of analyzed and dropped frames, so the app can
+// report "analysis degraded" instead of silently showing stale formants.
+#[wasm_bindgen]
+#[derive(Default)]
+pub struct FrameMonitor {
+    counters: counters::FrameCounters,
+}
+
+#[wasm_bindgen]
+impl FrameMonitor {
+    #[wasm_bindgen(constructor)]
+    pub fn new() -> FrameMonitor {
+        FrameMonitor::default()
+    }
+
+    // returns [F1,f2,f3,f4], or an empty array if the frame was invalid
+    // (too short, or containing NaN/Inf).
+    pub fn formant_detection_with_downsampling(
+        &self,
+        original_data: Vec<f64>,
+        lpc_order: usize,
+        original_sample_rate: f64,
+        downsample_factor: usize,
+        pre_emphasis: Option<f64>,
+    ) -> Vec<f64> {
+        analyzer::Analyzer::new(lpc_order, original_sample_rate)
+            .with_downsample_factor(downsample_factor)
+            .with_pre_emphasis(pre_emphasis.unwrap_or(DEFAULT_PRE_EMPHASIS))
+            .formants_counted(&original_data, &self.counters)
+            .unwrap_or_default()
+    }
+
+    // Float32Array variant of `formant_detection_with_downsampling`.
+    pub fn formant_detection_f32(
+        &self,
+        original_data: &[f32],
+        lpc_order: usize,
+        original_sample_rate: f64,
+        downsample_factor: usize,
+        pre_emphasis: Option<f64>,
+    ) -> Vec<f64> {
+        self.formant_detection_with_downsampling(
+            widen(original_data),
+            lpc_order,
+            original_sample_rate,
+            downsample_factor,
+            pre_emphasis,
+        )
+    }
+
+    // Call when a frame is skipped because analysis cannot keep up.
+    pub fn record_overload(&self) {
+        self.counters.record_dropped(counters::DropReason::Overload);
+    }
+
+    // Returns [analyzed, dropped_overload, dropped_invalid].
+    pub fn counters(&self) -> Vec<f64> {
+        let snapshot = self.counters.snapshot();
+        vec![
+            snapshot.analyzed as f64,
+            snapshot.dropped_overload as f64,
+            snapshot.dropped_invalid as f64,
+        ]
+    }
+
+    pub fn reset(&self) {
+        self.counters.reset();
+    }
+}
+
+// Streaming harmonic-percussive separation of magnitude spectra. Feeding each display
+// frame through `process` suppresses broadband transients (plosives, clicks) before the
+// spectrum is drawn or an envelope is fitted to it.
+#[wasm_bindgen]
+pub struct HarmonicFilter {
+    filter: hpss::HpssFilter,
+}
+
+#[wasm_bindgen]
+impl HarmonicFilter {
+    // `harmonic_frames` is the median length along time, `percussive_bins` along
+    // frequency (17 and 17 are reasonable for 1024-point spectra at 60 fps).
+    #[wasm_bindgen(constructor)]
+    pub fn new(harmonic_frames: usize, percussive_bins: usize) -> HarmonicFilter {
+        HarmonicFilter {
+            filter: hpss::HpssFilter::new(hpss::HpssConfig {
+                harmonic_frames,
+                percussive_bins,
+                ..hpss::HpssConfig::default()
+            }),
+        }
+    }
+
+    // Returns the harmonic part of `magnitudes`.
+    pub fn process(&mut self, magnitudes: &[f32]) -> Vec<f32> {
+        let frame: Vec<f64> = magnitudes.iter().map(|&x| x as f64).collect();
+        self.filter
+            .push(&frame)
+            .into_iter()
+            .map(|x| x as f32)
+            .collect()
+    }
+
+    pub fn reset(&mut self) {
+        self.filter.reset();
+    }
+}
+
+// One-call analysis for the live display: formants with bandwidths, f0, the FFT
+// spectrum and the LPC envelope of a frame in a single `AnalysisFrame`, instead of one
+// wasm call (and one copy of the frame) per quantity. Takes the `Float32Array` filled
+// by `AnalyserNode.getFloatTimeDomainData` as is.
+//
+//   const analyzer = new FrameAnalyzer(audioContext.sampleRate, 12, 4, 80);
+//   const { formants, pitch, spectrum, envelope } = analyzer.analyze(timeData);
+#[wasm_bindgen]
+pub struct FrameAnalyzer {
+    sample_rate: f64,
+    lpc_order: usize,
+    downsample_factor: usize,
+    floor: spectrum::SpectralFloor,
+    settings: quality::QualitySettings,
+    pitch_threshold: f64,
+    time_data: Vec<f32>,
+}
+
+#[wasm_bindgen]
+impl FrameAnalyzer {
+    // `floor_db` is interpreted as in `wasm_fourier_with_options`.
+    #[wasm_bindgen(constructor)]
+    pub fn new(
+        sample_rate: f64,
+        lpc_order: usize,
+        downsample_factor: usize,
+        floor_db: f64,
+        pre_emphasis: Option<f64>,
+    ) -> FrameAnalyzer {
+        FrameAnalyzer {
+            sample_rate,
+            lpc_order,
+            downsample_factor: downsample_factor.max(1),
+            floor: spectral_floor(floor_db),
+            settings: quality::QualitySettings {
+                pre_emphasis: pre_emphasis.unwrap_or(DEFAULT_PRE_EMPHASIS),
+                ..Default::default()
+            },
+            pitch_threshold: 0.1,
+            time_data: Vec::new(),
+        }
+    }
+
+    // Sets the LPC window, "hamming" (the default) or "gaussian".
+    pub fn set_window(&mut self, window: &str) -> Result<(), JsError> {
+        self.settings.window = parse_arg("FrameAnalyzer.set_window", "window", window)?;
+        Ok(())
+    }
+
+    // Sets the YIN absolute threshold used for f0 (default 0.1).
+    pub fn set_pitch_threshold(&mut self, threshold: f64) {
+        self.pitch_threshold = threshold;
+    }
+
+    #[wasm_bindgen(unchecked_return_type = "AnalysisFrame")]
+    pub fn analyze(&self, frame: &[f32]) -> JsValue {
+        frames::to_js(&self.analysis_frame(frame))
+    }
+
+    // Analyzes the current time-domain window of `node` (`fftSize` samples), read
+    // straight into wasm memory instead of through a JS `Float32Array`.
+    #[wasm_bindgen(unchecked_return_type = "AnalysisFrame")]
+    pub fn analyze_node(&mut self, node: &web_sys::AnalyserNode) -> JsValue {
+        self.time_data.resize(node.fft_size() as usize, 0.0);
+        node.get_float_time_domain_data(&mut self.time_data);
+        frames::to_js(&self.analysis_frame(&self.time_data))
+    }
+}
+
+impl FrameAnalyzer {
+    fn analysis_frame(&self, frame: &[f32]) -> AnalysisFrame {
+        AnalysisFrame::analyze(
+            &widen(frame),
+            self.lpc_order,
+            self.sample_rate,
+            self.downsample_factor,
+            self.floor,
+            &self.settings,
+            self.pitch_threshold,
+        )
+    }
+}
+
+// Streaming analysis for callers that receive audio in chunks of any size (worklet
+// quanta, MediaRecorder blobs, network packets): buffering and framing happen here,
+// and every completed hop yields a `TrackFrame`.
+//
+//   const stream = new StreamingAnalyzer(sampleRate, 12, 4, 0.05, 0.01);
+//   for (const frame of stream.push(chunk)) draw(frame.time, frame.formants);
+#[wasm_bindgen]
+pub struct StreamingAnalyzer {
+    inner: streaming::StreamingAnalyzer,
+}
+
+#[wasm_bindgen]
+impl StreamingAnalyzer {
+    // `frame_duration` and `time_step` are in seconds.
+    #[wasm_bindgen(constructor)]
+    pub fn new(
+        sample_rate: f64,
+        lpc_order: usize,
+        downsample_factor: usize,
+        frame_duration: f64,
+        time_step: f64,
+        pre_emphasis: Option<f64>,
+    ) -> StreamingAnalyzer {
+        let analyzer = analyzer::Analyzer::new(lpc_order, sample_rate)
+            .with_downsample_factor(downsample_factor)
+            .with_pre_emphasis(pre_emphasis.unwrap_or(DEFAULT_PRE_EMPHASIS));
+        StreamingAnalyzer {
+            inner: streaming::StreamingAnalyzer::new(
+                analyzer,
+                (frame_duration * sample_rate).round() as usize,
+                (time_step * sample_rate).round() as usize,
+            ),
+        }
+    }
+
+    // Creates a streaming analyzer from an `AnalysisConfig`, including its VAD setting.
+    pub fn from_config(sample_rate: f64, config: &AnalysisConfig) -> StreamingAnalyzer {
+        StreamingAnalyzer {
+            inner: config.inner.streaming(sample_rate),
+        }
+    }
+
+    // Returns the frames completed by `chunk`, oldest first (often none).
+    #[wasm_bindgen(unchecked_return_type = "TrackFrame[]")]
+    pub fn push(&mut self, chunk: &[f32]) -> JsValue {
+        frames::to_js(&self.push_frames(chunk))
+    }
+
+    // Like `push`, and pushes each frame's spectrum to `spectrogram`, so its columns,
+    // the formants and f0 all describe the same frames:
+    //
+    //   for (const f of stream.push_overlay(chunk, spec)) drawPoints(f.time, f.formants);
+    //   spec.render(width, height);
+    #[wasm_bindgen(unchecked_return_type = "OverlayFrame[]")]
+    pub fn push_overlay(&mut self, chunk: &[f32], spectrogram: &mut Spectrogram) -> JsValue {
+        frames::to_js(&self.overlay_frames(chunk, spectrogram))
+    }
+
+    // Turns voice activity detection on or off. Rejected frames come back with
+    // `silent: true` and no formants or f0, without running the analysis. The noise
+    // floor is learned from the stream; `threshold_db` (default 10) is the margin above
+    // it that counts as speech.
+    pub fn set_vad(&mut self, enabled: bool, threshold_db: Option<f64>) {
+        self.inner
+            .set_vad(enabled.then(|| vad_config(threshold_db)));
+    }
+
+    // Turns input repair on or off (see `sanitize_samples`), e.g. for devices that
+    // deliver NaN right after a switch.
+    pub fn set_sanitize(&mut self, enabled: bool, limit: Option<f64>) {
+        self.inner
+            .set_sanitize(enabled.then(|| limit.unwrap_or(sanitize::DEFAULT_LIMIT)));
+    }
+
+    // Samples repaired since creation or `reset`.
+    #[wasm_bindgen(unchecked_return_type = "SanitizeFrame")]
+    pub fn sanitize_report(&self) -> JsValue {
+        frames::to_js(&SanitizeFrame::from(self.inner.sanitize_report()))
+    }
+
+    // Returns [analyzed, dropped_overload, dropped_invalid] since creation or `reset`,
+    // as in `FrameMonitor.counters`. Dropped frames, such as those with NaN/Inf samples
+    // (without `set_sanitize`), come back without formants or f0; VAD-rejected frames
+    // count as neither.
+    pub fn counters(&self) -> Vec<f64> {
+        let snapshot = self.inner.counters();
+        vec![
+            snapshot.analyzed as f64,
+            snapshot.dropped_overload as f64,
+            snapshot.dropped_invalid as f64,
+        ]
+    }
+
+    pub fn reset(&mut self) {
+        self.inner.reset();
+    }
+}
+
+impl StreamingAnalyzer {
+    fn push_frames(&mut self, chunk: &[f32]) -> Vec<TrackFrame> {
+        self.inner
+            .push(chunk)
+            .into_iter()
+            .map(TrackFrame::from)
+            .collect()
+    }
+
+    fn overlay_frames(
+        &mut self,
+        chunk: &[f32],
+        spectrogram: &mut Spectrogram,
+    ) -> Vec<OverlayFrame> {
+        self.inner
+            .push_overlay(chunk, &mut spectrogram.inner)
+            .into_iter()
+            .map(OverlayFrame::from)
+            .collect()
+    }
+}
+
+// Colors a spectrum of linear magnitudes (e.g. from `wasm_fourier`) as one RGBA pixel
+// row, ready for an `ImageData` one pixel high. `colormap` is as in
+// `Spectrogram.set_colormap`; levels outside `min_db..max_db` are clamped.
+#[wasm_bindgen]
+pub fn colormap_row(
+    magnitudes: &[f32],
+    colormap: &str,
+    min_db: f32,
+    max_db: f32,
+) -> Result<Vec<u8>, JsError> {
+    let colormap = parse_arg("colormap_row", "colormap", colormap)?;
+    let mut row = vec![0; magnitudes.len() * 4];
+    colormap::magnitude_row_to_rgba(magnitudes, colormap, min_db, max_db, &mut row);
+    Ok(row)
+}
+
+// Scrolling spectrogram kept in wasm memory. Push one spectrum per hop and render
+// straight to RGBA, then wrap the pixels in an `ImageData` without copying:
+//
+//   const spec = new Spectrogram(1024, 600, -100, 0);
+//   spec.push(wasm_fourier(frame)); // or spec.push_db(analyserDbData)
+//   spec.render(canvas.width, canvas.height);
+//   const pixels = new Uint8ClampedArray(memory.buffer, spec.pixels_ptr(), spec.pixels_len());
+//   ctx.putImageData(new ImageData(pixels, canvas.width, canvas.height), 0, 0);
+//
+// As with `WorkletAnalyzer`, recreate the view after wasm memory grows.
+#[wasm_bindgen]
+pub struct Spectrogram {
+    inner: spectrogram::Spectrogram,
+    pixels: Vec<u8>,
+}
+
+#[wasm_bindgen]
+impl Spectrogram {
+    // `bins` values per column, `columns` columns of history, and the dB range mapped
+    // from black to white.
+    #[wasm_bindgen(constructor)]
+    pub fn new(bins: usize, columns: usize, min_db: f32, max_db: f32) -> Spectrogram {
+        Spectrogram {
+            inner: spectrogram::Spectrogram::new(bins, columns).with_range(min_db, max_db),
+            pixels: Vec::new(),
+        }
+    }
+
+    // Appends a column of linear magnitudes, as returned by `wasm_fourier`.
+    pub fn push(&mut self, magnitudes: &[f32]) {
+        self.inner.push_magnitudes(magnitudes);
+    }
+
+    // Appends a column in dB, as filled by `AnalyserNode.getFloatFrequencyData`.
+    pub fn push_db(&mut self, db: &[f32]) {
+        self.inner.push_db(db);
+    }
+
+    pub fn set_range(&mut self, min_db: f32, max_db: f32) {
+        self.inner.set_range(min_db, max_db);
+    }
+
+    // "grayscale" (the default), "viridis", "magma" or "inferno"; throws otherwise.
+    pub fn set_colormap(&mut self, colormap: &str) -> Result<(), JsError> {
+        self.inner
+            .set_colormap(parse_arg("Spectrogram.set_colormap", "colormap", colormap)?);
+        Ok(())
+    }
+
+    // Renders `width` x `height` RGBA pixels into the buffer at `pixels_ptr`.
+    pub fn render(&mut self, width: usize, height: usize) {
+        self.pixels.resize(width * height * 4, 0);
+        self.inner.render_into(&mut self.pixels, width, height);
+    }
+
+    pub fn pixels_ptr(&self) -> *const u8 {
+        self.pixels.as_ptr()
+    }
+
+    pub fn pixels_len(&self) -> usize {
+        self.pixels.len()
+    }
+
+    pub fn clear(&mut self) {
+        self.inner.clear();
+    }
+}
+
+// Steadies live readouts (f0, formants, ...) for display with one-euro filters, holding
+// the last value through brief unvoiced gaps:
+//
+//   const smoother = new DisplaySmoother(1.0, 0.01, 0.15);
+//   const values = Float64Array.of(f0, f1, f2); // -1 or NaN where missing
+//   smoother.update(values, dt);
+#[wasm_bindgen]
+pub struct DisplaySmoother {
+    inner: smoothing::DisplaySmoother,
+}
+
+#[wasm_bindgen]
+impl DisplaySmoother {
+    // `min_cutoff` (Hz) sets how much jitter is removed when a value is steady, `beta`
+    // how much faster it follows quick movements (0 for a plain exponential smoother),
+    // and `hold_time` (seconds) how long the last value stays up after a dropout.
+    #[wasm_bindgen(constructor)]
+    pub fn new(min_cutoff: f64, beta: f64, hold_time: f64) -> DisplaySmoother {
+        DisplaySmoother {
+            inner: smoothing::DisplaySmoother::new(smoothing::DisplaySmoothing {
+                min_cutoff,
+                beta,
+                hold_time,
+                ..smoothing::DisplaySmoothing::default()
+            }),
+        }
+    }
+
+    // Smooths one value per channel in place, `dt` seconds after the previous call.
+    // Missing values get the held value, or stay as they are once the hold runs out.
+    pub fn update(&mut self, values: &mut [f64], dt: f64) {
+        self.inner.update(values, dt);
+    }
+
+    pub fn reset(&mut self) {
+        self.inner.reset();
+    }
+}
+
+// F1/F2 density heatmap ("vowel cloud"), laid out like a vowel chart: F1 grows down
+// from the top row, F2 grows leftwards from the right column. Render it into the buffer
+// at `pixels_ptr`, one RGBA pixel per bin, and scale it up on a canvas:
+//
+//   const heatmap = new FormantHeatmap(150, 1200, 500, 3000, 64, 64);
+//   heatmap.push(f1, f2); // per voiced frame
+//   heatmap.render(1.5); // Gaussian blur of 1.5 bins
+//   const pixels = new Uint8ClampedArray(memory.buffer, heatmap.pixels_ptr(), heatmap.pixels_len());
+//   ctx.putImageData(new ImageData(pixels, heatmap.columns()), 0, 0);
+//
+// As with `Spectrogram`, recreate the view after wasm memory grows.
+#[wasm_bindgen]
+pub struct FormantHeatmap {
+    inner: heatmap::FormantHeatmap,
+    colormap: colormap::Colormap,
+    pixels: Vec<u8>,
+}
+
+#[wasm_bindgen]
+impl FormantHeatmap {
+    // F1 and F2 ranges in Hz and the number of F1 rows and F2 columns.
+    #[wasm_bindgen(constructor)]
+    pub fn new(
+        f1_min: f64,
+        f1_max: f64,
+        f2_min: f64,
+        f2_max: f64,
+        f1_bins: usize,
+        f2_bins: usize,
+    ) -> FormantHeatmap {
+        FormantHeatmap {
+            inner: heatmap::FormantHeatmap::new(heatmap::HeatmapConfig {
+                f1_range: (f1_min, f1_max),
+                f2_range: (f2_min, f2_max),
+                f1_bins,
+                f2_bins,
+            }),
+            colormap: colormap::Colormap::default(),
+            pixels: Vec::new(),
+        }
+    }
+
+    // Counts one point; returns false if it lies outside the ranges.
+    pub fn push(&mut self, f1: f64, f2: f64) -> bool {
+        self.inner.push(f1, f2)
+    }
+
+    // Counts the voiced frames `batch` has analyzed so far, with F1 and F2 smoothed as in
+    // `FormantBatch.smoothed_formants(2, 5, 0.25, false)`.
+    pub fn push_batch(&mut self, batch: &FormantBatch) {
+        let config = smoothing::FormantSmoothing::default();
+        let smoothed = smoothing::smooth_formant_tracks(&batch.results, 2, &config);
+        for (frame, formants) in batch.results.iter().zip(&smoothed) {
+            if frame.voiced && !frame.silent {
+                self.inner.push(formants[0], formants[1]);
+            }
+        }
+    }
+
+    pub fn rows(&self) -> usize {
+        self.inner.config().f1_bins
+    }
+
+    pub fn columns(&self) -> usize {
+        self.inner.config().f2_bins
+    }
+
+    // Number of points counted.
+    pub fn total(&self) -> f64 {
+        self.inner.total() as f64
+    }
+
+    // Row-major intensities in [0, 1] after a Gaussian blur of `blur` bins (0 for none).
+    pub fn intensity(&self, blur: f64) -> Vec<f32> {
+        self.inner.intensity(blur)
+    }
+
+    // "grayscale" (the default), "viridis", "magma" or "inferno"; throws otherwise.
+    pub fn set_colormap(&mut self, colormap: &str) -> Result<(), JsError> {
+        self.colormap = parse_arg("FormantHeatmap.set_colormap", "colormap", colormap)?;
+        Ok(())
+    }
+
+    // Renders `rows` x `columns` RGBA pixels into the buffer at `pixels_ptr`.
+    pub fn render(&mut self, blur: f64) {
+        self.pixels.resize(self.rows() * self.columns() * 4, 0);
+        self.inner
+            .render_into(&mut self.pixels, self.colormap, blur);
+    }
+
+    pub fn pixels_ptr(&self) -> *const u8 {
+        self.pixels.as_ptr()
+    }
+
+    pub fn pixels_len(&self) -> usize {
+        self.pixels.len()
+    }
+
+    pub fn clear(&mut self) {
+        self.inner.clear();
+    }
+}
+
+// Largest block `WorkletAnalyzer::analyze_in_place` accepts at once; AudioWorklet
+// render quanta are 128 samples.
+pub const WORKLET_INPUT_CAPACITY: usize = 1024;
+
+// Formant and pitch tracking from an AudioWorklet without copying samples or results
+// across the JS boundary. The input and output live at fixed places in wasm memory; the
+// worklet writes each quantum through a view of the input and reads results through a
+// view of the output:
+//
+//   const analyzer = new WorkletAnalyzer(sampleRate, 12, 4, 2048, 512, 4);
+//   const input = new Float32Array(memory.buffer, analyzer.input_ptr(), analyzer.input_len());
+//   const output = new Float64Array(memory.buffer, analyzer.output_ptr(), analyzer.output_len());
+//   // in process():
+//   input.set(inputs[0][0]);
+//   const frames = analyzer.analyze_in_place(inputs[0][0].length);
+//   for (let i = 0; i < frames; i++) report(output.subarray(i * 5, (i + 1) * 5)); // [f0, F1, ...]
+//
+// Quanta that complete no frame only buffer samples and do not allocate. Each analysis
+// still allocates inside the LPC pipeline (the frame copy, coefficients and formant
+// list), so quanta that complete a hop do.
+//
+// Views are detached when wasm memory grows; recreate them when `byteLength` is 0.
+#[wasm_bindgen]
+pub struct WorkletAnalyzer {
+    stream: streaming::StreamingAnalyzer,
+    input: Vec<f32>,
+    output: Vec<f64>,
+    num_formants: usize,
+}
+
+#[wasm_bindgen]
+impl WorkletAnalyzer {
+    // Analyzes the last `frame_len` samples every `hop` samples and reports f0 and the
+    // first `num_formants` formants of each frame.
+    #[wasm_bindgen(constructor)]
+    pub fn new(
+        sample_rate: f64,
+        lpc_order: usize,
+        downsample_factor: usize,
+        frame_len: usize,
+        hop: usize,
+        num_formants: usize,
+        pre_emphasis: Option<f64>,
+    ) -> WorkletAnalyzer {
+        let analyzer = analyzer::Analyzer::new(lpc_order, sample_rate)
+            .with_downsample_factor(downsample_factor)
+            .with_pre_emphasis(pre_emphasis.unwrap_or(DEFAULT_PRE_EMPHASIS));
+        let stream = streaming::StreamingAnalyzer::new(analyzer, frame_len, hop);
+        // One block completes at most one frame per `hop` samples.
+        let max_frames = WORKLET_INPUT_CAPACITY.div_ceil(stream.hop());
+        WorkletAnalyzer {
+            stream,
+            input: vec![0.0; WORKLET_INPUT_CAPACITY],
+            output: vec![f64::NAN; max_frames * (num_formants + 1)],
+            num_formants,
+        }
+    }
+
+    // Address of the input buffer (`input_len()` f32 samples) in wasm memory.
+    pub fn input_ptr(&self) -> *const f32 {
+        self.input.as_ptr()
+    }
+
+    pub fn input_len(&self) -> usize {
+        self.input.len()
+    }
+
+    // Address of the output buffer (`output_len()` f64 values) in wasm memory: one row
+    // of `num_formants + 1` values per frame, [f0, F1, F2, ...], with -1 for an unvoiced
+    // f0 and NaN for missing formants. It has room for every frame one block can
+    // complete.
+    pub fn output_ptr(&self) -> *const f64 {
+        self.output.as_ptr()
+    }
+
+    pub fn output_len(&self) -> usize {
+        self.output.len()
+    }
+
+    // Appends the first `count` samples of the input buffer to the analysis frame.
+    // Returns the number of frames completed, written in time order to the first rows of
+    // the output buffer; 0 leaves the output untouched.
+    pub fn analyze_in_place(&mut self, count: usize) -> usize {
+        let count = count.min(self.input.len());
+        let width = self.num_formants + 1;
+        let output = &mut self.output;
+        let mut written = 0;
+        self.stream.push_each(&self.input[..count], |frame| {
+            let row = &mut output[written * width..(written + 1) * width];
+            row[0] = frame.pitch;
+            for (i, value) in row[1..].iter_mut().enumerate() {
+                *value = frame.formants.get(i).copied().unwrap_or(f64::NAN);
+            }
+            written += 1;
+        });
+        written
+    }
+
+    // Forgets the buffered samples, e.g. after the input device changes.
+    pub fn reset(&mut self) {
+        self.stream.reset();
+        self.output.fill(f64::NAN);
+    }
+}
+
+// Frames analyzed per `FormantBatch::step` unless `set_frames_per_step` is called.
+pub const DEFAULT_FRAMES_PER_STEP: usize = 32;
+
+// Whole-recording formant analysis that runs a bounded number of frames per `step`, so
+// the caller decides how often to yield to the event loop:
+//
+//   const batch = new FormantBatch(samples, 44100, 12, 4, 0.025, 0.01);
+//   batch.set_frames_per_step(8); // fewer frames per step: less jank, lower throughput
+//   while (!batch.step()) await new Promise((r) => setTimeout(r, 0));
+//   const formants = batch.formants(4);
+#[wasm_bindgen]
+pub struct FormantBatch {
+    signal: Vec<f64>,
+    analyzer: analyzer::Analyzer,
+    frame_len: usize,
+    hop: usize,
+    frames_per_step: usize,
+    results: Vec<batch::TrackFrame>,
+    vad: Option<vad::Vad>,
+}
+
+#[wasm_bindgen]
+impl FormantBatch {
+    // `frame_duration` and `time_step` are in seconds.
+    #[wasm_bindgen(constructor)]
+    pub fn new(
+        signal: Vec<f64>,
+        sample_rate: f64,
+        lpc_order: usize,
+        downsample_factor: usize,
+        frame_duration: f64,
+        time_step: f64,
+        pre_emphasis: Option<f64>,
+    ) -> FormantBatch {
+        FormantBatch {
+            signal,
+            analyzer: analyzer::Analyzer::new(lpc_order, sample_rate)
+                .with_downsample_factor(downsample_factor)
+                .with_pre_emphasis(pre_emphasis.unwrap_or(DEFAULT_PRE_EMPHASIS)),
+            frame_len: ((frame_duration * sample_rate).round() as usize).max(1),
+            hop: ((time_step * sample_rate).round() as usize).max(1),
+            frames_per_step: DEFAULT_FRAMES_PER_STEP,
+            results: Vec::new(),
+            vad: None,
+        }
+    }
+
+    // Analyzes a decoded `AudioBuffer` (e.g. from `decodeAudioData`) at its own sample
+    // rate: one `channel`, or the average of all channels when it is omitted. Throws if
+    // `channel` does not exist.
+    pub fn from_audio_buffer(
+        buffer: &web_sys::AudioBuffer,
+        channel: Option<u32>,
+        lpc_order: usize,
+        downsample_factor: usize,
+        frame_duration: f64,
+        time_step: f64,
+        pre_emphasis: Option<f64>,
+    ) -> Result<FormantBatch, JsError> {
+        let signal = downmix(
+            buffer.number_of_channels(),
+            buffer.length() as usize,
+            channel,
+            |c, out| {
+                buffer
+                    .copy_from_channel(out, c as i32)
+                    .map_err(|_| format!("cannot read channel {} of the AudioBuffer", c))
+            },
+        )
+        .map_err(|e| arg_error("FormantBatch.from_audio_buffer", "buffer", e))?;
+        Ok(FormantBatch::new(
+            signal,
+            buffer.sample_rate() as f64,
+            lpc_order,
+            downsample_factor,
+            frame_duration,
+            time_step,
+            pre_emphasis,
+        ))
+    }
+
+    // Analyzes `signal` with the settings of an `AnalysisConfig`, including its VAD
+    // setting.
+    pub fn from_config(
+        signal: Vec<f64>,
+        sample_rate: f64,
+        config: &AnalysisConfig,
+    ) -> FormantBatch {
+        let config = &config.inner;
+        let mut signal = signal;
+        if let Some(limit) = config.sanitize {
+            sanitize::sanitize_in_place(&mut signal, limit);
+        }
+        let frame_len = config.frame_len(sample_rate).max(1);
+        let hop = config.hop(sample_rate).max(1);
+        FormantBatch {
+            vad: config
+                .vad
+                .map(|vad| vad::Vad::for_recording(vad, &signal, frame_len, hop)),
+            analyzer: config.analyzer(sample_rate),
+            signal,
+            frame_len,
+            hop,
+            frames_per_step: DEFAULT_FRAMES_PER_STEP,
+            results: Vec::new(),
+        }
+    }
+
+    // Turns voice activity detection on or off for the frames not yet analyzed (see
+    // `StreamingAnalyzer.set_vad`); the noise floor is taken from the whole signal.
+    pub fn set_vad(&mut self, enabled: bool, threshold_db: Option<f64>) {
+        self.vad = enabled.then(|| {
+            vad::Vad::for_recording(
+                vad_config(threshold_db),
+                &self.signal,
+                self.frame_len,
+                self.hop,
+            )
+        });
+    }
+
+    // Sets how many frames each `step` analyzes (at least 1). Can be changed between
+    // steps, e.g. lowered while the user is interacting.
+    pub fn set_frames_per_step(&mut self, frames_per_step: usize) {
+        self.frames_per_step = frames_per_step.max(1);
+    }
+
+    pub fn frames_per_step(&self) -> usize {
+        self.frames_per_step
+    }
+
+    pub fn total_frames(&self) -> usize {
+        if self.signal.len() < self.frame_len {
+            0
+        } else {
+            (self.signal.len() - self.frame_len) / self.hop + 1
+        }
+    }
+
+    // Analyzes the next chunk of frames; returns true once every frame is done. With
+    // the `threads` feature the frames of a chunk are analyzed in parallel.
+    pub fn step(&mut self) -> bool {
+        let total = self.total_frames();
+        let end = (self.results.len() + self.frames_per_step).min(total);
+        let frames = self.analyze_range(self.results.len()..end);
+        self.results.extend(frames);
+        self.results.len() == total
+    }
+
+    // Fraction of frames analyzed, in [0, 1].
+    pub fn progress(&self) -> f64 {
+        match self.total_frames() {
+            0 => 1.0,
+            total => self.results.len() as f64 / total as f64,
+        }
+    }
+
+    // Centre times, in seconds, of the frames analyzed so far.
+    pub fn times(&self) -> Vec<f64> {
+        self.results.iter().map(|f| f.time).collect()
+    }
+
+    // f0 of each frame analyzed so far, or -1 where unvoiced.
+    pub fn pitch(&self) -> Vec<f64> {
+        self.results.iter().map(|f| f.pitch).collect()
+    }
+
+    // Mean-square amplitude of each frame analyzed so far.
+    pub fn energy(&self) -> Vec<f64> {
+        self.results.iter().map(|f| f.energy).collect()
+    }
+
+    // 1 for each voiced frame analyzed so far, 0 otherwise.
+    pub fn voicing(&self) -> Vec<u8> {
+        self.results.iter().map(|f| f.voiced as u8).collect()
+    }
+
+    // Every frame analyzed so far, with time, formants, f0, energy and voicing.
+    #[wasm_bindgen(unchecked_return_type = "TrackFrame[]")]
+    pub fn frames(&self) -> JsValue {
+        frames::to_js(&self.track_frames())
+    }
+
+    // The frames analyzed so far as JSON, for saving a session; reload them with
+    // `track_frames_from_json`.
+    pub fn to_json(&self) -> String {
+        json::ToJson::to_json(&self.results)
+    }
+
+    // Returns the first `count` formants of each frame analyzed so far, row-major
+    // (frames x count), with NaN where a frame has fewer formants.
+    pub fn formants(&self, count: usize) -> Vec<f64> {
+        self.results
+            .iter()
+            .flat_map(|f| (0..count).map(move |i| f.formants.get(i).copied().unwrap_or(f64::NAN)))
+            .collect()
+    }
+
+    // Like `formants`, but each track is cleaned up for display or export: values more
+    // than `max_deviation` (a fraction, e.g. 0.25) from the running median of
+    // `median_width` frames become NaN, the rest are median filtered, and `kalman` adds a
+    // constant-velocity Kalman filter on top.
+    pub fn smoothed_formants(
+        &self,
+        count: usize,
+        median_width: usize,
+        max_deviation: f64,
+        kalman: bool,
+    ) -> Vec<f64> {
+        let config = smoothing::FormantSmoothing {
+            median_width,
+            max_deviation,
+            kalman: kalman.then(smoothing::KalmanConfig::default),
+        };
+        smoothing::smooth_formant_tracks(&self.results, count, &config).concat()
+    }
+
+    // Returns a `VibratoFrame` for each voiced run of the frames analyzed so far, as in
+    // `vibrato_segments`.
+    #[wasm_bindgen(unchecked_return_type = "VibratoFrame[]")]
+    pub fn vibrato(&self) -> JsValue {
+        frames::to_js(&self.vibrato_frames())
+    }
+
+    // Like `pitch`, but cleaned up for intonation plots; see `smooth_pitch_contour`.
+    pub fn smoothed_pitch(
+        &self,
+        median_width: usize,
+        max_gap: usize,
+        octave_correction: bool,
+    ) -> Vec<f64> {
+        smooth_pitch_contour(&self.pitch(), median_width, max_gap, octave_correction)
+    }
+}
+
+impl FormantBatch {
+    // Analyzes the frames with indices in `range`. The VAD adapts as it goes, so it
+    // classifies the frames in order before any of them is analyzed.
+    fn analyze_range(&mut self, range: std::ops::Range<usize>) -> Vec<batch::TrackFrame> {
+        let (signal, frame_len, hop) = (&self.signal, self.frame_len, self.hop);
+        let frame = |i: usize| &signal[i * hop..i * hop + frame_len];
+        let active: Vec<bool> = match &mut self.vad {
+            Some(vad) => range.clone().map(|i| vad.is_active(frame(i))).collect(),
+            None => vec![true; range.len()],
+        };
+        let analyzer = &self.analyzer;
+        let analyze = |(i, &active): (usize, &bool)| {
+            let start = (i * hop) as u64;
+            if active {
+                batch::TrackFrame::analyze(analyzer, frame(i), start)
+            } else {
+                batch::TrackFrame::silent(frame(i), start, analyzer.sample_rate())
+            }
+        };
+        #[cfg(feature = "threads")]
+        let frames = {
+            use rayon::prelude::*;
+            range.into_par_iter().zip(&active).map(analyze).collect()
+        };
+        #[cfg(not(feature = "threads"))]
+        let frames = range.zip(&active).map(analyze).collect();
+        frames
+    }
+
+    fn track_frames(&self) -> Vec<TrackFrame> {
+        self.results.iter().cloned().map(TrackFrame::from).collect()
+    }
+
+    fn vibrato_frames(&self) -> Vec<VibratoFrame> {
+        vibrato::vibrato_frames(&self.results, &vibrato::VibratoConfig::default())
+            .into_iter()
+            .map(VibratoFrame::from)
+            .collect()
+    }
+}
+
+// Cleans up an f0 contour (Hz per frame, -1 or NaN where unvoiced): octave jumps
+// against the surrounding 15 frames are folded back when `octave_correction` is set,
+// then a running median of `median_width` frames is applied and unvoiced gaps of up to
+// `max_gap` frames are interpolated on a semitone scale. Returns -1 where still
+// unvoiced, like `pitch_detection`.
+#[wasm_bindgen]
+pub fn smooth_pitch_contour(
+    track: &[f64],
+    median_width: usize,
+    max_gap: usize,
+    octave_correction: bool,
+) -> Vec<f64> {
+    let config = smoothing::PitchSmoothing {
+        median_width,
+        max_gap,
+        octave_window: if octave_correction {
+            smoothing::PitchSmoothing::default().octave_window
+        } else {
+            0
+        },
+    };
+    smoothing::smooth_pitch(track, &config)
+        .into_iter()
+        .map(|f0| if f0.is_nan() { -1.0 } else { f0 })
+        .collect()
+}
+
+// Running per-speaker statistics for a session: feed it each frame's formants and f0
+// and read back averages, percentiles and the vowel space without keeping the frames in
+// JS.
+//
+//   const stats = new SpeakerStats();
+//   stats.push(formants, f0); // per voiced frame
+//   const { meanFormants, vowelSpaceArea } = stats.summary();
+#[wasm_bindgen]
+#[derive(Default)]
+pub struct SpeakerStats {
+    inner: speaker::SpeakerStats,
+}
+
+#[wasm_bindgen]
+impl SpeakerStats {
+    #[wasm_bindgen(constructor)]
+    pub fn new() -> SpeakerStats {
+        SpeakerStats::default()
+    }
+
+    // Adds one frame: formants in Hz (NaN where missing) and f0 in Hz (-1 if unvoiced).
+    pub fn push(&mut self, formants: &[f64], f0: f64) {
+        self.inner.push(formants, f0);
+    }
+
+    // Adds the voiced frames `batch` has analyzed so far.
+    pub fn push_batch(&mut self, batch: &FormantBatch) {
+        for frame in &batch.results {
+            self.inner.push_frame(frame);
+        }
+    }
+
+    // Number of frames added.
+    pub fn frames(&self) -> usize {
+        self.inner.frames()
+    }
+
+    #[wasm_bindgen(unchecked_return_type = "SpeakerStatsFrame")]
+    pub fn summary(&self) -> JsValue {
+        frames::to_js(&SpeakerStatsFrame::from(&self.inner))
+    }
+
+    pub fn reset(&mut self) {
+        self.inner.reset();
+    }
+}
+
+// All analysis settings in one object that can be saved as JSON and handed to
+// `FormantBatch.from_config`, `StreamingAnalyzer.from_config` or analyze directly.
+// Unset options follow the quality tier ("fast", "balanced" or "accurate"); the LPC
+// order defaults to two per formant.
+//
+//   const config = AnalysisConfig.from_json(localStorage.settings ?? "{}");
+//   config.set_pitch_range(75, 300);
+//   localStorage.settings = config.to_json();
+//   const batch = FormantBatch.from_config(signal, sampleRate, config);
+#[wasm_bindgen]
+#[derive(Default)]
+pub struct AnalysisConfig {
+    inner: config::AnalysisConfig,
+}
+
+#[wasm_bindgen]
+impl AnalysisConfig {
+    #[wasm_bindgen(constructor)]
+    pub fn new() -> AnalysisConfig {
+        AnalysisConfig::default()
+    }
+
+    // Parses settings saved by `to_json`; missing fields keep their defaults. Throws if
+    // `json` is malformed or a value is out of range.
+    pub fn from_json(json: &str) -> Result<AnalysisConfig, JsError> {
+        let inner: config::AnalysisConfig =
+            json::from_json(json).map_err(|e| arg_error("AnalysisConfig.from_json", "json", e))?;
+        inner
+            .validate()
+            .map_err(|e| js_error("AnalysisConfig.from_json", e))?;
+        Ok(AnalysisConfig { inner })
+    }
+
+    pub fn to_json(&self) -> String {
+        json::ToJson::to_json(&self.inner)
+    }
+
+    // Throws naming the first setting out of range.
+    pub fn validate(&self) -> Result<(), JsError> {
+        self.inner
+            .validate()
+            .map_err(|e| js_error("AnalysisConfig.validate", e))
+    }
+
+    pub fn set_quality(&mut self, quality: &str) -> Result<(), JsError> {
+        self.inner.quality = parse_arg("AnalysisConfig.set_quality", "quality", quality)?;
+        Ok(())
+    }
+
+    // "hamming" or "gaussian"; omit to use the quality tier's window.
+    pub fn set_window(&mut self, window: Option<String>) -> Result<(), JsError> {
+        self.inner.window = window
+            .map(|w| parse_arg("AnalysisConfig.set_window", "window", &w))
+            .transpose()?;
+        Ok(())
+    }
+
+    pub fn set_pre_emphasis(&mut self, alpha: Option<f64>) {
+        self.inner.pre_emphasis = alpha;
+    }
+
+    // Omit to use two poles per formant.
+    pub fn set_lpc_order(&mut self, order: Option<usize>) {
+        self.inner.lpc_order = order;
+    }
+
+    pub fn lpc_order(&self) -> usize {
+        self.inner.lpc_order()
+    }
+
+    pub fn set_max_formant(&mut self, max_formant_hz: f64) {
+        self.inner.max_formant = max_formant_hz;
+    }
+
+    pub fn set_num_formants(&mut self, count: usize) {
+        self.inner.num_formants = count;
+    }
+
+    pub fn set_pitch_range(&mut self, floor: f64, ceiling: f64) {
+        self.inner.pitch_range = Some((floor, ceiling));
+    }
+
+    // `frame_duration` and `time_step` are in seconds.
+    pub fn set_framing(&mut self, frame_duration: f64, time_step: f64) {
+        self.inner.frame_duration = frame_duration;
+        self.inner.time_step = time_step;
+    }
+
+    // See `StreamingAnalyzer.set_vad`.
+    pub fn set_vad(&mut self, enabled: bool, threshold_db: Option<f64>) {
+        self.inner.vad = enabled.then(|| vad_config(threshold_db));
+    }
+
+    // See `StreamingAnalyzer.set_sanitize`.
+    pub fn set_sanitize(&mut self, enabled: bool, limit: Option<f64>) {
+        self.inner.sanitize = enabled.then(|| limit.unwrap_or(sanitize::DEFAULT_LIMIT));
+    }
+
+    // Analyzes a single frame, already cut to any length, as frame 0. Throws for an
+    // empty or non-finite frame, or one too short for the LPC order.
+    #[wasm_bindgen(unchecked_return_type = "TrackFrame")]
+    pub fn analyze_frame(&self, frame: &[f64], sample_rate: f64) -> Result<JsValue, JsError> {
+        self.inner
+            .analyzer(sample_rate)
+            .validate(frame)
+            .map_err(|e| arg_error("AnalysisConfig.analyze_frame", "frame", e))?;
+        Ok(frames::to_js(&self.track_frame(frame, sample_rate)))
+    }
+
+    // Analyzes a whole recording in one call.
+    #[wasm_bindgen(unchecked_return_type = "TrackFrame[]")]
+    pub fn analyze(&self, signal: &[f64], sample_rate: f64) -> JsValue {
+        frames::to_js(&self.track_frames(signal, sample_rate))
+    }
+}
+
+impl AnalysisConfig {
+    fn track_frame(&self, frame: &[f64], sample_rate: f64) -> TrackFrame {
+        let analyzer = self.inner.analyzer(sample_rate);
+        TrackFrame::from(batch::TrackFrame::analyze(&analyzer, frame, 0))
+    }
+
+    fn track_frames(&self, signal: &[f64], sample_rate: f64) -> Vec<TrackFrame> {
+        self.inner
+            .analyze(signal, sample_rate)
+            .into_iter()
+            .map(TrackFrame::from)
+            .collect()
+    }
+}
+
+// Repairs `samples` in place before analysis: NaN becomes 0 and infinities and values
+// beyond `limit` (default 1e6) are clamped to it. Returns a `SanitizeFrame` counting
+// the repaired samples, so a glitch can be reported instead of analyzed.
+//
+//   const { nonFinite } = sanitize_samples(timeData);
+//   if (nonFinite > 0) console.warn(`${nonFinite} NaN samples from the input device`);
+#[wasm_bindgen(unchecked_return_type = "SanitizeFrame")]
+pub fn sanitize_samples(samples: &mut [f32], limit: Option<f64>) -> JsValue {
+    frames::to_js(&sanitize_frame(samples, limit))
+}
+
+// Parses frames saved by `FormantBatch.to_json`, or throws an `Error` if `json` does
+// not hold an array of frames.
+#[wasm_bindgen(unchecked_return_type = "TrackFrame[]")]
+pub fn track_frames_from_json(json: &str) -> Result<JsValue, JsError> {
+    let frames: Vec<batch::TrackFrame> =
+        json::from_json(json).map_err(|e| arg_error("track_frames_from_json", "json", e))?;
+    let frames: Vec<TrackFrame> = frames.into_iter().map(TrackFrame::from).collect();
+    Ok(frames::to_js(&frames))
+}
+
+// Returns the pre-emphasis coefficient for Praat's "pre-emphasis from" `frequency`
+// (50 Hz in Praat) at `sample_rate`. Pass the rate after downsampling.
+#[wasm_bindgen]
+pub fn pre_emphasis_from_frequency(frequency: f64, sample_rate: f64) -> f64 {
+    ezformant::pre_emphasis_from_frequency(frequency, sample_rate)
+}
+
+// Formant dispersion in Hz, (F_n - F_1) / (n - 1), or NaN with fewer than two formants.
+#[wasm_bindgen]
+pub fn formant_dispersion(formants: &[f64]) -> f64 {
+    stats::dispersion(formants).unwrap_or(f64::NAN)
+}
+
+// Formant spacing in Hz (uniform-tube fit), or NaN if `formants` is empty.
+#[wasm_bindgen]
+pub fn formant_spacing(formants: &[f64]) -> f64 {
+    stats::spacing(formants).unwrap_or(f64::NAN)
+}
+
+// Frequency scale conversions, using the same formulas as the `units` options above.
+
+#[wasm_bindgen]
+pub fn hz_to_bark(hz: f64) -> f64 {
+    units::FrequencyUnit::Bark.from_hertz(hz)
+}
+
+#[wasm_bindgen]
+pub fn bark_to_hz(bark: f64) -> f64 {
+    units::FrequencyUnit::Bark.to_hertz(bark)
+}
+
+#[wasm_bindgen]
+pub fn hz_to_erb(hz: f64) -> f64 {
+    units::FrequencyUnit::Erb.from_hertz(hz)
+}
+
+#[wasm_bindgen]
+pub fn erb_to_hz(erb: f64) -> f64 {
+    units::FrequencyUnit::Erb.to_hertz(erb)
+}
+
+#[wasm_bindgen]
+pub fn hz_to_mel(hz: f64) -> f64 {
+    units::FrequencyUnit::Mel.from_hertz(hz)
+}
+
+#[wasm_bindgen]
+pub fn mel_to_hz(mel: f64) -> f64 {
+    units::FrequencyUnit::Mel.to_hertz(mel)
+}
+
+// Semitones relative to 100 Hz.
+#[wasm_bindgen]
+pub fn hz_to_semitones(hz: f64) -> f64 {
+    units::FrequencyUnit::Semitones.from_hertz(hz)
+}
+
+#[wasm_bindgen]
+pub fn semitones_to_hz(semitones: f64) -> f64 {
+    units::FrequencyUnit::Semitones.to_hertz(semitones)
+}
+
+// Fractional MIDI note number; 69 is A4 at 440 Hz.
+#[wasm_bindgen]
+pub fn hz_to_midi(hz: f64) -> f64 {
+    units::FrequencyUnit::Midi.from_hertz(hz)
+}
+
+#[wasm_bindgen]
+pub fn midi_to_hz(note: f64) -> f64 {
+    units::FrequencyUnit::Midi.to_hertz(note)
+}
+
+// Cents relative to `reference_hz`.
+#[wasm_bindgen]
+pub fn hz_to_cents(hz: f64, reference_hz: f64) -> f64 {
+    units::FrequencyUnit::Cents { reference_hz }.from_hertz(hz)
+}
+
+#[wasm_bindgen]
+pub fn cents_to_hz(cents: f64, reference_hz: f64) -> f64 {
+    units::FrequencyUnit::Cents { reference_hz }.to_hertz(cents)
+}
+
+// Normalizes one speaker's vowel formants, given row-major (tokens x `count`, as
+// returned by `FormantBatch.formants`) in Hz with NaN where missing. `method` is
+// "lobanov" (z-scores), "nearey1" (log-mean per formant), "nearey2" (log-mean over all
+// formants) or "bark" (Z3-Z1, Z3-Z2, Z2-Z1 per token, so 3 values per row). Call it
+// once per speaker; throws for an unknown method or a ragged array.
+#[wasm_bindgen]
+pub fn normalize_formants(
+    formants: &[f64],
+    count: usize,
+    method: &str,
+) -> Result<Vec<f64>, JsError> {
+    let method: normalization::Normalization = parse_arg("normalize_formants", "method", method)?;
+    if count == 0 || !formants.len().is_multiple_of(count) {
+        return Err(arg_error(
+            "normalize_formants",
+            "formants",
+            "must hold a multiple of `count` values",
+        ));
+    }
+    let tokens: Vec<Vec<f64>> = formants.chunks(count).map(<[f64]>::to_vec).collect();
+    Ok(normalization::normalize(&tokens, method).concat())
+}
+
+// Converts `values` in place from Hz to `units` (see `formant_detection_with_units`);
+// non-positive values such as the -1 "no pitch" marker are left unchanged.
+#[wasm_bindgen]
+pub fn convert_frequencies(values: &mut [f64], units: &str) -> Result<(), JsError> {
+    let units: units::FrequencyUnit = parse_arg("convert_frequencies", "units", units)?;
+    units.convert_in_place(values);
+    Ok(())
+}
+
+// ------------------
+// Helpers
+// ------------------
+
+// Pitch-synchronous analysis with the JS-facing optional arguments resolved.
+fn synchronous_formants(
+    signal: &[f64],
+    analyzer: &analyzer::Analyzer,
+    closed_phase_fraction: Option<f64>,
+    gci_window: Option<f64>,
+) -> Vec<pitch_synchronous::SynchronousFrame> {
+    let window = match closed_phase_fraction {
+        Some(fraction) if fraction <= 0.0 => pitch_synchronous::SynchronousWindow::Cycles(2),
+        Some(fraction) => pitch_synchronous::SynchronousWindow::ClosedPhase { fraction },
+        None => pitch_synchronous::SynchronousWindow::default(),
+    };
+    let mut gci = glottal::GciConfig::default();
+    if let Some(window_duration) = gci_window {
+        gci.window_duration = window_duration;
+    }
+    pitch_synchronous::pitch_synchronous_formants(
+        signal,
+        analyzer,
+        &pitch_synchronous::PitchSynchronousConfig { window, gci },
+    )
+}
+
+// Rejects frames the LPC model cannot fit: empty, non-finite, or no longer than
+// `lpc_order` once downsampled by `downsample_factor`. The error names the exported
+// `function` and its `argument` holding the frame; a `downsample_factor` of 0 is
+// reported against the function alone.
+fn check_frame(
+    function: &str,
+    argument: &str,
+    data: &[f64],
+    lpc_order: usize,
+    downsample_factor: usize,
+) -> Result<(), JsError> {
+    check_downsampled_frame(data, lpc_order, downsample_factor).map_err(|e| match e {
+        error::EzFormantError::InvalidParameter { .. } => js_error(function, e),
+        _ => arg_error(function, argument, e),
+    })
+}
+
+// `lpc::check_frame` for a frame decimated by `downsample_factor` before the analysis.
+fn check_downsampled_frame(
+    data: &[f64],
+    lpc_order: usize,
+    downsample_factor: usize,
+) -> error::Result<()> {
+    if downsample_factor == 0 {
+        return Err(error::EzFormantError::InvalidParameter {
+            name: "downsample_factor",
+            value: 0.0,
+        });
+    }
+    lpc::check_frame(data, lpc_order * downsample_factor)
+}
+
+// Checks LPC coefficients passed in from JS, see `lpc::check_coefficients`.
+fn check_coefficients(
+    function: &str,
+    coefficients: &[f64],
+    sample_rate: f64,
+) -> Result<(), JsError> {
+    lpc::check_coefficients(coefficients, sample_rate).map_err(|e| match e {
+        error::EzFormantError::InvalidParameter { .. } => js_error(function, e),
+        _ => arg_error(function, "coefficients", e),
+    })
+}
+
+// An `Error` prefixed with the exported function (`Class.method` for methods) that
+// failed, e.g. "welch_psd: input has 10 samples but at least 256 are required".
+fn js_error(function: &str, error: impl std::fmt::Display) -> JsError {
+    JsError::new(&format!("{}: {}", function, error))
+}
+
+// Like `js_error`, also naming the offending argument, e.g.
+// "formant_frame: `data`: input is empty".
+fn arg_error(function: &str, argument: &str, error: impl std::fmt::Display) -> JsError {
+    js_error(function, format!("`{}`: {}", argument, error))
+}
+
+// Parses the string `argument` of `function`, e.g. a window or colormap name.
+fn parse_arg<T>(function: &str, argument: &str, value: &str) -> Result<T, JsError>
+where
+    T: std::str::FromStr,
+    T::Err: std::fmt::Display,
+{
+    value.parse().map_err(|e| arg_error(function, argument, e))
+}
+
+// `sanitize_samples` on a Float32Array.
+fn sanitize_frame(samples: &mut [f32], limit: Option<f64>) -> SanitizeFrame {
+    let limit = limit.unwrap_or(sanitize::DEFAULT_LIMIT);
+    let mut report = sanitize::SanitizeReport::default();
+    for sample in samples.iter_mut() {
+        *sample = report.repair(*sample as f64, limit) as f32;
+    }
+    SanitizeFrame::from(report)
+}
+
+// Converts samples passed as a Float32Array to the f64 used by the analysis.
+fn widen(data: &[f32]) -> Vec<f64> {
+    data.iter().map(|&x| x as f64).collect()
+}
+
+// Reads `len` samples from one channel (`read` fills a buffer from channel `c`), or
+// from every channel when `channel` is `None`, and mixes them with `mix_to_mono`.
+fn downmix(
+    count: u32,
+    len: usize,
+    channel: Option<u32>,
+    mut read: impl FnMut(u32, &mut [f32]) -> Result<(), String>,
+) -> Result<Vec<f64>, String> {
+    let channels = match channel {
+        Some(c) if c < count => c..c + 1,
+        None if count > 0 => 0..count,
+        Some(c) => return Err(format!("channel {} out of range ({} channels)", c, count)),
+        None => return Err("no channels".to_string()),
+    };
+    let mut planar = Vec::with_capacity(channels.len());
+    for c in channels {
+        let mut samples = vec![0.0f32; len];
+        read(c, &mut samples)?;
+        planar.push(samples);
+    }
+    Ok(mix_to_mono(&planar))
+}
+
+// Maps the JS-facing `floor_db` argument to a floor: a positive value is a floor relative
+// to the frame's peak, anything else selects the default absolute floor.
+fn spectral_floor(floor_db: f64) -> spectrum::SpectralFloor {
+    if floor_db > 0.0 {
+        spectrum::SpectralFloor::RelativeDb(floor_db)
+    } else {
+        spectrum::SpectralFloor::default()
+    }
+}
+
+// Segmentation with the JS-facing optional arguments resolved.
+fn segmentation_frame(
+    signal: &[f64],
+    sampling_rate: f64,
+    frame_duration: f64,
+    time_step: f64,
+    vad_threshold_db: Option<f64>,
+) -> SegmentationFrame {
+    let config = batch::BatchConfig {
+        frame_duration,
+        time_step,
+        vad: Some(vad_config(vad_threshold_db)),
+        ..batch::BatchConfig::default()
+    };
+    SegmentationFrame::from(segmentation::segment_signal(signal, sampling_rate, &config))
+}
+
+// The default VAD with the JS-facing optional margin applied.
+fn vad_config(threshold_db: Option<f64>) -> vad::VadConfig {
+    let defaults = vad::VadConfig::default();
+    vad::VadConfig {
+        threshold_db: threshold_db.unwrap_or(defaults.threshold_db),
+        ..defaults
+    }
+}
+
+// ------------------
+// Tests
+// ------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::f64::consts::PI;
+
+    /// Helper function to manually downsample the data.
+    fn manual_downsample(data: &[f64], factor: usize) -> Vec<f64> {
+        data.iter().step_by(factor).cloned().collect()
+    }
+
+    #[test]
+    fn test_pitch_detection_f32_matches_f64() {
+        let sample_rate = 16000.0;
+        let signal: Vec<f64> = (0..2048)
+            .map(|n| (2.0 * PI * 196.0 * (n as f64) / sample_rate).sin())
+            .collect();
+        let signal_f32: Vec<f32> = signal.iter().map(|&x| x as f32).collect();
+
+        let f0 = pitch_detection_with_threshold(&signal, sample_rate, 0.1);
+        let f0_f32 = pitch_detection_f32(&signal_f32, sample_rate, 0.1);
+
+        assert!((f0 - 196.0).abs() < 0.5, "f0 = {}", f0);
+        assert!((f0 - f0_f32).abs() < 0.01);
+    }
+
+    #[test]
+    fn test_frame_checks_reject_a_zero_downsample_factor() {
+        let data = synthetic_vowel(16000.0, 0.05);
+        assert!(check_downsampled_frame(&data, 10, 4).is_ok());
+        assert!(matches!(
+            check_downsampled_frame(&data, 10, 0),
+            Err(error::EzFormantError::InvalidParameter {
+                name: "downsample_factor",
+                ..
+            })
+        ));
+        assert!(matches!(
+            check_downsampled_frame(&data[..40], 10, 4),
+            Err(error::EzFormantError::InputTooShort { required: 41, .. })
+        ));
+    }
+
+    #[test]
+    fn test_downmix_averages_or_selects_channels() {
+        let read = |c: u32, out: &mut [f32]| {
+            out.fill(c as f32 + 1.0);
+            Ok(())
+        };
+        assert_eq!(downmix(2, 3, None, read).unwrap(), vec![1.5; 3]);
+        assert_eq!(downmix(2, 3, Some(1), read).unwrap(), vec![2.0; 3]);
+        assert!(downmix(2, 3, Some(2), read).is_err());
+        assert!(downmix(0, 3, None, read).is_err());
+    }
+
+    #[test]
+    fn test_worklet_analyzer_reports_every_hop() {
+        let sample_rate = 16000.0;
+        let signal: Vec<f32> = synthetic_vowel(sample_rate, 0.5)
+            .iter()
+            .map(|&x| x as f32)
+            .collect();
+        let mut worklet = WorkletAnalyzer::new(sample_rate, 12, 2, 1024, 512, 3, None);
+        let (input_ptr, output_ptr) = (worklet.input_ptr(), worklet.output_ptr());
+
+        let mut updates = 0;
+        for quantum in signal.chunks(128) {
+            worklet.input[..quantum.len()].copy_from_slice(quantum);
+            updates += worklet.analyze_in_place(quantum.len());
+        }
+        // The first frame is complete after 8 quanta, then one analysis every 4.
+        assert_eq!(updates, 1 + (signal.len() - 1024) / 512);
+        // The buffers never move.
+        assert_eq!(worklet.input_ptr(), input_ptr);
+        assert_eq!(worklet.output_ptr(), output_ptr);
+
+        let end = 1024 + (updates - 1) * 512;
+        let last_frame: Vec<f64> = signal[end - 1024..end].iter().map(|&x| x as f64).collect();
+        let analyzer = analyzer::Analyzer::new(12, sample_rate).with_downsample_factor(2);
+        let formants = analyzer.formants(&last_frame);
+        assert_eq!(worklet.output[0], analyzer.pitch(&last_frame));
+        assert_eq!(&worklet.output[1..4], &formants[..3]);
+
+        // A hop shorter than the block: every frame gets its own row.
+        let mut worklet = WorkletAnalyzer::new(sample_rate, 12, 2, 1024, 64, 3, None);
+        worklet.input[..1024].copy_from_slice(&signal[..1024]);
+        assert_eq!(worklet.analyze_in_place(1024), 1);
+        worklet.input[..128].copy_from_slice(&signal[1024..1152]);
+        assert_eq!(worklet.analyze_in_place(128), 2);
+        for (row, end) in worklet.output.chunks(4).zip([1088, 1152]) {
+            let frame: Vec<f64> = signal[end - 1024..end].iter().map(|&x| x as f64).collect();
+            assert_eq!(row[0], analyzer.pitch(&frame));
+            assert_eq!(&row[1..], &analyzer.formants(&frame)[..3]);
+        }
+
+        worklet.reset();
+        assert!(worklet.output.iter().all(|x| x.is_nan()));
+    }
+
+    #[test]
+    #[allow(deprecated)]
+    fn test_formant_detection_f32_matches_f64() {
+        let sample_rate = 48000.0;
+        let data = synthetic_vowel(sample_rate, 0.05);
+        let data_f32: Vec<f32> = data.iter().map(|&x| x as f32).collect();
+
+        let formants = formant_detection_with_downsampling(data, 10, sample_rate, 4, None);
+        let monitor = FrameMonitor::new();
+        for formants_f32 in [
+            formant_detection_f32(&data_f32, 10, sample_rate, 4, None),
+            monitor.formant_detection_f32(&data_f32, 10, sample_rate, 4, None),
+        ] {
+            assert_eq!(formants.len(), formants_f32.len());
+            for (a, b) in formants.iter().zip(&formants_f32) {
+                assert!((a - b).abs() < 0.1, "{} vs {}", a, b);
+            }
+        }
+        assert_eq!(monitor.counters()[0], 1.0);
+    }
+
+    #[test]
+    #[allow(deprecated)]
+    fn test_short_frames_have_no_formants() {
+        let sample_rate = 16000.0;
+        let short: Vec<f64> = (0..12).map(|n| (n as f64 * 0.7).sin()).collect();
+        assert!(formant_detection(short.clone(), 12, sample_rate, None).is_empty());
+        assert!(peak_detection_with_metadata(short.clone(), 12, sample_rate, None).is_empty());
+        assert!(
+            formant_detection_with_downsampling(short.clone(), 12, sample_rate, 4, None).is_empty()
+        );
+
+        // 2 ms frames hold 8 samples after downsampling by 4.
+        let mut stream = StreamingAnalyzer::new(sample_rate, 12, 4, 0.002, 0.001, None);
+        let chunk: Vec<f32> = synthetic_vowel(sample_rate, 0.05)
+            .iter()
+            .map(|&x| x as f32)
+            .collect();
+        let frames = stream.push_frames(&chunk);
+        assert!(!frames.is_empty());
+        assert!(frames.iter().all(|f| f.formants.is_empty()));
+        assert_eq!(stream.counters()[2], frames.len() as f64);
+    }
+
+    #[test]
+    #[allow(deprecated)]
+    fn test_flat_wrapper_matches_formant_frame() {
+        let sample_rate = 16000.0;
+        let data = synthetic_vowel(sample_rate, 0.05);
+
+        let flat = lpc_filter_freq_response_with_peaks(data.clone(), 12, sample_rate, 256, None);
+        let frame = FormantFrame::analyze(
+            data.clone(),
+            12,
+            sample_rate,
+            256,
+            spectrum::SpectralFloor::default(),
+            DEFAULT_PRE_EMPHASIS,
+        );
+        let envelope = frame.envelope.unwrap();
+
+        assert_eq!(flat.len(), 4 + envelope.magnitudes.len());
+        assert_eq!(&flat[..4], &frame.formants[..4]);
+        assert_eq!(&flat[4..], &envelope.magnitudes[..]);
+        assert_eq!(envelope.frequencies.len(), envelope.magnitudes.len());
+
+        let legacy = lpc_filter_freq_response(data.clone(), 12, sample_rate, 256, None);
+        assert_eq!(legacy, envelope.magnitudes);
+        assert_eq!(
+            formant_detection(data, 12, sample_rate, None),
+            frame.formants
+        );
+    }
+
+    #[test]
+    fn test_try_formant_detection_matches_unchecked() {
+        // Only the success path: constructing a `JsError` needs a JS runtime.
+        let sample_rate = 16000.0;
+        let data = synthetic_vowel(sample_rate, 0.05);
+
+        let checked = try_formant_detection(&data, 12, sample_rate, 2, "balanced", None).ok();
+        let unchecked =
+            formant_detection_with_quality(data, 12, sample_rate, 2, "balanced", None).unwrap();
+        assert_eq!(checked, Some(unchecked));
+    }
+
+    #[test]
+    #[allow(deprecated)]
+    fn test_pre_emphasis_defaults_and_overrides() {
+        let sample_rate = 16000.0;
+        let data = synthetic_vowel(sample_rate, 0.05);
+
+        let default = formant_detection(data.clone(), 12, sample_rate, None);
+        let explicit = formant_detection(data.clone(), 12, sample_rate, Some(0.97));
+        assert_eq!(default, explicit);
+
+        // Praat's 50 Hz default.
+        let alpha = pre_emphasis_from_frequency(50.0, sample_rate);
+        assert!((alpha - 0.9806).abs() < 1e-4, "alpha = {}", alpha);
+        let praat = formant_detection(data.clone(), 12, sample_rate, Some(alpha));
+        let flat = formant_detection(data, 12, sample_rate, Some(0.0));
+        assert_ne!(praat, flat);
+    }
+
+    #[test]
+    fn test_spectrum_and_envelope_share_relative_floor() {
+        let sample_rate = 16000.0;
+        let data = synthetic_vowel(sample_rate, 0.064);
+        let floor_db = 60.0;
+
+        let frame = FormantFrame::analyze(
+            data.clone(),
+            12,
+            sample_rate,
+            256,
+            spectral_floor(floor_db),
+            DEFAULT_PRE_EMPHASIS,
+        );
+        let envelope = frame.envelope.unwrap().magnitudes;
+        let spectrum: Vec<f64> = wasm_fourier_with_options(
+            data.iter().map(|&x| x as f32).collect(),
+            false,
+            false,
+            1,
+            floor_db,
+        )
+        .into_iter()
+        .map(f64::from)
+        .collect();
+
+        // Both bottom out exactly `floor_db` below their own peak.
+        for magnitudes in [&envelope, &spectrum] {
+            let peak = magnitudes.iter().copied().fold(0.0, f64::max);
+            let low = magnitudes.iter().copied().fold(f64::INFINITY, f64::min);
+            let range_db = 20.0 * (peak / low).log10();
+            assert!(range_db <= floor_db + 1e-3, "range = {} dB", range_db);
+        }
+    }
+
+    #[test]
+    #[allow(deprecated)]
+    fn test_lpc_filter_freq_response_with_downsampling() {
+        // Parameters for the synthetic test signal
+        let original_sample_rate = 16000.0; // in Hz
+        let frequency = 440.0; // A4 note in Hz
+        let duration = 1.0; // in seconds
+        let num_samples = (original_sample_rate * duration) as usize;
+
+        // Generate a sine wave as the original data
+        let original_data: Vec<f64> = (0..num_samples)
+            .map(|n| (2.0 * PI * frequency * (n as f64) / original_sample_rate).sin())
+            .collect();
+
+        // LPC parameters
+        let lpc_order = 10;
+        let num_points = 512;
+
+        for downsample_factor in 1..=4 {
+            // Call the function that includes downsampling
+            let response_with_downsampling = lpc_filter_freq_response_with_downsampling(
+                original_data.clone(),
+                lpc_order,
+                original_sample_rate,
+                downsample_factor,
+                num_points,
+                None,
+            );
+
+            // `downsample` keeps every n-th sample; the entry point low-pass filters
+            // first, so compare it with an independently filtered signal.
+            assert_eq!(
+                downsample(&original_data, downsample_factor),
+                manual_downsample(&original_data, downsample_factor)
+            );
+            let downsampled_data = lowpass_decimate(&original_data, downsample_factor);
+            let downsampled_sample_rate = original_sample_rate / (downsample_factor as f64);
+
+            // Call the function without downsampling
+            let response_without_downsampling = lpc_filter_freq_response(
+                downsampled_data.clone(),
+                lpc_order,
+                downsampled_sample_rate,
+                num_points,
+                None,
+            );
+
+            // The resamplers differ in their filters, so allow 2% of the peak
+            let epsilon = 0.02
+                * response_without_downsampling
+                    .iter()
+                    .fold(0.0, |a, &b| b.max(a));
+
+            // Ensure both responses have the same number of points
+            assert_eq!(
+                response_with_downsampling.len(),
+                response_without_downsampling.len(),
+                "Frequency responses have different lengths"
+            );
+
+            // Compare each point in the frequency responses
+            for (i, (resp_ds, resp)) in response_with_downsampling
+                .iter()
+                .zip(response_without_downsampling.iter())
+                .enumerate()
+            {
+                let diff = (resp_ds - resp).abs();
+                if diff > epsilon {
+                    panic!(
+                        "Frequency response differs at index {} (factor {}): with_downsampling = {}, without_downsampling = {}, difference = {} exceeds epsilon = {}",
+                        i, downsample_factor, resp_ds, resp, diff, epsilon
+                    );
+                }
+            }
+        }
+    }
+
+    /// `duration` seconds of the core's test vowel at 120 Hz.
+    fn synthetic_vowel(sample_rate: f64, duration: f64) -> Vec<f64> {
+        ezformant::test_signals::vowel(sample_rate, 120.0, (sample_rate * duration) as usize)
+    }
+
+    #[test]
+    fn test_gain_db_envelope_has_the_fft_level() {
+        let sample_rate = 16000.0;
+        let data = synthetic_vowel(sample_rate, 0.064);
+        let fft_db: Vec<f64> = wasm_fourier(data.iter().map(|&x| x as f32).collect())
+            .iter()
+            .map(|&m| 20.0 * (m as f64).log10())
+            .collect();
+        let mean_power = |db: &[f64]| {
+            let power: f64 = db.iter().map(|d| 10f64.powf(d / 10.0)).sum();
+            10.0 * (power / db.len() as f64).log10()
+        };
+
+        // Parseval: the model spectrum carries the frame's energy, even though the
+        // harmonics of the FFT rise above it.
+        for downsample_factor in [1, 2] {
+            let frame = FormantFrame::analyze_gain_db(
+                &data,
+                12,
+                sample_rate,
+                downsample_factor,
+                256,
+                spectrum::SpectralFloor::default(),
+                &quality::QualitySettings::default(),
+            );
+            let envelope = frame.envelope.unwrap().magnitudes;
+            let band = &fft_db[..fft_db.len() / downsample_factor];
+            let difference = mean_power(&envelope) - mean_power(band);
+            assert!(difference.abs() < 1.5, "{} dB", difference);
+        }
+    }
+
+    #[test]
+    fn test_frame_analyzer_combines_the_separate_calls() {
+        let sample_rate = 16000.0;
+        let frame: Vec<f32> = synthetic_vowel(sample_rate, 0.064)
+            .iter()
+            .map(|&x| x as f32)
+            .collect();
+        let data: Vec<f64> = frame.iter().map(|&x| x as f64).collect();
+
+        let combined = FrameAnalyzer::new(sample_rate, 12, 2, 0.0, None).analysis_frame(&frame);
+        let separate = FormantFrame::analyze_gain_db(
+            &data,
+            12,
+            sample_rate,
+            2,
+            256,
+            spectrum::SpectralFloor::default(),
+            &quality::QualitySettings::default(),
+        );
+        assert_eq!(combined.envelope, separate.envelope.unwrap());
+        assert_eq!(combined.formants.len(), combined.bandwidths.len());
+        for (a, b) in combined.formants.iter().zip(&separate.formants) {
+            assert!((a - b).abs() < 1e-6);
+        }
+        assert!(combined.bandwidths.iter().all(|&b| b > 0.0));
+        assert_eq!(combined.pitch, PitchFrame::analyze(&data, sample_rate, 0.1));
+
+        // Every envelope point sits on an FFT bin.
+        assert_eq!(combined.spectrum.magnitudes.len(), 512);
+        assert_eq!(
+            &combined.spectrum.frequencies[..256],
+            &combined.envelope.frequencies[..]
+        );
+    }
+
+    /// Independent anti-aliasing reference: a Blackman-windowed sinc low-pass at 0.9
+    /// times the new Nyquist frequency, applied without phase shift, then every
+    /// `factor`-th sample.
+    fn lowpass_decimate(data: &[f64], factor: usize) -> Vec<f64> {
+        if factor == 1 {
+            return data.to_vec();
+        }
+        let cutoff = 0.45 / factor as f64;
+        let half = 32 * factor as isize;
+        let taps: Vec<f64> = (-half..=half)
+            .map(|k| {
+                let x = k as f64;
+                let sinc = if k == 0 {
+                    2.0 * cutoff
+                } else {
+                    (2.0 * PI * cutoff * x).sin() / (PI * x)
+                };
+                let phase = PI * (x / half as f64 + 1.0);
+                sinc * (0.42 - 0.5 * phase.cos() + 0.08 * (2.0 * phase).cos())
+            })
+            .collect();
+        let gain: f64 = taps.iter().sum();
+        (0..data.len())
+            .step_by(factor)
+            .map(|n| {
+                let n = n as isize;
+                (-half..=half)
+                    .zip(&taps)
+                    .filter(|(k, _)| (0..data.len() as isize).contains(&(n + k)))
+                    .map(|(k, tap)| data[(n + k) as usize] * tap)
+                    .sum::<f64>()
+                    / gain
+            })
+            .collect()
+    }
+
+    #[test]
+    #[allow(deprecated)]
+    fn test_formant_detection_with_downsampling_matches_independent_resampling() {
+        let original_sample_rate = 48000.0;
+        let original_data = synthetic_vowel(original_sample_rate, 0.05);
+        let lpc_order = 10;
+
+        for downsample_factor in 2..=4 {
+            let formants = formant_detection_with_downsampling(
+                original_data.clone(),
+                lpc_order,
+                original_sample_rate,
+                downsample_factor,
+                None,
+            );
+            let reference = formant_detection(
+                lowpass_decimate(&original_data, downsample_factor),
+                lpc_order,
+                original_sample_rate / downsample_factor as f64,
+                None,
+            );
+
+            for expected in [700.0, 1200.0, 2600.0] {
+                let nearest = |formants: &[f64]| {
+                    formants
+                        .iter()
+                        .copied()
+                        .min_by(|a, b| (a - expected).abs().total_cmp(&(b - expected).abs()))
+                        .unwrap()
+                };
+                let (got, want) = (nearest(&formants), nearest(&reference));
+                assert!(
+                    (got - want).abs() < 0.01 * expected,
+                    "factor {}: formant near {} is {} (reference {})",
+                    downsample_factor,
+                    expected,
+                    got,
+                    want
+                );
+            }
+        }
+    }
+
+    #[test]
+    #[allow(deprecated)]
+    fn test_formants_survive_non_integer_resampling() {
+        let target_rate = 16000.0;
+        let lpc_order = 12;
+        let reference = formant_detection(
+            synthetic_vowel(target_rate, 0.05),
+            lpc_order,
+            target_rate,
+            None,
+        );
+
+        for source_rate in [22050.0, 44100.0] {
+            // A ceiling of 8000 Hz resamples to 16 kHz, a non-integer ratio from both
+            // source rates.
+            let formants = formant_detection_with_max_formant(
+                synthetic_vowel(source_rate, 0.05),
+                lpc_order,
+                source_rate,
+                target_rate / 2.0,
+                None,
+            )
+            .unwrap();
+
+            for expected in [700.0, 1200.0] {
+                let nearest = |formants: &[f64]| {
+                    formants
+                        .iter()
+                        .copied()
+                        .min_by(|a, b| (a - expected).abs().total_cmp(&(b - expected).abs()))
+                        .unwrap()
+                };
+                let (got, want) = (nearest(&formants), nearest(&reference));
+                assert!(
+                    (got - want).abs() < 0.03 * expected,
+                    "{} Hz -> {} Hz: formant near {} is {} (native {})",
+                    source_rate,
+                    target_rate,
+                    expected,
+                    got,
+                    want
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_formant_batch_steps_in_chunks() {
+        let sample_rate = 16000.0;
+        let signal = synthetic_vowel(sample_rate, 0.2);
+
+        let mut batch = FormantBatch::new(signal.clone(), sample_rate, 12, 1, 0.025, 0.01, None);
+        batch.set_frames_per_step(0);
+        assert_eq!(batch.frames_per_step(), 1);
+        batch.set_frames_per_step(7);
+        let total = batch.total_frames();
+        assert_eq!(total, 18);
+
+        let mut steps = 1;
+        while !batch.step() {
+            steps += 1;
+        }
+        assert_eq!(steps, total.div_ceil(7));
+        assert_eq!(batch.progress(), 1.0);
+        assert!((batch.times()[1] - 0.0225).abs() < 1e-12);
+        assert_eq!(batch.voicing().len(), total);
+        assert!(batch.energy().iter().all(|&e| e > 0.0));
+        let saved: Vec<batch::TrackFrame> = json::from_json(&batch.to_json()).unwrap();
+        assert_eq!(saved, batch.results);
+
+        let analyzer = analyzer::Analyzer::new(12, sample_rate);
+        let last = analyzer.formants(&signal[170 * 16..170 * 16 + 400]);
+        let flat = batch.formants(2);
+        assert_eq!(flat.len(), total * 2);
+        assert_eq!(&flat[34..], &last[..2]);
+
+        // With a VAD, chunked steps match analyzing the whole recording at once.
+        let mut config = AnalysisConfig::new();
+        config.set_vad(true, None);
+        let mut gated = FormantBatch::from_config(signal.clone(), sample_rate, &config);
+        gated.set_frames_per_step(5);
+        while !gated.step() {}
+        assert_eq!(gated.results, config.inner.analyze(&signal, sample_rate));
+    }
+    #[test]
+    fn test_precomputed_coefficients_match_frame_analysis() {
+        let sample_rate = 16000.0;
+        let frame = synthetic_vowel(sample_rate, 0.05);
+        let floor = spectrum::SpectralFloor::default();
+        let expected = FormantFrame::analyze(frame.clone(), 12, sample_rate, 64, floor, 0.97);
+
+        let mut emphasized = frame.clone();
+        preprocess_signal(&mut emphasized, 0.97);
+        let (coefficients, _) = lpc::levinson(12, &lpc::autocorrelate(&emphasized, 12));
+        let grid = units::frequency_axis(64, sample_rate, units::FrequencyUnit::Hertz);
+        assert_eq!(
+            FormantFrame::from_coefficients(&coefficients, sample_rate, &grid, floor),
+            expected
+        );
+        assert_eq!(
+            formants_from_coefficients(&coefficients, sample_rate).unwrap(),
+            expected.formants
+        );
+        assert_eq!(
+            PeakFrame::from_coefficients(&coefficients, sample_rate),
+            PeakFrame::analyze(frame, 12, sample_rate, 0.97)
+        );
+    }
+
+    #[test]
+    fn test_poles_of_known_polynomial() {
+        // z^2 - z + 0.5: poles at 0.5 +- 0.5i, radius 1/sqrt(2), angle +-pi/4.
+        let poles = PoleFrame::from_coefficients(&[1.0, -1.0, 0.5], 8000.0);
+        assert_eq!(poles.roots.len(), 4);
+        for (value, expected) in poles.roots.iter().zip([0.5, -0.5, 0.5, 0.5]) {
+            assert!((value - expected).abs() < 1e-9, "{:?}", poles.roots);
         }
+        assert!(poles.radii.iter().all(|r| (r - 0.5f64.sqrt()).abs() < 1e-9));
+        assert!((poles.frequencies[0] + 1000.0).abs() < 1e-6);
+        assert!((poles.frequencies[1] - 1000.0).abs() < 1e-6);
     }
 }
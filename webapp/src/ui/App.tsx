@@ -17,6 +17,7 @@ type WorkerRequest =
 				lpcOrder: number;
 				sampleRate: number;
 				downsampleFactor: number;
+				requestedAt: number;
 			};
 	  };
 
@@ -24,9 +25,10 @@ type WorkerResponse =
 	| { type: "init"; status: "success" | "error"; error?: string }
 	| {
 			type: "calcFormants";
-			status: "success" | "error";
+			status: "success" | "skipped" | "error";
 			formants?: number[];
 			pitch?: number;
+			counters?: number[];
 			error?: string;
 	  };
 
@@ -75,6 +77,8 @@ type DebugInfo = {
 	windowDurationMs: number | null;
 	lastFrameComputeMs: number | null;
 	lpcCoefficients: number[] | null;
+	analyzedFrames: number;
+	droppedFrames: number;
 };
 
 const FFT_SIZE = 2048;
@@ -342,6 +346,8 @@ export default function App() {
 		windowDurationMs: null,
 		lastFrameComputeMs: null,
 		lpcCoefficients: null,
+		analyzedFrames: 0,
+		droppedFrames: 0,
 	});
 	const [snapshotError, setSnapshotError] = useState<string | null>(null);
 	const [showDeveloperUi] = useState(
@@ -751,6 +757,7 @@ export default function App() {
 					lpcOrder: formantOrder,
 					sampleRate: audioContext.sampleRate,
 					downsampleFactor,
+					requestedAt: performance.timeOrigin + performance.now(),
 				},
 			};
 			worker.postMessage(payload);
@@ -872,13 +879,15 @@ export default function App() {
 				analyser.getFloatTimeDomainData(dataArray);
 				const graphSize = 1024;
 				const { spectrumOrder, downsampleFactor } = lpcPresetRef.current;
-				const freqResponse = wasm.lpc_filter_freq_response_with_downsampling(
-					Float64Array.from(dataArray),
+				const lpcFrame = wasm.formant_frame_f32(
+					dataArray,
 					spectrumOrder,
 					audioContextRef.current?.sampleRate ?? 44100,
 					downsampleFactor,
 					graphSize,
+					0,
 				);
+				const freqResponse = lpcFrame.envelope?.magnitudes ?? [];
 
 				const maxResponse = Math.max(...freqResponse);
 				const normalizeConst = maxResponse > 0 ? maxResponse : 1;
@@ -1179,6 +1188,16 @@ export default function App() {
 						return;
 					}
 
+					if (message.type === "calcFormants" && message.counters) {
+						const [analyzed, droppedOverload, droppedInvalid] =
+							message.counters;
+						setDebugInfo((previous) => ({
+							...previous,
+							analyzedFrames: analyzed,
+							droppedFrames: droppedOverload + droppedInvalid,
+						}));
+					}
+
 					if (
 						message.type === "calcFormants" &&
 						message.status === "success" &&
@@ -1435,6 +1454,8 @@ export default function App() {
 			frameIntervalMs: debugInfo.frameIntervalMs,
 			windowDurationMs: debugInfo.windowDurationMs,
 			lastFrameComputeMs: debugInfo.lastFrameComputeMs,
+			analyzedFrames: debugInfo.analyzedFrames,
+			droppedFrames: debugInfo.droppedFrames,
 			formants,
 		},
 	});
@@ -1993,6 +2014,14 @@ export default function App() {
 											: "—"}
 									</div>
 								</div>
+								<div className="developer-stat">
+									<div className="label">Dropped frames</div>
+									<div className="value small">
+										{`${debugInfo.droppedFrames} / ${
+											debugInfo.analyzedFrames + debugInfo.droppedFrames
+										}`}
+									</div>
+								</div>
 							</div>
 							<div className="developer-section">
 								<div className="label">LPC coefficients</div>